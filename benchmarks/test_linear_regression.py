@@ -116,6 +116,26 @@ def test_sklearn_linear_regression_on_df(benchmark, n):
         reg.fit(df[X_VARS], df[Y])
 
 
+@pytest.mark.parametrize("n", SIZES)
+@pytest.mark.benchmark(group="qr_vs_qr_direct")
+def test_pds_qr_on_df(benchmark, n):
+    df = DF.sample(n=n, seed=SEED)
+
+    @benchmark
+    def func():
+        df.select(pds.lin_reg(*X_VARS, target=Y[0], solver="qr"))
+
+
+@pytest.mark.parametrize("n", SIZES)
+@pytest.mark.benchmark(group="qr_vs_qr_direct")
+def test_pds_qr_direct_on_df(benchmark, n):
+    df = DF.sample(n=n, seed=SEED)
+
+    @benchmark
+    def func():
+        df.select(pds.lin_reg(*X_VARS, target=Y[0], solver="qr_direct"))
+
+
 @pytest.mark.parametrize("n", SIZES)
 @pytest.mark.benchmark(group="lasso_on_df")
 def test_pds_lasso_on_df(benchmark, n):
@@ -137,6 +157,25 @@ def test_sklearn_lasso_on_df(benchmark, n):
         reg.fit(df[X_VARS], df[Y])
 
 
+# Many more features than rows, where FISTA should beat coordinate descent
+WIDE_N, WIDE_P = 200, 2000
+WIDE_X = np.random.default_rng(SEED).normal(size=(WIDE_N, WIDE_P))
+WIDE_DF = pl.DataFrame(WIDE_X, schema=[f"w{i}" for i in range(WIDE_P)]).with_columns(
+    y=pl.col("w0") - 2.0 * pl.col("w1") + 0.5 * pl.col("w2") + pds.random() * 0.01
+)
+WIDE_VARS = [f"w{i}" for i in range(WIDE_P)]
+
+
+@pytest.mark.parametrize("algorithm", ["cd", "fista"])
+@pytest.mark.benchmark(group="lasso_wide")
+def test_pds_lasso_wide(benchmark, algorithm):
+    @benchmark
+    def func():
+        WIDE_DF.select(
+            pds.lin_reg(*WIDE_VARS, target="y", l1_reg=0.05, tol=1e-8, algorithm=algorithm)
+        )
+
+
 @pytest.mark.parametrize("n", SIZES)
 @pytest.mark.benchmark(group="ridge_svd_on_df")
 def test_ridge_svd_on_df(benchmark, n):
@@ -177,3 +216,49 @@ def test_sklearn_ridge_cholesky_on_df(benchmark, n):
     def func():
         reg = Ridge(alpha=0.1, fit_intercept=False, solver="cholesky")
         reg.fit(df[X_VARS], df[Y])
+
+
+# Rank-1 Woodbury updates on a small design, where the rayon pool is pure overhead
+ROLLING_SIZE = 1_000_000
+ROLLING_DF = (
+    pds.frame(size=ROLLING_SIZE)
+    .select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(0.0, 1.0).alias("x2"),
+        pds.random(0.0, 1.0).alias("x3"),
+    )
+    .with_columns(
+        y=pl.col("x1") * 0.5 + pl.col("x2") * 0.25 - pl.col("x3") * 0.15 + pds.random() * 0.0001,
+    )
+)
+
+
+@pytest.mark.benchmark(group="rolling_lin_reg")
+def test_pds_rolling_lin_reg(benchmark):
+    @benchmark
+    def func():
+        ROLLING_DF.select(pds.rolling_lin_reg("x1", "x2", "x3", target="y", window_size=60))
+
+
+# Building the design matrix from the input Series, with and without nulls. With 100 features the
+# assembly of the matrix is a large part of the time of a plain lin_reg.
+DESIGN_SIZE, DESIGN_P = 100_000, 100
+DESIGN_VARS = [f"d{i}" for i in range(DESIGN_P)]
+DESIGN_DF = pl.DataFrame(
+    np.random.default_rng(SEED).normal(size=(DESIGN_SIZE, DESIGN_P)), schema=DESIGN_VARS
+).with_columns(y=pl.sum_horizontal(DESIGN_VARS) + pds.random() * 0.01)
+DESIGN_DF_NULLS = DESIGN_DF.with_columns(
+    pl.when(pds.random() < 0.001).then(None).otherwise(pl.col(c)).alias(c) for c in DESIGN_VARS
+)
+
+
+@pytest.mark.parametrize("n_features", [10, 100])
+@pytest.mark.parametrize("null_policy, nulls", [("skip", False), ("skip", True), ("zero", True)])
+@pytest.mark.benchmark(group="build_design")
+def test_pds_build_design(benchmark, n_features, null_policy, nulls):
+    df = DESIGN_DF_NULLS if nulls else DESIGN_DF
+    features = DESIGN_VARS[:n_features]
+
+    @benchmark
+    def func():
+        df.select(pds.lin_reg(*features, target="y", add_bias=True, null_policy=null_policy))
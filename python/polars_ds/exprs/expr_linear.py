@@ -3,10 +3,10 @@
 from __future__ import annotations
 import polars as pl
 import warnings
-from typing import List, Any, Literal
+from typing import List, Any, Literal, Tuple
 
 # Internal dependencies
-from polars_ds.typing import LRSolverMethods, NullPolicy
+from polars_ds.typing import LRSolverMethods, NullPolicy, StopCriterion
 from polars_ds._utils import pl_plugin
 from polars_ds.config import _lin_reg_expr_symbol
 
@@ -15,8 +15,54 @@ __all__ = [
     "lin_reg_w_rcond",
     "simple_lin_reg",
     "recursive_lin_reg",
+    "lin_reg_ewls",
+    "lin_reg_recursive_residuals",
+    "lin_reg_expanding",
     "rolling_lin_reg",
     "lin_reg_report",
+    "lin_reg_predict_path",
+    "lin_reg_predict_with_coeffs",
+    "lin_reg_predict_from_coeffs",
+    "lin_reg_train_test",
+    "lin_reg_predict_grouped",
+    "lin_reg_profile",
+    "lin_reg_bootstrap_ci",
+    "lin_reg_sign_stability",
+    "lin_reg_ridge_loocv",
+    "lin_reg_cv",
+    "lin_reg_robust",
+    "lin_reg_mm",
+    "lin_reg_ransac",
+    "lin_reg_theil_sen",
+    "lin_reg_huber",
+    "lin_reg_quantile",
+    "logistic_reg",
+    "poisson_reg",
+    "glm_reg",
+    "lin_reg_ar1",
+    "lin_reg_partial",
+    "lin_reg_residualize",
+    "lin_reg_fixed_effects",
+    "lin_reg_group_slopes",
+    "lin_reg_best_subset",
+    "lin_reg_stepwise",
+    "lin_reg_relative_importance",
+    "lin_reg_vif",
+    "lin_reg_adaptive_lasso",
+    "lin_reg_generalized_ridge",
+    "lin_reg_lasso_path",
+    "lin_reg_residual_acf",
+    "lin_reg_residual_pacf",
+    "lin_reg_white_test",
+    "lin_reg_breusch_pagan",
+    "lin_reg_chow_test",
+    "lin_reg_influence",
+    "lin_reg_group_influence",
+    "lin_reg_driscoll_kraay",
+    "lin_reg_conditioning",
+    "lin_reg_matrix_rank",
+    "lin_reg_xtx_eigenvalues",
+    "lin_reg_pred_interval",
     "query_lstsq",
     "query_lstsq_w_rcond",
     "query_recursive_lstsq",
@@ -25,6 +71,15 @@ __all__ = [
 ]
 
 
+def _clip_kwarg(clip: Tuple[float, float] | None) -> Tuple[float, float] | None:
+    if clip is None:
+        return None
+    lower, upper = clip
+    if not lower <= upper:
+        raise ValueError("`clip` must be a (lower, upper) tuple with lower <= upper.")
+    return (float(lower), float(upper))
+
+
 # Despite the typing requirments in the function signatures, we allow some slack
 # by accepting the most common Series/Array types.
 def lr_formula(s: Any) -> pl.Expr:
@@ -107,13 +162,27 @@ def lin_reg(
     *x: str | pl.Expr,
     target: str | pl.Expr | List[str | pl.Expr],
     add_bias: bool = False,
+    bias_first: bool = False,
     weights: str | pl.Expr | None = None,
+    offset: str | pl.Expr | None = None,
     return_pred: bool = False,
     l1_reg: float = 0.0,
-    l2_reg: float = 0.0,
+    l2_reg: float | List[float] = 0.0,
     tol: float = 1e-5,
-    solver: LRSolverMethods = "qr",
+    stop_criterion: StopCriterion = "coefficient",
+    solver: LRSolverMethods | Literal["svd_rcond", "tls"] = "qr",
     null_policy: NullPolicy = "skip",
+    clip: Tuple[float, float] | None = None,
+    penalize_bias: bool = False,
+    algorithm: Literal["cd", "fista"] = "cd",
+    pred_interval: bool = False,
+    pred_se: bool = False,
+    alpha: float = 0.05,
+    max_iter: int = 2000,
+    penalty_factor: List[float] | None = None,
+    strict: bool = False,
+    rcond: float = 0.0,
+    drop_nonfinite: bool = False,
 ) -> pl.Expr:
     """
     Computes least squares solution to the equation Ax = y where y is the target (or multiple targets).
@@ -121,24 +190,40 @@ def lin_reg(
     If both are > 0, then this is elastic net regression. If none of the cases above is true, as is the default case,
     then a normal regression will be performed.
 
-    If add_bias is true, it will be the last coefficient in the output and output will have len(variables) + 1.
+    If add_bias is true, it will be the last coefficient in the output and output will have len(variables) + 1,
+    unless bias_first is true, in which case it will be the first coefficient.
 
     If you only want to do simple lstsq (one predictive x variable and one target) and null policy doesn't matter,
     then `query_simple_lstsq` is a faster alternative.
 
     Memory hint: if data takes 100MB of memory, you need to have at least 200MB of memory to run this.
+    For a single target, if the target and all the variables are Float32, the fit stays in f32 and the
+    output will be Float32, which halves the memory usage.
 
     Parameters
     ----------
     x
         The variables used to predict target
     target
-        The target variable, or a list of targets for a multi-target linear regression
+        The target variable, or a list of targets for a multi-target linear regression. In the multi-target
+        case, the targets share the same design X, so XtX is factorized once and solved against X^T Y for all
+        targets. The output is then a struct with one coefficient list per target, named target_0, target_1, ...
     add_bias
         Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last. This is
+        for compatibility with tools that expect intercept-first ordering. This doesn't work if this is multi-target.
     weights
-        Whether to perform a weighted least squares or not. If this is weighted, then it will ignore
-        l1_reg or l2_reg parameters. This doesn't work if this is multi-target.
+        Whether to perform a weighted least squares or not. The weights are analytic, i.e. row i's squared
+        residual is multiplied by w_i in the loss, which also applies to Ridge, Lasso and Elastic Net. For Lasso
+        and Elastic Net, the loss is divided by the sum of the weights instead of the number of rows, so a row
+        with weight 2 is the same as two copies of it. Weights of rows skipped by the null policy are dropped
+        as well. This doesn't work if this is multi-target.
+    offset
+        If not None, a column that is added to the predictions with coefficient 1, and is not estimated. The
+        fit is on target - offset, and the offset is added back to the predictions. Nulls in the offset are
+        handled by the null policy like the variables. This doesn't work if this is multi-target, or with the
+        solvers 'svd_rcond' and 'tls'.
     return_pred
         If true, return prediction and residue. If false, return coefficients. Note that
         for coefficients, it reduces to one output (like max/min), but for predictions and
@@ -147,45 +232,225 @@ def lin_reg(
         Regularization factor for Lasso. Should be nonzero when method = l1.
         This is ignored if this is multi-target.
     l2_reg
-        Regularization factor for Ridge. Should be nonzero when method = l2.
+        Regularization factor for Ridge. Should be nonzero when method = l2. If this is multi-target, this can
+        also be a list with one factor per target, so that some targets are regularized more than others.
     tol
         For Lasso or elastic net regression, if maximum coordinate update is < tol, the algorithm is considered
-        to have converged. If not, it will run for at most 2000 iterations. This doesn't work if this is multi-target.
+        to have converged. If not, it will run for at most max_iter iterations. This doesn't work if this is
+        multi-target.
+    stop_criterion
+        For Lasso or elastic net regression. If "coefficient", stop when the maximum coordinate update is < tol.
+        If "objective", stop when |obj_new - obj_old| / |obj_old| < tol, where obj is the mean squared error / 2
+        plus the penalty terms. If "dual_gap", stop when the duality gap is < tol. The gap bounds how far obj is
+        from its minimum, so this is the most reliable rule. This doesn't work if this is multi-target.
     solver
-        Only applies when this is normal or l2 regression. One of ['svd', 'qr'].
-        Both 'svd' and 'qr' can handle rank deficient cases relatively well.
+        Only applies when this is normal or l2 regression. One of ['svd', 'qr', 'qr_direct'].
+        Both 'svd' and 'qr' can handle rank deficient cases relatively well. 'svd' and 'qr' solve the normal
+        equation, which squares the condition number of the data. 'qr_direct' runs QR on the data itself,
+        which is slower but much more accurate on ill-conditioned data.
+        'svd_rcond' is a truncated SVD, which sets the singular values of X below `rcond` * (max singular value)
+        to 0. This gives the minimum norm solution on the remaining directions, which is much more stable when the
+        features are nearly collinear. It only supports unweighted single target OLS and Ridge, and returns the
+        coefficients. See `lin_reg_w_rcond` for the singular values as well.
+        'tls' is total least squares (orthogonal regression), which is for when the features are measured with
+        noise as well as the target. OLS is biased towards 0 in that case, while TLS minimizes the orthogonal
+        distances to the fitted hyperplane, with the coefficients from the SVD of [X | y]. TLS is not scale
+        invariant, so the features and the target should be in comparable units. It only supports unweighted
+        single target regression without regularization, and returns the coefficients.
     null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
         One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
         fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
         the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
         columns. If this is multi-target, fill will fail if there are nulls in any of the targets.
+    clip
+        Only used when return_pred is true. If not None, a (lower, upper) tuple and predictions will be clamped into this range. Clipping happens
+        after the bias is added. NaN predictions are not clamped.
+    penalize_bias
+        Only applies to Ridge, Lasso and elastic net regression with add_bias. By default, the bias is not
+        penalized, so it is not shrunk towards 0: for Lasso and elastic net, it is set to the (weighted) mean of the
+        residuals of the other coefficients after each round of coordinate descent. If true, the bias is penalized
+        like the other coefficients, which some prefer for centered data. This doesn't work if this is multi-target.
+    algorithm
+        The solver for Lasso and elastic net. Either 'cd' (coordinate descent) or 'fista' (accelerated proximal
+        gradient, with the step size from the largest eigenvalue of XtX). Both minimize the same objective. FISTA
+        never forms XtX and can be much faster when there are many more features than rows. With FISTA, the
+        coefficients change less per iteration, so a smaller tol may be needed for the same accuracy. This doesn't
+        work if this is multi-target.
+    pred_interval
+        Only used when return_pred is true. If true, the output also has the fields `pred_lower` and `pred_upper`,
+        the bounds of the prediction interval pred -/+ t * s * sqrt(1 + h), where s is the residual standard
+        error, h is the leverage of the row and t is the 1 - alpha / 2 quantile of the t distribution. For Ridge,
+        the variance of the prediction is adjusted for the penalty, see `lin_reg_pred_interval`. Rows that are
+        skipped because of nulls get NaN. Only OLS and Ridge are supported, and this doesn't work if this is
+        multi-target or weighted.
+    pred_se
+        Only used when return_pred is true. If true, the output also has the field `pred_se`, the standard error
+        of the fitted mean s * sqrt(h), where s is the residual standard error and h is the leverage of the row.
+        This is the prediction interval's standard error without the variance of a new observation. For Ridge,
+        it is adjusted for the penalty like the prediction intervals. Rows that are skipped because of nulls get
+        NaN. Only OLS and Ridge are supported, and this doesn't work if this is multi-target or weighted.
+    alpha
+        Only used when pred_interval is true. The significance level of the prediction intervals. Must be in
+        (0, 1).
+    max_iter
+        For Lasso or elastic net regression, the max number of iterations. Must be > 0. This doesn't work if
+        this is multi-target.
+    penalty_factor
+        For Lasso or elastic net regression, one non-negative factor per variable in x, which multiplies l1_reg
+        for that variable, like glmnet's `penalty.factor`. A factor of 0 means the variable is not shrunk by the
+        l1 penalty. The l2 penalty is not affected. Missing factors are 1 and extra ones are ignored, so the bias
+        never has an l1 penalty. This doesn't work if this is multi-target.
+    strict
+        For Lasso or elastic net regression. If true, raise an error when the solver does not converge within
+        `max_iter` iterations. Otherwise, the last iterate is returned. This doesn't work if this is multi-target.
+    rcond
+        Only used when solver is 'svd_rcond'. Cut-off ratio for small singular values. If it is < machine
+        precision * max(n_rows, n_cols), it will be set to that.
+    drop_nonfinite
+        If true, NaN and +/-inf in the target and the variables are treated as nulls when null_policy is 'skip',
+        so their rows are dropped. Under the other null policies, they raise an error. If false, they go into
+        the fit as they are, and the result will likely be NaN or garbage. This doesn't work if this is
+        multi-target.
     """
+    if solver == "svd_rcond":
+        if (
+            isinstance(target, list)
+            or offset is not None
+            or isinstance(l2_reg, list)
+            or weights is not None
+            or l1_reg > 0.0
+            or return_pred
+        ):
+            raise ValueError(
+                "Solver 'svd_rcond' only supports unweighted single target OLS and Ridge coefficients "
+                "without an offset."
+            )
+        return (
+            lin_reg_w_rcond(
+                *x,
+                target=target,
+                add_bias=add_bias,
+                bias_first=bias_first,
+                rcond=rcond,
+                l2_reg=l2_reg,
+                null_policy=null_policy,
+            )
+            .struct.field("coeffs")
+            .alias("coeffs")
+        )
+    if solver == "tls":
+        if (
+            isinstance(target, list)
+            or offset is not None
+            or isinstance(l2_reg, list)
+            or weights is not None
+            or l1_reg > 0.0
+            or l2_reg > 0.0
+            or return_pred
+        ):
+            raise ValueError(
+                "Solver 'tls' only supports unweighted single target coefficients without regularization "
+                "or an offset."
+            )
+        cols = [lr_formula(target)]
+        cols.extend(lr_formula(z) for z in x)
+        lr_kwargs = {
+            "bias": add_bias,
+            "null_policy": null_policy,
+            "l1_reg": 0.0,
+            "l2_reg": 0.0,
+            "solver": "",
+            "tol": 0.0,
+            "bias_first": bias_first,
+        }
+        return pl_plugin(
+            symbol="pl_total_lstsq",
+            args=cols,
+            kwargs=lr_kwargs,
+            returns_scalar=True,
+            pass_name_to_apply=True,
+        )
+    if algorithm not in ("cd", "fista"):
+        raise ValueError("Input `algorithm` must be either 'cd' or 'fista'.")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+    if penalty_factor is not None:
+        penalty_factor = [float(f) for f in penalty_factor]
+        if any(not (f >= 0.0) for f in penalty_factor):
+            raise ValueError("Input `penalty_factor` must be non-negative.")
+    if pred_se and return_pred and (weights is not None or l1_reg > 0.0):
+        raise ValueError(
+            "Prediction standard errors are only supported for unweighted OLS and Ridge regression."
+        )
+    if pred_interval and return_pred:
+        if weights is not None or l1_reg > 0.0:
+            raise ValueError(
+                "Prediction intervals are only supported for unweighted OLS and Ridge regression."
+            )
+        if not (0.0 < alpha < 1.0):
+            raise ValueError("Input `alpha` must be in (0, 1).")
 
     if isinstance(target, list):
         n_targets = len(target)
         if n_targets == 0:
             raise ValueError("If `target` is a list, it cannot be empty.")
         elif n_targets == 1:
+            if isinstance(l2_reg, list):
+                if len(l2_reg) != 1:
+                    raise ValueError("If `l2_reg` is a list, it must have one value per target.")
+                l2_reg = l2_reg[0]
             return lin_reg(
                 *x,
                 target=target[0],
                 add_bias=add_bias,
+                bias_first=bias_first,
                 weights=weights,
+                offset=offset,
                 return_pred=return_pred,
                 l1_reg=l1_reg,
                 l2_reg=l2_reg,
                 tol=tol,
+                stop_criterion=stop_criterion,
                 solver=solver,
                 null_policy=null_policy,
+                clip=clip,
+                penalize_bias=penalize_bias,
+                algorithm=algorithm,
+                pred_interval=pred_interval,
+                pred_se=pred_se,
+                alpha=alpha,
+                max_iter=max_iter,
+                penalty_factor=penalty_factor,
+                strict=strict,
+                drop_nonfinite=drop_nonfinite,
             )
         else:
+            if drop_nonfinite:
+                raise ValueError("`drop_nonfinite` is not supported for multi-target regression.")
+            if offset is not None:
+                raise ValueError("`offset` is not supported for multi-target regression.")
+            if pred_interval and return_pred:
+                raise ValueError("Prediction intervals are not supported for multi-target regression.")
+            if pred_se and return_pred:
+                raise ValueError(
+                    "Prediction standard errors are not supported for multi-target regression."
+                )
             cols = [lr_formula(t).alias(f"target_{i}") for i, t in enumerate(target)]
+            if isinstance(l2_reg, list):
+                if len(l2_reg) != n_targets:
+                    raise ValueError("If `l2_reg` is a list, it must have one value per target.")
+                l2_regs = [float(v) for v in l2_reg]
+                l2_reg = 0.0
+            else:
+                l2_regs = []
             multi_target_lr_kwargs = {
                 "bias": add_bias,
                 "null_policy": null_policy,
                 "solver": solver,
                 "last_target_idx": n_targets,
                 "l2_reg": l2_reg,
+                "l2_regs": l2_regs,
+                "clip": _clip_kwarg(clip),
             }
             cols.extend(lr_formula(z) for z in x)
             if return_pred:
@@ -204,6 +469,8 @@ def lin_reg(
                     pass_name_to_apply=True,
                 ).alias("coeffs")
     else:
+        if isinstance(l2_reg, list):
+            raise ValueError("`l2_reg` can only be a list if this is multi-target.")
         weighted = weights is not None
         lr_kwargs = {
             "bias": add_bias,
@@ -212,7 +479,20 @@ def lin_reg(
             "l2_reg": l2_reg,
             "solver": solver,
             "tol": tol,
+            "stop_criterion": stop_criterion,
             "weighted": weighted,
+            "bias_first": bias_first,
+            "clip": _clip_kwarg(clip),
+            "penalize_bias": penalize_bias,
+            "algorithm": algorithm,
+            "pred_interval": pred_interval and return_pred,
+            "pred_se": pred_se and return_pred,
+            "alpha": alpha,
+            "max_iter": max_iter,
+            "penalty_factor": penalty_factor,
+            "strict": strict,
+            "drop_nonfinite": drop_nonfinite,
+            "has_offset": offset is not None,
         }
 
         if weighted:
@@ -220,6 +500,8 @@ def lin_reg(
         else:
             cols = [lr_formula(target)]
 
+        if offset is not None:
+            cols.append(lr_formula(offset))
         cols.extend(lr_formula(z) for z in x)
 
         if return_pred:
@@ -251,30 +533,2530 @@ def query_lstsq(
     solver: LRSolverMethods = "qr",
     null_policy: NullPolicy = "skip",
 ) -> pl.Expr:
-    warnings.warn(
-        "`query_lstsq` has been renamed to `lin_reg` and will be deprecated in future versions.",
-        DeprecationWarning,
-        stacklevel=2,
-    )
+    warnings.warn(
+        "`query_lstsq` has been renamed to `lin_reg` and will be deprecated in future versions.",
+        DeprecationWarning,
+        stacklevel=2,
+    )
+
+    return lin_reg(
+        *x,
+        target=target,
+        add_bias=add_bias,
+        weights=weights,
+        return_pred=return_pred,
+        l1_reg=l1_reg,
+        l2_reg=l2_reg,
+        tol=tol,
+        solver=solver,
+        null_policy=null_policy,
+    )
+
+
+def lin_reg_predict_path(
+    *x: str | pl.Expr,
+    coefficients: List[List[float]],
+    add_bias: bool = False,
+) -> pl.Expr:
+    """
+    Predicts with many coefficient sets on the same features, e.g. all the coefficients on a Lasso path,
+    or the members of an ensemble. All predictions are computed in one matrix product X * B, where each
+    column of B is a coefficient set. The output is a list column, where the i-th element of each row is
+    the prediction of the i-th coefficient set. Rows with any null feature will be null.
+
+    Parameters
+    ----------
+    x
+        The features, in the same order as the coefficients.
+    coefficients
+        A list of coefficient sets. Each set must have one value per feature, plus the bias as the last
+        value if add_bias is true.
+    add_bias
+        Whether the last value of each coefficient set is the bias.
+    """
+    coeffs = [[float(v) for v in c] for c in coefficients]
+    n_coeffs = len(x) + int(add_bias)
+    if len(coeffs) == 0:
+        raise ValueError("Input `coefficients` cannot be empty.")
+    if any(len(c) != n_coeffs for c in coeffs):
+        raise ValueError(
+            f"Every coefficient set must have {n_coeffs} values (one per feature, plus the bias if add_bias)."
+        )
+
+    return pl_plugin(
+        symbol="pl_predict_path",
+        args=[lr_formula(z) for z in x],
+        kwargs={"coefficients": coeffs, "bias": add_bias},
+        pass_name_to_apply=True,
+    ).alias("predictions")
+
+
+def lin_reg_predict_with_coeffs(
+    *x: str | pl.Expr,
+    coeffs: List[float] | None = None,
+    bias: float = 0.0,
+    model: bytes | Any | None = None,
+) -> pl.Expr:
+    """
+    Predicts with a linear model fit elsewhere, e.g. in another process. The model is given either by its
+    coefficients and bias, or as a fitted `LR`, `ElasticNet` or `OnlineLR` from `polars_ds.linear_models`,
+    or the bytes from their `to_bytes`. Rows with any null feature will be null.
+
+    Parameters
+    ----------
+    x
+        The features, in the same order as the coefficients.
+    coeffs
+        The coefficients, one per feature, without the bias. Ignored if model is given.
+    bias
+        The bias. Ignored if model is given.
+    model
+        A fitted linear model, or the output of its `to_bytes`.
+    """
+    if model is None:
+        if coeffs is None:
+            raise ValueError("One of `coeffs` and `model` must be given.")
+        kwargs = {"coefficients": [float(c) for c in coeffs], "bias": float(bias), "model": None}
+    else:
+        data = model if isinstance(model, (bytes, bytearray)) else model.to_bytes()
+        kwargs = {"coefficients": [], "bias": 0.0, "model": list(data)}
+
+    return pl_plugin(
+        symbol="pl_lstsq_predict_with_coeffs",
+        args=[lr_formula(z) for z in x],
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("prediction")
+
+
+def lin_reg_predict_from_coeffs(
+    *x: str | pl.Expr,
+    coeffs: str | pl.Expr,
+    add_bias: bool = False,
+) -> pl.Expr:
+    """
+    Predicts with coefficients given as a list column, e.g. the output of `lin_reg` on a training group,
+    joined onto another frame or group. If `coeffs` is a single list (e.g. `lin_reg(...)` itself in the same
+    context), it is applied to all rows. Otherwise, each row is predicted with its own list. Rows with any null
+    feature or null coefficients will be null.
+
+    Parameters
+    ----------
+    x
+        The features, in the same order as the coefficients.
+    coeffs
+        The list column of coefficients. Each list must have one value per feature, plus the bias as the last
+        value if add_bias is true.
+    add_bias
+        Whether the last value of each list is the bias. This should match `add_bias` in the fit, unless
+        bias_first was used.
+    """
+    return pl_plugin(
+        symbol="pl_predict_from_coeffs",
+        args=[lr_formula(coeffs)] + [lr_formula(z) for z in x],
+        kwargs={"bias": add_bias},
+        pass_name_to_apply=True,
+    ).alias("prediction")
+
+
+def lin_reg_train_test(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    train: str | pl.Expr,
+    add_bias: bool = False,
+    l1_reg: float = 0.0,
+    l2_reg: float = 0.0,
+    tol: float = 1e-5,
+    solver: LRSolverMethods = "qr",
+    penalize_bias: bool = False,
+    score: Literal["test", "train", "all"] = "test",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Fits the linear regression on the train rows and predicts the other rows, in one expression. The fit is
+    the same as `lin_reg` on the train rows only. The output is a struct with fields `pred` and `resid`, aligned
+    with the original rows. Rows that are not scored (see `score`) get nulls. The residual is null where the
+    target is null, so test rows with an unknown target still get predictions.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    train
+        A boolean column without nulls, true for the train rows. There must be at least one train row.
+    add_bias
+        Whether to add a bias term
+    l1_reg
+        Regularization factor for Lasso.
+    l2_reg
+        Regularization factor for Ridge.
+    tol
+        For Lasso or elastic net regression, if maximum coordinate update is < tol, the algorithm is considered
+        to have converged. If not, it will run for at most 2000 iterations.
+    solver
+        Only applies when this is normal or l2 regression. One of ['svd', 'qr', 'qr_direct'].
+    penalize_bias
+        Only applies to Ridge, Lasso and elastic net regression with add_bias. See `lin_reg`.
+    score
+        Which rows get predictions: 'test' (the rows where train is false), 'train' or 'all'.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. This applies
+        to the train rows for the fit. For the predictions, rows with a null feature get null, unless this is a
+        fill value, in which case the null features are filled.
+    """
+    if score not in ("test", "train", "all"):
+        raise ValueError("Input `score` must be one of 'test', 'train' and 'all'.")
+
+    cols = [lr_formula(train).cast(pl.Boolean), lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "l1_reg": l1_reg,
+        "l2_reg": l2_reg,
+        "tol": tol,
+        "penalize_bias": penalize_bias,
+        "score": score,
+    }
+    return pl_plugin(
+        symbol="pl_lstsq_train_test",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("lr_pred")
+
+
+def lin_reg_predict_grouped(
+    *x: str | pl.Expr,
+    coefficients: List[float],
+    group: str | pl.Expr,
+    weights: str | pl.Expr | None = None,
+    add_bias: bool = False,
+    how: Literal["sum", "mean"] = "sum",
+) -> pl.Expr:
+    """
+    Predicts with the given coefficients and aggregates the predictions per group, e.g. predict per
+    transaction and report per customer. The aggregate is the weighted sum or the weighted mean of the
+    predictions in the group. Since the prediction is linear, the features are aggregated first and the
+    per row predictions are never materialized. The output is a struct with fields `group`, `prediction`
+    and `weight` (the total weight of the group), one row per group, sorted by group.
+
+    Rows with any null feature are skipped. Nulls in the group column form their own group.
+
+    Parameters
+    ----------
+    x
+        The features, in the same order as the coefficients.
+    coefficients
+        One value per feature, plus the bias as the last value if add_bias is true.
+    group
+        The group column
+    weights
+        Non-negative, finite weights without nulls. If None, every row has weight 1.
+    add_bias
+        Whether the last value of coefficients is the bias.
+    how
+        Either 'sum' or 'mean'. The weighted mean of a group with total weight 0 is NaN.
+    """
+    coeffs = [float(v) for v in coefficients]
+    n_coeffs = len(x) + int(add_bias)
+    if len(x) == 0:
+        raise ValueError("At least one feature is required.")
+    if len(coeffs) != n_coeffs:
+        raise ValueError(
+            f"Input `coefficients` must have {n_coeffs} values (one per feature, plus the bias if add_bias)."
+        )
+    if how not in ("sum", "mean"):
+        raise ValueError("Input `how` must be either 'sum' or 'mean'.")
+
+    cols = [lr_formula(group)]
+    if weights is not None:
+        cols.append(lr_formula(weights))
+    cols.extend(lr_formula(z) for z in x)
+    return pl_plugin(
+        symbol="pl_predict_grouped",
+        args=cols,
+        kwargs={
+            "coefficients": coeffs,
+            "bias": add_bias,
+            "weighted": weights is not None,
+            "how": how,
+        },
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("grouped_prediction")
+
+
+def lin_reg_profile(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    l1_reg: float = 0.0,
+    l2_reg: float = 0.0,
+    tol: float = 1e-5,
+    stop_criterion: StopCriterion = "coefficient",
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+    warn_collinearity: float = 1e8,
+    penalize_bias: bool = False,
+    max_iter: int = 2000,
+) -> pl.Expr:
+    """
+    Runs the same linear regression as `lin_reg`, but also returns timing and iteration telemetry
+    of the fit. This is useful when diagnosing why a fit is slow, e.g. whether the time is dominated
+    by forming the XtX matrix or by the solver iterations. The output is a struct with fields
+    `gram_time` (seconds spent forming XtX and XtY), `solve_time` (seconds spent solving), `n_iter`
+    (number of iterations, always 1 for direct solvers), `converged` (whether the iterative solvers
+    converged within `max_iter` iterations, always true for direct solvers), `solver`, `rank`, `cond`, `ill_conditioned`,
+    `n_obs` (number of rows actually used in the fit, after null handling) and `coeffs`. `rank` is the
+    numerical rank of the design (bias column included). If it is less than the number of coefficients,
+    the model is not identified. `cond` is an estimate of the condition number of
+    the design (inf if rank deficient), and `ill_conditioned` is true if it exceeds `warn_collinearity`.
+    Both are as seen by the solver: 'qr_direct' factorizes the design itself, and the other solvers XtX.
+
+    Timing is only done in this expression, so the regular `lin_reg` has no extra overhead.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    l1_reg
+        Regularization factor for Lasso. If this is > 0, coordinate descent will be used.
+    l2_reg
+        Regularization factor for Ridge.
+    tol
+        For Lasso or elastic net regression, if maximum coordinate update is < tol, the algorithm is considered
+        to have converged. If not, it will run for at most max_iter iterations.
+    stop_criterion
+        For Lasso or elastic net regression. One of "coefficient", "objective" or "dual_gap". See `lin_reg`.
+    solver
+        Only applies when this is normal or l2 regression. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    warn_collinearity
+        The condition number above which `ill_conditioned` is true. The default is high enough to not trigger
+        on normal data.
+    penalize_bias
+        Whether to penalize the bias in Ridge, Lasso and elastic net regression. See `lin_reg`.
+    max_iter
+        For Lasso or elastic net regression, the max number of iterations. Must be > 0.
+    """
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    lr_kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "l1_reg": l1_reg,
+        "l2_reg": l2_reg,
+        "solver": solver,
+        "tol": tol,
+        "stop_criterion": stop_criterion,
+        "bias_first": bias_first,
+        "warn_collinearity": warn_collinearity,
+        "penalize_bias": penalize_bias,
+        "max_iter": max_iter,
+    }
+    return pl_plugin(
+        symbol="pl_lstsq_profile",
+        args=cols,
+        kwargs=lr_kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("profile")
+
+
+def lin_reg_ridge_loocv(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    lambdas: List[float],
+    add_bias: bool = False,
+    bias_first: bool = False,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Exact leave-one-out cross validation of Ridge regression over a grid of lambdas. For Ridge, the
+    leave-one-out residual of row i is r_i / (1 - h_ii), where r is the residual of the fit on all rows and
+    h_ii is the leverage of row i. With one SVD of the data, this is evaluated for every lambda without
+    refitting. The output is a struct with fields `best_lambda` (the lambda with the smallest error),
+    `lambdas`, `loo_mse` (the leave-one-out mean squared error for each lambda) and `coeffs` (the
+    coefficients at the best lambda).
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    lambdas
+        The non-negative lambdas (l2 regularization factors) to evaluate.
+    add_bias
+        Whether to add a bias term. The bias is not regularized.
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    lambdas = [float(v) for v in lambdas]
+    if len(lambdas) == 0 or any(not v >= 0.0 for v in lambdas):
+        raise ValueError("Input `lambdas` must be a non-empty list of non-negative numbers.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "lambdas": lambdas,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_ridge_loocv",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("ridge_loocv")
+
+
+def lin_reg_cv(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    lambdas: List[float],
+    l1_ratio: float = 1.0,
+    n_folds: int = 5,
+    folds: str | pl.Expr | None = None,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    seed: int | None = None,
+    tol: float = 1e-5,
+    max_iter: int = 2000,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    K-fold cross validation of Ridge / Lasso / Elastic Net regression over a grid of lambdas. For each lambda,
+    l1_reg = l1_ratio * lambda and l2_reg = (1 - l1_ratio) * lambda, and the fit is the same as `lin_reg` with
+    these regularization factors. Rows are randomly assigned to folds, unless `folds` is given, in which case
+the folds are the distinct values of that integer column. This allows grouped or blocked (time series)
+splits that random shuffling can't express.
+
+    The output is a struct with fields `lambda_min` (the lambda with the smallest mean CV error),
+    `lambda_1se` (the largest lambda whose mean CV error is within one standard error of the smallest),
+    `lambdas`, `cv_mse`, `cv_std` and `cv_se` (mean, sample std and standard error of the fold MSEs for each
+    lambda), `folds` and
+    `fold_mse` (the fold ids, sorted, and the test MSE of each fold at `lambda_min`), and
+    `coeffs_min` and `coeffs_1se` (the coefficients fit on all rows at the two lambdas). The standard error
+    is the sample std of the fold errors divided by sqrt(n_folds). `lambda_1se` gives a more regularized
+    model whose error is not distinguishable from the best, which is the default choice in glmnet.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    lambdas
+        The non-negative regularization strengths to evaluate.
+    l1_ratio
+        The proportion of lambda that goes to the l1 penalty. 1 means Lasso and 0 means Ridge.
+    n_folds
+        The number of folds. Must be >= 2 and <= the number of rows. Ignored if `folds` is given.
+    folds
+        An integer column with the fold of each row. Must have at least 2 distinct values, and the fold
+        ids are reported in `folds`. If given, `seed` and `n_folds` are ignored. Null policy 'ignore' is not
+        supported with this.
+    add_bias
+        Whether to add a bias term. The bias is not regularized.
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    seed
+        Random seed for the fold assignment.
+    tol
+        For Lasso and Elastic Net. The coordinate descent stops if the max absolute change in coefficients is < tol.
+    max_iter
+        For Lasso and Elastic Net. The max number of coordinate descent iterations.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    lambdas = [float(v) for v in lambdas]
+    if len(lambdas) == 0 or any(not v >= 0.0 for v in lambdas):
+        raise ValueError("Input `lambdas` must be a non-empty list of non-negative numbers.")
+    if not 0.0 <= l1_ratio <= 1.0:
+        raise ValueError("Input `l1_ratio` must be in [0, 1].")
+    if folds is None and n_folds < 2:
+        raise ValueError("Input `n_folds` must be >= 2.")
+
+    cols = [] if folds is None else [lr_formula(folds)]
+    cols.append(lr_formula(target))
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "lambdas": lambdas,
+        "l1_ratio": float(l1_ratio),
+        "n_folds": n_folds,
+        "seed": seed,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+        "custom_folds": folds is not None,
+    }
+    return pl_plugin(
+        symbol="pl_lstsq_cv",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("lstsq_cv")
+
+
+def lin_reg_robust(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    loss: Literal["huber", "bisquare"] = "huber",
+    c: float | None = None,
+    estimate_scale: bool = False,
+    tol: float = 1e-6,
+    max_iter: int = 100,
+    stop_criterion: StopCriterion = "coefficient",
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Robust linear regression by Iteratively Reweighted Least Squares (IRLS). Observations with large
+    residuals (relative to the residual scale) are downweighted by the chosen loss. The fit starts from
+    the OLS solution, and the initial scale is the normalized median absolute deviation (MAD) of the OLS
+    residuals. The output is a struct with fields `coeffs`, `scale` (the final residual scale),
+    `n_iter` and `converged`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    loss
+        One of 'huber' or 'bisquare' (Tukey's biweight).
+    c
+        The tuning constant of the loss. If None, 1.345 is used for 'huber' and 4.685 for 'bisquare',
+        which gives 95% efficiency under normal errors.
+    estimate_scale
+        If false, the scale is fixed at the initial MAD estimate. If true, the scale is estimated jointly
+        with the coefficients by Huber's proposal 2, which alternates with the weighted least square
+        steps. This usually gives more reliable weights, because the initial MAD is computed on OLS
+        residuals, which can be distorted by the outliers.
+    tol
+        The iteration stops if the max absolute change in the coefficients (and the scale, if estimated)
+        is < tol.
+    max_iter
+        The max number of IRLS iterations.
+    stop_criterion
+        If "coefficient", use the tol rule above. If "objective", the iteration stops if the relative change
+        in the IRLS objective, sum(s^2 * rho(r / s)) at the current scale s, is < tol.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if loss not in ("huber", "bisquare"):
+        raise ValueError("`loss` must be one of 'huber' or 'bisquare'.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "loss": loss,
+        "c": c,
+        "estimate_scale": estimate_scale,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "stop_criterion": stop_criterion,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_robust_lstsq",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("robust")
+
+
+def lin_reg_mm(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    c: float = 4.685,
+    n_subsamples: int = 500,
+    seed: int | None = None,
+    tol: float = 1e-7,
+    max_iter: int = 200,
+    stop_criterion: StopCriterion = "coefficient",
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Robust linear regression by MM-estimation. Unlike `lin_reg_robust`, which starts from OLS, this starts
+    from an S-estimate with 50% breakdown point, so it resists up to half of the data being outliers,
+    including outliers in x (bad leverage points). The S-estimate is found by the Fast-S algorithm: exact
+    fits on random subsets of rows are refined by a few IRLS steps, and the best few are iterated to
+    convergence. Then, with the residual scale fixed at the S-scale, a bisquare M-estimate is computed by
+    IRLS, which keeps the breakdown point and gives high efficiency under normal errors.
+
+    The output is a struct with fields `coeffs`, `scale` (the S-estimate of the residual scale), `weights`
+    (the final bisquare weight of each row used in the fit, 0 for outliers) and `converged`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    c
+        The bisquare tuning constant of the M step. The default 4.685 gives 95% efficiency under normal errors.
+    n_subsamples
+        The number of random subsets for the S-estimate. More subsets makes it more likely to find the
+        clean fit, at a higher cost.
+    seed
+        The random seed for the subsets. If None, the result may differ between runs.
+    tol
+        The iteration stops if the max absolute change in the coefficients is < tol.
+    max_iter
+        The max number of IRLS iterations, for the S step and the M step each.
+    stop_criterion
+        Only applies to the M step. If "coefficient", use the tol rule above. If "objective", the M step stops
+        if the relative change in the bisquare objective is < tol.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if c <= 0.0:
+        raise ValueError("Input `c` must be positive.")
+    if n_subsamples < 1:
+        raise ValueError("Input `n_subsamples` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "c": c,
+        "n_subsamples": n_subsamples,
+        "seed": seed,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "stop_criterion": stop_criterion,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_mm_regression",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("mm")
+
+
+def lin_reg_ransac(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    n_trials: int = 100,
+    min_samples: int | None = None,
+    residual_threshold: float | None = None,
+    seed: int | None = None,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Linear regression by RANSAC (random sample consensus). In each trial, a random subset of min_samples rows
+    is fit by least squares, and the rows with absolute residual <= residual_threshold under that fit are
+    its inliers. The trial with the most inliers wins (ties go to the smaller sum of squared inlier residuals),
+    and the coefficients are the least square fit on its inliers. This resists a large fraction of outliers,
+    as long as some trial draws only inliers.
+
+    The output is a struct with fields `coeffs`, `inliers` (a list of booleans, one for each input row,
+    which is false for outliers and for rows dropped by the null policy) and `n_inliers`. To fit on
+    the inliers only, explode `inliers` and use it as a filter.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    n_trials
+        The number of random subsets. Must be > 0.
+    min_samples
+        The size of each random subset. If None, the number of features (+1 if add_bias), which is the
+        smallest subset with an exact fit.
+    residual_threshold
+        The max absolute residual of an inlier. If None, the median absolute deviation of the target
+        from its median.
+    seed
+        The random seed for the subsets. If None, the result may differ between runs.
+    solver
+        The least square solver. One of ['svd', 'qr', 'qr_direct', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if n_trials < 1:
+        raise ValueError("Input `n_trials` must be >= 1.")
+    if min_samples is not None and min_samples < 1:
+        raise ValueError("Input `min_samples` must be >= 1.")
+    if residual_threshold is not None and residual_threshold < 0.0:
+        raise ValueError("Input `residual_threshold` must be non-negative.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "n_trials": n_trials,
+        "min_samples": min_samples,
+        "residual_threshold": residual_threshold,
+        "seed": seed,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_ransac_coeffs",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("ransac")
+
+
+def lin_reg_theil_sen(
+    x: str | pl.Expr,
+    target: str | pl.Expr,
+    bias_first: bool = False,
+    max_pairs: int | None = None,
+    seed: int | None = None,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Theil-Sen estimator for a simple regression with one variable. The slope is the median of the slopes
+    (y_j - y_i) / (x_j - x_i) of all pairs of rows with x_i != x_j, and the bias is the median of
+    y_i - slope * x_i. This resists up to ~29% of outliers, without any tuning. The output is the list
+    [slope, bias], or [bias, slope] if bias_first.
+
+    There are n(n-1)/2 pairs, so for large n, set max_pairs to estimate the median from that many random
+    pairs instead.
+
+    Parameters
+    ----------
+    x
+        The variable used to predict target
+    target
+        The target variable
+    bias_first
+        If true, the bias will be the first coefficient instead of the last.
+    max_pairs
+        If not None and smaller than the number of pairs, the slope is the median over this many pairs
+        drawn at random with replacement. Must be > 0.
+    seed
+        The random seed for the pairs. Only used when max_pairs is in effect. If None, the result may
+        differ between runs.
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if max_pairs is not None and max_pairs < 1:
+        raise ValueError("Input `max_pairs` must be >= 1.")
+
+    kwargs = {
+        "null_policy": null_policy,
+        "max_pairs": max_pairs,
+        "seed": seed,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_theil_sen",
+        args=[lr_formula(target), lr_formula(x)],
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("coeffs")
+
+
+def lin_reg_huber(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    delta: float = 1.345,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    tol: float = 1e-6,
+    max_iter: int = 100,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Huber regression by Iteratively Reweighted Least Squares (IRLS). Residuals larger than delta times
+    the residual scale are downweighted, so that a few gross outliers don't pull the fit. The fit starts
+    from the OLS solution, with the normalized median absolute deviation (MAD) of the OLS residuals as the
+    initial scale, and the scale is re-estimated in each iteration by Huber's proposal 2. This is
+    `lin_reg_robust` with loss = 'huber' and estimate_scale = True. Returns the coefficients.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    delta
+        The threshold, in units of the residual scale, above which residuals are downweighted. Must be
+        positive. The default 1.345 gives 95% efficiency under normal errors.
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    tol
+        The iteration stops if the max absolute change in the coefficients and the scale is < tol.
+    max_iter
+        The max number of IRLS iterations. Must be > 0.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if not (delta > 0.0):
+        raise ValueError("Input `delta` must be positive.")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "delta": delta,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_huber_reg",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("coeffs")
+
+
+def lin_reg_quantile(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    tau: float = 0.5,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    tol: float = 1e-8,
+    max_iter: int = 100,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Quantile regression, which models the tau-th conditional quantile of the target by minimizing the pinball
+    loss sum(r * (tau - 1{r < 0})), where r are the residuals. tau = 0.5 is the least absolute deviation (LAD)
+    regression, which models the conditional median. At the solution, about a fraction tau of the residuals
+    are negative. This is solved by the Frisch-Newton interior point method, as in rq.fit.fnb of the R package
+    quantreg, where each step is a weighted least square. Returns the coefficients.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    tau
+        The quantile. Must be in (0, 1).
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    tol
+        The iteration stops if the duality gap is < tol * (1 + |dual objective|).
+    max_iter
+        The max number of interior point iterations. Must be > 0.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if not (0.0 < tau < 1.0):
+        raise ValueError("Input `tau` must be in (0, 1).")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "tau": tau,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_quantile_reg",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("coeffs")
+
+
+def logistic_reg(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    return_pred: bool = False,
+    l2_reg: float = 0.0,
+    tol: float = 1e-8,
+    max_iter: int = 100,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Binary logistic regression by Iteratively Reweighted Least Squares (IRLS), i.e. Newton's method on the
+    negative log likelihood plus l2_reg / 2 * ||b||^2. Each Newton step is a weighted least square.
+    The coefficients are on the log-odds scale. If add_bias is true, the bias is not penalized.
+
+    If the classes are perfectly separated and l2_reg = 0, the maximum likelihood estimate doesn't exist
+    and the coefficients grow without bound. In that case, the iteration stops once all fitted probabilities
+    are within 1e-8 of the labels, and the coefficients are large but finite. Use l2_reg > 0 for a
+    well-defined fit.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable. Must be in [0, 1], typically 0/1 labels. Fractional values are fit as
+        quasi-binomial.
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    return_pred
+        If true, return the predicted probabilities of class 1, with the same number of rows as the input.
+        Rows that are skipped because of nulls get NaN. If false, return the coefficients.
+    l2_reg
+        The L2 regularization factor. Must be >= 0. This is 1 / C in scikit-learn's LogisticRegression.
+    tol
+        The iteration stops if the max absolute change in the coefficients is < tol.
+    max_iter
+        The max number of IRLS iterations. Must be > 0.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if l2_reg < 0.0:
+        raise ValueError("Input `l2_reg` must be >= 0.")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "l2_reg": l2_reg,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+    }
+    if return_pred:
+        return pl_plugin(
+            symbol="pl_logistic_pred",
+            args=cols,
+            kwargs=kwargs,
+            pass_name_to_apply=True,
+        ).alias("prob")
+    else:
+        return pl_plugin(
+            symbol="pl_logistic_coeffs",
+            args=cols,
+            kwargs=kwargs,
+            returns_scalar=True,
+            pass_name_to_apply=True,
+        ).alias("coeffs")
+
+
+def poisson_reg(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    offset: str | pl.Expr | None = None,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    return_pred: bool = False,
+    l2_reg: float = 0.0,
+    tol: float = 1e-8,
+    max_iter: int = 100,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Poisson regression, a GLM with log link for count targets, by Iteratively Reweighted Least Squares
+    (IRLS). The mean of the target is exp(x b + offset). The coefficients are on the log scale, and
+    l2_reg / 2 * ||b||^2 is added to the negative log likelihood. If add_bias is true, the bias is not
+    penalized. The output is a struct with fields `coeffs`, `n_iter` and `converged`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable. Must be non-negative, typically counts.
+    offset
+        If not None, a column that is added to the linear predictor with coefficient 1, and is not estimated.
+        To model rates, e.g. counts per unit of exposure, pass the log of the exposure, so that the mean is
+        exposure * exp(x b).
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    return_pred
+        If true, return the predicted means exp(x b + offset), with the same number of rows as the input.
+        Rows that are skipped because of nulls get NaN. If false, return the coefficients.
+    l2_reg
+        The L2 regularization factor. Must be >= 0.
+    tol
+        The iteration stops if the max absolute change in the coefficients is < tol.
+    max_iter
+        The max number of IRLS iterations. Must be > 0.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns, and this includes the offset.
+    """
+    if l2_reg < 0.0:
+        raise ValueError("Input `l2_reg` must be >= 0.")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+
+    cols = [lr_formula(target)]
+    if offset is not None:
+        cols.append(lr_formula(offset))
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "l2_reg": l2_reg,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+        "has_offset": offset is not None,
+    }
+    if return_pred:
+        return pl_plugin(
+            symbol="pl_poisson_pred",
+            args=cols,
+            kwargs=kwargs,
+            pass_name_to_apply=True,
+        ).alias("pred")
+    else:
+        return pl_plugin(
+            symbol="pl_poisson_coeffs",
+            args=cols,
+            kwargs=kwargs,
+            returns_scalar=True,
+            pass_name_to_apply=True,
+        ).alias("poisson")
+
+
+def glm_reg(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    family: Literal["gaussian", "binomial", "poisson", "gamma"] = "gaussian",
+    link: Literal["identity", "logit", "log", "inverse"] | None = None,
+    weights: str | pl.Expr | None = None,
+    offset: str | pl.Expr | None = None,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    return_pred: bool = False,
+    l2_reg: float = 0.0,
+    tol: float = 1e-8,
+    max_iter: int = 100,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    A generalized linear model with the given family and link, by Iteratively Reweighted Least Squares
+    (IRLS). The mean of the target is g^-1(x b + offset), where g is the link, and its variance is a function of
+    the mean given by the family. The coefficients are on the scale of the link, and l2_reg / 2 * ||b||^2
+    is added to the negative log likelihood. If add_bias is true, the bias is not penalized. The output
+    is a struct with fields `coeffs`, `n_iter` and `converged`.
+
+    With family = 'binomial' and the logit link, this is the same as `logistic_reg`, and with family =
+    'poisson' and the log link, the same as `poisson_reg`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable. Must be in [0, 1] for the binomial family, non-negative for Poisson and
+        positive for Gamma. A fractional target for the binomial family is not rounded. It is fit as
+        quasi-binomial, i.e. with the binomial estimating equations, as for proportions.
+    family
+        The distribution of the target. One of 'gaussian' (constant variance), 'binomial' (variance
+        mu * (1 - mu)), 'poisson' (variance mu) and 'gamma' (variance mu^2).
+    link
+        One of 'identity', 'logit', 'log' and 'inverse'. If None, the canonical link of the family is used,
+        which is 'identity', 'logit', 'log' and 'inverse' respectively.
+    weights
+        If not None, the prior weights of the rows, which must be non-negative. E.g. for the binomial family,
+        the target is a proportion and the weights are the number of trials.
+    offset
+        If not None, a column that is added to the linear predictor with coefficient 1, and is not estimated.
+        E.g. with the Poisson family and the log link, pass the log of the exposure to model rates.
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    return_pred
+        If true, return the predicted means g^-1(x b + offset), with the same number of rows as the input. Rows that
+        are skipped because of nulls get NaN. If false, return the coefficients.
+    l2_reg
+        The L2 regularization factor. Must be >= 0.
+    tol
+        The iteration stops if the max absolute change in the coefficients is < tol.
+    max_iter
+        The max number of IRLS iterations. Must be > 0.
+    solver
+        The solver for the weighted least square steps. One of ['svd', 'qr', 'qr_direct', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns, and this includes the offset. Weights cannot have nulls.
+    """
+    if family not in ("gaussian", "binomial", "poisson", "gamma"):
+        raise ValueError(
+            "Input `family` must be one of 'gaussian', 'binomial', 'poisson' and 'gamma'."
+        )
+    if link is not None and link not in ("identity", "logit", "log", "inverse"):
+        raise ValueError("Input `link` must be one of 'identity', 'logit', 'log' and 'inverse'.")
+    if l2_reg < 0.0:
+        raise ValueError("Input `l2_reg` must be >= 0.")
+    if max_iter <= 0:
+        raise ValueError("Input `max_iter` must be > 0.")
+
+    cols = [] if weights is None else [lr_formula(weights)]
+    cols.append(lr_formula(target))
+    if offset is not None:
+        cols.append(lr_formula(offset))
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "family": family,
+        "link": link,
+        "l2_reg": l2_reg,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+        "weighted": weights is not None,
+        "has_offset": offset is not None,
+    }
+    if return_pred:
+        return pl_plugin(
+            symbol="pl_glm_pred",
+            args=cols,
+            kwargs=kwargs,
+            pass_name_to_apply=True,
+        ).alias("pred")
+    else:
+        return pl_plugin(
+            symbol="pl_glm",
+            args=cols,
+            kwargs=kwargs,
+            returns_scalar=True,
+            pass_name_to_apply=True,
+        ).alias("glm")
+
+
+def lin_reg_ar1(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    prais_winsten: bool = False,
+    tol: float = 1e-6,
+    max_iter: int = 50,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "raise",
+) -> pl.Expr:
+    """
+    Linear regression with AR(1) errors, e_t = rho * e_{t-1} + u_t, by the iterated Cochrane-Orcutt
+    procedure. Starting from OLS, rho is estimated from the lag 1 autocorrelation of the residuals, the
+    target and the features are quasi-differenced (z_t - rho * z_{t-1}) and the model is refit. This is
+    repeated until rho stabilizes. The output is a struct with fields `coeffs`, `rho`, `n_iter` and
+    `converged`.
+
+    The rows must be in time order. The estimated rho is kept inside (-1, 1).
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term. The bias is the intercept of the original (untransformed) model.
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    prais_winsten
+        If true, use the Prais-Winsten transform, which keeps the first observation scaled by
+        sqrt(1 - rho^2). If false, the first observation is dropped (Cochrane-Orcutt). Prais-Winsten is
+        more efficient in small samples.
+    tol
+        The iteration stops if the absolute change in rho is < tol.
+    max_iter
+        The max number of iterations.
+    solver
+        The solver for the least square steps. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns. Dropping rows breaks the time adjacency, so 'raise' is the default here.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "prais_winsten": prais_winsten,
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_cochrane_orcutt",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("ar1")
+
+
+def lin_reg_partial(
+    *controls: str | pl.Expr,
+    target: str | pl.Expr,
+    focal: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Computes the coefficient of the focal predictor by partialling out the controls (Frisch-Waugh-Lovell).
+    Both the target and the focal predictor are regressed on the controls, and the slope of the target
+    residuals on the focal residuals is returned. This equals the coefficient of the focal predictor in the
+    multiple regression of the target on the focal predictor and the controls. The output is a struct with fields
+    `beta` (the partialled slope), `std_err` (same as the standard error in the multiple regression) and
+    `partial_corr` (the partial correlation between the target and the focal predictor).
+
+    Parameters
+    ----------
+    controls
+        The control variables to partial out. Can be empty.
+    target
+        The target variable
+    focal
+        The focal predictor
+    add_bias
+        Whether to add a bias term to the controls
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target), lr_formula(focal)]
+    cols.extend(lr_formula(z) for z in controls)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_partial_regression",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("partial_regression")
+
+
+def lin_reg_residualize(
+    *controls: str | pl.Expr,
+    column: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Regresses `column` on the controls and returns the residuals, i.e. the column with the linear effect of
+    the controls removed. This is the residual maker (annihilator) M = I - Z (ZtZ)^-1 Zt applied to the column,
+    where Z are the controls. It is the building block of partial regression (Frisch-Waugh-Lovell) and
+    control function methods. The output has the same length as the input, and the rows dropped because of
+    nulls are null. The output has the name of `column`.
+
+    Parameters
+    ----------
+    controls
+        The control variables to partial out. Can be empty.
+    column
+        The column to residualize
+    add_bias
+        Whether to add a bias term to the controls. If true, the residuals have mean 0.
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        `column` has null, the rows with nulls will always be dropped. Null-fill only applies to the controls.
+    """
+    cols = [lr_formula(column)]
+    cols.extend(lr_formula(z) for z in controls)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_residualize",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    )
+
+
+def lin_reg_fixed_effects(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    group: str | pl.Expr,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Entity fixed effects regression for panel data. The target and the features are demeaned within each
+    group (the "within" transform) and OLS is run on the demeaned data. This gives the same coefficients
+    as adding one dummy variable per group, without materializing the dummies. There is no bias term, since
+    it is absorbed by the group effects. The output is a struct with fields `features`, `beta`, `std_err`,
+    `t`, `p>|t|`, `within_r2` (the r2 of the demeaned model) and `n_groups`.
+
+    The standard errors use n - n_groups - n_features degrees of freedom, to account for the estimated
+    group means.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    group
+        The group (entity) column. Any hashable type.
+    solver
+        The solver for the least square. One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. When filling,
+        rows with null in the target or the group are still dropped. Null handling is done before the demeaning.
+    """
+    cols = [lr_formula(group), lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_fixed_effects_lstsq",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("fixed_effects")
+
+
+def lin_reg_group_slopes(
+    x: str | pl.Expr,
+    target: str | pl.Expr,
+    group: str | pl.Expr,
+    confidence: float = 0.95,
+    null_policy: Literal["raise", "skip"] = "skip",
+) -> pl.Expr:
+    """
+    Fits a separate bias and slope of x for each group, which is the same as regressing target on the group
+    dummies and their interactions with x, and compares the slopes of every pair of groups by the Tukey-Kramer
+    (Tukey HSD) method. The error variance is pooled over all groups. The output is a struct with fields
+    `group_a`, `group_b`, `diff` (slope of group_b - slope of group_a), `std_err`, `lower`, `upper` (the
+    simultaneous confidence interval of diff) and `p_adj` (the p-value adjusted for all pairwise comparisons),
+    one row per pair. Groups are sorted by value and cast to string in the output.
+
+    Parameters
+    ----------
+    x
+        The variable whose slope is compared
+    target
+        The target variable
+    group
+        The group column. Each group needs at least 2 distinct values of x.
+    confidence
+        The simultaneous confidence level of the intervals. Must be in (0, 1).
+    null_policy
+        Either 'raise' or 'skip'. If 'skip', rows with any null are dropped.
+    """
+    if not (0.0 < confidence < 1.0):
+        raise ValueError("Input `confidence` must be in (0, 1).")
+
+    return pl_plugin(
+        symbol="pl_group_slopes",
+        args=[lr_formula(group), lr_formula(target), lr_formula(x)],
+        kwargs={"null_policy": null_policy, "confidence": confidence},
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("group_slopes")
+
+
+def lin_reg_best_subset(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    max_size: int | None = None,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Exhaustive best subset selection. Every subset of the features, with up to `max_size` features, is fit
+    by least squares, and the best subset by each of AIC, BIC and adjusted R2 is returned. The output is a
+    struct with fields `criterion` ('aic', 'bic' or 'adj_r2'), `features` (the selected feature names, with
+    '__bias__' for the bias), `coeffs` (in the same order as `features`) and `value` (the value of the
+    criterion), one row per criterion. AIC and BIC are the Gaussian ones up to a constant, so only their
+    differences are meaningful. The bias, if any, is in every model.
+
+    There are 2^p subsets, so this only supports up to 20 features. XtX is only computed once.
+
+    Parameters
+    ----------
+    x
+        The candidate features
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    max_size
+        The max number of features in a subset. If None, all subsets are considered.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if len(x) > 20:
+        raise ValueError("Best subset selection supports at most 20 features.")
+    if max_size is not None and max_size < 1:
+        raise ValueError("Input `max_size` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "max_size": max_size,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_best_subset",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("best_subset")
+
+
+def lin_reg_stepwise(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    criterion: Literal["aic", "bic", "adj_r2"] = "aic",
+    max_size: int | None = None,
+    max_vif: float | None = None,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Forward stepwise selection. Starting from the model without features (only the bias, if any), the
+    candidate that improves the criterion the most is added, until no candidate improves it or `max_size`
+    features are selected. The output is a struct with fields `features` (the selected feature names, in the
+    order they were added, with '__bias__' for the bias), `coeffs` (in the same order as `features`), `value`
+    (the value of the criterion) and `rejected` (candidates refused for collinearity, see `max_vif`).
+
+    If `max_vif` is given, before each step, the VIF of every candidate against the already selected features
+    is computed, and candidates with VIF > max_vif are refused for good, since the VIF can only grow as more
+    features are selected. This keeps the selected features from being collinear. XtX is only computed once.
+
+    Parameters
+    ----------
+    x
+        The candidate features
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term. The bias is in every model.
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    criterion
+        One of 'aic', 'bic' and 'adj_r2'. AIC and BIC are the Gaussian ones.
+    max_size
+        The max number of selected features. If None, there is no limit.
+    max_vif
+        If given, candidates whose VIF against the selected features is above this are refused. Common choices
+        are 5 or 10. Must be >= 1.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if criterion not in ("aic", "bic", "adj_r2"):
+        raise ValueError("Input `criterion` must be one of 'aic', 'bic' and 'adj_r2'.")
+    if max_size is not None and max_size < 1:
+        raise ValueError("Input `max_size` must be >= 1.")
+    if max_vif is not None and not max_vif >= 1.0:
+        raise ValueError("Input `max_vif` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "criterion": criterion,
+        "max_size": max_size,
+        "max_vif": None if max_vif is None else float(max_vif),
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_stepwise",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("stepwise")
+
+
+def lin_reg_vif(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = True,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Computes the variance inflation factor (VIF) of each feature, to diagnose multicollinearity. Each feature
+    is regressed on the other features (and the bias), and VIF = 1 / (1 - R2) of that fit, which is the factor
+    by which the variance of its coefficient is inflated by the correlation with the other features. A common
+    rule of thumb is that VIF > 10 signals a problem. A feature that is a linear combination of the others gets
+    VIF = inf. The output is a struct with fields `feature` and `vif`, one row per feature. The bias gets no VIF.
+
+    Parameters
+    ----------
+    x
+        The features
+    target
+        The target variable. It is only used to pick the same rows as `lin_reg` with the same null_policy.
+    add_bias
+        Whether the model has a bias term. If true, as is usual, R2 is centered, so a constant shift of a feature
+        doesn't change its VIF. If false, R2 is 1 - RSS / sum(x^2).
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+    }
+    return pl_plugin(
+        symbol="pl_vif",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("vif")
+
+
+def lin_reg_relative_importance(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Decomposes the R2 of the least square fit by feature, using the LMG (Lindeman, Merenda and Gold)
+    method, which is the Shapley value of each feature when the value of a subset of features is the R2 of
+    the fit on it. The importance of a feature is its average increase in R2 over all orders in which the
+    features can be added, so the importances sum to the R2 of the full model. The output is a struct with
+    fields `feature`, `importance` and `share` (importance / R2), one row per feature.
+
+    If add_bias is true, the bias is in every model and R2 is the usual centered one. Otherwise, R2 is
+    1 - RSS / sum(y^2). There are 2^p subsets, so this only supports up to 20 features.
+
+    Parameters
+    ----------
+    x
+        The features
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if len(x) > 20:
+        raise ValueError("Relative importance supports at most 20 features.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+    }
+    return pl_plugin(
+        symbol="pl_relative_importance",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("relative_importance")
+
+
+def lin_reg_adaptive_lasso(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    l1_reg: float,
+    gamma: float = 1.0,
+    l2_init: float = 0.01,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    tol: float = 1e-5,
+    max_iter: int = 2000,
+    null_policy: NullPolicy = "raise",
+    strict: bool = False,
+) -> pl.Expr:
+    """
+    Adaptive Lasso (Zou, 2006). This first fits a Ridge regression with `l2_init` to get initial
+    estimates b_init, and then runs Lasso where the L1 penalty of each coefficient is weighted by
+    1 / |b_init|^`gamma`. Features with near-zero initial estimates are penalized heavily, and those with
+    large ones lightly, which gives more consistent variable selection than plain Lasso. The bias, if any,
+    is not penalized. The output is the list of coefficients, like `lin_reg`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    l1_reg
+        The L1 regularization factor of the Lasso step. Must be >= 0.
+    gamma
+        The power of the initial estimates in the penalty weights. Must be > 0.
+    l2_init
+        The L2 regularization factor of the initial Ridge fit. If 0, the initial fit is OLS.
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    tol
+        For the Lasso step. Stops when the max absolute change in the coefficients is < tol.
+    max_iter
+        Max number of iterations of the Lasso step.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    strict
+        If true, raise an error when the Lasso step does not converge within `max_iter` iterations.
+        Otherwise, the last iterate is returned.
+    """
+    if l1_reg < 0:
+        raise ValueError("Input `l1_reg` must be >= 0.")
+    if gamma <= 0:
+        raise ValueError("Input `gamma` must be > 0.")
+    if max_iter < 1:
+        raise ValueError("Input `max_iter` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "l1_reg": float(l1_reg),
+        "gamma": float(gamma),
+        "l2_init": abs(l2_init),
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "bias_first": bias_first,
+        "strict": strict,
+    }
+    return pl_plugin(
+        symbol="pl_adaptive_lasso",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("coeffs")
+
+
+def lin_reg_generalized_ridge(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    penalty: Any,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "raise",
+) -> pl.Expr:
+    """
+    Generalized (Tikhonov) ridge regression, which minimizes ||y - Xb||^2 + ||Gamma b||^2 for a penalty
+    matrix Gamma. The coefficients are (XtX + Gamma^T Gamma)^-1 XtY. With Gamma = sqrt(l2_reg) * I, this is the
+    usual Ridge regression. With a difference operator, e.g. the second difference with rows
+    [..., 1, -2, 1, ...], it penalizes the roughness of the coefficients, which is a smoothing prior for
+    ordered features such as lags. The bias, if any, is not penalized. The output is the list of coefficients,
+    like `lin_reg`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    penalty
+        The penalty matrix Gamma, as a 2D NumPy array or a list of rows. It must have one column per
+        feature, not counting the bias, and any number of rows.
+    add_bias
+        Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    solver
+        One of ['svd', 'qr', 'cholesky'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    rows = penalty.tolist() if hasattr(penalty, "tolist") else [list(r) for r in penalty]
+    if len(rows) == 0 or not all(isinstance(r, list) for r in rows):
+        raise ValueError("Input `penalty` must be a non-empty 2D matrix.")
+    ncols = len(rows[0])
+    if ncols == 0 or any(len(r) != ncols for r in rows):
+        raise ValueError("All rows of `penalty` must have the same, non-zero length.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "gamma": [float(v) for r in rows for v in r],
+        "gamma_ncols": ncols,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_generalized_ridge",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("coeffs")
+
+
+def lin_reg_lasso_path(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    lambdas: List[float],
+    l2_reg: float = 0.0,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    tol: float = 1e-5,
+    max_iter: int = 2000,
+    stop_criterion: StopCriterion = "coefficient",
+    null_policy: NullPolicy = "skip",
+    strict: bool = False,
+) -> pl.Expr:
+    """
+    Fits Lasso (or elastic net, if l2_reg > 0) by coordinate descent at every l1 penalty in `lambdas`, for
+    model selection. The lambdas are visited from the largest to the smallest, and each fit starts from the
+    solution at the previous lambda (warm start), which is much faster than fitting each lambda from scratch.
+    The output is a list with one list of coefficients per lambda, in the order of `lambdas`. Each list of
+    coefficients is like the output of `lin_reg` with the same l1_reg. The predictions of the whole path can be
+    computed with `lin_reg_predict_path`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    lambdas
+        The l1 regularization factors. Must be non-empty and non-negative.
+    l2_reg
+        The l2 regularization factor, which is the same for every lambda.
+    add_bias
+        Whether to add a bias term. The bias is not penalized.
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
+    tol
+        The tolerance of each fit. See `stop_criterion`.
+    max_iter
+        Max number of iterations of each fit.
+    stop_criterion
+        If "coefficient", stop when the maximum coordinate update is < tol. If "objective", stop when the
+        relative change in the objective is < tol. If "dual_gap", stop when the duality gap is < tol.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    strict
+        If true, raise an error when any fit does not converge within `max_iter` iterations.
+    """
+    lambdas = [float(v) for v in lambdas]
+    if len(lambdas) == 0 or any(not (v >= 0.0) for v in lambdas):
+        raise ValueError("Input `lambdas` must be non-empty and non-negative.")
+    if max_iter < 1:
+        raise ValueError("Input `max_iter` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "lambdas": lambdas,
+        "l2_reg": float(l2_reg),
+        "tol": abs(tol),
+        "max_iter": max_iter,
+        "stop_criterion": stop_criterion,
+        "bias_first": bias_first,
+        "strict": strict,
+    }
+    return pl_plugin(
+        symbol="pl_lasso_path",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("lasso_path")
+
+
+def lin_reg_residual_acf(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    lag: int = 10,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Computes the autocorrelation function (ACF) of the OLS residuals at lags 1 to `lag`. The residuals are
+    demeaned first. This is a quick check for autocorrelated errors in time series regressions, where the
+    classical standard errors are wrong (see `std_err="hac"` in `lin_reg_report`). The output is a struct with
+    fields `acf` (a list of the autocorrelations) and `bound` (2 / sqrt(n), the approximate 95% bound under no
+    autocorrelation).
+
+    The residuals are assumed to be in time order, so the data must be sorted by time. Rows dropped because of
+    nulls (e.g. with null_policy = "skip") are simply left out, so the lags are counted over the remaining rows,
+    and a dropped row in the middle shifts all the later rows by one lag. If that matters, fill the nulls first.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    lag
+        The max lag. Must be >= 1.
+    add_bias
+        Whether to add a bias term
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if lag < 1:
+        raise ValueError("`lag` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "lag": lag,
+    }
+    return pl_plugin(
+        symbol="pl_residual_acf",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("residual_acf")
+
+
+def lin_reg_residual_pacf(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    lag: int = 10,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Computes the partial autocorrelation function (PACF) of the OLS residuals at lags 1 to `lag`, by the
+    Durbin-Levinson recursion. This helps identify the order of autocorrelation in the errors, e.g. AR(1)
+    vs AR(2). The residuals are assumed to be in time order. The output is a struct with fields `pacf` (a
+    list of the PACF values) and `bound` (2 / sqrt(n), the approximate 95% bound under no autocorrelation).
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    lag
+        The max lag. Must be >= 1.
+    add_bias
+        Whether to add a bias term
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if lag < 1:
+        raise ValueError("`lag` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "lag": lag,
+    }
+    return pl_plugin(
+        symbol="pl_residual_pacf",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("residual_pacf")
+
+
+def lin_reg_white_test(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    White's test for heteroskedasticity. The squared OLS residuals are regressed on the regressors, their
+    squares and their cross-products (with a constant), and the LM statistic n * R^2 of this auxiliary
+    regression is chi-square distributed under homoskedasticity. Compared to the Breusch-Pagan test, this
+    doesn't assume a functional form for the heteroskedasticity. The output is a struct with fields
+    `statistic`, `dof` and `pvalue`.
+
+    Note: the number of auxiliary regressors grows quadratically in the number of variables.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term to the original regression. The auxiliary regression always has one.
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_white_test",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("white_test")
+
+
+def lin_reg_breusch_pagan(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Breusch-Pagan test for heteroskedasticity, in Koenker's studentized form. The squared OLS residuals are
+    regressed on the regressors (with a constant), and the LM statistic n * R^2 of this auxiliary regression
+    is chi-square distributed with k degrees of freedom under homoskedasticity, where k is the number of
+    variables. This only detects error variances that change linearly with the variables. See
+    `lin_reg_white_test` for a test without this assumption. The output is a struct with fields
+    `statistic`, `dof` and `pvalue`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term to the original regression. The auxiliary regression always has one.
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_breusch_pagan",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("breusch_pagan")
+
+
+def lin_reg_chow_test(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    split: int | str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Chow test for a structural break at a known point. The model is fit on all rows (pooled), and separately
+    on the rows before and after the break. With k the number of coefficients (bias included) and n the
+    number of rows, the statistic is F = ((RSS_pooled - RSS_before - RSS_after) / k) / ((RSS_before + RSS_after)
+    / (n - 2k)), which follows F(k, n - 2k) under the null of no break. The output is a struct with fields
+    `statistic`, `df1` (k), `df2` (n - 2k) and `pvalue`. Each part must have at least k rows. The test assumes
+    the same error variance before and after the break.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    split
+        Where the break is. If an int, the rows with index >= `split` are after the break. Otherwise, a boolean
+        condition (a str in SQL syntax, or an expression) that is true on the rows after the break. The index and
+        the condition refer to the rows of the input, before rows with nulls are dropped.
+    add_bias
+        Whether to add a bias term
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if isinstance(split, int):
+        if split < 1:
+            raise ValueError("Input `split` must be >= 1 if it is an index.")
+        after = pl.int_range(pl.len()) >= split
+    else:
+        after = lr_formula(split).cast(pl.Boolean)
+
+    cols = [after, lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_chow_test",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("chow_test")
+
+
+def lin_reg_influence(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Row level influence diagnostics of an OLS fit. The output is a struct with fields `leverage` (the diagonal
+    of the hat matrix, h_ii = x_i^T (XtX)^-1 x_i), `studentized_resid` (the internally studentized residual,
+    r_i = e_i / (s * sqrt(1 - h_ii)) with s^2 = SSR / (n - p)) and `cooks_distance`
+    (D_i = e_i^2 / (p * s^2) * h_ii / (1 - h_ii)^2), one row per input row. Here p is the number of coefficients,
+    including the bias. A common rule of thumb flags a row as influential when D_i > 4 / n.
+
+    Rows with a leverage of 1 (a row that alone determines a coefficient) have an infinite Cook's distance
+    and a NaN studentized residual. Rows dropped because of nulls (e.g. with null_policy = "skip") are null.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_influence",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("influence")
+
+
+def lin_reg_group_influence(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    group: str | pl.Expr,
+    add_bias: bool = False,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Influence of groups of rows (clusters) on an OLS fit, which helps detect a whole entity (a firm, a region)
+    that drives the fit. For each row, the leverage h_ii and DFFITS_i = t_i * sqrt(h_ii / (1 - h_ii)) are
+    computed, where t_i is the externally studentized residual, and they are aggregated by group. The output
+    is a struct with fields `group`, `n` (number of rows), `leverage` (sum of the leverages), `dffits_abs_sum`
+    (sum of |DFFITS|), `dffits_norm` (l2 norm of the DFFITS) and `dffits_max_abs` (max |DFFITS|), one row per
+    group, sorted by group. A common rule of thumb flags a row as influential when |DFFITS| > 2 * sqrt(p / n).
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    group
+        The group column
+    add_bias
+        Whether to add a bias term
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns. Nulls in the group column form their own group.
+    """
+    cols = [lr_formula(group), lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+    }
+    return pl_plugin(
+        symbol="pl_group_influence",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("group_influence")
+
+
+def lin_reg_driscoll_kraay(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    time: str | pl.Expr,
+    panel: str | pl.Expr,
+    add_bias: bool = False,
+    lags: int | None = None,
+    solver: LRSolverMethods = "qr",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Pooled OLS on panel data with Driscoll-Kraay standard errors, which are robust to heteroskedasticity,
+    serial correlation and cross-sectional correlation (e.g. common shocks hitting all entities at the same
+    time). The moment conditions x_i * e_i are summed over all entities in each time period, and the
+    Newey-West (Bartlett kernel) long run covariance of these period sums, with the given number of lags,
+    is used in the sandwich (XtX)^-1 S (XtX)^-1. This is consistent as the number of time periods grows, so
+    it needs a reasonably long panel. There is no small sample correction.
+
+    The output is a struct with fields `features`, `beta`, `dk_se`, `t`, `p>|t|` and `cov` (the row of the
+    covariance matrix for this coefficient, in the same order as `features`), one row per coefficient. The
+    p values use a t distribution with #periods - 1 degrees of freedom.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    time
+        The time period of each row. Periods are ordered by their values and are assumed to be evenly
+        spaced. There must be at least 2 periods.
+    panel
+        The entity of each row. Each (panel, time) pair must appear at most once. The panel is not used
+        otherwise, and the panel may be unbalanced.
+    add_bias
+        Whether to add a bias term
+    lags
+        The max lag of the Newey-West estimator, which is the number of periods over which the errors can be
+        serially correlated. If None, floor(4 * (T / 100)^(2/9)) is used, where T is the number of periods.
+    solver
+        One of ['svd', 'qr', 'qr_direct'].
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns. The time and panel columns must not have nulls in the rows that are used.
+    """
+    if lags is not None and lags < 0:
+        raise ValueError("Input `lags` must be >= 0.")
+
+    cols = [lr_formula(time), lr_formula(panel), lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "solver": solver,
+        "lags": lags,
+    }
+    return pl_plugin(
+        symbol="pl_driscoll_kraay",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("driscoll_kraay")
+
+
+def lin_reg_conditioning(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Reports the condition number (largest over smallest singular value) of the features, before and after
+    a bias column is appended. If the features are far from 0 relative to their spread, the constant column
+    is nearly a combination of them, and the condition number with the bias can be orders of magnitude
+    larger than without. Ill-conditioned designs make coefficients unstable, and make Ridge penalties act
+    very unevenly. The output is a struct with fields `cond_without_bias`, `cond_with_bias` and `cond_centered`,
+    the last being the condition number with the bias after centering the features. If `cond_with_bias` is much
+    larger than `cond_centered`, you should center the features. Centering doesn't change the other coefficients
+    of an unregularized fit, only the bias. The condition number is infinite when the design is rank deficient.
+
+    Parameters
+    ----------
+    x
+        The features. Don't include a bias column.
+    target
+        The target variable. It is only used to pick the same rows as `lin_reg` with the same null_policy.
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    return pl_plugin(
+        symbol="pl_conditioning_report",
+        args=cols,
+        kwargs={"null_policy": null_policy},
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("conditioning")
+
+
+def lin_reg_matrix_rank(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    rtol: float | None = None,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Reports the numerical rank of the design X from its column pivoted QR decomposition X P = Q R. A diagonal
+    entry of R is treated as 0 if |R_ii| <= rtol * |R_00|. If the rank is less than the number of columns, some
+    features are (numerically) linear combinations of the others, e.g. a duplicated column, and the coefficients
+    of a linear regression are not identified. The output is a struct with fields `rank`, `n_cols` (the number of
+    columns, the bias included) and `note`, which explains the rank deficiency and is null if X has full rank.
+
+    Parameters
+    ----------
+    x
+        The features
+    target
+        The target variable. It is only used to pick the same rows as `lin_reg` with the same null_policy.
+    add_bias
+        Whether to add a bias column
+    rtol
+        The relative tolerance. If None, it is max(n_rows, n_cols) * machine epsilon. Must be >= 0.
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if rtol is not None and rtol < 0:
+        raise ValueError("Input `rtol` must be >= 0.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "rtol": None if rtol is None else float(rtol),
+    }
+    return pl_plugin(
+        symbol="pl_matrix_rank",
+        args=cols,
+        kwargs=kwargs,
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("matrix_rank")
+
+
+def lin_reg_xtx_eigenvalues(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Returns the eigenvalues d_i of XtX in decreasing order, as a list. They are computed as the squared
+    singular values of X, so XtX is never formed. Ridge with penalty lambda shrinks the component of the
+    coefficients along the i-th eigenvector by d_i / (d_i + lambda), so directions with eigenvalues much
+    smaller than lambda are shrunk almost to 0, and directions with eigenvalues much larger are barely
+    touched. Eigenvalues close to 0 mean the features are nearly collinear.
+
+    Parameters
+    ----------
+    x
+        The features
+    target
+        The target variable. It is only used to decide which rows are dropped because of nulls.
+    add_bias
+        Whether to add a bias column to X. Note that the bias is not penalized by Ridge in this package.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    return pl_plugin(
+        symbol="pl_xtx_eigenvalues",
+        args=cols,
+        kwargs={"bias": add_bias, "null_policy": null_policy},
+        returns_scalar=True,
+        pass_name_to_apply=True,
+    ).alias("eigenvalues")
+
+
+def lin_reg_pred_interval(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    l2_reg: float = 0.0,
+    confidence: float = 0.95,
+    interval: Literal["prediction", "confidence"] = "prediction",
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Fits OLS, or Ridge if `l2_reg` > 0, and returns the in-sample predictions with per-row variances and
+    intervals. With A = XtX + l2_reg * I (no penalty on the bias), the coefficients have covariance
+    s^2 A^-1 XtX A^-1, so the variance of the fitted mean at x_i is `fit_var` = s^2 x_i A^-1 XtX A^-1 x_i^t,
+    and that of the prediction error of a new observation is `pred_var` = `fit_var` + s^2. The residual
+    variance s^2 is RSS / (n - trace(H)), where trace(H) is the effective degrees of freedom of the ridge fit,
+    which is also the degrees of freedom of the t quantile used by the intervals. For OLS these are the usual
+    formulas. Note that the ridge intervals don't account for the shrinkage bias of the fitted values.
 
-    return lin_reg(
-        *x,
-        target=target,
-        add_bias=add_bias,
-        weights=weights,
-        return_pred=return_pred,
-        l1_reg=l1_reg,
-        l2_reg=l2_reg,
-        tol=tol,
-        solver=solver,
-        null_policy=null_policy,
-    )
+    The output is a struct with fields `prediction`, `fit_var`, `pred_var`, `lower` and `upper`, with the same
+    length as the input. Rows dropped because of nulls are null.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term
+    l2_reg
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed.
+    confidence
+        The confidence level of the intervals. Must be in (0, 1).
+    interval
+        'prediction' for intervals of a new observation, using `pred_var`, or 'confidence' for intervals of the
+        mean, using `fit_var`.
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if not 0.0 < confidence < 1.0:
+        raise ValueError("Input `confidence` must be in (0, 1).")
+    if interval not in ("prediction", "confidence"):
+        raise ValueError("Input `interval` must be one of ['prediction', 'confidence'].")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "l2_reg": abs(l2_reg),
+        "confidence": confidence,
+        "interval": interval,
+    }
+    return pl_plugin(
+        symbol="pl_lstsq_pred_interval",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("pred_interval")
 
 
 def lin_reg_w_rcond(
     *x: str | pl.Expr,
     target: str | pl.Expr,
     add_bias: bool = False,
+    bias_first: bool = False,
     rcond: float = 0.0,
     l2_reg: float = 0.0,
     null_policy: NullPolicy = "raise",
@@ -294,6 +3076,8 @@ def lin_reg_w_rcond(
         The target variable
     add_bias
         Whether to add a bias term
+    bias_first
+        If true and add_bias is true, the bias will be the first coefficient instead of the last.
     rcond
         Cut-off ratio for small singular values. If rcond < machine precision * MAX(M,N),
         it will be set to machine precision * MAX(M,N).
@@ -315,6 +3099,7 @@ def lin_reg_w_rcond(
         "l2_reg": l2_reg,
         "solver": "",
         "tol": abs(rcond),
+        "bias_first": bias_first,
     }
     return pl_plugin(
         symbol=_lin_reg_expr_symbol("pl_lstsq_w_rcond"),
@@ -350,12 +3135,22 @@ def recursive_lin_reg(
     add_bias: bool = False,
     l2_reg: float = 0.0,
     null_policy: NullPolicy = "raise",
+    clip: Tuple[float, float] | None = None,
+    with_std_err: bool = False,
+    horizon: int = 0,
 ) -> pl.Expr:
     """
     Using the first `start_with` rows of data as basis, start computing the least square solutions
     by updating the betas per row. A prediction for that row will also be included in the output.
     This uses the famous Sherman-Morrison-Woodbury Formula under the hood.
 
+    If `with_std_err` is true, the output also has the fields `std_err` and `t`, which are lists aligned
+    with `coeffs`. The residual variance behind them is tracked recursively, as SSR / (#rows so far -
+    #features), where each new row adds its squared one-step-ahead prediction error, scaled by the
+    leverage. For OLS this matches a full refit on the rows so far, and for Ridge it is an approximation.
+    Early in the sequence, with few degrees of freedom, the estimates are noisy, and they are NaN until
+    there are more rows than features. This is always computed in f64.
+
     Note: You have to be careful about the order of data when using this in aggregation contexts.
 
     In the author's opinion, this should be called "cumulative" instead of resursive because of
@@ -375,7 +3170,8 @@ def recursive_lin_reg(
     add_bias
         Whether to add a bias term
     l2_reg
-        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed.
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed. The bias
+        is not penalized, same as in `lin_reg`.
     null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
         One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
         fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
@@ -383,10 +3179,24 @@ def recursive_lin_reg(
         columns. If null_policy is `skip` or `fill`, and nulls actually exist, it will keep skipping until we have
         scanned `start_at` many valid rows. And if subsequently we get a row with null values, then null will
         be returned for that row.
+    clip
+        If not None, a (lower, upper) tuple and predictions will be clamped into this range. Clipping happens
+        after the bias is added. NaN predictions are not clamped.
+    with_std_err
+        Whether to also output the standard errors and t values of the coefficients at every row.
+    horizon
+        Must be >= 0. The coefficients in row t are always fit on the rows up to and including t. The
+        prediction in row t applies them to the features in row t + `horizon`, so it is the `horizon`-step-ahead
+        forecast of the target in row t + `horizon`, made with the data available at t. The default 0 gives
+        the in-sample prediction for row t. The prediction is null when row t + `horizon` is past the end of
+        the data, or when that row was dropped because of nulls. Note that the forecast for row s lives in
+        row s - `horizon`, so shift the prediction by `horizon` to line it up with the target.
     """
 
     if start_with < 1:
         raise ValueError("You must start with >= 1 rows for recursive lstsq.")
+    if horizon < 0:
+        raise ValueError("Input `horizon` must be >= 0.")
 
     cols = [lr_formula(target)]
     features = [lr_formula(z) for z in x]
@@ -402,9 +3212,101 @@ def recursive_lin_reg(
         "bias": add_bias,
         "lambda": abs(l2_reg),
         "min_size": 0,  # Not used for recursive
+        "clip": _clip_kwarg(clip),
+        "horizon": horizon,
+    }
+    if with_std_err:
+        symbol = "pl_recursive_lstsq_inference"
+    else:
+        symbol = _lin_reg_expr_symbol("pl_recursive_lstsq")
+    return pl_plugin(
+        symbol=symbol,
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    )
+
+
+def lin_reg_ewls(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    start_with: int,
+    forget: float | None = None,
+    halflife: float | None = None,
+    add_bias: bool = False,
+    l2_reg: float = 0.0,
+    null_policy: NullPolicy = "raise",
+    clip: Tuple[float, float] | None = None,
+    horizon: int = 0,
+) -> pl.Expr:
+    """
+    Exponentially weighted recursive least squares (EWLS). Same as `recursive_lin_reg`, but before each
+    new row is added, all previous rows are down-weighted by the forgetting factor, so the coefficients
+    in row t minimize sum_k forget^(t - k) * (y_k - x_k b)^2 and track changes in the relationship. The
+    effective memory is about 1 / (1 - forget) rows. The first `start_with` rows, which give the initial
+    fit, share the same weight. The output has the same fields as `recursive_lin_reg`.
+
+    Note: You have to be careful about the order of data when using this in aggregation contexts.
+
+    Parameters
+    ----------
+    x:
+        The variables used to predict target
+    target:
+        The target variable
+    start_with:
+        Must be >= 1. You `start_with` n rows of data to train the first linear regression. If `start_with` = N,
+        the first N-1 rows will be null.
+    forget
+        The forgetting factor, in (0, 1]. 1 means no forgetting, which is the same as `recursive_lin_reg`.
+        Exactly one of `forget` and `halflife` must be given.
+    halflife
+        Must be > 0. The number of rows after which the weight of a row is halved, i.e.
+        forget = 0.5 ** (1 / halflife).
+    add_bias
+        Whether to add a bias term
+    l2_reg
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed. The bias is not
+        penalized. The penalty decays with the rows, so it matters less and less as the series goes on.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns. Rows that are dropped don't count towards the decay.
+    clip
+        If not None, a (lower, upper) tuple and predictions will be clamped into this range. Clipping happens
+        after the bias is added. NaN predictions are not clamped.
+    horizon
+        Must be >= 0. The prediction in row t applies the coefficients of row t to the features in row
+        t + `horizon`. See `recursive_lin_reg`.
+    """
+    if (forget is None) == (halflife is None):
+        raise ValueError("Exactly one of `forget` and `halflife` must be given.")
+    if halflife is not None:
+        if not (halflife > 0.0):
+            raise ValueError("Input `halflife` must be > 0.")
+        forget = 0.5 ** (1.0 / halflife)
+    if not (0.0 < forget <= 1.0):
+        raise ValueError("Input `forget` must be in (0, 1].")
+    if start_with < 1:
+        raise ValueError("You must start with >= 1 rows for recursive lstsq.")
+    if horizon < 0:
+        raise ValueError("Input `horizon` must be >= 0.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "null_policy": null_policy,
+        "n": start_with,
+        "bias": add_bias,
+        "lambda": abs(l2_reg),
+        "min_size": 0,  # Not used for recursive
+        "clip": _clip_kwarg(clip),
+        "horizon": horizon,
+        "forget": forget,
     }
     return pl_plugin(
-        symbol=_lin_reg_expr_symbol("pl_recursive_lstsq"),
+        symbol="pl_ewls",
         args=cols,
         kwargs=kwargs,
         pass_name_to_apply=True,
@@ -435,6 +3337,129 @@ def query_recursive_lstsq(
     )
 
 
+def lin_reg_recursive_residuals(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    start_with: int | None = None,
+    add_bias: bool = False,
+    null_policy: NullPolicy = "skip",
+) -> pl.Expr:
+    """
+    Recursive residuals and their CUSUM, for the Brown-Durbin-Evans test of parameter stability. This doesn't
+    need the break point to be known. The OLS fit on the first `start_with` rows is updated one row at a time,
+    like in `recursive_lin_reg`, and the recursive residual of row t is the one-step-ahead prediction error of
+    the fit on the rows before t, divided by sqrt(1 + x_t (XtX)^-1 x_t^t). Under a stable model with iid normal
+    errors, the recursive residuals are iid N(0, s^2). The CUSUM is their cumulative sum divided by s, where
+    s^2 is their mean square, and the model is rejected at the 5% level if it ever crosses +/- `cusum_bound`,
+    which is 0.948 * (sqrt(m) + 2 * r / sqrt(m)) at the r-th of the m recursive residuals.
+
+    The output is a struct with fields `recursive_resid`, `cusum` and `cusum_bound`, with the same length as the
+    input. The first `start_with` rows only fit the initial model and are null, and so are the rows dropped
+    because of nulls. The rows are assumed to be in time order.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    start_with
+        The number of rows of the initial fit. Must be >= the number of coefficients, which is the default.
+    add_bias
+        Whether to add a bias term
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    n_coeffs = len(x) + int(add_bias)
+    if start_with is None:
+        start_with = n_coeffs
+    if start_with < max(n_coeffs, 1):
+        raise ValueError("Input `start_with` must be >= the number of coefficients.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "null_policy": null_policy,
+        "n": start_with,
+        "bias": add_bias,
+        "lambda": 0.0,
+        "min_size": 0,  # Not used
+    }
+    return pl_plugin(
+        symbol="pl_recursive_residuals",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("recursive_residuals")
+
+
+def lin_reg_expanding(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    start_with: int,
+    add_bias: bool = False,
+    l2_reg: float = 0.0,
+    null_policy: NullPolicy = "skip",
+    clip: Tuple[float, float] | None = None,
+) -> pl.Expr:
+    """
+    Expanding window (walk forward) out of sample predictions, for backtesting. For each row t after the first
+    `start_with` rows, the model is fit on all rows before t and predicts row t. Like in `recursive_lin_reg`,
+    the fit is updated one row at a time by the Sherman-Morrison-Woodbury formula instead of refitting, so
+    each row costs O(#features^2).
+
+    The output is a struct with fields `prediction` and `resid`, which is target - prediction, with the same
+    length as the input. The first `start_with` rows only fit the initial model and are null, and so are the
+    rows dropped because of nulls. The out of sample R^2 is 1 - sum(resid^2) / sum((target - mean)^2) over the
+    non-null rows. The rows are assumed to be in time order.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    start_with
+        The number of rows of the initial fit. Must be >= 1. If it is < the number of features, the early
+        predictions will be numerically unstable.
+    add_bias
+        Whether to add a bias term
+    l2_reg
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed. The bias
+        is not penalized, same as in `lin_reg`.
+    null_policy: Literal['raise', 'skip', 'zero', 'one']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    clip
+        If not None, a (lower, upper) tuple and predictions will be clamped into this range. The residuals are
+        computed from the clamped predictions.
+    """
+    if start_with < 1:
+        raise ValueError("Input `start_with` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "null_policy": null_policy,
+        "n": start_with,
+        "bias": add_bias,
+        "lambda": abs(l2_reg),
+        "min_size": 0,  # Not used
+        "clip": _clip_kwarg(clip),
+    }
+    return pl_plugin(
+        symbol="pl_expanding_lstsq",
+        args=cols,
+        kwargs=kwargs,
+        pass_name_to_apply=True,
+    ).alias("expanding")
+
+
 def rolling_lin_reg(
     *x: str | pl.Expr,
     target: str | pl.Expr,
@@ -443,6 +3468,7 @@ def rolling_lin_reg(
     l2_reg: float = 0.0,
     min_valid_rows: int | None = None,
     null_policy: NullPolicy = "raise",
+    clip: Tuple[float, float] | None = None,
 ) -> pl.Expr:
     """
     Using every `window_size` rows of data as feature matrix, and computes least square solutions
@@ -463,7 +3489,8 @@ def rolling_lin_reg(
     add_bias
         Whether to add a bias term
     l2_reg
-        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed.
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed. The bias
+        is not penalized, same as in `lin_reg`.
     min_valid_rows
         Minimum number of valid rows to evaluate the model. This is only used when null policy is `skip`. E.g.
         if there are nulls in the windows, the window must have at least `min_valid_rows` valid rows in order to
@@ -473,6 +3500,9 @@ def rolling_lin_reg(
         fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: For
         rolling lstsq, null-fill only works when target doesn't have nulls, and WILL NOT drop rows where the
         target is null.
+    clip
+        If not None, a (lower, upper) tuple and predictions will be clamped into this range. Clipping happens
+        after the bias is added. NaN predictions are not clamped.
     """
 
     if window_size < 2:
@@ -500,6 +3530,7 @@ def rolling_lin_reg(
         "bias": add_bias,
         "lambda": abs(l2_reg),
         "min_size": min_size,
+        "clip": _clip_kwarg(clip),
     }
     return pl_plugin(
         symbol=_lin_reg_expr_symbol("pl_rolling_lstsq"),
@@ -539,9 +3570,14 @@ def lin_reg_report(
     *x: str | pl.Expr,
     target: str | pl.Expr,
     weights: str | pl.Expr | None = None,
+    weight_type: Literal["analytic", "frequency"] = "analytic",
     add_bias: bool = False,
+    bias_first: bool = False,
     null_policy: NullPolicy = "raise",
-    std_err: Literal["se", "hc0", "hc1", "hc2", "hc3"] = "se",
+    std_err: Literal["se", "classical", "hc0", "hc1", "hc2", "hc3", "hac"] = "se",
+    l2_reg: float = 0.0,
+    alpha: float = 0.05,
+    hac_lags: int | None = None,
 ) -> pl.Expr:
     """
     Creates an ordinary least square report with more stats about each coefficient.
@@ -549,6 +3585,38 @@ def lin_reg_report(
     Note: if columns are not linearly independent, some numerical issue may occur. This uses
     the closed form solution to compute the least square report.
 
+    The residual degrees of freedom, reported as `df_resid`, is n - trace(H), where H is the hat matrix of
+    the fit. This is n - p for OLS and WLS. For Ridge (l2_reg > 0), the trace is the effective number of
+    parameters, which is < p, and the standard errors are sqrt(diag(sigma^2 * A^-1 X^t W X A^-1)) with
+    A = X^t W X + l2_reg * I (the bias is not penalized). Note that Ridge coefficients are biased, so the
+    t values and p-values are only approximate.
+
+    The number of rows actually used in the fit, after null handling (e.g. null_policy = "skip"), is
+    reported as `n_obs`.
+
+    `rank` is the numerical rank of the design X (the bias column included), from the column pivoted QR
+    decomposition of X. If it is less than the number of coefficients, e.g. with a duplicated feature, the
+    coefficients are not identified and the ones reported are only one of infinitely many solutions. In that
+    case, `note` explains this. Otherwise, `note` is null. See `lin_reg_matrix_rank`.
+
+    `std_beta` is the standardized coefficient beta_j * std(x_j) / std(y), the change in the target, in its
+    standard deviations, per standard deviation of the feature. It is comparable across features on different
+    scales. With weights, the standard deviations are weighted. The bias has no standardized coefficient, so it
+    is NaN for the bias.
+
+    `durbin_watson` is the Durbin-Watson statistic sum((r_t - r_{t-1})^2) / sum(r_t^2) of the residuals r, which
+    is about 2 * (1 - rho), where rho is the lag 1 autocorrelation of the residuals. Values well below 2 suggest
+    positive autocorrelation. It assumes the rows are in time order and contiguous: rows dropped because of nulls
+    are left out, so their neighbors are treated as adjacent. With weights, it is computed on sqrt(w) * r.
+
+    The goodness of fit is given by `r2`, `adj_r2` and `resid_se`, the residual standard error
+    sqrt(RSS / df_resid). Same as statsmodels, R2 is measured against the total sum of squares of the
+    target around its mean if add_bias, and around 0 (uncentered) otherwise. With weights, all sums of
+    squares are weighted.
+
+    `f_stat` and `f_pvalue` are the overall F test that all coefficients but the bias are 0. The statistic
+    is ((TSS - RSS) / k) / (RSS / df_resid), where k is the number of features, not counting the bias.
+
     Parameters
     ----------
     x
@@ -557,41 +3625,80 @@ def lin_reg_report(
         The target variable
     weights
         If not None, this will then compute the stats for a weights least square.
+    weight_type
+        How to interpret the weights. Only used when weights is not None. "analytic" weights are inverse
+        variances, so n is the number of rows and sigma^2 = sum(w * r^2) / (n - p). "frequency" weights are
+        row counts, i.e. each row stands for w identical rows. Then n = sum(w), sigma^2 = sum(w * r^2) / (sum(w) - p),
+        and r2 is computed as if the data was expanded. In both cases, std_err = sqrt(sigma^2 * diag((X^t W X)^-1)),
+        and the t distribution uses n - p degrees of freedom. Use "frequency" for aggregated data.
     add_bias
-        Whether to add a bias term. If bias is added, it is always the last feature.
+        Whether to add a bias term. If bias is added, it is the last feature unless bias_first is true.
+    bias_first
+        If true and add_bias is true, the bias row will be the first row of the report instead of the last.
     null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
         One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
         fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
         the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
         columns.
     std_err
-        One of "se", "hc0", "hc1", "hc2", "hc3", where "se" means we compute the standard error
+        One of "se", "hc0", "hc1", "hc2", "hc3", where "se" (or "classical") means we compute the standard error
         under the assumption of homoskedasticity, and the hc options are different options for
         heteroskedasticity. The hc0-hc3 are called Heteroskedasticity-Consistent Standard Errors, and their
         formulas can be found here: https://jslsoc.sitehost.iu.edu/files_research/testing_tests/hccm/00TAS.pdf.
+        They are the sandwich (XtX)^-1 X^t diag(w_i * r_i^2) X (XtX)^-1, where w_i is 1 for hc0, n / df_resid for
+        hc1, 1 / (1 - h_ii) for hc2 and 1 / (1 - h_ii)^2 for hc3, h_ii being the leverage of row i. "hac" gives
+        the Newey-West standard errors, which are also robust to autocorrelated residuals. Its middle term is the
+        long run covariance of the scores x_i * r_i with Bartlett weights 1 - l / (hac_lags + 1) on the products at
+        lag l, and there is no small sample correction (the same as statsmodels' cov_type="HAC"). HAC assumes
+        the rows are in time order, so the data must be sorted by time first. The t values, p-values and
+        confidence intervals use the chosen standard errors.
         This won't be used if weights are used (The author is not super familiar with the theory). If any other
         string is provided, it will default to "se".
+    l2_reg
+        Regularization factor for Ridge. Must be >= 0.
+    alpha
+        Significance level of the confidence intervals. The report has the bounds `ci_lower` and `ci_upper`
+        = beta -/+ t_(1 - alpha / 2) * std_err, where t is the quantile of the t distribution with df_resid
+        degrees of freedom. The default of 0.05 gives 95% confidence intervals. Must be in (0, 1). The columns
+        `0.025` and `0.975` are always the 95% bounds, whatever alpha is. They are kept for backward
+        compatibility, are deprecated in favor of `ci_lower` and `ci_upper`, and will be removed in a future
+        release.
+    hac_lags
+        Only used when std_err is "hac". The number of lags of the Newey-West covariance. If None, the rule of
+        thumb floor(4 * (n / 100)^(2/9)) is used, where n is the number of rows.
     """
+    if weight_type not in ("analytic", "frequency"):
+        raise ValueError("Input `weight_type` must be one of 'analytic' and 'frequency'.")
+    if l2_reg < 0.0:
+        raise ValueError("Input `l2_reg` must be >= 0.")
+    if not (0.0 < alpha < 1.0):
+        raise ValueError("Input `alpha` must be in (0, 1).")
+    if hac_lags is not None and hac_lags < 0:
+        raise ValueError("Input `hac_lags` must be >= 0.")
 
     lr_kwargs = {
         "bias": add_bias,
         "null_policy": null_policy,
         "l1_reg": 0.0,
-        "l2_reg": 0.0,
+        "l2_reg": l2_reg,
         "solver": "qr",
         "tol": 0.0,
         "std_err": std_err.lower(),
+        "hac_lags": hac_lags,
+        "bias_first": bias_first,
+        "weight_type": weight_type,
+        "alpha": alpha,
     }
 
     t = lr_formula(target)
     if weights is None:
-        cols = [t.var(), t]
+        cols = [t]
         cols.extend(lr_formula(z) for z in x)
         symbol = _lin_reg_expr_symbol("pl_lin_reg_report")
 
     else:
         w = lr_formula(weights)
-        cols = [w.cast(pl.Float64).rechunk(), t.var(), t]
+        cols = [w.cast(pl.Float64).rechunk(), t]
         cols.extend(lr_formula(z) for z in x)
         symbol = _lin_reg_expr_symbol("pl_wls_report")
 
@@ -604,6 +3711,135 @@ def lin_reg_report(
     )
 
 
+def lin_reg_bootstrap_ci(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    l2_reg: float = 0.0,
+    n_boot: int = 1000,
+    confidence: float = 0.95,
+    seed: int | None = None,
+    null_policy: NullPolicy = "raise",
+) -> pl.Expr:
+    """
+    Computes distribution-free percentile confidence intervals for the coefficients by the bootstrap.
+    Rows are resampled with replacement `n_boot` times and the regression is refit on each resample.
+    Resampling is implemented as a weighted least square where each row's weight is the number of times
+    it is drawn, so no data is copied. The output is a struct with fields `features`, `beta` (the
+    full sample estimate), `boot_se` (standard deviation of the bootstrap estimates), `lower` and `upper`.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term. If bias is added, it is the last feature unless bias_first is true.
+    bias_first
+        If true and add_bias is true, the bias row will be the first row of the output instead of the last.
+    l2_reg
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed.
+    n_boot
+        Number of bootstrap resamples. Must be >= 2.
+    confidence
+        The confidence level of the intervals. Must be in (0, 1).
+    seed
+        A random seed for the resampling. If None, results will not be reproducible.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if n_boot < 2:
+        raise ValueError("`n_boot` must be >= 2.")
+    if not (0.0 < confidence < 1.0):
+        raise ValueError("`confidence` must be in (0, 1).")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "l2_reg": abs(l2_reg),
+        "n_boot": n_boot,
+        "confidence": confidence,
+        "seed": seed,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_lstsq_bootstrap_ci",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    )
+
+
+def lin_reg_sign_stability(
+    *x: str | pl.Expr,
+    target: str | pl.Expr,
+    add_bias: bool = False,
+    bias_first: bool = False,
+    l2_reg: float = 0.0,
+    n_boot: int = 1000,
+    seed: int | None = None,
+    null_policy: NullPolicy = "raise",
+) -> pl.Expr:
+    """
+    Sign stability of the coefficients under the bootstrap. Rows are resampled with replacement `n_boot`
+    times, the regression is refit on each resample as in `lin_reg_bootstrap_ci`, and for each coefficient,
+    the fraction of the resamples in which its sign is the same as the full sample estimate is reported.
+    Fractions well below 1 mean that even the direction of the effect is unreliable. The output is a struct
+    with fields `features`, `beta` (the full sample estimate) and `sign_agreement`. The agreement is null
+    (NaN) if the full sample estimate is exactly 0.
+
+    Parameters
+    ----------
+    x
+        The variables used to predict target
+    target
+        The target variable
+    add_bias
+        Whether to add a bias term. If bias is added, it is the last feature unless bias_first is true.
+    bias_first
+        If true and add_bias is true, the bias row will be the first row of the output instead of the last.
+    l2_reg
+        The L2 regularization factor. If this is > 0, then a Ridge regression will be performed.
+    n_boot
+        Number of bootstrap resamples. Must be >= 1.
+    seed
+        A random seed for the resampling. If None, results will not be reproducible.
+    null_policy: Literal['raise', 'skip', 'zero', 'one', 'ignore']
+        One of options shown here, but you can also pass in any numeric string. E.g you may pass '1.25' to mean
+        fill nulls with 1.25. If the string cannot be converted to a float, an error will be thrown. Note: if
+        the target column has null, the rows with nulls will always be dropped. Null-fill only applies to non-target
+        columns.
+    """
+    if n_boot < 1:
+        raise ValueError("`n_boot` must be >= 1.")
+
+    cols = [lr_formula(target)]
+    cols.extend(lr_formula(z) for z in x)
+    kwargs = {
+        "bias": add_bias,
+        "null_policy": null_policy,
+        "l2_reg": abs(l2_reg),
+        "n_boot": n_boot,
+        "seed": seed,
+        "bias_first": bias_first,
+    }
+    return pl_plugin(
+        symbol="pl_coef_sign_stability",
+        args=cols,
+        kwargs=kwargs,
+        changes_length=True,
+        pass_name_to_apply=True,
+    ).alias("sign_stability")
+
+
 def query_lstsq_report(
     *x: str | pl.Expr,
     target: str | pl.Expr,
@@ -14,10 +14,10 @@ from __future__ import annotations
 
 import polars as pl
 import numpy as np
-from typing import List, Tuple
-from .typing import LRSolverMethods, NullPolicy, PolarsFrame
+from typing import List, Literal, Tuple
+from .typing import LRSolverMethods, NullPolicy, PolarsFrame, StopCriterion
 
-from polars_ds._polars_ds import PyLR, PyElasticNet, PyOnlineLR
+from polars_ds._polars_ds import PyLR, PyElasticNet, PyOnlineLR, py_cross_val_score
 
 import sys
 
@@ -105,6 +105,7 @@ class LR:
         lambda_: float = 0.0,
         solver: LRSolverMethods = "qr",
         feature_names_in_: List[str] | None = None,
+        warn_collinearity: float = 1e8,
     ):
         """
         Parameters
@@ -112,14 +113,23 @@ class LR:
         lambda_
             The regularization parameters for ridge. If this is positive, then this class will solve Ridge.
         solver
-            Use one of 'svd', 'cholesky' and 'qr' method to solve the least square equation. Default is 'qr'.
+            Use one of 'svd', 'cholesky', 'qr' and 'qr_direct' method to solve the least square equation. Default
+            is 'qr'. 'qr_direct' runs QR on X instead of XtX, which is more accurate on ill-conditioned data.
         fit_bias
             Whether to add a bias term. Also known as intercept in other packages.
         feature_names_in_
             Names for the incoming features, if available. If None, the names will be empty. They will be
             learned if .fit_df() is run later, or .set_input_features() is set later.
-        """
-        self._lr = PyLR(solver=solver, lambda_=lambda_, fit_bias=fit_bias)
+        warn_collinearity
+            If the condition number of the design matrix in a fit exceeds this, the fit is flagged as
+            ill-conditioned. See `collinearity_warning`. Nothing is printed. Use float('inf') to turn this off.
+        """
+        self._lr = PyLR(
+            solver=solver,
+            lambda_=lambda_,
+            has_bias=fit_bias,
+            warn_collinearity=warn_collinearity,
+        )
         self.feature_names_in_: List[str] = (
             [] if feature_names_in_ is None else list(feature_names_in_)
         )
@@ -151,9 +161,95 @@ class LR:
         lr._lr.set_coeffs_and_bias(coefficients, bias)
         return lr
 
+    def to_parts(self) -> dict:
+        """
+        Returns the model as plain values: `coeffs`, `bias`, `fit_bias`, `lambda_` and `solver`.
+        `LR.from_parts(**lr.to_parts())` gives back the same model, including `fit_bias` when the bias is 0,
+        which `from_values` can't tell apart from no bias. The rank and condition number of the last fit
+        are not included.
+        """
+        coeffs, bias, fit_bias, lambda_, solver = self._lr.to_parts()
+        return {
+            "coeffs": coeffs,
+            "bias": bias,
+            "fit_bias": fit_bias,
+            "lambda_": lambda_,
+            "solver": solver,
+        }
+
+    @classmethod
+    def from_parts(
+        cls,
+        coeffs: List[float],
+        bias: float,
+        fit_bias: bool,
+        lambda_: float = 0.0,
+        solver: LRSolverMethods = "qr",
+        feature_names_in_: List[str] | None = None,
+    ) -> Self:
+        """
+        Constructs a LR class instance from the output of `to_parts`.
+
+        Parameters
+        ----------
+        coeffs
+            Iterable of numbers representing the coefficients
+        bias
+            Value for the bias term. Ignored if fit_bias is False.
+        fit_bias
+            Whether the model has a bias term
+        lambda_
+            The regularization parameter for ridge
+        solver
+            The solver, used if the model is fit again
+        feature_names_in_
+            Names for the incoming features, if available. If None, the names will be empty.
+        """
+        model = cls.__new__(cls)
+        model._lr = PyLR.from_parts(
+            np.ascontiguousarray(coeffs, dtype=np.float64).flatten(),
+            float(bias),
+            bool(fit_bias),
+            float(lambda_),
+            solver,
+        )
+        model.feature_names_in_ = [] if feature_names_in_ is None else list(feature_names_in_)
+        return model
+
     def is_fit(self) -> bool:
         return self._lr.is_fit()
 
+    def to_bytes(self) -> bytes:
+        """
+        Serializes the model into a compact binary format. Load it back by `LR.from_bytes`. Pickling the
+        model also goes through this.
+        """
+        return self._lr.to_bytes()
+
+    @classmethod
+    def from_bytes(cls, data: bytes, feature_names_in_: List[str] | None = None) -> Self:
+        """
+        Loads a model serialized by `to_bytes`.
+
+        Parameters
+        ----------
+        data
+            The output of `to_bytes`
+        feature_names_in_
+            Names for the incoming features, if available. They are not part of the serialized model.
+        """
+        model = cls.__new__(cls)
+        model._lr = PyLR.from_bytes(data)
+        model.feature_names_in_ = [] if feature_names_in_ is None else list(feature_names_in_)
+        return model
+
+    def __getstate__(self) -> dict:
+        return {"model": self.to_bytes(), "feature_names_in_": self.feature_names_in_}
+
+    def __setstate__(self, state: dict):
+        self._lr = PyLR.from_bytes(state["model"])
+        self.feature_names_in_ = list(state["feature_names_in_"])
+
     def __repr__(self) -> str:
         if self._lr.lambda_ > 0.0:
             output = "Linear Regression (Ridge) Model\n"
@@ -187,7 +283,57 @@ class LR:
         return self._lr.coeffs
 
     def bias(self) -> float:
-        self._lr.bias
+        return self._lr.bias
+
+    def rank(self) -> int:
+        """
+        Returns the numerical rank of the design matrix (bias column included) used in the last fit.
+        If this is less than the number of coefficients (+1 if there is bias), the model is not identified
+        and the coefficients returned are the minimum norm solution. The rank is as seen by the solver:
+        'qr_direct' factorizes X itself, while the other solvers go through XtX, which squares the
+        condition number, so columns collinear up to ~1e-8 count as dependent. This is 0 if the model is
+        not fit or the coefficients are set directly.
+        """
+        return self._lr.rank()
+
+    def condition_number(self) -> float:
+        """
+        Returns the estimated condition number of the design matrix (bias column included) used in the last
+        fit. This is inf if the design is rank deficient, and 0 if the model is not fit or the coefficients
+        are set directly.
+        """
+        return self._lr.condition_number()
+
+    def collinearity_warning(self) -> str | None:
+        """
+        Returns a warning message if the condition number of the design matrix in the last fit exceeds the
+        `warn_collinearity` threshold, and None otherwise.
+        """
+        return self._lr.collinearity_warning()
+
+    def check_full_rank(self) -> None:
+        """
+        Raises a ValueError if the design matrix (bias column included) in the last fit does not have full
+        column rank, i.e. the coefficients are not identified. Ridge still gives a unique solution, but the
+        rank doesn't depend on `lambda_`. The model must be fit on data, not set by `from_values`.
+        """
+        self._lr.check_full_rank()
+
+    def with_coefficients_masked(self, mask: List[bool]) -> "LR":
+        """
+        Returns a copy of the model where the coefficients at the True positions of the mask are set to 0.
+        The bias is never masked. This is useful for what-if analysis, e.g. to see the effect of dropping a
+        predictor on the predictions, without refitting.
+
+        Parameters
+        ----------
+        mask
+            A list of booleans with the same length as the coefficients.
+        """
+        lr = LR.__new__(LR)
+        lr._lr = self._lr.with_coefficients_masked([bool(m) for m in mask])
+        lr.feature_names_in_ = list(self.feature_names_in_)
+        return lr
 
     def fit(self, X: np.ndarray, y: np.ndarray, null_policy: NullPolicy = "ignore") -> Self:
         """
@@ -316,6 +462,9 @@ class ElasticNet:
         tol: float = 1e-5,
         max_iter: int = 2000,
         feature_names_in_: List[str] | None = None,
+        stop_criterion: StopCriterion = "coefficient",
+        penalize_bias: bool = False,
+        penalty_factor: List[float] | None = None,
     ):
         """
         Initializes an ElasticNet regressor. This is equivalent to Sklearn's Elastic Net if you set
@@ -336,11 +485,37 @@ class ElasticNet:
         feature_names_in_
             Names for the incoming features, if available. If None, the names will be empty. They will be
             learned if .fit_df() is run later, or .set_input_features() is set later.
+        stop_criterion
+            If "coefficient", stop when the max absolute change in the coefficients is < tol. If
+            "objective", stop when the relative decrease of the objective (squared error plus penalties)
+            is < tol. If "dual_gap", stop when the duality gap, which bounds the distance of the objective
+            to its minimum, is < tol.
+        penalize_bias
+            Only applies if fit_bias is true. If false, the bias is not penalized: it is set to the mean of
+            the residuals of the other coefficients after each round of coordinate descent, so it is not shrunk
+            towards 0. If true, it is penalized like the other coefficients, which some prefer for centered data.
+        penalty_factor
+            One non-negative factor per feature, which multiplies l1_reg for that feature. A factor of 0 means
+            the feature is not shrunk by the l1 penalty. Missing factors are 1 and extra ones are ignored, so
+            the bias is only penalized if penalize_bias is true.
         """
         if l1_reg <= 0.0 and l2_reg <= 0.0:
             raise ValueError("Cannot have both l1_reg and l2_reg <= 0.")
-
-        self._en = PyElasticNet(l1_reg, l2_reg, fit_bias, tol, max_iter)
+        if penalty_factor is not None:
+            penalty_factor = [float(f) for f in penalty_factor]
+            if any(not (f >= 0.0) for f in penalty_factor):
+                raise ValueError("Input `penalty_factor` must be non-negative.")
+
+        self._en = PyElasticNet(
+            l1_reg,
+            l2_reg,
+            fit_bias,
+            tol,
+            max_iter,
+            stop_criterion,
+            penalize_bias,
+            penalty_factor,
+        )
         self.feature_names_in_: List[str] = (
             [] if feature_names_in_ is None else list(feature_names_in_)
         )
@@ -377,6 +552,37 @@ class ElasticNet:
     def is_fit(self) -> bool:
         return self._en.is_fit()
 
+    def to_bytes(self) -> bytes:
+        """
+        Serializes the model into a compact binary format. Load it back by `ElasticNet.from_bytes`.
+        Pickling the model also goes through this.
+        """
+        return self._en.to_bytes()
+
+    @classmethod
+    def from_bytes(cls, data: bytes, feature_names_in_: List[str] | None = None) -> Self:
+        """
+        Loads a model serialized by `to_bytes`.
+
+        Parameters
+        ----------
+        data
+            The output of `to_bytes`
+        feature_names_in_
+            Names for the incoming features, if available. They are not part of the serialized model.
+        """
+        model = cls.__new__(cls)
+        model._en = PyElasticNet.from_bytes(data)
+        model.feature_names_in_ = [] if feature_names_in_ is None else list(feature_names_in_)
+        return model
+
+    def __getstate__(self) -> dict:
+        return {"model": self.to_bytes(), "feature_names_in_": self.feature_names_in_}
+
+    def __setstate__(self, state: dict):
+        self._en = PyElasticNet.from_bytes(state["model"])
+        self.feature_names_in_ = list(state["feature_names_in_"])
+
     def __repr__(self) -> str:
         output = f"Elastic Net Model\nl1, l2 regularizers: {self._en.regularizers}\n"
         if self._en.is_fit():
@@ -411,6 +617,18 @@ class ElasticNet:
     def bias(self) -> float:
         return self._en.bias
 
+    def converged(self) -> bool:
+        """
+        Whether the last fit converged within max_iter iterations.
+        """
+        return self._en.converged
+
+    def n_iter(self) -> int:
+        """
+        The number of iterations run in the last fit.
+        """
+        return self._en.n_iter
+
     def fit(self, X: np.ndarray, y: np.ndarray, null_policy: NullPolicy = "ignore") -> Self:
         """
         Fit the Elastic Net model on NumPy data.
@@ -522,14 +740,23 @@ class OnlineLR:
         self,
         lambda_: float = 0.0,
         fit_bias: bool = False,
+        forget: float = 1.0,
     ):
         """
         lambda_
             The L2 regularization factor
         fit_bias
             Whether this should fit the bias term
+        forget
+            The forgetting factor in (0, 1] for tracking relationships that drift over time. Before each
+            update, all previous data is down-weighted by this factor, so the model minimizes
+            sum_k forget^(t - k) * (y_k - x_k b)^2. This is recursive least squares with a forgetting factor,
+            and the effective memory is about 1 / (1 - forget) rows. For Ridge, the penalty decays in the
+            same way. The default of 1.0 means no forgetting.
         """
-        self._lr = PyOnlineLR(lambda_, fit_bias)
+        if not (0.0 < forget <= 1.0):
+            raise ValueError("Input `forget` must be in (0, 1].")
+        self._lr = PyOnlineLR(lambda_, fit_bias, forget)
 
     @classmethod
     def from_coeffs_bias_inverse(cls, coeffs: List[float], bias: float, inv: np.ndarray) -> Self:
@@ -554,6 +781,34 @@ class OnlineLR:
     def is_fit(self) -> bool:
         return self._lr.is_fit()
 
+    def to_bytes(self) -> bytes:
+        """
+        Serializes the model into a compact binary format, including the inverse of XtX, so that online
+        updates can continue after it is loaded. Load it back by `OnlineLR.from_bytes`. Pickling the
+        model also goes through this.
+        """
+        return self._lr.to_bytes()
+
+    @classmethod
+    def from_bytes(cls, data: bytes) -> Self:
+        """
+        Loads a model serialized by `to_bytes`.
+
+        Parameters
+        ----------
+        data
+            The output of `to_bytes`
+        """
+        model = cls.__new__(cls)
+        model._lr = PyOnlineLR.from_bytes(data)
+        return model
+
+    def __getstate__(self) -> dict:
+        return {"model": self.to_bytes()}
+
+    def __setstate__(self, state: dict):
+        self._lr = PyOnlineLR.from_bytes(state["model"])
+
     def __repr__(self) -> str:
         if self._lr.lambda_ > 0.0:
             output = "Online Linear Regression (Ridge) Model\n"
@@ -574,7 +829,7 @@ class OnlineLR:
         return self._lr.coeffs
 
     def bias(self) -> float:
-        self._lr.bias
+        return self._lr.bias
 
     def inv(self) -> np.ndarray:
         """
@@ -601,6 +856,35 @@ class OnlineLR:
         self._lr.fit(X, y)
         return self
 
+    def reset(self) -> Self:
+        """
+        Drops all the data seen so far, e.g. after a regime change or before the model is reused on another
+        partition. The coefficients, the bias and the inverse are cleared, so the model must be fit again
+        before it can be updated. The settings (lambda_, fit_bias and forget) are kept.
+        """
+        self._lr.reset()
+        return self
+
+    def refit(self, X: np.ndarray, y: np.ndarray) -> Self:
+        """
+        Resets the model and fits it on the new data. This is the same as fitting a new model with the
+        same settings.
+
+        Parameters
+        ----------
+        X
+            The feature Matrix. NumPy 2D matrix only.
+        y
+            The target data. NumPy array. Must be reshape-able to (-1, 1).
+        """
+        if np.any(np.isnan(X)) | np.any(np.isnan(y)):
+            raise ValueError(
+                "Online regression currently must fit without null for the initial fit."
+            )
+
+        self._lr.refit(X, y)
+        return self
+
     def update(self, X: np.ndarray, y: np.ndarray | float, c: float = 1.0) -> Self:
         """
         Updates the online linear regression model with one row of data. If the row contains np.nan,
@@ -615,10 +899,14 @@ class OnlineLR:
         c
             The middle term (C) in the woodbury matrix identity. A value of 1.0 means we add
             the impact of the new data, and a value of -1.0 means we remove the impact of the
-            data. Any other value will `scale` the impact of the data.
+            data. Any other value will `scale` the impact of the data. If forget < 1, the previous
+            data is down-weighted before the row is added, and only c = 1.0 is supported.
         """
         if not self.is_fit():
             raise ValueError("You cannot update before the initial fit of the matrix.")
+        forgetting = self._lr.forget < 1.0
+        if forgetting and c != 1.0:
+            raise ValueError("With a forgetting factor, only additions (c = 1.0) are supported.")
 
         x_2d = X.reshape((1, -1))
         if isinstance(y, float):
@@ -626,7 +914,36 @@ class OnlineLR:
         else:
             y_2d = y.reshape((1, 1))
 
-        self._lr.update(x_2d, y_2d, c)
+        if forgetting:
+            self._lr.update_with_forget(x_2d, y_2d)
+        else:
+            self._lr.update(x_2d, y_2d, c)
+        return self
+
+    def update_batch(self, X: np.ndarray, y: np.ndarray, c: float = 1.0) -> Self:
+        """
+        Updates the online linear regression model with a block of rows at once. This gives the same
+        result as calling `update` row by row, but uses one block Woodbury update. Rows containing
+        np.nan will be ignored. If the block would make XtX singular, e.g. when it removes the only rows
+        that span a direction, the rows are applied one by one, and the singular ones are skipped as in
+        `update`.
+
+        Parameters
+        ----------
+        X
+            The new rows. NumPy 2D matrix only.
+        y
+            The new targets. NumPy array. Must be reshape-able to (-1, 1).
+        c
+            The middle term (C) in the woodbury matrix identity, applied to every row. A value of 1.0
+            adds the rows and a value of -1.0 removes them.
+        """
+        if not self.is_fit():
+            raise ValueError("You cannot update before the initial fit of the matrix.")
+        if self._lr.forget < 1.0:
+            raise ValueError("Batch updates are not supported with a forgetting factor.")
+
+        self._lr.update_batch(X, y.reshape((-1, 1)), c)
         return self
 
     def predict(self, X: np.ndarray) -> np.ndarray:
@@ -639,3 +956,68 @@ class OnlineLR:
             Data to predict on, as a matrix
         """
         return self._lr.predict(X).reshape((-1, 1))
+
+
+def cross_val_score(
+    X: np.ndarray,
+    y: np.ndarray,
+    lambdas: List[float],
+    n_folds: int = 5,
+    method: Literal["normal", "l1", "l2", "elastic"] = "l2",
+    l1_ratio: float = 0.5,
+    fit_bias: bool = False,
+    seed: int | None = None,
+    tol: float = 1e-5,
+    max_iter: int = 2000,
+) -> Tuple[np.ndarray, np.ndarray]:
+    """
+    K-fold cross validation over a grid of lambdas, for choosing the regularization strength. The rows
+    are randomly assigned to `n_folds` folds of (almost) equal size, and for each fold and lambda, the
+    model is fit on the other folds and scored by the MSE on the fold. The fold assignment is the same
+    as in `pds.lin_reg_cv` for the same seed. Returns the mean and the sample std of the validation MSEs
+    of the folds, one per lambda. The best lambda is the one with the smallest mean.
+
+    Parameters
+    ----------
+    X
+        The feature Matrix. NumPy 2D matrix only.
+    y
+        The target data. NumPy array. Must be reshape-able to (-1, 1).
+    lambdas
+        The non-negative regularization strengths to evaluate.
+    n_folds
+        The number of folds. Must be >= 2, and every fold must have at least as many rows as there are
+        coefficients (+1 if fit_bias).
+    method
+        The model. 'normal' and 'l2' are fit by `LR`, where lambda is `lambda_` and is ignored for 'normal'.
+        'l1' and 'elastic' are fit by `ElasticNet`, where l1_reg = lambda for 'l1', and l1_reg = l1_ratio * lambda
+        and l2_reg = (1 - l1_ratio) * lambda for 'elastic'.
+    l1_ratio
+        Only used when method is 'elastic'. Must be in [0, 1].
+    fit_bias
+        Whether to add a bias term. The bias is not regularized.
+    seed
+        Random seed for the fold assignment.
+    tol
+        For 'l1' and 'elastic'. The tolerance of coordinate descent.
+    max_iter
+        For 'l1' and 'elastic'. The max number of iterations of coordinate descent.
+    """
+    if method not in ("normal", "l1", "l2", "elastic"):
+        raise ValueError("Input `method` must be one of 'normal', 'l1', 'l2' and 'elastic'.")
+    if not 0.0 <= l1_ratio <= 1.0:
+        raise ValueError("Input `l1_ratio` must be in [0, 1].")
+
+    mean_mse, std_mse = py_cross_val_score(
+        np.ascontiguousarray(X, dtype=np.float64),
+        np.ascontiguousarray(y, dtype=np.float64).reshape((-1, 1)),
+        [float(v) for v in lambdas],
+        n_folds=n_folds,
+        method=method,
+        l1_ratio=float(l1_ratio),
+        has_bias=fit_bias,
+        seed=seed,
+        tol=abs(tol),
+        max_iter=max_iter,
+    )
+    return np.array(mean_mse), np.array(std_mse)
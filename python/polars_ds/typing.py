@@ -20,8 +20,9 @@ SimpleImputeMethod: TypeAlias = Literal["mean", "median", "mode"]
 SimpleScaleMethod: TypeAlias = Literal["min_max", "standard", "abs_max"]
 Noise: TypeAlias = Literal["gaussian", "uniform"]
 LRMethods: TypeAlias = Literal["normal", "l2", "l1"]
-LRSolverMethods: TypeAlias = Literal["svd", "qr", "cholesky"]
+LRSolverMethods: TypeAlias = Literal["svd", "qr", "cholesky", "qr_direct"]
 NullPolicy: TypeAlias = Literal["raise", "skip", "one", "zero", "ignore"]
+StopCriterion: TypeAlias = Literal["coefficient", "objective", "dual_gap"]
 MultiAUCStrategy: TypeAlias = Literal["weighted", "macro"]
 EncoderDefaultStrategy: TypeAlias = Literal["mean", "null", "zero"]
 # Copy of Polars
@@ -12,7 +12,7 @@ mod utils;
 use pyo3::{
     pymodule,
     types::{PyModule, PyModuleMethods},
-    Bound, PyResult, Python,
+    wrap_pyfunction, Bound, PyResult, Python,
 };
 
 #[pymodule]
@@ -22,6 +22,7 @@ fn _polars_ds(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<pymodels::py_lr::PyElasticNet>()?;
     m.add_class::<pymodels::py_lr::PyOnlineLR>()?;
     m.add_class::<pymodels::py_kdt::PyKDT>()?;
+    m.add_function(wrap_pyfunction!(pymodels::py_lr::py_cross_val_score, m)?)?;
     Ok(())
 }
 
@@ -0,0 +1,94 @@
+use faer::{linalg::solvers::Solve, mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub fn rng_from_seed(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(s) => StdRng::seed_from_u64(s),
+        _ => StdRng::from_entropy(),
+    }
+}
+
+/// Bootstraps the coefficients of a (Ridge) linear regression. Drawing nrows rows with
+/// replacement is the same as running a weighted least square where the weight of a row is
+/// the number of times the row is drawn. So instead of copying rows, each replicate solves
+/// X^t W X b = X^t W y with multinomial count weights.
+///
+/// Returns a n_features x n_boot matrix, where each column is the coefficients of one replicate.
+/// If has_bias, the bias must be the last column of X and it will not be regularized.
+pub fn faer_bootstrap_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambda: T,
+    has_bias: bool,
+    n_boot: usize,
+    seed: Option<u64>,
+) -> Mat<T> {
+    let nrows = x.nrows();
+    let ncols = x.ncols();
+    let n1 = ncols.abs_diff(has_bias as usize);
+    let mut rng = rng_from_seed(seed);
+
+    let mut out = Mat::<T>::zeros(ncols, n_boot);
+    let mut counts = vec![T::zero(); nrows];
+    for b in 0..n_boot {
+        counts.fill(T::zero());
+        for _ in 0..nrows {
+            let i = rng.gen_range(0..nrows);
+            counts[i] = counts[i] + T::one();
+        }
+        let w = ColRef::from_slice(&counts);
+        let xtw = x.transpose() * w.as_diagonal();
+        let mut xtwx = &xtw * x;
+        if lambda > T::zero() {
+            for i in 0..n1 {
+                *xtwx.get_mut(i, i) = *xtwx.get(i, i) + lambda;
+            }
+        }
+        let coeffs = xtwx.col_piv_qr().solve(xtw * y);
+        out.col_mut(b).copy_from(coeffs.col(0));
+    }
+    out
+}
+
+/// For each coefficient, the fraction of the bootstrap replicates (columns of boot, as returned by
+/// faer_bootstrap_lstsq) whose sign is the same as the full sample estimate in beta. A replicate of 0 never
+/// agrees, and the fraction is NaN if the full sample estimate is 0.
+pub fn sign_agreement<T: RealField + Float>(boot: MatRef<T>, beta: &[T]) -> Vec<T> {
+    let n_boot = T::from(boot.ncols()).unwrap();
+    beta.iter()
+        .enumerate()
+        .map(|(i, b)| {
+            if *b == T::zero() {
+                T::nan()
+            } else {
+                let agree = boot.row(i).iter().filter(|v| **v * *b > T::zero()).count();
+                T::from(agree).unwrap() / n_boot
+            }
+        })
+        .collect()
+}
+
+/// Sorts the samples and returns the (lower, upper) percentile bounds for the given
+/// confidence level, using linear interpolation between order statistics. NaNs are put last.
+pub fn percentile_interval<T: RealField + Float>(samples: &mut [T], confidence: T) -> (T, T) {
+    samples.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Greater));
+    let half_alpha = (T::one() - confidence) / T::from(2.0).unwrap();
+    (
+        sorted_quantile(samples, half_alpha),
+        sorted_quantile(samples, T::one() - half_alpha),
+    )
+}
+
+/// Quantile of already sorted data, with linear interpolation.
+pub fn sorted_quantile<T: RealField + Float>(sorted: &[T], q: T) -> T {
+    if sorted.is_empty() {
+        return T::nan();
+    }
+    let pos = q * T::from(sorted.len() - 1).unwrap();
+    let lo = pos.floor().to_usize().unwrap_or(0).min(sorted.len() - 1);
+    let hi = (lo + 1).min(sorted.len() - 1);
+    let frac = pos - T::from(lo).unwrap();
+    sorted[lo] + (sorted[hi] - sorted[lo]) * frac
+}
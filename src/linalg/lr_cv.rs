@@ -0,0 +1,434 @@
+/// Tuning of the regularization strength of linear regressions.
+use super::{
+    lr_bootstrap::rng_from_seed,
+    lr_solvers::{faer_coordinate_descent_gram, faer_solve_gram, ElasticNet, LR},
+    LRMethods, LRSolverMethods, LinalgErrors, LinearRegression, StopCriterion,
+};
+use faer::{mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+use rand::seq::SliceRandom;
+
+pub struct RidgeLoocv<T: RealField + Float> {
+    pub best_lambda: T,
+    pub errors: Vec<T>,       // LOO mean squared error, one per lambda
+    pub coefficients: Mat<T>, // Coefficients at the best lambda, bias last if any
+}
+
+/// Exact leave-one-out CV of Ridge regression for each lambda in lambdas, with one SVD.
+///
+/// For Ridge, the LOO residual of row i is r_i / (1 - h_ii), where r is the residual of the fit on all
+/// rows and h_ii is the i-th diagonal entry of the hat matrix H = U diag(s^2 / (s^2 + lambda)) U^t. So no
+/// refitting is needed. If has_bias, the last column of x must be the bias column. The bias is not
+/// regularized, which is the same as centering x and y, and then H gets an extra 1/n on the diagonal.
+///
+/// Returns the LOO MSE for each lambda, the lambda with the smallest error and the coefficients at
+/// that lambda. Singular values <= max(n, m) * eps * max singular value are treated as 0.
+pub fn faer_ridge_loocv<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    has_bias: bool,
+) -> Result<RidgeLoocv<T>, LinalgErrors> {
+    let n = x.nrows();
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    if lambdas.is_empty() || lambdas.iter().any(|l| l.is_nan() || *l < T::zero()) {
+        return Err(LinalgErrors::Other(
+            "Lambdas must be non-empty and non-negative.".into(),
+        ));
+    }
+    if n <= 1 || n1 == 0 {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+
+    let nf = T::from(n).unwrap();
+    let (x_mean, y_mean) = if has_bias {
+        let x_mean = (0..n1)
+            .map(|j| x.col(j).iter().copied().fold(T::zero(), |a, b| a + b) / nf)
+            .collect::<Vec<_>>();
+        let y_mean = y.col(0).iter().copied().fold(T::zero(), |a, b| a + b) / nf;
+        (x_mean, y_mean)
+    } else {
+        (vec![T::zero(); n1], T::zero())
+    };
+    let xc = Mat::from_fn(n, n1, |i, j| *x.get(i, j) - x_mean[j]);
+    let yc = Col::<T>::from_fn(n, |i| *y.get(i, 0) - y_mean);
+    let h0 = if has_bias { nf.recip() } else { T::zero() };
+
+    let svd = xc
+        .thin_svd()
+        .map_err(|_| LinalgErrors::Other("SVD failed to converge.".into()))?;
+    let u = svd.U();
+    let s = svd.S().column_vector();
+    let max_s = s.iter().copied().fold(T::zero(), T::max);
+    let threshold = max_s * T::epsilon() * T::from(n.max(n1)).unwrap();
+    // Directions with 0 singular value are dropped
+    let rank = s.iter().take_while(|v| **v > threshold).count();
+    let uty = u.transpose() * &yc;
+
+    let mut errors = Vec::with_capacity(lambdas.len());
+    for lambda in lambdas.iter().copied() {
+        let d = (0..rank)
+            .map(|j| {
+                let s2 = *s.get(j) * *s.get(j);
+                s2 / (s2 + lambda)
+            })
+            .collect::<Vec<_>>();
+        let mut sse = T::zero();
+        for i in 0..n {
+            let (fitted, h) = (0..rank).fold((T::zero(), h0), |(f, h), j| {
+                let uij = *u.get(i, j);
+                (f + uij * d[j] * *uty.get(j), h + uij * uij * d[j])
+            });
+            let loo_res = (*yc.get(i) - fitted) / (T::one() - h);
+            sse = sse + loo_res * loo_res;
+        }
+        errors.push(sse / nf);
+    }
+
+    let best = errors
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, e)| if *e < errors[best] { i } else { best });
+    let best_lambda = lambdas[best];
+
+    // b = V diag(s / (s^2 + lambda)) Ut yc, and the bias is y_mean - x_mean * b.
+    let v = svd.V();
+    let w = Col::<T>::from_fn(rank, |j| {
+        let sj = *s.get(j);
+        sj / (sj * sj + best_lambda) * *uty.get(j)
+    });
+    let b = v.get(.., 0..rank) * &w;
+    let coefficients = Mat::from_fn(x.ncols(), 1, |i, _| {
+        if i < n1 {
+            *b.get(i)
+        } else {
+            y_mean - (0..n1).fold(T::zero(), |acc, j| acc + x_mean[j] * *b.get(j))
+        }
+    });
+
+    Ok(RidgeLoocv {
+        best_lambda,
+        errors,
+        coefficients,
+    })
+}
+
+pub struct LstsqCv<T: RealField + Float> {
+    pub lambda_min: T,      // Lambda with the smallest mean CV error
+    pub lambda_1se: T, // Largest lambda with mean CV error within one standard error of the minimum
+    pub cv_mean: Vec<T>, // Mean of the fold MSEs, one per lambda
+    pub cv_std: Vec<T>, // Sample standard deviation of the fold MSEs, one per lambda
+    pub cv_se: Vec<T>, // Standard error of the fold MSEs, one per lambda
+    pub fold_mse: Vec<T>, // Test MSE of each fold at lambda_min
+    pub coeffs_min: Mat<T>, // Coefficients fit on all rows at lambda_min
+    pub coeffs_1se: Mat<T>, // Coefficients fit on all rows at lambda_1se
+}
+
+/// Solves the Ridge / Elastic Net problem given the Gram matrix. l1_reg and l2_reg have the same
+/// meaning as in the regular fits: Ridge solves (XtX + l2_reg * I) b = XtY, and Elastic Net runs
+/// coordinate descent.
+#[allow(clippy::too_many_arguments)]
+fn solve_penalized_gram<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    m: T,
+    l1_reg: T,
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+) -> Mat<T> {
+    if l1_reg > T::zero() {
+        faer_coordinate_descent_gram(xtx, xty, m, l1_reg, l2_reg, has_bias, tol, max_iter).0
+    } else {
+        let mut xtx = xtx.to_owned();
+        for i in 0..xtx.ncols().abs_diff(has_bias as usize) {
+            *xtx.get_mut(i, i) = *xtx.get(i, i) + l2_reg;
+        }
+        faer_solve_gram(xtx, xty.to_owned(), LRSolverMethods::QR)
+    }
+}
+
+/// K-fold cross validation of Ridge / Lasso / Elastic Net over a grid of lambdas. For each lambda,
+/// l1_reg = l1_ratio * lambda and l2_reg = (1 - l1_ratio) * lambda, so l1_ratio = 0 is Ridge and
+/// l1_ratio = 1 is Lasso. Rows are randomly assigned to n_folds folds of (almost) equal size. If
+/// has_bias, the last column of x must be the bias column.
+///
+/// Besides lambda_min, this returns lambda_1se, the largest lambda whose mean CV error is within one
+/// standard error of the smallest mean CV error. The standard error of a lambda is the sample std of
+/// its fold errors / sqrt(n_folds). Both are refit on all rows.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_lstsq_kfold_cv<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    l1_ratio: T,
+    has_bias: bool,
+    n_folds: usize,
+    seed: Option<u64>,
+    tol: T,
+    max_iter: usize,
+) -> Result<LstsqCv<T>, LinalgErrors> {
+    let n = x.nrows();
+    if n_folds < 2 || n < n_folds {
+        return Err(LinalgErrors::Other(
+            "Number of folds must be >= 2 and <= number of rows.".into(),
+        ));
+    }
+
+    let fold_of = kfold_assignment(n, n_folds, seed);
+    faer_lstsq_fold_cv(
+        x, y, lambdas, l1_ratio, has_bias, &fold_of, n_folds, tol, max_iter,
+    )
+}
+
+/// Same as faer_lstsq_kfold_cv, but with the given fold assignment. fold_of[i] is the fold of row i,
+/// in 0..n_folds, and every fold must have at least one row. This allows grouped or blocked (time
+/// series) splits.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_lstsq_fold_cv<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    l1_ratio: T,
+    has_bias: bool,
+    fold_of: &[usize],
+    n_folds: usize,
+    tol: T,
+    max_iter: usize,
+) -> Result<LstsqCv<T>, LinalgErrors> {
+    let n = x.nrows();
+    if lambdas.is_empty() || lambdas.iter().any(|l| l.is_nan() || *l < T::zero()) {
+        return Err(LinalgErrors::Other(
+            "Lambdas must be non-empty and non-negative.".into(),
+        ));
+    }
+    if fold_of.len() != n {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    if n_folds < 2 {
+        return Err(LinalgErrors::Other(
+            "There must be at least 2 folds.".into(),
+        ));
+    }
+    let mut fold_sizes = vec![0usize; n_folds];
+    for f in fold_of.iter().copied() {
+        if f >= n_folds {
+            return Err(LinalgErrors::Other(format!(
+                "Fold {} is out of range for {} folds.",
+                f, n_folds
+            )));
+        }
+        fold_sizes[f] += 1;
+    }
+    if fold_sizes.contains(&0) {
+        return Err(LinalgErrors::Other(
+            "Every fold must have at least one row.".into(),
+        ));
+    }
+
+    let fit = |xtx: MatRef<T>, xty: MatRef<T>, m: usize, lambda: T| {
+        solve_penalized_gram(
+            xtx,
+            xty,
+            T::from(m).unwrap(),
+            lambda * l1_ratio,
+            lambda * (T::one() - l1_ratio),
+            has_bias,
+            tol,
+            max_iter,
+        )
+    };
+
+    // fold_errors[k * n_folds + f] is the test MSE of lambda k on fold f
+    let mut fold_errors = vec![T::zero(); lambdas.len() * n_folds];
+    for f in 0..n_folds {
+        let train = (0..n).filter(|i| fold_of[*i] != f).collect::<Vec<_>>();
+        let test = (0..n).filter(|i| fold_of[*i] == f).collect::<Vec<_>>();
+        let x_train = Mat::from_fn(train.len(), x.ncols(), |i, j| *x.get(train[i], j));
+        let y_train = Mat::from_fn(train.len(), 1, |i, _| *y.get(train[i], 0));
+        let x_test = Mat::from_fn(test.len(), x.ncols(), |i, j| *x.get(test[i], j));
+        let xtx = x_train.transpose() * &x_train;
+        let xty = x_train.transpose() * &y_train;
+        for (k, lambda) in lambdas.iter().copied().enumerate() {
+            let coeffs = fit(xtx.as_ref(), xty.as_ref(), train.len(), lambda);
+            let pred = &x_test * &coeffs;
+            let sse = test.iter().enumerate().fold(T::zero(), |acc, (i, row)| {
+                let r = *y.get(*row, 0) - *pred.get(i, 0);
+                acc + r * r
+            });
+            fold_errors[k * n_folds + f] = sse / T::from(test.len()).unwrap();
+        }
+    }
+
+    let kf = T::from(n_folds).unwrap();
+    let (cv_mean, cv_std) = fold_mean_std(&fold_errors, n_folds);
+    let cv_se = cv_std.iter().map(|s| *s / kf.sqrt()).collect::<Vec<_>>();
+
+    let best = cv_mean
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, e)| if *e < cv_mean[best] { i } else { best });
+    let lambda_min = lambdas[best];
+    let fold_mse = fold_errors[best * n_folds..(best + 1) * n_folds].to_vec();
+    let bound = cv_mean[best] + cv_se[best];
+    let lambda_1se = lambdas
+        .iter()
+        .zip(cv_mean.iter())
+        .filter(|(_, e)| **e <= bound)
+        .fold(lambda_min, |acc, (l, _)| acc.max(*l));
+
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    Ok(LstsqCv {
+        lambda_min,
+        lambda_1se,
+        coeffs_min: fit(xtx.as_ref(), xty.as_ref(), n, lambda_min),
+        coeffs_1se: fit(xtx.as_ref(), xty.as_ref(), n, lambda_1se),
+        cv_mean,
+        cv_std,
+        cv_se,
+        fold_mse,
+    })
+}
+
+/// Randomly assigns each of the n rows to one of n_folds folds of (almost) equal size. The assignment
+/// only depends on n, n_folds and seed.
+fn kfold_assignment(n: usize, n_folds: usize, seed: Option<u64>) -> Vec<usize> {
+    let mut order = (0..n).collect::<Vec<_>>();
+    order.shuffle(&mut rng_from_seed(seed));
+    let mut fold_of = vec![0; n];
+    for (p, i) in order.into_iter().enumerate() {
+        fold_of[i] = p % n_folds;
+    }
+    fold_of
+}
+
+/// The mean and sample std of each chunk of n_folds fold errors.
+fn fold_mean_std<T: RealField + Float>(fold_errors: &[T], n_folds: usize) -> (Vec<T>, Vec<T>) {
+    let kf = T::from(n_folds).unwrap();
+    fold_errors
+        .chunks_exact(n_folds)
+        .map(|errs| {
+            let mean = errs.iter().copied().fold(T::zero(), |a, b| a + b) / kf;
+            let var = errs
+                .iter()
+                .fold(T::zero(), |acc, e| acc + (*e - mean) * (*e - mean))
+                / (kf - T::one());
+            (mean, var.sqrt())
+        })
+        .unzip()
+}
+
+pub struct CrossValScore<T: RealField + Float> {
+    pub mean_mse: Vec<T>, // Mean of the validation MSEs of the folds, one per lambda
+    pub std_mse: Vec<T>,  // Sample standard deviation of the validation MSEs, one per lambda
+}
+
+/// K-fold cross validation of the models given by method over a grid of lambdas, with the model
+/// structs themselves: LR for LRMethods::Normal and LRMethods::L2, and ElasticNet for LRMethods::L1
+/// and LRMethods::ElasticNet. lambda is the Ridge lambda of LR, the l1_reg of Lasso, and for Elastic
+/// Net, l1_reg = l1_ratio * lambda and l2_reg = (1 - l1_ratio) * lambda. For LRMethods::Normal, lambda
+/// is ignored. x must not contain the bias column. The models add it if has_bias.
+///
+/// Rows are assigned to folds as in faer_lstsq_kfold_cv, so the folds are the same for the same seed.
+/// Every fold must have at least as many rows as there are coefficients (bias included), since a
+/// validation MSE on fewer rows than parameters is too noisy to compare lambdas with.
+#[allow(clippy::too_many_arguments)]
+pub fn cross_val_score<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    n_folds: usize,
+    method: LRMethods,
+    l1_ratio: T,
+    has_bias: bool,
+    seed: Option<u64>,
+    tol: T,
+    max_iter: usize,
+) -> Result<CrossValScore<T>, LinalgErrors> {
+    let n = x.nrows();
+    if x.nrows() != y.nrows() {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    if lambdas.is_empty() || lambdas.iter().any(|l| l.is_nan() || *l < T::zero()) {
+        return Err(LinalgErrors::Other(
+            "Lambdas must be non-empty and non-negative.".into(),
+        ));
+    }
+    if n_folds < 2 || n < n_folds {
+        return Err(LinalgErrors::Other(
+            "Number of folds must be >= 2 and <= number of rows.".into(),
+        ));
+    }
+    // Folds have n / n_folds or n / n_folds + 1 rows
+    let n_coeffs = x.ncols() + has_bias as usize;
+    if n / n_folds < n_coeffs {
+        return Err(LinalgErrors::Other(format!(
+            "Every fold must have at least as many rows as coefficients ({}), but the smallest of the {} folds has {} rows.",
+            n_coeffs,
+            n_folds,
+            n / n_folds
+        )));
+    }
+
+    let fit_predict = |x_train: MatRef<T>,
+                       y_train: MatRef<T>,
+                       x_test: MatRef<T>,
+                       lambda: T|
+     -> Result<Mat<T>, LinalgErrors> {
+        match method {
+            LRMethods::Normal | LRMethods::L2 => {
+                let lambda = if method == LRMethods::L2 {
+                    lambda
+                } else {
+                    T::zero()
+                };
+                let mut lr = LR::new("qr", lambda, has_bias);
+                lr.fit(x_train, y_train)?;
+                lr.predict(x_test)
+            }
+            LRMethods::L1 | LRMethods::ElasticNet => {
+                let l1_ratio = if method == LRMethods::L1 {
+                    T::one()
+                } else {
+                    l1_ratio
+                };
+                let mut en = ElasticNet::new(
+                    l1_ratio * lambda,
+                    (T::one() - l1_ratio) * lambda,
+                    has_bias,
+                    tol,
+                    max_iter,
+                    StopCriterion::CoefficientChange,
+                    false,
+                );
+                en.fit(x_train, y_train)?;
+                en.predict(x_test)
+            }
+        }
+    };
+
+    let fold_of = kfold_assignment(n, n_folds, seed);
+    // fold_errors[k * n_folds + f] is the validation MSE of lambda k on fold f
+    let mut fold_errors = vec![T::zero(); lambdas.len() * n_folds];
+    for f in 0..n_folds {
+        let train = (0..n).filter(|i| fold_of[*i] != f).collect::<Vec<_>>();
+        let test = (0..n).filter(|i| fold_of[*i] == f).collect::<Vec<_>>();
+        let x_train = Mat::from_fn(train.len(), x.ncols(), |i, j| *x.get(train[i], j));
+        let y_train = Mat::from_fn(train.len(), 1, |i, _| *y.get(train[i], 0));
+        let x_test = Mat::from_fn(test.len(), x.ncols(), |i, j| *x.get(test[i], j));
+        for (k, lambda) in lambdas.iter().copied().enumerate() {
+            let pred = fit_predict(x_train.as_ref(), y_train.as_ref(), x_test.as_ref(), lambda)?;
+            let sse = test.iter().enumerate().fold(T::zero(), |acc, (i, row)| {
+                let r = *y.get(*row, 0) - *pred.get(i, 0);
+                acc + r * r
+            });
+            fold_errors[k * n_folds + f] = sse / T::from(test.len()).unwrap();
+        }
+    }
+
+    let (mean_mse, std_mse) = fold_mean_std(&fold_errors, n_folds);
+    Ok(CrossValScore { mean_mse, std_mse })
+}
@@ -0,0 +1,509 @@
+/// Diagnostics on the residuals of a linear regression.
+use super::{
+    lr_solvers::{faer_lstsq_residuals, faer_pinv, faer_qr_rank, faer_solve_lstsq},
+    LRSolverMethods, LinalgErrors,
+};
+use faer::{mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+
+/// Sample autocorrelations of the residuals at lags 0..=h. The residuals are demeaned
+/// first. Lags >= len(residuals) have autocorrelation 0.
+pub fn residual_acf<T: Float>(residuals: &[T], h: usize) -> Vec<T> {
+    let n = residuals.len();
+    if n == 0 {
+        return vec![T::nan(); h + 1];
+    }
+    let mean = residuals.iter().fold(T::zero(), |acc, r| acc + *r) / T::from(n).unwrap();
+    let e = residuals.iter().map(|r| *r - mean).collect::<Vec<_>>();
+    let c0 = e.iter().fold(T::zero(), |acc, v| acc + *v * *v);
+    (0..=h)
+        .map(|k| {
+            if k >= n {
+                T::zero()
+            } else {
+                e[..n - k]
+                    .iter()
+                    .zip(e[k..].iter())
+                    .fold(T::zero(), |acc, (a, b)| acc + *a * *b)
+                    / c0
+            }
+        })
+        .collect()
+}
+
+/// The Durbin-Watson statistic sum((e_t - e_{t-1})^2) / sum(e_t^2) of the residuals in time order.
+/// It is about 2 * (1 - rho), where rho is the lag 1 autocorrelation, so 2 means no autocorrelation.
+/// NaN if there are no residuals.
+pub fn durbin_watson<T: Float>(residuals: &[T]) -> T {
+    let sse = residuals.iter().fold(T::zero(), |acc, e| acc + *e * *e);
+    let diff = residuals
+        .windows(2)
+        .fold(T::zero(), |acc, w| acc + (w[1] - w[0]) * (w[1] - w[0]));
+    diff / sse
+}
+
+/// Standardized (beta) coefficients b_j * std(x_j) / std(y), the change in y, in its standard
+/// deviations, per standard deviation of x_j. With weights, the standard deviations are weighted. If
+/// has_bias, the bias is the last column of x, and its standardized coefficient is NaN.
+pub fn standardized_coefficients<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    betas: &[T],
+    weights: Option<&[T]>,
+    has_bias: bool,
+) -> Vec<T> {
+    // The normalization of the variance cancels out in the ratio
+    let spread = |c: ColRef<T>| {
+        let w = |i: usize| weights.map_or(T::one(), |w| w[i]);
+        let (sw, swx) = (0..c.nrows()).fold((T::zero(), T::zero()), |(sw, swx), i| {
+            (sw + w(i), swx + w(i) * c[i])
+        });
+        let mean = swx / sw;
+        (0..c.nrows())
+            .fold(T::zero(), |acc, i| {
+                acc + w(i) * (c[i] - mean) * (c[i] - mean)
+            })
+            .sqrt()
+    };
+    let y_spread = spread(y.col(0));
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    betas
+        .iter()
+        .enumerate()
+        .map(|(j, b)| {
+            if j < n1 {
+                *b * spread(x.col(j)) / y_spread
+            } else {
+                T::nan()
+            }
+        })
+        .collect()
+}
+
+/// Partial autocorrelations at lags 1..=h by the Durbin-Levinson recursion, given the
+/// autocorrelations at lags 0..=h.
+pub fn pacf_durbin_levinson<T: Float>(acf: &[T]) -> Vec<T> {
+    let h = acf.len().saturating_sub(1);
+    let mut pacf = Vec::with_capacity(h);
+    // phi holds phi_{k, 1..=k} of the AR(k) fit
+    let mut phi: Vec<T> = Vec::with_capacity(h);
+    let mut prev: Vec<T> = Vec::with_capacity(h);
+    for k in 1..=h {
+        let (num, den) = phi
+            .iter()
+            .enumerate()
+            .fold((acf[k], T::one()), |(num, den), (j, p)| {
+                (num - *p * acf[k - 1 - j], den - *p * acf[j + 1])
+            });
+        let phi_kk = num / den;
+        prev.clear();
+        prev.extend_from_slice(&phi);
+        for j in 0..phi.len() {
+            phi[j] = prev[j] - phi_kk * prev[k - 2 - j];
+        }
+        phi.push(phi_kk);
+        pacf.push(phi_kk);
+    }
+    pacf
+}
+
+/// The auxiliary design of White's test: the regressors, their squares and their pairwise
+/// products, followed by a constant column. x should not contain the bias column.
+pub fn white_aux_design<T: RealField + Float>(x: MatRef<T>) -> Mat<T> {
+    let (nrows, k) = (x.nrows(), x.ncols());
+    let ncols = k + k * (k + 1) / 2 + 1;
+    let mut aux = Mat::<T>::zeros(nrows, ncols);
+    for j in 0..k {
+        aux.col_mut(j).copy_from(x.col(j));
+    }
+    let mut c = k;
+    for i in 0..k {
+        for j in i..k {
+            for r in 0..nrows {
+                *aux.get_mut(r, c) = *x.get(r, i) * *x.get(r, j);
+            }
+            c += 1;
+        }
+    }
+    aux.col_mut(c).fill(T::one());
+    aux
+}
+
+/// The LM statistic n * R^2 of the regression of the squared residuals on aux, which must have a
+/// constant column, and its degrees of freedom, the rank of aux minus 1 (for the constant).
+fn squared_residuals_lm<T: RealField + Float>(aux: MatRef<T>, residuals: &[T]) -> (T, usize) {
+    let n = residuals.len();
+    let e2 = Mat::<T>::from_fn(n, 1, |i, _| residuals[i] * residuals[i]);
+    let rank = faer_qr_rank(aux.col_piv_qr().R());
+    let coeffs = faer_solve_lstsq(aux, e2.as_ref(), T::zero(), true, LRSolverMethods::QR);
+    let ssr = (&e2 - aux * coeffs).col(0).squared_norm_l2();
+    let mean = e2.col(0).iter().fold(T::zero(), |acc, v| acc + *v) / T::from(n).unwrap();
+    let sst = e2
+        .col(0)
+        .iter()
+        .fold(T::zero(), |acc, v| acc + (*v - mean) * (*v - mean));
+    let r2 = T::one() - ssr / sst;
+    (T::from(n).unwrap() * r2, rank.saturating_sub(1))
+}
+
+/// White's test for heteroskedasticity. Regresses the squared residuals on the auxiliary design and
+/// returns (n * R^2, degrees of freedom). Degrees of freedom is the rank of the auxiliary design
+/// minus 1 (for the constant), so duplicated columns, e.g. squares of dummies, are not counted twice.
+pub fn white_test<T: RealField + Float>(x: MatRef<T>, residuals: &[T]) -> (T, usize) {
+    let aux = white_aux_design(x);
+    squared_residuals_lm(aux.as_ref(), residuals)
+}
+
+/// The Breusch-Pagan test for heteroskedasticity, in Koenker's studentized form. Regresses the squared
+/// residuals on the regressors x (without the bias column) and a constant, and returns (n * R^2, degrees
+/// of freedom), where degrees of freedom is the rank of x, k if x has full rank. Unlike White's test, it
+/// only detects variances that are linear in the regressors, but it has k instead of O(k^2) degrees of
+/// freedom.
+pub fn breusch_pagan_test<T: RealField + Float>(x: MatRef<T>, residuals: &[T]) -> (T, usize) {
+    let aux = Mat::from_fn(x.nrows(), x.ncols() + 1, |i, j| {
+        if j < x.ncols() {
+            *x.get(i, j)
+        } else {
+            T::one()
+        }
+    });
+    squared_residuals_lm(aux.as_ref(), residuals)
+}
+
+pub struct RowInfluence<T: RealField + Float> {
+    pub leverage: Vec<T>, // Diagonal of the hat matrix
+    pub dffits: Vec<T>,
+    pub internal: Vec<T>, // Internally studentized residuals
+    pub cooks: Vec<T>,    // Cook's distance
+}
+
+/// Row level influence of an OLS fit. The leverage is h_ii = x_i (XtX)^+ x_i^t. The externally
+/// studentized residual t_i = e_i / (s_(i) * sqrt(1 - h_ii)) uses the residual variance without row i,
+/// s_(i)^2 = (SSR - e_i^2 / (1 - h_ii)) / (n - p - 1), where p = trace(H) is the rank of X. Then
+/// DFFITS_i = t_i * sqrt(h_ii / (1 - h_ii)), the scaled change in the i-th fitted value when row i is
+/// left out. Rows with h_ii = 1 have NaN DFFITS.
+///
+/// The internally studentized residual r_i = e_i / (s * sqrt(1 - h_ii)) uses s^2 = SSR / (n - p), and
+/// Cook's distance is D_i = r_i^2 / p * h_ii / (1 - h_ii) = e_i^2 / (p * s^2) * h_ii / (1 - h_ii)^2.
+/// Rows with h_ii = 1 (up to n * epsilon) have NaN r_i and infinite D_i.
+pub fn faer_row_influence<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+    how: LRSolverMethods,
+) -> RowInfluence<T> {
+    let n = x.nrows();
+    let coeffs = faer_solve_lstsq(x, y, T::zero(), has_bias, how);
+    let res = y - x * &coeffs;
+    let residuals = res.col_as_slice(0);
+    let xtx_inv = faer_pinv((x.transpose() * x).as_ref());
+    let xh = x * &xtx_inv;
+    let leverage = (0..n)
+        .map(|i| (0..x.ncols()).fold(T::zero(), |acc, j| acc + *xh.get(i, j) * *x.get(i, j)))
+        .collect::<Vec<_>>();
+
+    let ssr = res.col(0).squared_norm_l2();
+    let p = leverage.iter().fold(T::zero(), |acc, h| acc + *h);
+    let dof = T::from(n).unwrap() - p - T::one();
+    let dffits = residuals
+        .iter()
+        .zip(leverage.iter())
+        .map(|(e, h)| {
+            let one_minus_h = T::one() - *h;
+            if one_minus_h <= T::epsilon() || dof <= T::zero() {
+                return T::nan();
+            }
+            let s2 = (ssr - *e * *e / one_minus_h).max(T::zero()) / dof;
+            let t = *e / (s2 * one_minus_h).sqrt();
+            t * (*h / one_minus_h).sqrt()
+        })
+        .collect();
+
+    let s2 = ssr / (T::from(n).unwrap() - p);
+    // h_ii of a row that alone determines a coefficient is 1 only up to rounding
+    let h_tol = T::epsilon() * T::from(n).unwrap();
+    let (internal, cooks) = residuals
+        .iter()
+        .zip(leverage.iter())
+        .map(|(e, h)| {
+            let one_minus_h = T::one() - *h;
+            if one_minus_h <= h_tol {
+                return (T::nan(), T::infinity());
+            }
+            let r = *e / (s2 * one_minus_h).sqrt();
+            (r, r * r / p * *h / one_minus_h)
+        })
+        .unzip();
+
+    RowInfluence {
+        leverage,
+        dffits,
+        internal,
+        cooks,
+    }
+}
+
+/// The 2-norm condition number of x, i.e. its largest singular value over its smallest, by SVD of x
+/// itself. This is infinite if x is rank deficient or has more columns than rows, and NaN if x is empty.
+pub fn faer_cond<T: RealField + Float>(x: MatRef<T>) -> T {
+    if x.nrows() == 0 || x.ncols() == 0 {
+        return T::nan();
+    }
+    if x.nrows() < x.ncols() {
+        return T::infinity();
+    }
+    match x.thin_svd() {
+        Ok(svd) => {
+            let s = svd.S().column_vector();
+            let (max, min) = s.iter().fold((T::zero(), T::infinity()), |(max, min), v| {
+                (max.max(*v), min.min(*v))
+            });
+            if min == T::zero() {
+                T::infinity()
+            } else {
+                max / min
+            }
+        }
+        Err(_) => T::nan(),
+    }
+}
+
+/// Eigenvalues of XtX in decreasing order, as the squared singular values of x, so XtX is never formed.
+/// If x has more columns than rows, the missing eigenvalues are 0. Ridge with penalty lambda shrinks
+/// the component of the coefficients along the i-th eigenvector by d_i / (d_i + lambda).
+pub fn faer_xtx_eigenvalues<T: RealField + Float>(x: MatRef<T>) -> Result<Vec<T>, LinalgErrors> {
+    if x.nrows() == 0 || x.ncols() == 0 {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    let svd = x
+        .thin_svd()
+        .map_err(|_| LinalgErrors::Other("SVD did not converge.".into()))?;
+    let mut eigenvalues = svd
+        .S()
+        .column_vector()
+        .iter()
+        .map(|v| *v * *v)
+        .collect::<Vec<_>>();
+    eigenvalues.resize(x.ncols(), T::zero());
+    eigenvalues.sort_unstable_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(eigenvalues)
+}
+
+pub struct ConditioningReport<T: RealField + Float> {
+    pub without_bias: T, // Condition number of the features alone
+    pub with_bias: T,    // After appending a constant column
+    pub centered: T,     // After centering the features and appending a constant column
+}
+
+/// Condition numbers of the features x (without a bias column) before and after appending the bias.
+/// If the features are far from 0 relative to their spread, the constant column is nearly a linear
+/// combination of them, and with_bias is much larger than without_bias. centered is what it would be
+/// if the features were centered first, which only rescales the bias and leaves the other coefficients
+/// of an unregularized fit unchanged.
+pub fn faer_conditioning_report<T: RealField + Float>(x: MatRef<T>) -> ConditioningReport<T> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let n = T::from(nrows).unwrap();
+    let means = (0..ncols)
+        .map(|j| x.col(j).iter().fold(T::zero(), |acc, v| acc + *v) / n)
+        .collect::<Vec<_>>();
+    let with_bias = Mat::from_fn(nrows, ncols + 1, |i, j| {
+        if j < ncols {
+            *x.get(i, j)
+        } else {
+            T::one()
+        }
+    });
+    let centered = Mat::from_fn(nrows, ncols + 1, |i, j| {
+        if j < ncols {
+            *x.get(i, j) - means[j]
+        } else {
+            T::one()
+        }
+    });
+    ConditioningReport {
+        without_bias: faer_cond(x),
+        with_bias: faer_cond(with_bias.as_ref()),
+        centered: faer_cond(centered.as_ref()),
+    }
+}
+
+/// Variance inflation factors. For each feature j, column j is regressed on the other columns of x
+/// (the bias included) and VIF_j = 1 / (1 - R2_j) = TSS_j / RSS_j, where TSS_j is centered if has_bias.
+/// If has_bias, the last column of x must be the bias, which gets no VIF. A feature that is (numerically)
+/// a linear combination of the others, including a constant column when there is a bias, has R2_j = 1
+/// and VIF_j = inf. A feature with no other columns to regress on has VIF 1.
+pub fn faer_vif<T: RealField + Float>(x: MatRef<T>, has_bias: bool) -> Vec<T> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let n1 = ncols.abs_diff(has_bias as usize);
+    let n = T::from(nrows).unwrap();
+    // RSS_j below tiny * ||x_j||^2 is rounding error, i.e. R2_j = 1. TSS_j can be 0 (a constant column).
+    let tiny = T::epsilon() * n;
+    (0..n1)
+        .map(|j| {
+            if ncols == 1 {
+                return T::one();
+            }
+            let xj = x.get(.., j..j + 1);
+            let tss = if has_bias {
+                let mean = xj.col(0).iter().fold(T::zero(), |acc, v| acc + *v) / n;
+                xj.col(0)
+                    .iter()
+                    .fold(T::zero(), |acc, v| acc + (*v - mean) * (*v - mean))
+            } else {
+                xj.col(0).squared_norm_l2()
+            };
+            let others = Mat::from_fn(nrows, ncols - 1, |i, k| {
+                *x.get(i, if k < j { k } else { k + 1 })
+            });
+            let coeffs = faer_solve_lstsq(
+                others.as_ref(),
+                xj,
+                T::zero(),
+                has_bias,
+                LRSolverMethods::QR,
+            );
+            let rss = (xj - &others * &coeffs).col(0).squared_norm_l2();
+            if rss <= tiny * xj.col(0).squared_norm_l2() {
+                T::infinity()
+            } else {
+                tss / rss
+            }
+        })
+        .collect()
+}
+
+/// The Chow test for a structural break. The model is fit on all rows (pooled), and separately on the
+/// rows before (after[i] = false) and after (after[i] = true) the break. With k the number of columns of x
+/// (bias included), F = ((RSS_pooled - RSS_1 - RSS_2) / k) / ((RSS_1 + RSS_2) / (n - 2k)), which is F(k, n - 2k)
+/// distributed under no break. Returns (F, k, n - 2k). Each part must have at least k rows.
+pub fn faer_chow_test<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    after: &[bool],
+    how: LRSolverMethods,
+) -> Result<(T, usize, usize), LinalgErrors> {
+    let (n, k) = (x.nrows(), x.ncols());
+    if after.len() != n || y.nrows() != n {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    let n2 = after.iter().filter(|b| **b).count();
+    let n1 = n - n2;
+    if k == 0 || n1 < k || n2 < k || n <= k + k {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    let rss = |rows: &[usize]| {
+        let sub_x = Mat::from_fn(rows.len(), k, |i, j| *x.get(rows[i], j));
+        let sub_y = Mat::from_fn(rows.len(), 1, |i, _| *y.get(rows[i], 0));
+        faer_lstsq_residuals(sub_x.as_ref(), sub_y.as_ref(), how)
+            .col(0)
+            .squared_norm_l2()
+    };
+    let (before_rows, after_rows): (Vec<usize>, Vec<usize>) = (0..n).partition(|i| !after[*i]);
+    let rss_pooled = faer_lstsq_residuals(x, y, how).col(0).squared_norm_l2();
+    let rss_split = rss(&before_rows) + rss(&after_rows);
+
+    let df1 = k;
+    let df2 = n - k - k;
+    let f =
+        ((rss_pooled - rss_split) / T::from(df1).unwrap()) / (rss_split / T::from(df2).unwrap());
+    Ok((f.max(T::zero()), df1, df2))
+}
+
+/// The CUSUM of recursive residuals w (Brown, Durbin and Evans, 1975), W_r = sum_{j <= r} w_j / s, where
+/// s^2 = sum(w^2) / m is the residual variance and m the number of non-NaN residuals. Also returns the
+/// 5% significance bounds at each step, +/- 0.948 * (sqrt(m) + 2 r / sqrt(m)), where r = 1..=m counts the
+/// residuals so far. The CUSUM crossing a bound rejects parameter stability. NaN residuals are skipped and
+/// their CUSUM and bound are NaN.
+pub fn recursive_cusum<T: Float>(w: &[T]) -> (Vec<T>, Vec<T>) {
+    let (m, ss) = w
+        .iter()
+        .filter(|v| !v.is_nan())
+        .fold((0usize, T::zero()), |(m, ss), v| (m + 1, ss + *v * *v));
+    let m = T::from(m).unwrap();
+    let s = (ss / m).sqrt();
+    let sqrt_m = m.sqrt();
+    let a = T::from(0.948).unwrap();
+    let two = T::from(2.).unwrap();
+    let mut cusum = Vec::with_capacity(w.len());
+    let mut bound = Vec::with_capacity(w.len());
+    let mut acc = T::zero();
+    let mut r = T::zero();
+    for v in w {
+        if v.is_nan() {
+            cusum.push(T::nan());
+            bound.push(T::nan());
+        } else {
+            acc = acc + *v;
+            r = r + T::one();
+            cusum.push(acc / s);
+            bound.push(a * (sqrt_m + two * r / sqrt_m));
+        }
+    }
+    (cusum, bound)
+}
+
+/// The Newey-West lag rule of thumb, floor(4 * (n / 100)^(2/9)), for n time periods.
+pub fn newey_west_default_lags(n: usize) -> usize {
+    (4.0 * (n as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize
+}
+
+/// Newey-West long run covariance of the rows of h, which are moment conditions in time order. With
+/// the Bartlett kernel, S = G_0 + sum_{l = 1..=lags} (1 - l / (lags + 1)) (G_l + G_l^t), where
+/// G_l = sum_t h_t^t h_{t - l}. The rows are not demeaned.
+pub fn newey_west_long_run<T: RealField + Float>(h: MatRef<T>, lags: usize) -> Mat<T> {
+    let (n, k) = (h.nrows(), h.ncols());
+    let mut s = h.transpose() * h;
+    for l in 1..=lags.min(n.saturating_sub(1)) {
+        let w = T::one() - T::from(l).unwrap() / T::from(lags + 1).unwrap();
+        let g = h.get(l.., ..).transpose() * h.get(..n - l, ..);
+        s = Mat::from_fn(k, k, |i, j| {
+            *s.get(i, j) + w * (*g.get(i, j) + *g.get(j, i))
+        });
+    }
+    s
+}
+
+pub struct DriscollKraay<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub cov: Mat<T>, // Driscoll-Kraay covariance of the coefficients
+}
+
+/// OLS with Driscoll-Kraay (1998) standard errors for panel data, which are robust to heteroskedasticity,
+/// serial correlation and cross-sectional correlation. time_of[i] is the time period of row i, in
+/// 0..n_periods, and periods must be evenly spaced. The moment conditions x_i e_i are summed over the
+/// rows of each period, h_t = sum_{i in t} x_i e_i, and the covariance is the sandwich
+/// (XtX)^-1 S (XtX)^-1, where S is the Newey-West long run covariance of the h_t with the given lags.
+/// There is no small sample correction.
+pub fn faer_driscoll_kraay<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    time_of: &[usize],
+    n_periods: usize,
+    lags: usize,
+    how: LRSolverMethods,
+) -> Result<DriscollKraay<T>, LinalgErrors> {
+    let (n, k) = (x.nrows(), x.ncols());
+    if time_of.len() != n || y.nrows() != n {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    if k == 0 || n <= k || n_periods < 2 {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    if time_of.iter().any(|t| *t >= n_periods) {
+        return Err(LinalgErrors::Other("Time period is out of range.".into()));
+    }
+    let coefficients = faer_solve_lstsq(x, y, T::zero(), false, how);
+    let res = y - x * &coefficients;
+    let mut h = Mat::<T>::zeros(n_periods, k);
+    for (i, t) in time_of.iter().copied().enumerate() {
+        let e = *res.get(i, 0);
+        for j in 0..k {
+            *h.get_mut(t, j) = *h.get(t, j) + *x.get(i, j) * e;
+        }
+    }
+    let s = newey_west_long_run(h.as_ref(), lags);
+    let bread = faer_pinv((x.transpose() * x).as_ref());
+    let cov = &bread * &s * &bread;
+    Ok(DriscollKraay { coefficients, cov })
+}
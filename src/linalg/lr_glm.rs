@@ -0,0 +1,766 @@
+/// Generalized linear models (Gaussian, logistic, Poisson, Gamma) by Iteratively Reweighted Least
+/// Squares (IRLS).
+use super::{lr_solvers::faer_weighted_lstsq, LRSolverMethods, LinalgErrors, LinearRegression};
+use faer::{mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+
+/// Max number of times a Newton step is halved when it doesn't decrease the objective.
+const MAX_HALVINGS: usize = 30;
+/// If every fitted probability is within this of its 0/1 label, the classes are separated.
+const SEPARATION_TOL: f64 = 1e-8;
+
+#[inline(always)]
+fn sigmoid<T: Float>(eta: T) -> T {
+    if eta >= T::zero() {
+        (T::one() + (-eta).exp()).recip()
+    } else {
+        let e = eta.exp();
+        e / (T::one() + e)
+    }
+}
+
+/// log(1 + exp(eta)), without overflow.
+#[inline(always)]
+fn softplus<T: Float>(eta: T) -> T {
+    eta.max(T::zero()) + (-eta.abs()).exp().ln_1p()
+}
+
+/// The distribution of the target, which gives the variance function of the GLM.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum GlmFamily {
+    /// Normal. y real, constant variance
+    Gaussian,
+    /// Binary outcomes or proportions. y in [0, 1], variance mu (1 - mu). A fractional y is not
+    /// rounded: the fit is quasi-binomial, with the same estimating equations as the binomial
+    #[default]
+    Binomial,
+    /// Counts. y >= 0, variance mu
+    Poisson,
+    /// Positive, skewed. y > 0, variance mu^2
+    Gamma,
+}
+
+impl TryFrom<&str> for GlmFamily {
+    type Error = LinalgErrors;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "gaussian" => Ok(Self::Gaussian),
+            "binomial" => Ok(Self::Binomial),
+            "poisson" => Ok(Self::Poisson),
+            "gamma" => Ok(Self::Gamma),
+            _ => Err(LinalgErrors::Other(format!(
+                "Unknown GLM family: {}.",
+                value
+            ))),
+        }
+    }
+}
+
+/// The link g of a GLM, with g(mu) = eta = x b + offset.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum GlmLink {
+    Identity,
+    #[default]
+    Logit,
+    Log,
+    Inverse,
+}
+
+impl TryFrom<&str> for GlmLink {
+    type Error = LinalgErrors;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "identity" => Ok(Self::Identity),
+            "logit" => Ok(Self::Logit),
+            "log" => Ok(Self::Log),
+            "inverse" => Ok(Self::Inverse),
+            _ => Err(LinalgErrors::Other(format!("Unknown GLM link: {}.", value))),
+        }
+    }
+}
+
+impl GlmLink {
+    /// g(mu)
+    #[inline(always)]
+    fn link<T: Float>(&self, mu: T) -> T {
+        match self {
+            GlmLink::Identity => mu,
+            GlmLink::Logit => (mu / (T::one() - mu)).ln(),
+            GlmLink::Log => mu.ln(),
+            GlmLink::Inverse => mu.recip(),
+        }
+    }
+
+    /// The mean of y given the linear predictor eta, i.e. the inverse link.
+    #[inline(always)]
+    pub fn mean<T: Float>(&self, eta: T) -> T {
+        match self {
+            GlmLink::Identity => eta,
+            GlmLink::Logit => sigmoid(eta),
+            GlmLink::Log => eta.exp(),
+            GlmLink::Inverse => eta.recip(),
+        }
+    }
+
+    /// d mu / d eta, in terms of the mean mu.
+    #[inline(always)]
+    fn mu_eta<T: Float>(&self, mu: T) -> T {
+        match self {
+            GlmLink::Identity => T::one(),
+            GlmLink::Logit => mu * (T::one() - mu),
+            GlmLink::Log => mu,
+            GlmLink::Inverse => -(mu * mu),
+        }
+    }
+}
+
+impl GlmFamily {
+    /// The canonical link, with which IRLS is exactly Newton's method.
+    pub fn canonical_link(&self) -> GlmLink {
+        match self {
+            GlmFamily::Gaussian => GlmLink::Identity,
+            GlmFamily::Binomial => GlmLink::Logit,
+            GlmFamily::Poisson => GlmLink::Log,
+            GlmFamily::Gamma => GlmLink::Inverse,
+        }
+    }
+
+    /// The variance of y at the mean mu, up to dispersion.
+    #[inline(always)]
+    fn variance<T: Float>(&self, mu: T) -> T {
+        match self {
+            GlmFamily::Gaussian => T::one(),
+            GlmFamily::Binomial => mu * (T::one() - mu),
+            GlmFamily::Poisson => mu,
+            GlmFamily::Gamma => mu * mu,
+        }
+    }
+
+    /// The negative log likelihood of one row, up to dispersion and without the terms that don't
+    /// depend on eta. With the canonical links of the binomial and Poisson families, it is computed
+    /// from eta directly, which doesn't overflow. Otherwise it is NaN if the mean is out of the range
+    /// of the family.
+    #[inline(always)]
+    fn nll<T: Float>(&self, link: GlmLink, eta: T, y: T) -> T {
+        match (self, link) {
+            (GlmFamily::Binomial, GlmLink::Logit) => softplus(eta) - y * eta,
+            (GlmFamily::Poisson, GlmLink::Log) => eta.exp() - y * eta,
+            _ => {
+                let mu = link.mean(eta);
+                if !self.valid_mean(mu) {
+                    return T::nan();
+                }
+                match self {
+                    GlmFamily::Gaussian => (y - mu) * (y - mu) / (T::one() + T::one()),
+                    GlmFamily::Binomial => -(xlogy(y, mu) + xlogy(T::one() - y, T::one() - mu)),
+                    GlmFamily::Poisson => mu - xlogy(y, mu),
+                    GlmFamily::Gamma => y / mu + mu.ln(),
+                }
+            }
+        }
+    }
+
+    /// Whether mu is in the range of the means of the family.
+    #[inline(always)]
+    fn valid_mean<T: Float>(&self, mu: T) -> bool {
+        match self {
+            GlmFamily::Gaussian => mu.is_finite(),
+            GlmFamily::Binomial => mu > T::zero() && mu < T::one(),
+            GlmFamily::Poisson | GlmFamily::Gamma => mu > T::zero() && mu.is_finite(),
+        }
+    }
+
+    fn check_target<T: Float>(&self, y: T) -> bool {
+        match self {
+            GlmFamily::Gaussian => y.is_finite(),
+            GlmFamily::Binomial => y >= T::zero() && y <= T::one(),
+            GlmFamily::Poisson => y >= T::zero() && y.is_finite(),
+            GlmFamily::Gamma => y > T::zero() && y.is_finite(),
+        }
+    }
+
+    fn target_error(&self) -> LinalgErrors {
+        match self {
+            GlmFamily::Gaussian => {
+                LinalgErrors::Other("Target for a Gaussian GLM must be finite.".to_string())
+            }
+            GlmFamily::Binomial => {
+                LinalgErrors::Other("Target for logistic regression must be in [0, 1].".to_string())
+            }
+            GlmFamily::Poisson => LinalgErrors::Other(
+                "Target for Poisson regression must be non-negative.".to_string(),
+            ),
+            GlmFamily::Gamma => {
+                LinalgErrors::Other("Target for a Gamma GLM must be positive.".to_string())
+            }
+        }
+    }
+}
+
+/// x * ln(y), which is 0 if x = 0.
+#[inline(always)]
+fn xlogy<T: Float>(x: T, y: T) -> T {
+    if x == T::zero() {
+        T::zero()
+    } else {
+        x * y.ln()
+    }
+}
+
+/// The linear predictor x b + offset.
+fn linear_predictor<T: RealField + Float>(
+    x: MatRef<T>,
+    coeffs: &Mat<T>,
+    offset: Option<&[T]>,
+) -> Mat<T> {
+    let mut eta = x * coeffs;
+    if let Some(o) = offset {
+        eta.col_mut(0)
+            .iter_mut()
+            .zip(o.iter())
+            .for_each(|(e, oi)| *e = *e + *oi);
+    }
+    eta
+}
+
+/// The penalized negative log likelihood sum(pw_i * nll(eta_i, y_i)) + lambda / 2 * ||b||^2, where
+/// pw are the prior weights (1 if None) and the bias (the last coefficient if has_bias) is not penalized.
+#[allow(clippy::too_many_arguments)]
+fn glm_objective<T: RealField + Float>(
+    family: GlmFamily,
+    link: GlmLink,
+    eta: &Mat<T>,
+    y: MatRef<T>,
+    weights: Option<&[T]>,
+    coeffs: &Mat<T>,
+    lambda: T,
+    has_bias: bool,
+) -> T {
+    let nll = (0..y.nrows()).fold(T::zero(), |acc, i| {
+        let pw = weights.map_or(T::one(), |w| w[i]);
+        acc + pw * family.nll(link, *eta.get(i, 0), *y.get(i, 0))
+    });
+    let n_penalized = coeffs.nrows() - has_bias as usize;
+    let ss = (0..n_penalized).fold(T::zero(), |acc, i| {
+        acc + *coeffs.get(i, 0) * *coeffs.get(i, 0)
+    });
+    nll + lambda * ss / (T::one() + T::one())
+}
+
+pub struct GlmFit<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub n_iter: usize,
+    pub converged: bool,
+    pub separated: bool, // The classes are (almost) perfectly separated. Coefficients are not finite in theory
+}
+
+/// Fits a GLM by IRLS, i.e. Fisher scoring on the penalized negative log likelihood
+/// sum(pw * nll(x b + offset, y)) + lambda / 2 * ||b||^2, where pw are the prior weights (all 1 if
+/// None). Each step is the weighted least square of the working response z = eta + (y - mu) / mu'
+/// on x with weights pw * mu'^2 / var(mu), solved by faer_weighted_lstsq, where mu' = d mu / d eta.
+/// With the canonical link of the family, mu' = var(mu) and this is Newton's method. The L2 penalty
+/// is added as extra rows sqrt(lambda) * I with target 0 and weight 1, one for each coefficient
+/// except the bias, which is not penalized. If has_bias, the last column of x must be the bias. The
+/// offset, if given, is added to the linear predictor and is not estimated. E.g. for Poisson rates,
+/// it is the log of the exposure.
+///
+/// The iteration starts from b = 0, unless the mean at eta = 0 is out of the range of the family
+/// (e.g. Gamma with the inverse link), in which case the first step starts from the means
+/// (y + mean(y)) / 2. It stops when the max absolute change in the coefficients is < tol, or when
+/// max_iter is reached. A step that increases the objective, or leaves the range of the family, is
+/// halved, up to 30 times. If that still doesn't decrease the objective, the iteration stops, and it
+/// has only converged if the full step was already < tol.
+///
+/// For the binomial family with the logit link, if the classes are perfectly separated and lambda = 0,
+/// the maximum likelihood estimate doesn't exist and the coefficients grow without bound. In that case,
+/// the iteration stops as soon as all fitted probabilities are within 1e-8 of the labels, and
+/// separated is set. A positive lambda always gives finite coefficients.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_glm_irls<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    weights: Option<&[T]>,
+    offset: Option<&[T]>,
+    family: GlmFamily,
+    link: GlmLink,
+    lambda: T,
+    has_bias: bool,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+) -> GlmFit<T> {
+    let (n, p) = (x.nrows(), x.ncols());
+    let n_penalized = if lambda > T::zero() {
+        p - has_bias as usize
+    } else {
+        0
+    };
+    let sqrt_lambda = lambda.sqrt();
+    // x with the penalty rows. Only the top n rows of z and w change in the iteration.
+    let x_aug = Mat::from_fn(n + n_penalized, p, |i, j| {
+        if i < n {
+            *x.get(i, j)
+        } else if i - n == j {
+            sqrt_lambda
+        } else {
+            T::zero()
+        }
+    });
+    let mut z = Mat::<T>::zeros(n + n_penalized, 1);
+    let mut w = vec![T::one(); n + n_penalized];
+    let min_w = T::epsilon();
+    let sep_tol = T::from(SEPARATION_TOL).unwrap();
+    let half = T::from(0.5).unwrap();
+    let off = |i: usize| offset.map_or(T::zero(), |o| o[i]);
+    let prior = |i: usize| weights.map_or(T::one(), |w| w[i]);
+    let canonical = link == family.canonical_link();
+
+    let mut coeffs = Mat::<T>::zeros(p, 1);
+    let (mut eta, mut obj) = if family.valid_mean(link.mean(T::zero())) {
+        let eta = linear_predictor(x, &coeffs, offset);
+        let obj = glm_objective(family, link, &eta, y, weights, &coeffs, lambda, has_bias);
+        (eta, obj)
+    } else {
+        // The first step starts from the data. There are no coefficients yet, so it always goes.
+        let y_mean = (0..n).fold(T::zero(), |acc, i| acc + *y.get(i, 0)) / T::from(n).unwrap();
+        let eta = Mat::from_fn(n, 1, |i, _| link.link((*y.get(i, 0) + y_mean) * half));
+        (eta, T::infinity())
+    };
+    let mut n_iter = 0;
+    let mut converged = false;
+    let mut separated = false;
+    while n_iter < max_iter {
+        n_iter += 1;
+        for (i, wi) in w.iter_mut().take(n).enumerate() {
+            let e = *eta.get(i, 0);
+            let mu = link.mean(e);
+            let var = family.variance(mu);
+            let resid = *y.get(i, 0) - mu;
+            if canonical {
+                // mu' = var(mu)
+                *wi = var.max(min_w);
+                *z.get_mut(i, 0) = e - off(i) + resid / *wi;
+                *wi = *wi * prior(i);
+            } else {
+                // Keep the sign of mu' so z moves in the right direction
+                let d = match link.mu_eta(mu) {
+                    d if d.abs() >= min_w => d,
+                    d if d < T::zero() => -min_w,
+                    _ => min_w,
+                };
+                *wi = prior(i) * (d * d / var.max(min_w)).max(min_w);
+                *z.get_mut(i, 0) = e - off(i) + resid / d;
+            }
+        }
+        let mut new_coeffs = faer_weighted_lstsq(x_aug.as_ref(), z.as_ref(), &w, how);
+        let mut new_eta = linear_predictor(x, &new_coeffs, offset);
+        let objective = |eta: &Mat<T>, coeffs: &Mat<T>| {
+            glm_objective(family, link, eta, y, weights, coeffs, lambda, has_bias)
+        };
+        let mut new_obj = objective(&new_eta, &new_coeffs);
+        let full_step = (0..p).fold(T::zero(), |acc, i| {
+            acc.max((*new_coeffs.get(i, 0) - *coeffs.get(i, 0)).abs())
+        });
+        // Step halving
+        let mut halvings = 0;
+        while (new_obj > obj || new_obj.is_nan()) && halvings < MAX_HALVINGS {
+            new_coeffs = Mat::from_fn(p, 1, |i, _| {
+                (*new_coeffs.get(i, 0) + *coeffs.get(i, 0)) * half
+            });
+            new_eta = linear_predictor(x, &new_coeffs, offset);
+            new_obj = objective(&new_eta, &new_coeffs);
+            halvings += 1;
+        }
+        if new_obj > obj || new_obj.is_nan() {
+            // No decrease along the Newton direction. If the full step is already within tol, we are at
+            // the minimum up to rounding. Otherwise the step failed, e.g. on an overflow.
+            converged = full_step < tol;
+            break;
+        }
+        let max_change = (0..p).fold(T::zero(), |acc, i| {
+            acc.max((*new_coeffs.get(i, 0) - *coeffs.get(i, 0)).abs())
+        });
+        coeffs = new_coeffs;
+        eta = new_eta;
+        obj = new_obj;
+        if max_change < tol {
+            converged = true;
+            break;
+        }
+        if family == GlmFamily::Binomial
+            && link == GlmLink::Logit
+            && lambda <= T::zero()
+            && (0..n).all(|i| (*y.get(i, 0) - sigmoid(*eta.get(i, 0))).abs() < sep_tol)
+        {
+            separated = true;
+            break;
+        }
+    }
+
+    GlmFit {
+        coefficients: coeffs,
+        n_iter,
+        converged,
+        separated,
+    }
+}
+
+/// Checks the shapes and the target for a GLM fit.
+fn check_glm_data<T: RealField + Float>(
+    family: GlmFamily,
+    X: MatRef<T>,
+    y: MatRef<T>,
+) -> Result<(), LinalgErrors> {
+    if X.nrows() != y.nrows() {
+        Err(LinalgErrors::DimensionMismatch)
+    } else if X.nrows() < X.ncols() || X.nrows() == 0 {
+        Err(LinalgErrors::NotEnoughData)
+    } else if y.col(0).iter().any(|v| !family.check_target(*v)) {
+        Err(family.target_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Binary logistic regression, fit by IRLS. See faer_glm_irls. As a LinearRegression, the
+/// coefficients and the bias are on the log-odds scale, and predict returns the log-odds. Use
+/// predict_proba for the probabilities.
+pub struct LogisticRegression<T: RealField + Float> {
+    pub solver: LRSolverMethods,
+    pub lambda: T,
+    pub has_bias: bool,
+    pub tol: T,
+    pub max_iter: usize,
+    pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + 1) x 1 if there is bias
+    pub n_iter: usize,        // Number of IRLS iterations in the last fit
+    pub converged: bool,      // Whether the last fit converged
+    pub separated: bool,      // Whether the classes were perfectly separated in the last fit
+}
+
+impl<T: RealField + Float> LogisticRegression<T> {
+    pub fn new(solver: &str, lambda: T, has_bias: bool, tol: T, max_iter: usize) -> Self {
+        LogisticRegression {
+            solver: solver.into(),
+            lambda,
+            has_bias,
+            tol,
+            max_iter,
+            coefficients: Mat::new(),
+            n_iter: 0,
+            converged: false,
+            separated: false,
+        }
+    }
+
+    /// The predicted probabilities of class 1, as a m x 1 matrix.
+    pub fn predict_proba(&self, X: MatRef<T>) -> Result<Mat<T>, LinalgErrors> {
+        let mut result = LinearRegression::predict(self, X)?;
+        result.col_mut(0).iter_mut().for_each(|v| *v = sigmoid(*v));
+        Ok(result)
+    }
+}
+
+impl<T: RealField + Float> LinearRegression<T> for LogisticRegression<T> {
+    fn fitted_values(&self) -> MatRef<T> {
+        self.coefficients.as_ref()
+    }
+
+    fn has_bias(&self) -> bool {
+        self.has_bias
+    }
+
+    fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
+        let fit = faer_glm_irls(
+            X,
+            y,
+            None,
+            None,
+            GlmFamily::Binomial,
+            GlmLink::Logit,
+            self.lambda,
+            self.has_bias,
+            self.solver,
+            self.tol,
+            self.max_iter,
+        );
+        self.coefficients = fit.coefficients;
+        self.n_iter = fit.n_iter;
+        self.converged = fit.converged;
+        self.separated = fit.separated;
+    }
+
+    fn fit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        check_glm_data(GlmFamily::Binomial, X, y)?;
+        self.fit_unchecked(X, y);
+        Ok(())
+    }
+}
+
+/// Poisson regression with log link, fit by IRLS. See faer_glm_irls. As a LinearRegression, the
+/// coefficients and the bias are on the log scale, and predict returns the log of the mean. Use
+/// predict_mean for the mean, exp(x b + offset). For rates, fit with the log of the exposure as the
+/// offset, which is not estimated.
+pub struct PoissonRegression<T: RealField + Float> {
+    pub solver: LRSolverMethods,
+    pub lambda: T,
+    pub has_bias: bool,
+    pub tol: T,
+    pub max_iter: usize,
+    pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + 1) x 1 if there is bias
+    pub n_iter: usize,        // Number of IRLS iterations in the last fit
+    pub converged: bool,      // Whether the last fit converged
+}
+
+impl<T: RealField + Float> PoissonRegression<T> {
+    pub fn new(solver: &str, lambda: T, has_bias: bool, tol: T, max_iter: usize) -> Self {
+        PoissonRegression {
+            solver: solver.into(),
+            lambda,
+            has_bias,
+            tol,
+            max_iter,
+            coefficients: Mat::new(),
+            n_iter: 0,
+            converged: false,
+        }
+    }
+
+    fn fit_with_offset_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>, offset: Option<&[T]>) {
+        let fit = faer_glm_irls(
+            X,
+            y,
+            None,
+            offset,
+            GlmFamily::Poisson,
+            GlmLink::Log,
+            self.lambda,
+            self.has_bias,
+            self.solver,
+            self.tol,
+            self.max_iter,
+        );
+        self.coefficients = fit.coefficients;
+        self.n_iter = fit.n_iter;
+        self.converged = fit.converged;
+    }
+
+    /// Fits with an offset, which is added to the linear predictor and is not estimated. Same as fit
+    /// otherwise.
+    pub fn fit_with_offset(
+        &mut self,
+        X: MatRef<T>,
+        y: MatRef<T>,
+        offset: Option<&[T]>,
+    ) -> Result<(), LinalgErrors> {
+        check_glm_data(GlmFamily::Poisson, X, y)?;
+        if offset.is_some_and(|o| o.len() != X.nrows()) {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        self.fit_with_offset_unchecked(X, y, offset);
+        Ok(())
+    }
+
+    /// The predicted means exp(x b + offset), as a m x 1 matrix.
+    pub fn predict_mean(&self, X: MatRef<T>, offset: Option<&[T]>) -> Result<Mat<T>, LinalgErrors> {
+        if offset.is_some_and(|o| o.len() != X.nrows()) {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        let mut result = LinearRegression::predict(self, X)?;
+        result
+            .col_mut(0)
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v = (*v + offset.map_or(T::zero(), |o| o[i])).exp());
+        Ok(result)
+    }
+}
+
+impl<T: RealField + Float> LinearRegression<T> for PoissonRegression<T> {
+    fn fitted_values(&self) -> MatRef<T> {
+        self.coefficients.as_ref()
+    }
+
+    fn has_bias(&self) -> bool {
+        self.has_bias
+    }
+
+    fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
+        self.fit_with_offset_unchecked(X, y, None);
+    }
+
+    fn fit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        self.fit_with_offset(X, y, None)
+    }
+}
+
+/// A GLM with any family and link, fit by IRLS. See faer_glm_irls. As a LinearRegression, the
+/// coefficients and the bias are on the scale of the link, and predict returns the linear predictor
+/// x b. Use predict_mean for the mean g^-1(x b + offset). LogisticRegression and PoissonRegression
+/// are the same as this with the canonical link, without weights.
+pub struct GlmRegression<T: RealField + Float> {
+    pub family: GlmFamily,
+    pub link: GlmLink,
+    pub solver: LRSolverMethods,
+    pub lambda: T,
+    pub has_bias: bool,
+    pub tol: T,
+    pub max_iter: usize,
+    pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + 1) x 1 if there is bias
+    pub n_iter: usize,        // Number of IRLS iterations in the last fit
+    pub converged: bool,      // Whether the last fit converged
+}
+
+impl<T: RealField + Float> GlmRegression<T> {
+    /// If link is None, the canonical link of the family is used.
+    pub fn new(
+        family: GlmFamily,
+        link: Option<GlmLink>,
+        solver: &str,
+        lambda: T,
+        has_bias: bool,
+        tol: T,
+        max_iter: usize,
+    ) -> Self {
+        GlmRegression {
+            family,
+            link: link.unwrap_or(family.canonical_link()),
+            solver: solver.into(),
+            lambda,
+            has_bias,
+            tol,
+            max_iter,
+            coefficients: Mat::new(),
+            n_iter: 0,
+            converged: false,
+        }
+    }
+
+    fn fit_weighted_unchecked(
+        &mut self,
+        X: MatRef<T>,
+        y: MatRef<T>,
+        weights: Option<&[T]>,
+        offset: Option<&[T]>,
+    ) {
+        let fit = faer_glm_irls(
+            X,
+            y,
+            weights,
+            offset,
+            self.family,
+            self.link,
+            self.lambda,
+            self.has_bias,
+            self.solver,
+            self.tol,
+            self.max_iter,
+        );
+        self.coefficients = fit.coefficients;
+        self.n_iter = fit.n_iter;
+        self.converged = fit.converged;
+    }
+
+    /// Fits with prior weights, which must be non-negative, and an offset, which is added to the
+    /// linear predictor and is not estimated. Same as fit otherwise.
+    pub fn fit_weighted(
+        &mut self,
+        X: MatRef<T>,
+        y: MatRef<T>,
+        weights: Option<&[T]>,
+        offset: Option<&[T]>,
+    ) -> Result<(), LinalgErrors> {
+        check_glm_data(self.family, X, y)?;
+        if weights.is_some_and(|w| w.len() != X.nrows())
+            || offset.is_some_and(|o| o.len() != X.nrows())
+        {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        if weights.is_some_and(|w| {
+            w.iter()
+                .any(|v| v.is_nan() || *v < T::zero() || !v.is_finite())
+        }) {
+            return Err(LinalgErrors::Other(
+                "Weights must be finite and non-negative.".to_string(),
+            ));
+        }
+        self.fit_weighted_unchecked(X, y, weights, offset);
+        Ok(())
+    }
+
+    /// The predicted means g^-1(x b + offset), as a m x 1 matrix.
+    pub fn predict_mean(&self, X: MatRef<T>, offset: Option<&[T]>) -> Result<Mat<T>, LinalgErrors> {
+        if offset.is_some_and(|o| o.len() != X.nrows()) {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        let mut result = LinearRegression::predict(self, X)?;
+        result
+            .col_mut(0)
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, v)| *v = self.link.mean(*v + offset.map_or(T::zero(), |o| o[i])));
+        Ok(result)
+    }
+}
+
+impl<T: RealField + Float> LinearRegression<T> for GlmRegression<T> {
+    fn fitted_values(&self) -> MatRef<T> {
+        self.coefficients.as_ref()
+    }
+
+    fn has_bias(&self) -> bool {
+        self.has_bias
+    }
+
+    fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
+        self.fit_weighted_unchecked(X, y, None, None);
+    }
+
+    fn fit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        self.fit_weighted(X, y, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fit(y: &[f64], max_iter: usize) -> GlmFit<f64> {
+        let x = Mat::from_fn(y.len(), 1, |_, _| 1.0);
+        let y = Mat::from_fn(y.len(), 1, |i, _| y[i]);
+        faer_glm_irls(
+            x.as_ref(),
+            y.as_ref(),
+            None,
+            None,
+            GlmFamily::Poisson,
+            GlmLink::Log,
+            0.0,
+            false,
+            LRSolverMethods::QR,
+            1e-8,
+            max_iter,
+        )
+    }
+
+    #[test]
+    fn test_irls_failed_step_is_not_converged() {
+        // The first step puts exp(eta) out of range, and no halving brings it back
+        let failed = fit(&[0.0, 1e300], 100);
+        assert!(!failed.converged);
+        assert_eq!(failed.n_iter, 1);
+
+        let ok = fit(&[1.0, 2.0, 3.0], 100);
+        assert!(ok.converged);
+        assert!((ok.coefficients.get(0, 0).exp() - 2.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_family_and_link_from_str() {
+        assert!(GlmFamily::try_from("poisson").ok() == Some(GlmFamily::Poisson));
+        assert!(GlmFamily::try_from("binomial").ok() == Some(GlmFamily::Binomial));
+        assert!(GlmFamily::try_from("poison").is_err());
+        assert!(GlmLink::try_from("logit").ok() == Some(GlmLink::Logit));
+        assert!(GlmLink::try_from("probit").is_err());
+    }
+}
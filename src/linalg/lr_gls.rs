@@ -0,0 +1,97 @@
+/// Feasible GLS for linear regression with AR(1) errors.
+use super::{lr_solvers::faer_solve_lstsq, LRSolverMethods};
+use faer::{mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+
+pub struct Ar1Fit<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub rho: T,
+    pub n_iter: usize,
+    pub converged: bool,
+}
+
+/// The lag 1 autocorrelation of the residuals: sum(r_t * r_{t-1}) / sum(r_{t-1}^2).
+/// Residuals from a regression with bias have mean 0, so they are not demeaned.
+pub fn ar1_rho<T: RealField + Float>(residuals: &[T]) -> T {
+    let (num, den) = residuals
+        .windows(2)
+        .fold((T::zero(), T::zero()), |(num, den), w| {
+            (num + w[1] * w[0], den + w[0] * w[0])
+        });
+    if den > T::zero() {
+        num / den
+    } else {
+        T::zero()
+    }
+}
+
+/// Quasi-differences the columns of m: m_t - rho * m_{t-1} for t >= 1. If prais_winsten is true,
+/// the first row is kept and scaled by sqrt(1 - rho^2). Otherwise, the first row is dropped.
+/// Note: a bias column of 1s becomes a column of (1 - rho)s, so its coefficient is still the
+/// intercept of the original model.
+pub fn ar1_transform<T: RealField + Float>(m: MatRef<T>, rho: T, prais_winsten: bool) -> Mat<T> {
+    let n = m.nrows();
+    if prais_winsten {
+        let s = (T::one() - rho * rho).max(T::zero()).sqrt();
+        Mat::from_fn(n, m.ncols(), |i, j| {
+            if i == 0 {
+                s * *m.get(0, j)
+            } else {
+                *m.get(i, j) - rho * *m.get(i - 1, j)
+            }
+        })
+    } else {
+        Mat::from_fn(n.saturating_sub(1), m.ncols(), |i, j| {
+            *m.get(i + 1, j) - rho * *m.get(i, j)
+        })
+    }
+}
+
+fn residuals<T: RealField + Float>(x: MatRef<T>, y: MatRef<T>, coeffs: &Mat<T>) -> Vec<T> {
+    let pred = x * coeffs;
+    (0..y.nrows())
+        .map(|i| *y.get(i, 0) - *pred.get(i, 0))
+        .collect()
+}
+
+/// Iterated Cochrane-Orcutt (or Prais-Winsten, if prais_winsten is true) estimation. Rows of x and y
+/// must be in time order. Starting from OLS, rho is estimated from the residuals of the original
+/// model, the data is quasi-differenced by rho and the model is refit. This is repeated until the
+/// absolute change in rho is < tol, or max_iter is reached.
+///
+/// The estimated rho is kept inside (-1, 1) so that the transform stays stationary.
+pub fn faer_cochrane_orcutt<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    prais_winsten: bool,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+) -> Ar1Fit<T> {
+    let bound = T::one() - T::epsilon().sqrt();
+    let mut coeffs = faer_solve_lstsq(x, y, T::zero(), false, how);
+    let mut rho = T::zero();
+    let mut n_iter = 0;
+    let mut converged = false;
+    while n_iter < max_iter {
+        n_iter += 1;
+        let new_rho = ar1_rho(&residuals(x, y, &coeffs)).max(-bound).min(bound);
+        let x_star = ar1_transform(x, new_rho, prais_winsten);
+        let y_star = ar1_transform(y, new_rho, prais_winsten);
+        coeffs = faer_solve_lstsq(x_star.as_ref(), y_star.as_ref(), T::zero(), false, how);
+        let change = (new_rho - rho).abs();
+        rho = new_rho;
+        if change < tol {
+            converged = true;
+            break;
+        }
+    }
+
+    Ar1Fit {
+        coefficients: coeffs,
+        rho,
+        n_iter,
+        converged,
+    }
+}
@@ -1,5 +1,4 @@
 #![allow(non_snake_case)]
-use core::f64;
 use faer::{
     linalg::solvers::{DenseSolveCore, Solve},
     mat::Mat,
@@ -8,7 +7,25 @@ use faer::{
 use faer_traits::{math_utils::is_nan, RealField};
 use num::Float;
 
-use super::{LinalgErrors, LinearRegression};
+use super::{
+    lr_solvers::{faer_pinv, faer_qr_rank},
+    LinalgErrors, LinearRegression,
+};
+
+/// Below this many entries in the updated inverse, the Woodbury matmuls run sequentially. Spinning
+/// up the rayon pool costs far more than the work itself for the small problems rolling and
+/// recursive regressions usually have.
+const WOODBURY_PAR_THRESHOLD: usize = 128 * 128;
+
+/// Parallelism for a Woodbury update of an m x m inverse.
+#[inline(always)]
+fn woodbury_par(m: usize) -> Par {
+    if m * m < WOODBURY_PAR_THRESHOLD {
+        Par::Seq
+    } else {
+        Par::rayon(0)
+    }
+}
 
 #[inline]
 pub fn has_nan<T: RealField>(mat: MatRef<T>) -> bool {
@@ -20,18 +37,25 @@ pub struct OnlineLR<T: RealField + Float> {
     pub has_bias: bool,
     pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + ) x 1 if there is bias
     pub inv: Mat<T>,          // Current Inverse of X^t X
+    pub forget: T, // Forgetting factor in (0, 1] for update_with_forget. 1 means no forgetting
 }
 
 impl<T: RealField + Float> OnlineLR<T> {
     pub fn new(lambda: T, has_bias: bool) -> Self {
         OnlineLR {
-            lambda: lambda,
-            has_bias: has_bias,
+            lambda,
+            has_bias,
             coefficients: Mat::new(),
             inv: Mat::new(),
+            forget: T::one(),
         }
     }
 
+    pub fn with_forget(mut self, forget: T) -> Self {
+        self.forget = forget;
+        self
+    }
+
     pub fn set_coeffs_bias_inverse(
         &mut self,
         coeffs: &[T],
@@ -58,6 +82,19 @@ impl<T: RealField + Float> OnlineLR<T> {
         }
     }
 
+    /// Drops all the history: the coefficients (with the bias) and the inverse of XtX go back to the
+    /// empty state of a new model, so it must be fit again before it is updated. The settings are kept.
+    pub fn reset(&mut self) {
+        self.coefficients = Mat::new();
+        self.inv = Mat::new();
+    }
+
+    /// Resets the model and fits it on X and y, as if it were new.
+    pub fn refit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        self.reset();
+        self.fit(X, y)
+    }
+
     pub fn get_inv(&self) -> Result<MatRef<T>, LinalgErrors> {
         if self.inv.shape() == (0, 0) {
             Err(LinalgErrors::MatNotLearnedYet)
@@ -66,7 +103,9 @@ impl<T: RealField + Float> OnlineLR<T> {
         }
     }
 
-    pub fn update_unchecked(&mut self, new_x: MatRef<T>, new_y: MatRef<T>, c: T) {
+    /// Adds (c = 1) or removes (c = -1) a row. If the update would make XtX singular, e.g. when the
+    /// only row that spans a direction is removed, the model is left unchanged and this returns false.
+    pub fn update_unchecked(&mut self, new_x: MatRef<T>, new_y: MatRef<T>, c: T) -> bool {
         if self.has_bias() {
             let ones = Mat::full(new_x.nrows(), 1, T::one());
             let new_new_x = faer::concat![[new_x, ones]];
@@ -76,7 +115,7 @@ impl<T: RealField + Float> OnlineLR<T> {
                 new_new_x.as_ref(),
                 new_y,
                 c,
-            );
+            )
         } else {
             woodbury_step(
                 self.inv.as_mut(),
@@ -88,9 +127,80 @@ impl<T: RealField + Float> OnlineLR<T> {
         }
     }
 
-    pub fn update(&mut self, new_x: MatRef<T>, new_y: MatRef<T>, c: T) {
+    /// Same as update_unchecked, but rows with NaN are ignored. Returns false only if the update was
+    /// singular and skipped.
+    pub fn update(&mut self, new_x: MatRef<T>, new_y: MatRef<T>, c: T) -> bool {
         if !(has_nan(new_x) || has_nan(new_y)) {
             self.update_unchecked(new_x, new_y, c)
+        } else {
+            true
+        }
+    }
+
+    /// Updates with a block of k rows at once, using the block Sherman-Morrison-Woodbury identity
+    /// with the k x k middle term (c * I + X inv X^t)^(-1). This gives the same result as calling
+    /// update row by row with the same c, but only needs one k x k solve. Rows with NaN are ignored.
+    /// If the middle term is singular, e.g. when the removed rows are the only ones that span a
+    /// direction, the rows are applied one by one instead, and the singular ones are skipped as in
+    /// update. Returns false if any row was skipped.
+    pub fn update_batch(&mut self, new_x: MatRef<T>, new_y: MatRef<T>, c: T) -> bool {
+        let valid = (0..new_x.nrows())
+            .filter(|&i| !(has_nan(new_x.row(i).as_mat()) || has_nan(new_y.row(i).as_mat())))
+            .collect::<Vec<_>>();
+        if valid.is_empty() {
+            return true;
+        }
+        let x = Mat::from_fn(valid.len(), new_x.ncols(), |i, j| new_x[(valid[i], j)]);
+        let y = Mat::from_fn(valid.len(), 1, |i, _| new_y[(valid[i], 0)]);
+        let x = if self.has_bias() {
+            let ones = Mat::full(x.nrows(), 1, T::one());
+            faer::concat![[x, ones]]
+        } else {
+            x
+        };
+        woodbury_block_step(
+            self.inv.as_mut(),
+            self.coefficients.as_mut(),
+            x.as_ref(),
+            y.as_ref(),
+            c,
+        ) || (0..x.nrows()).fold(true, |ok, i| {
+            woodbury_step(
+                self.inv.as_mut(),
+                self.coefficients.as_mut(),
+                x.as_ref().subrows(i, 1),
+                y.as_ref().subrows(i, 1),
+                c,
+            ) && ok
+        })
+    }
+
+    /// Adds a row with exponential forgetting. Before the row is added, all previous rows are
+    /// down-weighted by the forgetting factor f, i.e. XtX becomes f * XtX, so inv is scaled by 1 / f.
+    /// The coefficients don't change by the scaling alone. This is recursive least squares (RLS) with
+    /// a forgetting factor: after t rows, the coefficients minimize sum_k f^(t - k) (y_k - x_k b)^2,
+    /// so the effective memory is about 1 / (1 - f) rows. For Ridge, the penalty decays in the same way.
+    /// With f = 1, this is the same as update with c = 1. Rows with NaN are ignored and nothing decays.
+    /// As in update, this returns false if the update would make XtX singular, and then the model is
+    /// left unchanged, without the decay.
+    pub fn update_with_forget(&mut self, new_x: MatRef<T>, new_y: MatRef<T>) -> bool {
+        if has_nan(new_x) || has_nan(new_y) {
+            return true;
+        }
+        if self.forget < T::one() {
+            let before = self.inv.clone();
+            let scale = self.forget.recip();
+            self.inv
+                .as_mut()
+                .col_iter_mut()
+                .for_each(|col| col.iter_mut().for_each(|v| *v = *v * scale));
+            let ok = self.update_unchecked(new_x, new_y, T::one());
+            if !ok {
+                self.inv = before;
+            }
+            ok
+        } else {
+            self.update_unchecked(new_x, new_y, T::one())
         }
     }
 }
@@ -123,9 +233,10 @@ impl<T: RealField + Float> LinearRegression<T> for OnlineLR<T> {
 /// Returns the coefficients for lstsq as a nrows x 1 matrix together with the inverse of XtX
 /// The uses QR (column pivot) decomposition as default method to compute inverse,
 /// Column Pivot QR is chosen to deal with rank deficient cases. It is also slightly
-/// faster compared to other methods.
+/// faster compared to other methods. If XtX is rank deficient, the pseudo-inverse is used
+/// instead, so that the coefficients are the minimum norm solution.
 #[inline(always)]
-pub fn faer_qr_lstsq_with_inv<T: RealField + Copy>(
+pub fn faer_qr_lstsq_with_inv<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
     lambda: T,
@@ -144,20 +255,27 @@ pub fn faer_qr_lstsq_with_inv<T: RealField + Copy>(
     }
 
     let qr = xtx.col_piv_qr();
-    let inv = qr.inverse();
-    let weights = qr.solve(xt * y);
-
-    (inv, weights)
+    if faer_qr_rank(qr.R()) < xtx.ncols() {
+        let inv = faer_pinv(xtx.as_ref());
+        let weights = &inv * (xt * y);
+        (inv, weights)
+    } else {
+        let inv = qr.inverse();
+        let weights = qr.solve(xt * y);
+        (inv, weights)
+    }
 }
 
 /// Given all data, we start running a lstsq starting at position n and compute new coefficients
 /// recurisively.
 /// This will return all coefficients for rows >= n. This will only be used in Polars Expressions.
+/// If has_bias, the last column of x is the bias, and it is not penalized in Ridge, same as in LR.
 pub fn faer_recursive_lstsq<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
     n: usize,
     lambda: T,
+    has_bias: bool,
 ) -> Vec<Mat<T>> {
     let xn = x.nrows();
     // x: size xn x m
@@ -171,7 +289,8 @@ pub fn faer_recursive_lstsq<T: RealField + Float>(
     // This is because if add_bias, the 1 is added to
     // all data already. No need to let OnlineLR add the 1 for the user.
     let mut online_lr = OnlineLR::new(lambda, false);
-    online_lr.fit_unchecked(x0, y0); // safe because things are checked in plugin / python functions.
+    // safe because things are checked in plugin / python functions.
+    (online_lr.inv, online_lr.coefficients) = faer_qr_lstsq_with_inv(x0, y0, lambda, has_bias);
     coefficients.push(online_lr.fitted_values().to_owned());
     for j in n..xn {
         let next_x = x.get(j..j + 1, ..); // 1 by m, m = # of columns
@@ -182,14 +301,161 @@ pub fn faer_recursive_lstsq<T: RealField + Float>(
     coefficients
 }
 
+/// Exponentially weighted recursive least squares (EWLS). Same as faer_recursive_lstsq, but each new
+/// row is added by OnlineLR::update_with_forget, so all previous rows are down-weighted by forget before
+/// it, i.e. inv is scaled by 1 / forget before each Woodbury step. The coefficients at row t minimize
+/// sum_k forget^(t - k) (y_k - x_k b)^2, where the first n rows, which give the initial fit, share the
+/// weight of row n - 1. With forget = 1, this is the same as faer_recursive_lstsq. forget must be in
+/// (0, 1]. Rows with NaN are ignored and nothing decays.
+pub fn faer_ewls<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    n: usize,
+    lambda: T,
+    forget: T,
+    has_bias: bool,
+) -> Vec<Mat<T>> {
+    let xn = x.nrows();
+    let mut coefficients = Vec::with_capacity(xn - n + 1);
+    let x0 = x.get(..n, ..);
+    let y0 = y.get(..n, ..);
+    // The bias is already in x, same as in faer_recursive_lstsq
+    let mut online_lr = OnlineLR::new(lambda, false).with_forget(forget);
+    (online_lr.inv, online_lr.coefficients) = faer_qr_lstsq_with_inv(x0, y0, lambda, has_bias);
+    coefficients.push(online_lr.fitted_values().to_owned());
+    for j in n..xn {
+        online_lr.update_with_forget(x.get(j..j + 1, ..), y.get(j..j + 1, ..));
+        coefficients.push(online_lr.fitted_values().to_owned());
+    }
+    coefficients
+}
+
+/// Same as faer_recursive_lstsq, but also returns the standard errors of the coefficients at each step.
+/// The residual sum of squares is tracked recursively: it starts from the residuals of the initial fit,
+/// and each new row adds e^2 / (1 + x P x^t), where e is the error of the prediction made before the
+/// update and P is the inverse of XtX before the update. For OLS, this is the same SSR as a full refit.
+/// For Ridge, it is an approximation. The residual variance is SSR / (#rows so far - #features), so
+/// the standard errors are NaN until there are more rows than features, and noisy early on.
+pub fn faer_recursive_lstsq_with_se<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    n: usize,
+    lambda: T,
+    has_bias: bool,
+) -> Vec<(Mat<T>, Vec<T>)> {
+    let xn = x.nrows();
+    let m = x.ncols();
+    let mut out = Vec::with_capacity(xn - n + 1);
+    let x0 = x.get(..n, ..);
+    let y0 = y.get(..n, ..);
+
+    let std_err = |inv: MatRef<T>, ssr: T, n_rows: usize| {
+        let sigma2 = if n_rows > m {
+            ssr / T::from(n_rows - m).unwrap()
+        } else {
+            T::nan()
+        };
+        (0..m)
+            .map(|j| (sigma2 * *inv.get(j, j)).sqrt())
+            .collect::<Vec<_>>()
+    };
+
+    let mut online_lr = OnlineLR::new(lambda, false);
+    (online_lr.inv, online_lr.coefficients) = faer_qr_lstsq_with_inv(x0, y0, lambda, has_bias);
+    let res = y0 - x0 * online_lr.fitted_values();
+    let mut ssr = res.col(0).squared_norm_l2();
+    let mut n_rows = n;
+    out.push((
+        online_lr.fitted_values().to_owned(),
+        std_err(online_lr.inv.as_ref(), ssr, n),
+    ));
+    for j in n..xn {
+        let next_x = x.get(j..j + 1, ..);
+        let next_y = y.get(j..j + 1, ..);
+        // Rows with NaN are skipped, same as in OnlineLR::update
+        if !(has_nan(next_x) || has_nan(next_y)) {
+            let e = *next_y.get(0, 0) - *(next_x * online_lr.fitted_values()).get(0, 0);
+            let h = *(next_x * &online_lr.inv * next_x.transpose()).get(0, 0);
+            online_lr.update_unchecked(next_x, next_y, T::one());
+            ssr = ssr + e * e / (T::one() + h);
+            n_rows += 1;
+        }
+        out.push((
+            online_lr.fitted_values().to_owned(),
+            std_err(online_lr.inv.as_ref(), ssr, n_rows),
+        ));
+    }
+    out
+}
+
+/// Recursive residuals (Brown, Durbin and Evans, 1975). The OLS fit on the first n rows is updated one
+/// row at a time, and for each row j >= n, the residual is w_j = (y_j - x_j b) / sqrt(1 + x_j P x_j^t),
+/// where b and P = (XtX)^-1 are from the rows before j. Under a constant model with iid N(0, s^2)
+/// errors, the w_j are iid N(0, s^2). This returns the xn - n residuals of rows n..xn. Rows with NaN
+/// are skipped and have a NaN residual.
+pub fn faer_recursive_residuals<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    n: usize,
+) -> Vec<T> {
+    let xn = x.nrows();
+    let mut out = Vec::with_capacity(xn.saturating_sub(n));
+    let mut online_lr = OnlineLR::new(T::zero(), false);
+    online_lr.fit_unchecked(x.get(..n, ..), y.get(..n, ..));
+    for j in n..xn {
+        let next_x = x.get(j..j + 1, ..);
+        let next_y = y.get(j..j + 1, ..);
+        if has_nan(next_x) || has_nan(next_y) {
+            out.push(T::nan());
+        } else {
+            let e = *next_y.get(0, 0) - *(next_x * online_lr.fitted_values()).get(0, 0);
+            let h = *(next_x * &online_lr.inv * next_x.transpose()).get(0, 0);
+            out.push(e / (T::one() + h).sqrt());
+            online_lr.update_unchecked(next_x, next_y, T::one());
+        }
+    }
+    out
+}
+
+/// Expanding window (walk forward) predictions. The (Ridge) fit on the first n rows is updated one row
+/// at a time, and for each row j >= n, the prediction is x_j b, where b is the fit on the rows before j.
+/// So every prediction is out of sample, and each step is a Woodbury update instead of a refit. This
+/// returns the xn - n predictions of rows n..xn. Rows with NaN are skipped and have a NaN prediction.
+pub fn faer_expanding_predictions<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    n: usize,
+    lambda: T,
+    has_bias: bool,
+) -> Vec<T> {
+    let xn = x.nrows();
+    let mut out = Vec::with_capacity(xn.saturating_sub(n));
+    let mut online_lr = OnlineLR::new(lambda, false);
+    (online_lr.inv, online_lr.coefficients) =
+        faer_qr_lstsq_with_inv(x.get(..n, ..), y.get(..n, ..), lambda, has_bias);
+    for j in n..xn {
+        let next_x = x.get(j..j + 1, ..);
+        let next_y = y.get(j..j + 1, ..);
+        if has_nan(next_x) || has_nan(next_y) {
+            out.push(T::nan());
+        } else {
+            out.push(*(next_x * online_lr.fitted_values()).get(0, 0));
+            online_lr.update_unchecked(next_x, next_y, T::one());
+        }
+    }
+    out
+}
+
 /// Given all data, we start running a lstsq starting at position n and compute new coefficients recurisively.
 /// This will return all coefficients for rows >= n. This will only be used in Polars Expressions.
-/// This supports Normal or Ridge regression
+/// This supports Normal or Ridge regression. If has_bias, the last column of x is the bias, and it is
+/// not penalized.
 pub fn faer_rolling_lstsq<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
     n: usize,
     lambda: T,
+    has_bias: bool,
 ) -> Vec<Mat<T>> {
     let xn = x.nrows();
     // x: size xn x m
@@ -203,32 +469,56 @@ pub fn faer_rolling_lstsq<T: RealField + Float>(
     // This is because if add_bias, the 1 is added to
     // all data already. No need to let OnlineLR add the 1 for the user.
     let mut online_lr = OnlineLR::new(lambda, false);
-    online_lr.fit_unchecked(x0, y0);
+    (online_lr.inv, online_lr.coefficients) = faer_qr_lstsq_with_inv(x0, y0, lambda, has_bias);
     coefficients.push(online_lr.fitted_values().to_owned());
 
     for j in n..xn {
         let remove_x = x.get(j - n..j - n + 1, ..);
         let remove_y = y.get(j - n..j - n + 1, ..);
-        online_lr.update(remove_x, remove_y, T::one().neg());
-
         let next_x = x.get(j..j + 1, ..); // 1 by m, m = # of columns
         let next_y = y.get(j..j + 1, ..); // 1 by 1
-        online_lr.update(next_x, next_y, T::one());
+        if !(online_lr.update(remove_x, remove_y, T::one().neg())
+            && online_lr.update(next_x, next_y, T::one()))
+        {
+            // A singular step. Refit the window instead.
+            refit_window(&mut online_lr, x, y, j + 1 - n..j + 1, lambda, has_bias);
+        }
         coefficients.push(online_lr.fitted_values().to_owned());
     }
     coefficients
 }
 
+/// Fits online_lr from scratch on the rows in range of x and y, without the rows with NaN. This is
+/// the fallback when a Woodbury step of a rolling regression is singular.
+fn refit_window<T: RealField + Float>(
+    online_lr: &mut OnlineLR<T>,
+    x: MatRef<T>,
+    y: MatRef<T>,
+    range: std::ops::Range<usize>,
+    lambda: T,
+    has_bias: bool,
+) {
+    let rows = range
+        .filter(|&i| !(has_nan(x.get(i..i + 1, ..)) || has_nan(y.get(i..i + 1, ..))))
+        .collect::<Vec<_>>();
+    let xs = Mat::from_fn(rows.len(), x.ncols(), |i, j| *x.get(rows[i], j));
+    let ys = Mat::from_fn(rows.len(), 1, |i, _| *y.get(rows[i], 0));
+    (online_lr.inv, online_lr.coefficients) =
+        faer_qr_lstsq_with_inv(xs.as_ref(), ys.as_ref(), lambda, has_bias);
+}
+
 /// Given all data, we start running a lstsq starting at position n and compute new coefficients recurisively.
 /// This will return all coefficients for rows >= n. This will only be used in Polars Expressions.
 /// If # of non-null rows in the window is < m, a Matrix with size (0, 0) will be returned.
-/// This supports Normal or Ridge regression
+/// This supports Normal or Ridge regression. If has_bias, the last column of x is the bias, and it is
+/// not penalized.
 pub fn faer_rolling_skipping_lstsq<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
     n: usize,
     m: usize,
     lambda: T,
+    has_bias: bool,
 ) -> Vec<Mat<T>> {
     let xn = x.nrows();
     let ncols = x.ncols();
@@ -268,7 +558,8 @@ pub fn faer_rolling_skipping_lstsq<T: RealField + Float>(
             // faer::mat::from_row_major_slice(&x_slice, y_slice.len(), ncols);
             let y0 = MatRef::from_column_major_slice(&y_slice, y_slice.len(), 1);
             // faer::mat::from_row_major_slice(&y_slice, y_slice.len(), 1);
-            online_lr.fit_unchecked(x0, y0);
+            (online_lr.inv, online_lr.coefficients) =
+                faer_qr_lstsq_with_inv(x0, y0, lambda, has_bias);
             coefficients.push(online_lr.fitted_values().to_owned());
             break;
         } else {
@@ -286,16 +577,22 @@ pub fn faer_rolling_skipping_lstsq<T: RealField + Float>(
         let remove_x = x.get(j - n..j - n + 1, ..);
         let remove_y = y.get(j - n..j - n + 1, ..);
 
+        let mut ok = true;
         if !(has_nan(remove_x) | has_nan(remove_y)) {
-            non_null_cnt_in_window -= 1; // removed one non-null column
-            online_lr.update_unchecked(remove_x, remove_y, T::one().neg()); // No need to check for nan
+            // Removed one non-null column. No need to check for nan
+            non_null_cnt_in_window -= 1;
+            ok = online_lr.update_unchecked(remove_x, remove_y, T::one().neg());
         }
 
         let next_x = x.get(j..j + 1, ..); // 1 by m, m = # of columns
         let next_y = y.get(j..j + 1, ..); // 1 by 1
         if !(has_nan(next_x) | has_nan(next_y)) {
             non_null_cnt_in_window += 1;
-            online_lr.update_unchecked(next_x, next_y, T::one()); // No need to check for nan
+            ok = ok && online_lr.update_unchecked(next_x, next_y, T::one()); // No need to check for nan
+        }
+        if !ok {
+            // A singular step. Refit the window instead.
+            refit_window(&mut online_lr, x, y, j + 1 - n..j + 1, lambda, has_bias);
         }
 
         if non_null_cnt_in_window >= m {
@@ -307,7 +604,14 @@ pub fn faer_rolling_skipping_lstsq<T: RealField + Float>(
     coefficients
 }
 
-/// Update the inverse and the weights for one step in a Woodbury update.
+/// Below this, |c + x inv x^t| is treated as 0 and the Woodbury step is singular.
+#[inline(always)]
+fn woodbury_tol<T: RealField + Float>(c: T, quad: T) -> T {
+    T::epsilon().sqrt() * (c.abs() + quad.abs())
+}
+
+/// Update the inverse and the weights for one step in a Woodbury update. If the step is singular,
+/// i.e. c + x inv x^t is ~0, nothing is updated and this returns false.
 /// Reference: https://cpb-us-w2.wpmucdn.com/sites.gatech.edu/dist/2/436/files/2017/07/22-notes-6250-f16.pdf
 /// https://en.wikipedia.org/wiki/Woodbury_matrix_identity
 #[inline(always)]
@@ -317,21 +621,29 @@ pub fn woodbury_step<T: RealField + Float>(
     new_x: MatRef<T>,
     new_y: MatRef<T>,
     c: T, // +1 or -1, for a "update" and a "removal"
-) {
+) -> bool {
     // It is truly amazing that the C in the Woodbury identity essentially controls the update and
     // and removal of a new record (rolling)... Linear regression seems to be designed by God to work so well
 
+    let par = woodbury_par(inverse.nrows());
     let u = &inverse * new_x.transpose(); // corresponding to u in the reference
                                           // right = left.transpose() by the fact that if A is symmetric, invertible, A-1 is also symmetric
-    let z = (c + *(new_x * &u).get(0, 0)).recip();
+    let quad = *(new_x * &u).get(0, 0);
+    let denom = c + quad;
+    // The denominator is ~0 when the update makes XtX singular, e.g. removing the only row that spans
+    // a direction. Then 1 / denom blows up and would poison inv and the weights for good.
+    if denom.is_nan() || denom.abs() <= woodbury_tol(c, quad) {
+        return false;
+    }
+    let z = denom.recip();
     // Update the information matrix's inverse. Page 56 of the gatech reference
     faer::linalg::matmul::matmul(
         inverse,
         faer::Accum::Add,
         &u,
-        &u.transpose(),
+        u.transpose(),
         z.neg(),
-        Par::rayon(0), //
+        par,
     ); // inv is updated
 
     // Difference from estimate using prior weights vs. actual next y
@@ -343,6 +655,131 @@ pub fn woodbury_step<T: RealField + Float>(
         u,
         y_diff,
         z,
-        Par::rayon(0), //
+        par,
     ); // weights are updated
+    true
+}
+
+/// Update the inverse and the weights for a block of k rows in one Woodbury update. This generalizes
+/// woodbury_step, where the scalar 1 / (c + x inv x^t) becomes the inverse of the k x k matrix
+/// S = c * I + X inv X^t. S is symmetric but may be indefinite when c < 0, so LU is used. If a pivot
+/// of the LU is ~0 (see woodbury_tol, with the largest x inv x^t of the block), S is singular, nothing
+/// is updated and this returns false.
+#[inline(always)]
+pub fn woodbury_block_step<T: RealField + Float>(
+    inverse: MatMut<T>,
+    weights: MatMut<T>,
+    new_x: MatRef<T>,
+    new_y: MatRef<T>,
+    c: T, // +1 or -1, for a "update" and a "removal"
+) -> bool {
+    let par = woodbury_par(inverse.nrows());
+    let u = &inverse * new_x.transpose(); // m x k
+    let mut s = new_x * &u; // k x k
+    let quad = s
+        .diagonal()
+        .column_vector()
+        .iter()
+        .fold(T::zero(), |acc, v| acc.max(v.abs()));
+    s.diagonal_mut()
+        .column_vector_mut()
+        .iter_mut()
+        .for_each(|v| *v = *v + c);
+    let lu = s.partial_piv_lu();
+    // Same as the check in woodbury_step, for each pivot
+    let tol = woodbury_tol(c, quad);
+    if lu
+        .U()
+        .diagonal()
+        .column_vector()
+        .iter()
+        .any(|v| v.is_nan() || v.abs() <= tol)
+    {
+        return false;
+    }
+
+    // Difference from estimate using prior weights vs. actual next y, using the prior inverse
+    let y_diff = new_y - (new_x * &weights);
+    faer::linalg::matmul::matmul(
+        weights,
+        faer::Accum::Add,
+        &u,
+        lu.solve(y_diff),
+        T::one(),
+        par,
+    ); // weights are updated
+
+    faer::linalg::matmul::matmul(
+        inverse,
+        faer::Accum::Add,
+        &u,
+        lu.solve(u.transpose()),
+        T::one().neg(),
+        par,
+    ); // inv is updated
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_batch_singular_block() {
+        // The second feature is only non-zero in the first two rows, so removing both of them at once
+        // makes XtX singular
+        let x = Mat::from_fn(10, 2, |i, j| match j {
+            0 => 1.0 + i as f64,
+            _ if i < 2 => 1.0 + i as f64,
+            _ => 0.0,
+        });
+        let y = Mat::from_fn(10, 1, |i, _| {
+            2.0 * *x.get(i, 0) - *x.get(i, 1) + 0.1 * i as f64
+        });
+
+        let mut by_batch = OnlineLR::new(0.0, false);
+        let mut by_row = OnlineLR::new(0.0, false);
+        assert!(by_batch.fit(x.as_ref(), y.as_ref()).is_ok());
+        assert!(by_row.fit(x.as_ref(), y.as_ref()).is_ok());
+
+        // The block is singular, so the rows are removed one by one, and the last one is skipped
+        assert!(!by_batch.update_batch(x.get(0..2, ..), y.get(0..2, ..), -1.0));
+        assert!(by_row.update(x.get(0..1, ..), y.get(0..1, ..), -1.0));
+        assert!(!by_row.update(x.get(1..2, ..), y.get(1..2, ..), -1.0));
+
+        assert!(by_batch
+            .inv
+            .col_iter()
+            .all(|c| c.iter().all(|v| v.is_finite())));
+        for (a, b) in by_batch
+            .coefficients
+            .col_as_slice(0)
+            .iter()
+            .zip(by_row.coefficients.col_as_slice(0))
+        {
+            assert!((a - b).abs() < 1e-8);
+        }
+
+        // A regular block is a single step
+        assert!(by_batch.update_batch(x.get(0..1, ..), y.get(0..1, ..), 1.0));
+    }
+
+    #[test]
+    fn test_update_with_forget_singular() {
+        let x = Mat::from_fn(10, 2, |i, j| 1.0 + (i * (j + 1)) as f64 / 3.0 + j as f64);
+        let y = Mat::from_fn(10, 1, |i, _| *x.get(i, 0) - 0.5 * *x.get(i, 1));
+        let mut olr = OnlineLR::new(0.0, true).with_forget(0.9);
+        assert!(olr.fit(x.as_ref(), y.as_ref()).is_ok());
+        let (inv, coeffs) = (olr.inv.clone(), olr.coefficients.clone());
+
+        // x inv x^t overflows, so the step is skipped, and the decay is undone
+        let huge = Mat::from_fn(1, 2, |_, _| 1e200);
+        assert!(!olr.update_with_forget(huge.as_ref(), y.get(0..1, ..)));
+        assert!(olr.inv == inv);
+        assert!(olr.coefficients == coeffs);
+
+        // A regular row decays the old rows
+        assert!(olr.update_with_forget(x.get(0..1, ..), y.get(0..1, ..)));
+        assert!(olr.inv != inv);
+    }
 }
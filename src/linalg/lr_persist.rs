@@ -0,0 +1,363 @@
+/// A compact binary format for fitted linear models, so a model fit in one process can be loaded and
+/// used in another without refitting. All numbers are stored little endian, floats as f64 regardless
+/// of T. Each model starts with a 4 byte tag and a version byte, so the bytes of one model can't be
+/// loaded as another.
+use super::{
+    lr_online_solvers::OnlineLR,
+    lr_solvers::{ElasticNet, LR},
+    LRSolverMethods, LinalgErrors, LinearRegression, StopCriterion,
+};
+use faer::Mat;
+use faer_traits::RealField;
+use num::Float;
+
+const LR_TAG: &[u8; 4] = b"PDLR";
+const ELASTIC_NET_TAG: &[u8; 4] = b"PDEN";
+const ONLINE_LR_TAG: &[u8; 4] = b"PDOL";
+const VERSION: u8 = 1;
+
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn new(tag: &[u8; 4]) -> Self {
+        let mut buf = Vec::with_capacity(64);
+        buf.extend_from_slice(tag);
+        buf.push(VERSION);
+        Writer { buf }
+    }
+
+    fn bool(&mut self, v: bool) {
+        self.buf.push(v as u8);
+    }
+
+    fn usize(&mut self, v: usize) {
+        self.buf.extend_from_slice(&(v as u64).to_le_bytes());
+    }
+
+    fn float<T: Float>(&mut self, v: T) {
+        self.buf
+            .extend_from_slice(&v.to_f64().unwrap_or(f64::NAN).to_le_bytes());
+    }
+
+    fn floats<T: Float>(&mut self, v: &[T]) {
+        self.usize(v.len());
+        v.iter().for_each(|x| self.float(*x));
+    }
+
+    fn str(&mut self, v: &str) {
+        self.usize(v.len());
+        self.buf.extend_from_slice(v.as_bytes());
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8], tag: &[u8; 4]) -> Result<Self, LinalgErrors> {
+        let mut reader = Reader { buf };
+        if reader.take(4)? != tag {
+            return Err(LinalgErrors::Other(
+                "The bytes are not a serialized model of this type.".into(),
+            ));
+        }
+        let version = reader.take(1)?[0];
+        if version != VERSION {
+            return Err(LinalgErrors::Other(format!(
+                "Unsupported model format version {}.",
+                version
+            )));
+        }
+        Ok(reader)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], LinalgErrors> {
+        if self.buf.len() < n {
+            return Err(LinalgErrors::Other(
+                "The serialized model is truncated.".into(),
+            ));
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    fn bool(&mut self) -> Result<bool, LinalgErrors> {
+        Ok(self.take(1)?[0] != 0)
+    }
+
+    fn usize(&mut self) -> Result<usize, LinalgErrors> {
+        let b: [u8; 8] = self.take(8)?.try_into().unwrap();
+        Ok(u64::from_le_bytes(b) as usize)
+    }
+
+    fn float<T: Float>(&mut self) -> Result<T, LinalgErrors> {
+        let b: [u8; 8] = self.take(8)?.try_into().unwrap();
+        Ok(T::from(f64::from_le_bytes(b)).unwrap())
+    }
+
+    fn floats<T: Float>(&mut self) -> Result<Vec<T>, LinalgErrors> {
+        let n = self.usize()?;
+        if n > self.buf.len() / 8 {
+            return Err(LinalgErrors::Other(
+                "The serialized model is truncated.".into(),
+            ));
+        }
+        (0..n).map(|_| self.float()).collect()
+    }
+
+    fn str(&mut self) -> Result<String, LinalgErrors> {
+        let n = self.usize()?;
+        let b = self.take(n)?;
+        String::from_utf8(b.to_vec())
+            .map_err(|_| LinalgErrors::Other("Invalid string in the serialized model.".into()))
+    }
+
+    fn finish(self) -> Result<(), LinalgErrors> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            Err(LinalgErrors::Other(
+                "Trailing bytes after the serialized model.".into(),
+            ))
+        }
+    }
+}
+
+/// The first column of m, or nothing if m is not fit (0 x 0).
+fn first_column<T: RealField + Float>(m: &Mat<T>) -> &[T] {
+    if m.ncols() == 0 {
+        &[]
+    } else {
+        m.col_as_slice(0)
+    }
+}
+
+/// The inverse of first_column. Nothing gives a 0 x 0 matrix, so the model is not fit.
+fn column<T: RealField + Float>(v: &[T]) -> Mat<T> {
+    if v.is_empty() {
+        Mat::new()
+    } else {
+        Mat::from_fn(v.len(), 1, |i, _| v[i])
+    }
+}
+
+fn stop_criterion_to_u8(s: StopCriterion) -> u8 {
+    match s {
+        StopCriterion::CoefficientChange => 0,
+        StopCriterion::RelativeObjective => 1,
+        StopCriterion::DualGap => 2,
+    }
+}
+
+fn stop_criterion_from_u8(v: u8) -> Result<StopCriterion, LinalgErrors> {
+    match v {
+        0 => Ok(StopCriterion::CoefficientChange),
+        1 => Ok(StopCriterion::RelativeObjective),
+        2 => Ok(StopCriterion::DualGap),
+        _ => Err(LinalgErrors::Other(
+            "Invalid stop criterion in the serialized model.".into(),
+        )),
+    }
+}
+
+impl<T: RealField + Float> LR<T> {
+    /// Serializes the model: the solver, lambda, the coefficients with the bias, and the fit
+    /// diagnostics (rank, condition number and the collinearity threshold).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(LR_TAG);
+        w.str(self.solver.as_str());
+        w.float(self.lambda);
+        w.bool(self.has_bias);
+        w.floats(first_column(&self.coefficients));
+        w.usize(self.rank);
+        w.float(self.cond);
+        w.float(self.warn_collinearity);
+        w.buf
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LinalgErrors> {
+        let mut r = Reader::new(bytes, LR_TAG)?;
+        let solver: LRSolverMethods = r.str()?.as_str().into();
+        let lambda = r.float()?;
+        let has_bias = r.bool()?;
+        let coefficients = column(&r.floats()?);
+        let rank = r.usize()?;
+        let cond = r.float()?;
+        let warn_collinearity = r.float()?;
+        r.finish()?;
+        if has_bias && coefficients.nrows() == 0 {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        Ok(LR {
+            solver,
+            lambda,
+            coefficients,
+            has_bias,
+            rank,
+            cond,
+            warn_collinearity,
+        })
+    }
+}
+
+impl<T: RealField + Float> ElasticNet<T> {
+    /// Serializes the model: the regularizers, the solver settings, the penalty factors, the
+    /// coefficients with the bias and the convergence status of the last fit.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(ELASTIC_NET_TAG);
+        w.float(self.l1_reg);
+        w.float(self.l2_reg);
+        w.bool(self.has_bias);
+        w.float(self.tol);
+        w.usize(self.max_iter);
+        w.buf.push(stop_criterion_to_u8(self.stop_criterion));
+        w.bool(self.penalize_bias);
+        w.bool(self.penalty_factor.is_some());
+        if let Some(f) = &self.penalty_factor {
+            w.floats(f);
+        }
+        w.floats(first_column(&self.coefficients));
+        w.bool(self.converged);
+        w.usize(self.n_iter);
+        w.buf
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LinalgErrors> {
+        let mut r = Reader::new(bytes, ELASTIC_NET_TAG)?;
+        let l1_reg = r.float()?;
+        let l2_reg = r.float()?;
+        let has_bias = r.bool()?;
+        let tol = r.float()?;
+        let max_iter = r.usize()?;
+        let stop_criterion = stop_criterion_from_u8(r.take(1)?[0])?;
+        let penalize_bias = r.bool()?;
+        let penalty_factor = if r.bool()? { Some(r.floats()?) } else { None };
+        let coefficients = column(&r.floats()?);
+        let converged = r.bool()?;
+        let n_iter = r.usize()?;
+        r.finish()?;
+        if has_bias && coefficients.nrows() == 0 {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        Ok(ElasticNet {
+            l1_reg,
+            l2_reg,
+            coefficients,
+            has_bias,
+            tol,
+            max_iter,
+            stop_criterion,
+            penalize_bias,
+            penalty_factor,
+            converged,
+            n_iter,
+        })
+    }
+}
+
+impl<T: RealField + Float> OnlineLR<T> {
+    /// Serializes the full streaming state: lambda, the forgetting factor, the coefficients with the
+    /// bias and the current inverse of X^t X, so updates can continue after the model is loaded.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut w = Writer::new(ONLINE_LR_TAG);
+        w.float(self.lambda);
+        w.float(self.forget);
+        w.bool(self.has_bias);
+        w.floats(first_column(&self.coefficients));
+        w.usize(self.inv.nrows());
+        w.usize(self.inv.ncols());
+        let inv = (0..self.inv.ncols())
+            .flat_map(|j| self.inv.col_as_slice(j).iter().copied())
+            .collect::<Vec<_>>();
+        w.floats(&inv);
+        w.buf
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LinalgErrors> {
+        let mut r = Reader::new(bytes, ONLINE_LR_TAG)?;
+        let lambda = r.float()?;
+        let forget = r.float()?;
+        let has_bias = r.bool()?;
+        let coefficients = column(&r.floats()?);
+        let (nrows, ncols) = (r.usize()?, r.usize()?);
+        let inv = r.floats()?;
+        r.finish()?;
+        // inv is the square inverse of X^t X, one row and column per coefficient
+        if nrows.checked_mul(ncols) != Some(inv.len())
+            || nrows != ncols
+            || nrows != coefficients.nrows()
+            || (has_bias && coefficients.nrows() == 0)
+        {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        Ok(OnlineLR {
+            lambda,
+            has_bias,
+            coefficients,
+            inv: Mat::from_fn(nrows, ncols, |i, j| inv[i + j * nrows]),
+            forget,
+        })
+    }
+}
+
+/// The coefficients (without the bias) and the bias of a model serialized by LR, ElasticNet or
+/// OnlineLR's to_bytes. The model type is read from the tag.
+pub fn coeffs_and_bias_from_bytes<T: RealField + Float>(
+    bytes: &[u8],
+) -> Result<(Vec<T>, T), LinalgErrors> {
+    let tag = bytes.get(..4).unwrap_or_default();
+    let (coeffs, bias) = if tag == LR_TAG {
+        let lr = LR::<T>::from_bytes(bytes)?;
+        (lr.coeffs_as_vec()?, lr.bias())
+    } else if tag == ELASTIC_NET_TAG {
+        let lr = ElasticNet::<T>::from_bytes(bytes)?;
+        (lr.coeffs_as_vec()?, lr.bias())
+    } else if tag == ONLINE_LR_TAG {
+        let lr = OnlineLR::<T>::from_bytes(bytes)?;
+        (lr.coeffs_as_vec()?, lr.bias())
+    } else {
+        return Err(LinalgErrors::Other(
+            "The bytes are not a serialized linear model.".into(),
+        ));
+    };
+    Ok((coeffs, bias))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn online_lr_bytes(coeffs: &[f64], nrows: usize, ncols: usize, inv: &[f64]) -> Vec<u8> {
+        let mut w = Writer::new(ONLINE_LR_TAG);
+        w.float(0.);
+        w.float(1.);
+        w.bool(false);
+        w.floats(coeffs);
+        w.usize(nrows);
+        w.usize(ncols);
+        w.floats(inv);
+        w.buf
+    }
+
+    #[test]
+    fn test_online_lr_from_bytes_dimensions() {
+        let ok = online_lr_bytes(&[1., 2.], 2, 2, &[1., 0., 0., 1.]);
+        assert!(OnlineLR::<f64>::from_bytes(&ok).is_ok());
+        // An unfit model has no coefficients and an empty inverse
+        assert!(OnlineLR::<f64>::from_bytes(&online_lr_bytes(&[], 0, 0, &[])).is_ok());
+
+        // nrows * ncols overflows to inv.len()
+        let overflow = online_lr_bytes(&[1., 2.], 1 << 32, 1 << 32, &[]);
+        assert!(OnlineLR::<f64>::from_bytes(&overflow).is_err());
+        // Not square
+        let not_square = online_lr_bytes(&[1., 2.], 1, 4, &[1., 0., 0., 1.]);
+        assert!(OnlineLR::<f64>::from_bytes(&not_square).is_err());
+        // Square, but not one row per coefficient
+        let wrong_size = online_lr_bytes(&[1.], 2, 2, &[1., 0., 0., 1.]);
+        assert!(OnlineLR::<f64>::from_bytes(&wrong_size).is_err());
+    }
+}
@@ -0,0 +1,884 @@
+/// Robust linear regression by Iteratively Reweighted Least Squares (IRLS).
+use super::{
+    lr_bootstrap::rng_from_seed,
+    lr_solvers::{faer_solve_lstsq, faer_weighted_lstsq, LR},
+    LRSolverMethods, LinalgErrors, LinearRegression, StopCriterion,
+};
+use crate::stats_utils::normal;
+use faer::{mat::Mat, prelude::*};
+use faer_traits::RealField;
+use num::Float;
+use rand::{seq::index::sample, Rng};
+
+/// Consistency constant so that MAD / MAD_NORMAL estimates the std of normal residuals.
+const MAD_NORMAL: f64 = 0.6744897501960817;
+/// Tuning constant for Huber's proposal 2 scale estimate.
+const PROPOSAL_2_C: f64 = 1.5;
+/// Bisquare tuning constant of the S-estimate. With S_B, this gives 50% breakdown point.
+const S_C: f64 = 1.547645;
+/// Right hand side of the M-scale equation mean(rho(r / s)) = S_B, with rho bounded by 1.
+const S_B: f64 = 0.5;
+/// Number of IRLS steps run on each random subsample, and the number of best subsamples that are
+/// iterated to convergence. These are the defaults of the Fast-S algorithm.
+const FAST_S_STEPS: usize = 2;
+const FAST_S_BEST: usize = 5;
+/// The fraction of the max step to the boundary taken by the interior point method of quantile regression.
+const IPM_STEP_FRACTION: f64 = 0.99995;
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum RobustLoss {
+    #[default]
+    Huber,
+    Bisquare,
+}
+
+impl From<&str> for RobustLoss {
+    fn from(value: &str) -> Self {
+        match value {
+            "bisquare" | "tukey" => RobustLoss::Bisquare,
+            _ => RobustLoss::Huber,
+        }
+    }
+}
+
+impl RobustLoss {
+    /// The default tuning constants, which give 95% efficiency under normal errors.
+    pub fn default_c(&self) -> f64 {
+        match self {
+            RobustLoss::Huber => 1.345,
+            RobustLoss::Bisquare => 4.685,
+        }
+    }
+
+    /// IRLS weight w(u) = psi(u) / u for a standardized residual u.
+    #[inline(always)]
+    pub fn weight<T: Float>(&self, u: T, c: T) -> T {
+        let a = u.abs();
+        match self {
+            RobustLoss::Huber => {
+                if a <= c {
+                    T::one()
+                } else {
+                    c / a
+                }
+            }
+            RobustLoss::Bisquare => {
+                if a < c {
+                    let t = T::one() - (u / c) * (u / c);
+                    t * t
+                } else {
+                    T::zero()
+                }
+            }
+        }
+    }
+
+    /// The loss rho(u) for a standardized residual u, with rho(0) = 0 and psi = rho'.
+    #[inline(always)]
+    pub fn rho<T: Float>(&self, u: T, c: T) -> T {
+        let a = u.abs();
+        let two = T::one() + T::one();
+        match self {
+            RobustLoss::Huber => {
+                if a <= c {
+                    u * u / two
+                } else {
+                    c * a - c * c / two
+                }
+            }
+            RobustLoss::Bisquare => {
+                let c2_6 = c * c / T::from(6.0).unwrap();
+                if a < c {
+                    let t = T::one() - (u / c) * (u / c);
+                    c2_6 * (T::one() - t * t * t)
+                } else {
+                    c2_6
+                }
+            }
+        }
+    }
+}
+
+/// The IRLS objective at the given scale, sum(s^2 * rho(r / s)), which is in the units of squared residuals.
+fn robust_objective<T: Float>(residuals: &[T], scale: T, loss: RobustLoss, c: T) -> T {
+    residuals.iter().fold(T::zero(), |acc, r| {
+        acc + scale * scale * loss.rho(*r / scale, c)
+    })
+}
+
+pub struct RobustFit<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub scale: T,
+    pub n_iter: usize,
+    pub converged: bool,
+}
+
+/// The median of the values, which are sorted in place. NaN if empty.
+fn median_inplace<T: Float>(v: &mut [T]) -> T {
+    if v.is_empty() {
+        return T::nan();
+    }
+    v.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Greater));
+    let n = v.len();
+    if n % 2 == 0 {
+        (v[n / 2 - 1] + v[n / 2]) / T::from(2.0).unwrap()
+    } else {
+        v[n / 2]
+    }
+}
+
+/// Median absolute deviation (from 0) of the residuals, scaled to be consistent for the normal std.
+pub fn mad_scale<T: RealField + Float>(residuals: &[T]) -> T {
+    let mut abs_r = residuals.iter().map(|r| r.abs()).collect::<Vec<_>>();
+    median_inplace(&mut abs_r) / T::from(MAD_NORMAL).unwrap()
+}
+
+/// E[min(Z^2, c^2)] for Z standard normal. This makes proposal 2 consistent under normal errors.
+fn proposal_2_beta(c: f64) -> f64 {
+    let tail = normal::sf_unchecked(c, 0., 1.);
+    let pdf = (-0.5 * c * c).exp() / (2.0 * std::f64::consts::PI).sqrt();
+    (1.0 - 2.0 * tail) - 2.0 * c * pdf + 2.0 * c * c * tail
+}
+
+/// One fixed point step of Huber's proposal 2: s^2 = sum(psi(r/s)^2) * s^2 / ((n - p) * beta).
+fn proposal_2_step<T: RealField + Float>(residuals: &[T], scale: T, dof: T, beta: T) -> T {
+    let c = T::from(PROPOSAL_2_C).unwrap();
+    let bound = c * scale;
+    let ss = residuals.iter().fold(T::zero(), |acc, r| {
+        let r = r.abs().min(bound);
+        acc + r * r
+    });
+    (ss / (dof * beta)).sqrt()
+}
+
+fn residuals<T: RealField + Float>(x: MatRef<T>, y: MatRef<T>, coeffs: &Mat<T>) -> Vec<T> {
+    let pred = x * coeffs;
+    (0..y.nrows())
+        .map(|i| *y.get(i, 0) - *pred.get(i, 0))
+        .collect()
+}
+
+/// Iteratively reweighted least square from the given coefficients and scale. Returns the coefficients,
+/// the scale, the number of iterations and whether it converged. See faer_robust_lstsq.
+#[allow(clippy::too_many_arguments)]
+fn irls<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    mut coeffs: Mat<T>,
+    mut scale: T,
+    loss: RobustLoss,
+    c: T,
+    estimate_scale: bool,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> RobustFit<T> {
+    let mut r = residuals(x, y, &coeffs);
+    let dof = T::from(x.nrows().saturating_sub(x.ncols()).max(1)).unwrap();
+    let beta = T::from(proposal_2_beta(PROPOSAL_2_C)).unwrap();
+    let use_objective = stop == StopCriterion::RelativeObjective;
+    let mut obj = if use_objective {
+        robust_objective(&r, scale, loss, c)
+    } else {
+        T::zero()
+    };
+
+    let mut n_iter = 0;
+    let mut converged = false;
+    let mut w = vec![T::one(); x.nrows()];
+    while n_iter < max_iter {
+        if scale.is_nan() || scale <= T::epsilon() {
+            // The fit is (almost) exact. Nothing to reweight.
+            converged = !scale.is_nan();
+            break;
+        }
+        n_iter += 1;
+        for (wi, ri) in w.iter_mut().zip(r.iter()) {
+            *wi = loss.weight(*ri / scale, c);
+        }
+        let new_coeffs = faer_weighted_lstsq(x, y, &w, how);
+        let mut max_change = (0..coeffs.nrows()).fold(T::zero(), |acc, i| {
+            acc.max((*new_coeffs.get(i, 0) - *coeffs.get(i, 0)).abs())
+        });
+        coeffs = new_coeffs;
+        r = residuals(x, y, &coeffs);
+        if estimate_scale {
+            let new_scale = proposal_2_step(&r, scale, dof, beta);
+            max_change = max_change.max((new_scale - scale).abs());
+            scale = new_scale;
+        }
+        let new_obj = if use_objective {
+            robust_objective(&r, scale, loss, c)
+        } else {
+            T::zero()
+        };
+        if stop.is_converged(max_change, obj, new_obj, tol) {
+            converged = true;
+            break;
+        }
+        obj = new_obj;
+    }
+
+    RobustFit {
+        coefficients: coeffs,
+        scale,
+        n_iter,
+        converged,
+    }
+}
+
+/// Robust regression by IRLS. The starting point is the OLS solution and the initial scale is
+/// the normalized MAD of the OLS residuals. If estimate_scale is false, the scale is fixed at
+/// this value. If true, the coefficients and the scale are estimated jointly by alternating a
+/// weighted least square step and a Huber proposal 2 step for the scale.
+///
+/// By default, the iteration stops when the max absolute change in coefficients (and the scale, if
+/// estimated) is < tol, or when max_iter is reached. With StopCriterion::RelativeObjective, it stops
+/// when the relative change in sum(s^2 * rho(r / s)) at the current scale is < tol.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_robust_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    loss: RobustLoss,
+    c: T,
+    estimate_scale: bool,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> RobustFit<T> {
+    let coeffs = faer_solve_lstsq(x, y, T::zero(), false, how);
+    let scale = mad_scale(&residuals(x, y, &coeffs));
+    irls(
+        x,
+        y,
+        coeffs,
+        scale,
+        loss,
+        c,
+        estimate_scale,
+        how,
+        tol,
+        max_iter,
+        stop,
+    )
+}
+
+/// The bisquare rho, scaled so that rho(u) = 1 for |u| >= c.
+#[inline(always)]
+fn bisquare_rho<T: Float>(u: T, c: T) -> T {
+    if u.abs() < c {
+        let t = T::one() - (u / c) * (u / c);
+        T::one() - t * t * t
+    } else {
+        T::one()
+    }
+}
+
+/// One fixed point step of the M-scale equation mean(rho(r / s)) = S_B.
+#[inline(always)]
+fn m_scale_step<T: Float>(residuals: &[T], scale: T) -> T {
+    let c = T::from(S_C).unwrap();
+    let n = T::from(residuals.len()).unwrap();
+    let mean_rho = residuals
+        .iter()
+        .fold(T::zero(), |acc, r| acc + bisquare_rho(*r / scale, c))
+        / n;
+    scale * (mean_rho / T::from(S_B).unwrap()).sqrt()
+}
+
+/// Solves the M-scale equation mean(rho(r / s)) = S_B by fixed point iterations, starting from the MAD.
+pub fn m_scale<T: RealField + Float>(residuals: &[T], tol: T, max_iter: usize) -> T {
+    let mut scale = mad_scale(residuals);
+    for _ in 0..max_iter {
+        if scale.is_nan() || scale <= T::epsilon() {
+            break;
+        }
+        let new_scale = m_scale_step(residuals, scale);
+        let change = (new_scale / scale - T::one()).abs();
+        scale = new_scale;
+        if change < tol {
+            break;
+        }
+    }
+    scale
+}
+
+/// Runs S-estimation IRLS steps from coeffs: reweight by the bisquare with the S constant, solve the
+/// weighted least square and take one step on the M-scale. Returns the coefficients and the M-scale of
+/// their residuals. If fully is true, this runs until the relative change in scale is < tol.
+fn s_refine<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    mut coeffs: Mat<T>,
+    steps: usize,
+    fully: bool,
+    how: LRSolverMethods,
+    tol: T,
+) -> (Mat<T>, T) {
+    let c = T::from(S_C).unwrap();
+    let mut r = residuals(x, y, &coeffs);
+    let mut scale = mad_scale(&r);
+    let mut w = vec![T::one(); x.nrows()];
+    for _ in 0..steps {
+        if scale.is_nan() || scale <= T::epsilon() {
+            break;
+        }
+        scale = m_scale_step(&r, scale);
+        for (wi, ri) in w.iter_mut().zip(r.iter()) {
+            *wi = RobustLoss::Bisquare.weight(*ri / scale, c);
+        }
+        let new_coeffs = faer_weighted_lstsq(x, y, &w, how);
+        let max_change = (0..coeffs.nrows()).fold(T::zero(), |acc, i| {
+            acc.max((*new_coeffs.get(i, 0) - *coeffs.get(i, 0)).abs())
+        });
+        coeffs = new_coeffs;
+        r = residuals(x, y, &coeffs);
+        if fully && max_change < tol {
+            break;
+        }
+    }
+    let scale = m_scale(&r, tol, steps.max(50));
+    (coeffs, scale)
+}
+
+pub struct MMFit<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub scale: T,        // The S-estimate of scale, which is kept fixed in the M step
+    pub weights: Vec<T>, // Final bisquare weights of the rows
+    pub converged: bool, // Whether the M step converged
+}
+
+/// MM-estimation of a linear regression (Yohai 1987). First, an S-estimate with 50% breakdown point
+/// is found by the Fast-S algorithm (Salibian-Barrera and Yohai 2006): the exact fits on n_subsamples
+/// random subsets of #features rows are refined by a few IRLS steps, and the best few by M-scale are
+/// iterated to convergence. The one with the smallest M-scale is the S-estimate. Then, with the scale
+/// fixed at the S-scale, a bisquare M-estimate with tuning constant c (4.685 gives 95% efficiency under
+/// normal errors) is computed by IRLS, starting from the S-estimate coefficients. stop only applies
+/// to the M step.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_mm_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    c: T,
+    n_subsamples: usize,
+    seed: Option<u64>,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> MMFit<T> {
+    let (n, p) = (x.nrows(), x.ncols());
+    let mut rng = rng_from_seed(seed);
+    let mut candidates = Vec::with_capacity(n_subsamples);
+    for _ in 0..n_subsamples {
+        let idx = sample(&mut rng, n, p);
+        let xs = Mat::from_fn(p, p, |i, j| *x.get(idx.index(i), j));
+        let ys = Mat::from_fn(p, 1, |i, _| *y.get(idx.index(i), 0));
+        let coeffs = faer_solve_lstsq(xs.as_ref(), ys.as_ref(), T::zero(), false, how);
+        if (0..p).any(|i| !coeffs.get(i, 0).is_finite()) {
+            continue;
+        }
+        let (coeffs, scale) = s_refine(x, y, coeffs, FAST_S_STEPS, false, how, tol);
+        if scale.is_finite() {
+            candidates.push((scale, coeffs));
+        }
+    }
+    candidates.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let (s_coeffs, s_scale) = candidates
+        .into_iter()
+        .take(FAST_S_BEST)
+        .map(|(_, coeffs)| s_refine(x, y, coeffs, max_iter, true, how, tol))
+        .fold(
+            None,
+            |best: Option<(Mat<T>, T)>, (coeffs, scale)| match best {
+                Some((_, s)) if s <= scale => best,
+                _ => Some((coeffs, scale)),
+            },
+        )
+        // No usable subsample. Fall back to OLS.
+        .unwrap_or_else(|| {
+            let coeffs = faer_solve_lstsq(x, y, T::zero(), false, how);
+            let scale = m_scale(&residuals(x, y, &coeffs), tol, max_iter);
+            (coeffs, scale)
+        });
+
+    let fit = irls(
+        x,
+        y,
+        s_coeffs,
+        s_scale,
+        RobustLoss::Bisquare,
+        c,
+        false,
+        how,
+        tol,
+        max_iter,
+        stop,
+    );
+    let weights = residuals(x, y, &fit.coefficients)
+        .into_iter()
+        .map(|r| {
+            if s_scale > T::epsilon() {
+                RobustLoss::Bisquare.weight(r / s_scale, c)
+            } else {
+                T::one()
+            }
+        })
+        .collect();
+
+    MMFit {
+        coefficients: fit.coefficients,
+        scale: s_scale,
+        weights,
+        converged: fit.converged,
+    }
+}
+
+/// The largest step length f <= 1 / beta, such that v + f * dv >= 0.
+fn max_step<T: Float>(v: &[T], dv: &[T]) -> T {
+    v.iter()
+        .zip(dv.iter())
+        .filter(|(_, d)| **d < T::zero())
+        .fold(T::from(1e20).unwrap(), |acc, (vi, d)| acc.min(-*vi / *d))
+}
+
+#[inline(always)]
+fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+    a.iter()
+        .zip(b.iter())
+        .fold(T::zero(), |acc, (u, v)| acc + *u * *v)
+}
+
+/// The step lengths (primal, dual) that keep a, s, z, w positive.
+fn ipm_step_lengths<T: Float>(a: &[T], da: &[T], z: &[T], dz: &[T], w: &[T], dw: &[T]) -> (T, T) {
+    let beta = T::from(IPM_STEP_FRACTION).unwrap();
+    let ds = da.iter().map(|d| -*d).collect::<Vec<_>>();
+    let s = a.iter().map(|v| T::one() - *v).collect::<Vec<_>>();
+    let fp = (beta * max_step(a, da).min(max_step(&s, &ds))).min(T::one());
+    let fd = (beta * max_step(w, dw).min(max_step(z, dz))).min(T::one());
+    (fp, fd)
+}
+
+/// Quantile regression by the Frisch-Newton interior point method of Portnoy and Koenker (1997), as in
+/// rq.fit.fnb of the R package quantreg. The pinball loss sum(r * (tau - 1{r < 0})) is minimized through
+/// its dual linear program: max y^t a such that X^t a = (1 - tau) X^t 1 and 0 <= a <= 1. Each Newton step
+/// (with Mehrotra's predictor-corrector) is a weighted least square on x, solved by faer_weighted_lstsq.
+/// The coefficients are the dual variables of this program. The iteration starts from the OLS solution,
+/// and stops when the duality gap is < tol * (1 + |y^t a|), or when max_iter is reached. The scale in the
+/// output is the normalized MAD of the final residuals.
+pub fn faer_quantile_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    tau: T,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+) -> RobustFit<T> {
+    let (n, p) = (x.nrows(), x.ncols());
+    let one = T::one();
+    let nf = T::from(n).unwrap();
+    let times_x = |v: &Mat<T>| (x * v).col(0).iter().copied().collect::<Vec<_>>();
+    let yv = y.col(0).iter().copied().collect::<Vec<_>>();
+    // b = (1 - tau) X^t 1, the right hand side of the equality constraints
+    let b = (0..p)
+        .map(|j| x.col(j).iter().fold(T::zero(), |acc, v| acc + *v) * (one - tau))
+        .collect::<Vec<_>>();
+
+    // Primal a with slack s = 1 - a, dual coefficients, and the dual slacks z, w for a >= 0 and a <= 1
+    let mut a = vec![one - tau; n];
+    let mut coeffs = faer_solve_lstsq(x, y, T::zero(), false, how);
+    let fitted = times_x(&coeffs);
+    // Reduced cost of the primal, i.e. the negative residuals. Nudged away from 0.
+    let r0 = fitted
+        .iter()
+        .zip(yv.iter())
+        .map(|(f, yi)| {
+            let r = *f - *yi;
+            if r == T::zero() {
+                T::from(1e-3).unwrap()
+            } else {
+                r
+            }
+        })
+        .collect::<Vec<_>>();
+    let mut z = r0.iter().map(|r| r.max(T::zero())).collect::<Vec<_>>();
+    let mut w = r0.iter().map(|r| (-*r).max(T::zero())).collect::<Vec<_>>();
+    let gap = |a: &[T], coeffs: &Mat<T>, w: &[T]| {
+        let primal = -dot(&yv, a);
+        let dual_b = (0..p).fold(T::zero(), |acc, j| acc - *coeffs.get(j, 0) * b[j]);
+        let wu = w.iter().fold(T::zero(), |acc, v| acc + *v);
+        (primal - dual_b + wu, primal)
+    };
+    let (mut g, mut primal) = gap(&a, &coeffs, &w);
+
+    let mut n_iter = 0;
+    while g >= tol * (one + primal.abs()) && n_iter < max_iter {
+        n_iter += 1;
+        let s = a.iter().map(|v| one - *v).collect::<Vec<_>>();
+        let q = (0..n)
+            .map(|i| (z[i] / a[i] + w[i] / s[i]).recip())
+            .collect::<Vec<_>>();
+        let r = (0..n).map(|i| z[i] - w[i]).collect::<Vec<_>>();
+
+        // Predictor (affine scaling) step
+        let target = Mat::from_fn(n, 1, |i, _| r[i]);
+        let mut dy = faer_weighted_lstsq(x, target.as_ref(), &q, how);
+        let xdy = times_x(&dy);
+        let mut da = (0..n).map(|i| q[i] * (xdy[i] - r[i])).collect::<Vec<_>>();
+        let mut dz = (0..n)
+            .map(|i| -z[i] * (da[i] / a[i] + one))
+            .collect::<Vec<_>>();
+        let mut dw = (0..n)
+            .map(|i| -w[i] * (-da[i] / s[i] + one))
+            .collect::<Vec<_>>();
+        let (mut fp, mut fd) = ipm_step_lengths(&a, &da, &z, &dz, &w, &dw);
+
+        if fp.min(fd) < one {
+            // Corrector step, with the centering parameter of Mehrotra
+            let mu = dot(&z, &a) + dot(&w, &s);
+            let g_aff = (0..n).fold(T::zero(), |acc, i| {
+                acc + (z[i] + fd * dz[i]) * (a[i] + fp * da[i])
+                    + (w[i] + fd * dw[i]) * (s[i] - fp * da[i])
+            });
+            let mu = mu * (g_aff / mu).powi(3) / (nf + nf);
+            let dadz = (0..n).map(|i| da[i] * dz[i]).collect::<Vec<_>>();
+            let dsdw = (0..n).map(|i| -da[i] * dw[i]).collect::<Vec<_>>();
+            let xi = (0..n)
+                .map(|i| mu * (a[i].recip() - s[i].recip()))
+                .collect::<Vec<_>>();
+            let target = Mat::from_fn(n, 1, |i, _| r[i] + dadz[i] - dsdw[i] - xi[i]);
+            dy = faer_weighted_lstsq(x, target.as_ref(), &q, how);
+            let xdy = times_x(&dy);
+            da = (0..n)
+                .map(|i| q[i] * (xdy[i] - *target.get(i, 0)))
+                .collect();
+            dz = (0..n)
+                .map(|i| mu / a[i] - z[i] - z[i] * da[i] / a[i] - dadz[i])
+                .collect();
+            dw = (0..n)
+                .map(|i| mu / s[i] - w[i] + w[i] * da[i] / s[i] - dsdw[i])
+                .collect();
+            (fp, fd) = ipm_step_lengths(&a, &da, &z, &dz, &w, &dw);
+        }
+
+        for i in 0..n {
+            a[i] = a[i] + fp * da[i];
+            z[i] = z[i] + fd * dz[i];
+            w[i] = w[i] + fd * dw[i];
+        }
+        // The dual variables of the program are the negative coefficients
+        for j in 0..p {
+            *coeffs.get_mut(j, 0) = *coeffs.get(j, 0) - fd * *dy.get(j, 0);
+        }
+        (g, primal) = gap(&a, &coeffs, &w);
+    }
+
+    RobustFit {
+        scale: mad_scale(&residuals(x, y, &coeffs)),
+        converged: g < tol * (one + primal.abs()),
+        coefficients: coeffs,
+        n_iter,
+    }
+}
+
+/// Quantile regression, which models the tau-th conditional quantile of the target by minimizing the
+/// pinball loss. tau = 0.5 is the least absolute deviation (LAD) regression. See faer_quantile_lstsq.
+pub struct QuantileRegression<T: RealField + Float> {
+    pub tau: T,
+    pub solver: LRSolverMethods,
+    pub has_bias: bool,
+    pub tol: T,
+    pub max_iter: usize,
+    pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + 1) x 1 if there is bias
+    pub n_iter: usize,        // Number of iterations in the last fit
+    pub converged: bool,      // Whether the last fit converged
+}
+
+impl<T: RealField + Float> QuantileRegression<T> {
+    pub fn new(tau: T, solver: &str, has_bias: bool, tol: T, max_iter: usize) -> Self {
+        QuantileRegression {
+            tau,
+            solver: solver.into(),
+            has_bias,
+            tol,
+            max_iter,
+            coefficients: Mat::new(),
+            n_iter: 0,
+            converged: false,
+        }
+    }
+}
+
+impl<T: RealField + Float> LinearRegression<T> for QuantileRegression<T> {
+    fn fitted_values(&self) -> MatRef<T> {
+        self.coefficients.as_ref()
+    }
+
+    fn has_bias(&self) -> bool {
+        self.has_bias
+    }
+
+    fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
+        let fit = faer_quantile_lstsq(X, y, self.tau, self.solver, self.tol, self.max_iter);
+        self.coefficients = fit.coefficients;
+        self.n_iter = fit.n_iter;
+        self.converged = fit.converged;
+    }
+
+    fn fit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        if !(self.tau > T::zero() && self.tau < T::one()) {
+            return Err(LinalgErrors::Other(
+                "Quantile `tau` must be in (0, 1).".to_string(),
+            ));
+        } else if X.nrows() != y.nrows() {
+            return Err(LinalgErrors::DimensionMismatch);
+        } else if X.nrows() < X.ncols() || X.nrows() == 0 {
+            return Err(LinalgErrors::NotEnoughData);
+        }
+        self.fit_unchecked(X, y);
+        Ok(())
+    }
+}
+
+/// Huber regression, which minimizes the Huber loss of the residuals in units of a robust scale. Residuals
+/// larger than delta times the scale are downweighted. This is faer_robust_lstsq with the Huber loss and
+/// tuning constant delta, where the scale is re-estimated in each iteration by Huber's proposal 2,
+/// starting from the normalized MAD of the OLS residuals. delta = 1.345 gives 95% efficiency under
+/// normal errors.
+pub struct HuberRegression<T: RealField + Float> {
+    pub delta: T,
+    pub solver: LRSolverMethods,
+    pub has_bias: bool,
+    pub tol: T,
+    pub max_iter: usize,
+    pub coefficients: Mat<T>, // n_features x 1 matrix, or (n_features + 1) x 1 if there is bias
+    pub scale: T,             // The residual scale at the end of the last fit
+    pub n_iter: usize,        // Number of IRLS iterations in the last fit
+    pub converged: bool,      // Whether the last fit converged
+}
+
+impl<T: RealField + Float> HuberRegression<T> {
+    pub fn new(delta: T, solver: &str, has_bias: bool, tol: T, max_iter: usize) -> Self {
+        HuberRegression {
+            delta,
+            solver: solver.into(),
+            has_bias,
+            tol,
+            max_iter,
+            coefficients: Mat::new(),
+            scale: T::nan(),
+            n_iter: 0,
+            converged: false,
+        }
+    }
+}
+
+impl<T: RealField + Float> LinearRegression<T> for HuberRegression<T> {
+    fn fitted_values(&self) -> MatRef<T> {
+        self.coefficients.as_ref()
+    }
+
+    fn has_bias(&self) -> bool {
+        self.has_bias
+    }
+
+    fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
+        let fit = faer_robust_lstsq(
+            X,
+            y,
+            RobustLoss::Huber,
+            self.delta,
+            true,
+            self.solver,
+            self.tol,
+            self.max_iter,
+            StopCriterion::CoefficientChange,
+        );
+        self.coefficients = fit.coefficients;
+        self.scale = fit.scale;
+        self.n_iter = fit.n_iter;
+        self.converged = fit.converged;
+    }
+
+    fn fit(&mut self, X: MatRef<T>, y: MatRef<T>) -> Result<(), LinalgErrors> {
+        if self.delta.is_nan() || self.delta <= T::zero() {
+            return Err(LinalgErrors::Other(
+                "Huber threshold `delta` must be positive.".to_string(),
+            ));
+        } else if X.nrows() != y.nrows() {
+            return Err(LinalgErrors::DimensionMismatch);
+        } else if X.nrows() < X.ncols() || X.nrows() == 0 {
+            return Err(LinalgErrors::NotEnoughData);
+        }
+        self.fit_unchecked(X, y);
+        Ok(())
+    }
+}
+
+pub struct RansacFit<T: RealField + Float> {
+    pub lr: LR<T>,          // Fit on the inliers of the best trial
+    pub inliers: Vec<bool>, // Whether each row is within the threshold of the best trial model
+    pub n_inliers: usize,
+}
+
+/// RANSAC (Fischler and Bolles 1981). In each of n_trials trials, a random subset of min_samples rows
+/// is drawn and fit by LR, and the rows whose absolute residual under that fit is <= residual_threshold
+/// are its inliers. The trial with the most inliers wins, and ties go to the smaller sum of squared
+/// inlier residuals. The returned LR is refit on the inliers of the winning trial. X must not contain
+/// the bias column: the LR adds it if has_bias. If residual_threshold is None, the median absolute
+/// deviation of y (from its median, not normalized) is used. Trials on rank deficient subsets are skipped.
+#[allow(clippy::too_many_arguments)]
+pub fn ransac_fit<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+    solver: &str,
+    n_trials: usize,
+    min_samples: Option<usize>,
+    residual_threshold: Option<T>,
+    seed: Option<u64>,
+) -> Result<RansacFit<T>, LinalgErrors> {
+    let n = x.nrows();
+    let p = x.ncols() + has_bias as usize;
+    if n != y.nrows() {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    let min_samples = min_samples.unwrap_or(p);
+    if n_trials == 0 {
+        return Err(LinalgErrors::Other(
+            "The number of trials must be positive.".to_string(),
+        ));
+    } else if min_samples < p.max(1) || min_samples > n {
+        return Err(LinalgErrors::Other(
+            "`min_samples` must be >= #features (+1 if bias) and <= #rows.".to_string(),
+        ));
+    }
+    let threshold = match residual_threshold {
+        Some(t) => t,
+        None => {
+            let mut yv = (0..n).map(|i| *y.get(i, 0)).collect::<Vec<_>>();
+            let med = median_inplace(&mut yv);
+            let mut dev = yv.into_iter().map(|v| (v - med).abs()).collect::<Vec<_>>();
+            median_inplace(&mut dev)
+        }
+    };
+    if threshold.is_nan() || threshold < T::zero() {
+        return Err(LinalgErrors::Other(
+            "`residual_threshold` must be non-negative.".to_string(),
+        ));
+    }
+
+    let mut rng = rng_from_seed(seed);
+    // (n_inliers, sum of squared inlier residuals, inlier mask) of the best trial
+    let mut best: Option<(usize, T, Vec<bool>)> = None;
+    for _ in 0..n_trials {
+        let idx = sample(&mut rng, n, min_samples);
+        let xs = Mat::from_fn(min_samples, x.ncols(), |i, j| *x.get(idx.index(i), j));
+        let ys = Mat::from_fn(min_samples, 1, |i, _| *y.get(idx.index(i), 0));
+        let mut lr = LR::new(solver, T::zero(), has_bias);
+        lr.fit_unchecked(xs.as_ref(), ys.as_ref());
+        if lr.rank < p {
+            continue;
+        }
+        let pred = lr.predict(x)?;
+        let (mut n_in, mut ss) = (0, T::zero());
+        let mask = (0..n)
+            .map(|i| {
+                let r = (*y.get(i, 0) - *pred.get(i, 0)).abs();
+                let is_in = r <= threshold;
+                if is_in {
+                    n_in += 1;
+                    ss = ss + r * r;
+                }
+                is_in
+            })
+            .collect::<Vec<_>>();
+        let better = match &best {
+            Some((b_in, b_ss, _)) => n_in > *b_in || (n_in == *b_in && ss < *b_ss),
+            None => true,
+        };
+        if better {
+            best = Some((n_in, ss, mask));
+        }
+    }
+
+    let (n_inliers, _, inliers) = best.ok_or_else(|| {
+        LinalgErrors::Other("All RANSAC trials drew a rank deficient subset.".to_string())
+    })?;
+    let rows = (0..n).filter(|i| inliers[*i]).collect::<Vec<_>>();
+    let xs = Mat::from_fn(rows.len(), x.ncols(), |i, j| *x.get(rows[i], j));
+    let ys = Mat::from_fn(rows.len(), 1, |i, _| *y.get(rows[i], 0));
+    let mut lr = LR::new(solver, T::zero(), has_bias);
+    lr.fit(xs.as_ref(), ys.as_ref())?;
+    Ok(RansacFit {
+        lr,
+        inliers,
+        n_inliers,
+    })
+}
+
+/// Theil-Sen estimator for simple regression: the slope is the median of the pairwise slopes
+/// (y_j - y_i) / (x_j - x_i) over pairs with x_i != x_j, and the intercept is the median of
+/// y_i - slope * x_i. It resists up to ~29% of outliers. All n(n-1)/2 pairs are used, unless
+/// max_pairs is given and smaller, in which case max_pairs random pairs are drawn with replacement.
+/// Returns (slope, intercept).
+pub fn theil_sen<T: RealField + Float>(
+    x: &[T],
+    y: &[T],
+    max_pairs: Option<usize>,
+    seed: Option<u64>,
+) -> Result<(T, T), LinalgErrors> {
+    let n = x.len();
+    if n != y.len() {
+        return Err(LinalgErrors::DimensionMismatch);
+    } else if n < 2 {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    let pair_slope = |i: usize, j: usize| {
+        let dx = x[j] - x[i];
+        if dx == T::zero() {
+            None
+        } else {
+            Some((y[j] - y[i]) / dx)
+        }
+    };
+    let n_pairs = n * (n - 1) / 2;
+    let mut slopes = match max_pairs {
+        Some(m) if m < n_pairs => {
+            if m == 0 {
+                return Err(LinalgErrors::Other(
+                    "`max_pairs` must be positive.".to_string(),
+                ));
+            }
+            let mut rng = rng_from_seed(seed);
+            (0..m)
+                .filter_map(|_| {
+                    let i = rng.gen_range(0..n);
+                    // j is uniform over the other rows
+                    let j = (i + rng.gen_range(1..n)) % n;
+                    pair_slope(i, j)
+                })
+                .collect::<Vec<_>>()
+        }
+        _ => (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .filter_map(|(i, j)| pair_slope(i, j))
+            .collect::<Vec<_>>(),
+    };
+    if slopes.is_empty() {
+        return Err(LinalgErrors::Other(
+            "Theil-Sen needs at least 2 distinct values of x.".to_string(),
+        ));
+    }
+    let slope = median_inplace(&mut slopes);
+    let mut intercepts = x
+        .iter()
+        .zip(y.iter())
+        .map(|(xi, yi)| *yi - slope * *xi)
+        .collect::<Vec<_>>();
+    let intercept = median_inplace(&mut intercepts);
+    Ok((slope, intercept))
+}
@@ -0,0 +1,355 @@
+/// Feature selection for linear regression.
+use super::{lr_solvers::faer_solve_gram, LRSolverMethods, LinalgErrors};
+use faer::{linalg::solvers::Solve, mat::Mat, prelude::*, Side};
+use faer_traits::RealField;
+use num::Float;
+
+/// Max number of candidate features for the exhaustive best subset search. There are 2^p subsets.
+pub const MAX_SUBSET_FEATURES: usize = 20;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SubsetCriterion {
+    Aic,
+    Bic,
+    AdjR2,
+}
+
+impl SubsetCriterion {
+    pub const ALL: [SubsetCriterion; 3] = [Self::Aic, Self::Bic, Self::AdjR2];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Aic => "aic",
+            Self::Bic => "bic",
+            Self::AdjR2 => "adj_r2",
+        }
+    }
+
+    /// The criterion of a least square fit with k coefficients (bias included) on n rows. tss is the
+    /// total sum of squares with tss_dof degrees of freedom. AIC and BIC are the Gaussian ones, up to
+    /// a constant.
+    #[inline(always)]
+    pub fn value<T: Float>(&self, rss: T, n: T, k: T, tss: T, tss_dof: T) -> T {
+        match self {
+            Self::Aic => n * (rss / n).ln() + (k + k),
+            Self::Bic => n * (rss / n).ln() + k * n.ln(),
+            Self::AdjR2 => T::one() - (rss / (n - k)) / (tss / tss_dof),
+        }
+    }
+
+    /// Whether a is strictly better than b. Smaller is better, except for adjusted R2.
+    #[inline(always)]
+    pub fn is_better<T: Float>(&self, a: T, b: T) -> bool {
+        match self {
+            Self::AdjR2 => a > b,
+            _ => a < b,
+        }
+    }
+}
+
+impl From<&str> for SubsetCriterion {
+    fn from(value: &str) -> Self {
+        match value {
+            "bic" => Self::Bic,
+            "adj_r2" => Self::AdjR2,
+            _ => Self::Aic,
+        }
+    }
+}
+
+pub struct SubsetFit<T: RealField + Float> {
+    pub criterion: SubsetCriterion,
+    pub features: Vec<usize>, // Indices of the selected features, in increasing order
+    pub coefficients: Mat<T>, // Coefficients of the selected features, then the bias if has_bias
+    pub value: T,             // Value of the criterion
+}
+
+/// Least square on the columns idx of X, from the Gram matrices XtX and XtY of all columns. yty is the
+/// squared norm of y. Returns the coefficients and the residual sum of squares yty - b^T XtY_idx.
+fn subset_fit<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    yty: T,
+    idx: &[usize],
+) -> (Mat<T>, T) {
+    let k = idx.len();
+    let sub_xtx = Mat::from_fn(k, k, |i, j| *xtx.get(idx[i], idx[j]));
+    let sub_xty = Mat::from_fn(k, 1, |i, _| *xty.get(idx[i], 0));
+    // The sub-Gram matrix is positive definite unless the subset is collinear.
+    let coeffs = match sub_xtx.llt(Side::Lower) {
+        Ok(llt) => llt.solve(&sub_xty),
+        Err(_) => faer_solve_gram(sub_xtx, sub_xty.clone(), LRSolverMethods::QR),
+    };
+    let rss = (yty - *(coeffs.transpose() * &sub_xty).get(0, 0)).max(T::zero());
+    (coeffs, rss)
+}
+
+/// Exhaustive best subset selection. Every subset of the features with 1 to max_size features is fit
+/// by least squares, and the best subset by each of AIC, BIC and adjusted R2 is returned, in the order
+/// of SubsetCriterion::ALL. If has_bias, the last column of x must be the bias, which is in every model.
+///
+/// XtX and XtY are computed once, and each subset is solved on the corresponding sub-matrices. The
+/// residual sum of squares of a subset is then yty - b^T XtY. Subsets with as many coefficients as rows
+/// are skipped. This errors if there are more than MAX_SUBSET_FEATURES features.
+pub fn faer_best_subset<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+    max_size: usize,
+) -> Result<Vec<SubsetFit<T>>, LinalgErrors> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let p = ncols.abs_diff(has_bias as usize);
+    if p > MAX_SUBSET_FEATURES {
+        return Err(LinalgErrors::Other(format!(
+            "Best subset selection supports at most {} features, but got {}.",
+            MAX_SUBSET_FEATURES, p
+        )));
+    }
+    if p == 0 || nrows <= 1 + has_bias as usize {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    let yty = y.col(0).squared_norm_l2();
+    let n = T::from(nrows).unwrap();
+    let (tss, tss_dof) = if has_bias {
+        // The last entry of XtY is the sum of y
+        let sum_y = *xty.get(p, 0);
+        (yty - sum_y * sum_y / n, n - T::one())
+    } else {
+        (yty, n)
+    };
+
+    let max_size = max_size.clamp(1, p).min(nrows - 1 - has_bias as usize);
+    let mut best: Vec<Option<(T, u32, Mat<T>)>> = vec![None; SubsetCriterion::ALL.len()];
+    let mut idx = Vec::with_capacity(ncols);
+    for mask in 1u32..(1u32 << p) {
+        if mask.count_ones() as usize > max_size {
+            continue;
+        }
+        idx.clear();
+        idx.extend((0..p).filter(|j| mask & (1 << j) != 0));
+        if has_bias {
+            idx.push(p);
+        }
+        let (coeffs, rss) = subset_fit(xtx.as_ref(), xty.as_ref(), yty, &idx);
+        let k = T::from(idx.len()).unwrap();
+
+        for (criterion, b) in SubsetCriterion::ALL.iter().zip(best.iter_mut()) {
+            let value = criterion.value(rss, n, k, tss, tss_dof);
+            let replace = match b {
+                Some((v, _, _)) => criterion.is_better(value, *v),
+                None => !value.is_nan(),
+            };
+            if replace {
+                *b = Some((value, mask, coeffs.clone()));
+            }
+        }
+    }
+
+    Ok(SubsetCriterion::ALL
+        .into_iter()
+        .zip(best)
+        .filter_map(|(criterion, b)| {
+            b.map(|(value, mask, coefficients)| SubsetFit {
+                criterion,
+                features: (0..p).filter(|j| mask & (1 << j) != 0).collect(),
+                coefficients,
+                value,
+            })
+        })
+        .collect())
+}
+
+/// The LMG (Lindeman, Merenda and Gold) decomposition of R2, which is the Shapley value of each feature
+/// in the game where the value of a subset of features is the R2 of the least square fit on it. The
+/// importance of feature j is the average, over all orderings of the features, of the increase in R2
+/// when j is added after the features before it. The importances sum to the R2 of the full model. If
+/// has_bias, the last column of x must be the bias, which is in every model, and R2 is centered.
+/// Otherwise, R2 is 1 - RSS / yty.
+///
+/// The R2 of every subset is computed from sub-matrices of XtX, so this is exponential in the number of
+/// features. It errors if there are more than MAX_SUBSET_FEATURES features.
+pub fn faer_relative_importance<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+) -> Result<Vec<T>, LinalgErrors> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let p = ncols.abs_diff(has_bias as usize);
+    if p > MAX_SUBSET_FEATURES {
+        return Err(LinalgErrors::Other(format!(
+            "Relative importance supports at most {} features, but got {}.",
+            MAX_SUBSET_FEATURES, p
+        )));
+    }
+    if p == 0 || nrows <= ncols {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    let yty = y.col(0).squared_norm_l2();
+    let tss = if has_bias {
+        let sum_y = *xty.get(p, 0);
+        yty - sum_y * sum_y / T::from(nrows).unwrap()
+    } else {
+        yty
+    };
+
+    // R2 of every subset, indexed by bitmask. The empty subset (bias only) has R2 = 0.
+    let mut r2 = vec![T::zero(); 1 << p];
+    let mut idx = Vec::with_capacity(ncols);
+    for (mask, r) in r2.iter_mut().enumerate().skip(1) {
+        idx.clear();
+        idx.extend((0..p).filter(|j| mask & (1 << j) != 0));
+        if has_bias {
+            idx.push(p);
+        }
+        let (_, rss) = subset_fit(xtx.as_ref(), xty.as_ref(), yty, &idx);
+        *r = T::one() - rss / tss;
+    }
+
+    // A subset S without j, with |S| = s, comes right before j in s! (p - s - 1)! of the p! orderings.
+    // That is a fraction 1 / (p * C(p - 1, s)).
+    let pf = T::from(p).unwrap();
+    let mut weights = Vec::with_capacity(p);
+    let mut binom = T::one();
+    for s in 0..p {
+        weights.push((pf * binom).recip());
+        binom = binom * T::from(p - 1 - s).unwrap() / T::from(s + 1).unwrap();
+    }
+
+    let mut importance = vec![T::zero(); p];
+    // The full set has no feature left to add
+    for (mask, r) in r2.iter().enumerate().take((1 << p) - 1) {
+        let w = weights[mask.count_ones() as usize];
+        for (j, imp) in importance.iter_mut().enumerate() {
+            if mask & (1 << j) == 0 {
+                *imp = *imp + w * (r2[mask | (1 << j)] - *r);
+            }
+        }
+    }
+    Ok(importance)
+}
+
+pub struct StepwiseFit<T: RealField + Float> {
+    pub features: Vec<usize>, // Indices of the selected features, in the order they were added
+    pub coefficients: Mat<T>, // Coefficients of the selected features, then the bias if has_bias
+    pub value: T,             // Value of the criterion
+    pub rejected: Vec<usize>, // Candidates refused for collinearity, in the order they were refused
+}
+
+/// The VIF of feature j against the features in idx (bias included, if any), 1 / (1 - R2_j), where R2_j
+/// is the R2 of regressing column j on the columns idx. Everything comes from XtX. tss_j is the total
+/// sum of squares of column j, which is centered if there is a bias.
+fn gram_vif<T: RealField + Float>(xtx: MatRef<T>, j: usize, idx: &[usize], tss_j: T) -> T {
+    if idx.is_empty() {
+        return T::one();
+    }
+    let (_, rss) = subset_fit(xtx, xtx.get(.., j..j + 1), *xtx.get(j, j), idx);
+    tss_j / rss
+}
+
+/// Forward stepwise selection. Starting from the model with no features (only the bias, if has_bias),
+/// the candidate that improves the criterion the most is added, until no candidate improves it or
+/// max_size features are selected. If has_bias, the last column of x must be the bias, which is in every
+/// model.
+///
+/// If max_vif is given, a candidate whose VIF against the already selected features (and the bias) is
+/// above max_vif is refused and never considered again, which is fine since adding features never
+/// decreases a VIF. So the selected features are never collinear. Everything is computed from XtX and XtY.
+pub fn faer_forward_stepwise<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+    criterion: SubsetCriterion,
+    max_size: usize,
+    max_vif: Option<T>,
+) -> Result<StepwiseFit<T>, LinalgErrors> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let p = ncols.abs_diff(has_bias as usize);
+    if p == 0 || nrows <= 1 + has_bias as usize {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    let yty = y.col(0).squared_norm_l2();
+    let n = T::from(nrows).unwrap();
+    let centered = |ss: T, sum: T| if has_bias { ss - sum * sum / n } else { ss };
+    let (tss, tss_dof) = if has_bias {
+        (centered(yty, *xty.get(p, 0)), n - T::one())
+    } else {
+        (yty, n)
+    };
+
+    let max_size = max_size.clamp(1, p).min(nrows - 1 - has_bias as usize);
+    let mut idx: Vec<usize> = if has_bias { vec![p] } else { vec![] };
+    let mut coefficients = if has_bias {
+        Mat::from_fn(1, 1, |_, _| *xty.get(p, 0) / n)
+    } else {
+        Mat::zeros(0, 1)
+    };
+    let k0 = T::from(idx.len()).unwrap();
+    let mut value = criterion.value(tss, n, k0, tss, tss_dof);
+    let mut features = Vec::with_capacity(max_size);
+    let mut rejected = Vec::new();
+    let mut candidates = (0..p).collect::<Vec<_>>();
+    let mut trial = Vec::with_capacity(ncols);
+
+    while features.len() < max_size {
+        if let Some(threshold) = max_vif {
+            candidates.retain(|j| {
+                // With a bias, the last column of XtX has the column sums
+                let sum_j = if has_bias { *xtx.get(*j, p) } else { T::zero() };
+                let tss_j = centered(*xtx.get(*j, *j), sum_j);
+                // A NaN VIF (constant column) is refused too
+                let keep = gram_vif(xtx.as_ref(), *j, &idx, tss_j) <= threshold;
+                if !keep {
+                    rejected.push(*j);
+                }
+                keep
+            });
+        }
+
+        let k = T::from(idx.len() + 1).unwrap();
+        let mut best: Option<(T, usize, Mat<T>)> = None;
+        for (c, j) in candidates.iter().enumerate() {
+            // The features, then the bias, so that the bias stays the last coefficient
+            trial.clear();
+            trial.extend_from_slice(&idx[..features.len()]);
+            trial.push(*j);
+            if has_bias {
+                trial.push(p);
+            }
+            let (coeffs, rss) = subset_fit(xtx.as_ref(), xty.as_ref(), yty, &trial);
+            let v = criterion.value(rss, n, k, tss, tss_dof);
+            let replace = match &best {
+                Some((b, _, _)) => criterion.is_better(v, *b),
+                None => !v.is_nan(),
+            };
+            if replace {
+                best = Some((v, c, coeffs));
+            }
+        }
+        match best {
+            Some((v, c, coeffs)) if criterion.is_better(v, value) => {
+                let j = candidates.remove(c);
+                features.push(j);
+                idx.insert(features.len() - 1, j);
+                coefficients = coeffs;
+                value = v;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(StepwiseFit {
+        features,
+        coefficients,
+        value,
+        rejected,
+    })
+}
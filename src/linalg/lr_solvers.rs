@@ -1,8 +1,30 @@
 #![allow(non_snake_case)]
-use super::{LRSolverMethods, LinalgErrors, LinearRegression};
-use faer::{linalg::solvers::Solve, mat::Mat, prelude::*};
+use super::{
+    lr_diagnostics::faer_xtx_eigenvalues, LRSolverMethods, LassoAlgorithm, LinalgErrors,
+    LinearRegression, StopCriterion,
+};
+use faer::{
+    linalg::solvers::{DenseSolveCore, Solve, SolveLstsqCore},
+    mat::Mat,
+    prelude::*,
+    Side,
+};
 use faer_traits::RealField;
 use num::Float;
+use std::time::Instant;
+
+/// Default condition number of the design above which a fit is flagged as ill-conditioned. At
+/// ~1/sqrt(eps), XtX has condition number ~1/eps, and the normal equations lose all precision.
+pub const DEFAULT_COND_THRESHOLD: f64 = 1e8;
+
+/// A flat view of a LR model. The coefficients (without bias) are borrowed from the model.
+pub struct LRParts<'a, T> {
+    pub coefficients: &'a [T],
+    pub bias: T,
+    pub has_bias: bool,
+    pub lambda: T,
+    pub solver: &'static str,
+}
 
 /// A struct that handles regular linear regression and Ridge regression.
 pub struct LR<T: RealField + Float> {
@@ -10,6 +32,9 @@ pub struct LR<T: RealField + Float> {
     pub lambda: T,
     pub coefficients: Mat<T>, // n_features x 1 matrix, doesn't contain bias
     pub has_bias: bool,
+    pub rank: usize, // Numerical rank of the design in the last fit, bias column included
+    pub cond: T,     // Estimated condition number of the design in the last fit, 0 if not fit
+    pub warn_collinearity: T, // The fit is flagged as ill-conditioned if cond > this
 }
 
 impl<T: RealField + Float> LR<T> {
@@ -19,20 +44,102 @@ impl<T: RealField + Float> LR<T> {
             lambda: lambda,
             coefficients: Mat::new(),
             has_bias: has_bias,
+            rank: 0,
+            cond: T::zero(),
+            warn_collinearity: T::from(DEFAULT_COND_THRESHOLD).unwrap(),
         }
     }
 
     pub fn from_values(coeffs: &[T], bias: T) -> Self {
-        LR {
-            solver: LRSolverMethods::default(),
-            lambda: T::zero(),
-            coefficients: faer::mat::Mat::from_fn(coeffs.len(), 1, |i, _| coeffs[i]),
-            has_bias: bias.abs() > T::epsilon(),
+        let mut lr = LR::new("qr", T::zero(), false);
+        lr.set_coeffs_and_bias(coeffs, bias);
+        lr
+    }
+
+    /// Same as from_values, but has_bias is given instead of inferred from the bias, so a model
+    /// with a bias of exactly 0 keeps its bias term.
+    pub fn from_values_with_has_bias(coeffs: &[T], bias: T, has_bias: bool) -> Self {
+        let n = coeffs.len();
+        let coefficients = if has_bias {
+            Mat::from_fn(n + 1, 1, |i, _| if i < n { coeffs[i] } else { bias })
+        } else {
+            ColRef::<T>::from_slice(coeffs).as_mat().to_owned()
+        };
+        let mut lr = LR::new("qr", T::zero(), has_bias);
+        lr.coefficients = coefficients;
+        lr
+    }
+
+    /// Flattens the model into plain values, so it can cross an FFI boundary without faer types.
+    /// LR::from_parts(&lr.to_parts()?) gives back the same coefficients, bias, has_bias, lambda and
+    /// solver, and so does LR::from_values_with_has_bias for the coefficients, bias and has_bias. The fit
+    /// diagnostics (rank and condition number) are not part of it.
+    pub fn to_parts(&self) -> Result<LRParts<'_, T>, LinalgErrors> {
+        if self.coefficients.nrows() == 0 {
+            return Err(LinalgErrors::MatNotLearnedYet);
+        }
+        let n = self.coefficients.nrows() - self.has_bias as usize;
+        Ok(LRParts {
+            coefficients: &self.coefficients.col_as_slice(0)[..n],
+            bias: self.bias(),
+            has_bias: self.has_bias,
+            lambda: self.lambda,
+            solver: self.solver.as_str(),
+        })
+    }
+
+    pub fn from_parts(parts: &LRParts<'_, T>) -> Self {
+        let mut lr = LR::from_values_with_has_bias(parts.coefficients, parts.bias, parts.has_bias);
+        lr.solver = parts.solver.into();
+        lr.lambda = parts.lambda;
+        lr
+    }
+
+    /// The numerical rank of the design matrix (with the bias column, if any) used in the last fit,
+    /// as seen by the solver. See faer_design_rank_cond. This is 0 if the model is not fit, or if the
+    /// coefficients are set directly.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// The estimated condition number of the design matrix (with the bias column, if any) used in the
+    /// last fit. This is infinite if the design is rank deficient, and 0 if the model is not fit.
+    pub fn condition_number(&self) -> T {
+        self.cond
+    }
+
+    /// Returns a warning if the condition number of the design in the last fit exceeds the
+    /// warn_collinearity threshold. Nothing is printed during the fit.
+    pub fn collinearity_warning(&self) -> Option<String> {
+        if self.cond > self.warn_collinearity {
+            Some(format!(
+                "Design matrix is ill-conditioned (condition number {:e} > {:e}). The features may be \
+                 collinear and the coefficients may be unstable.",
+                self.cond.to_f64().unwrap_or(f64::NAN),
+                self.warn_collinearity.to_f64().unwrap_or(f64::NAN)
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Returns RankDeficient if the design matrix in the last fit does not have full column rank,
+    /// i.e. the coefficients are not identified. Ridge (lambda > 0) still gives a unique solution,
+    /// but the rank is about the design and doesn't depend on lambda.
+    pub fn check_full_rank(&self) -> Result<(), LinalgErrors> {
+        self.check_is_fit()?;
+        let n = self.fitted_values().nrows();
+        if self.rank < n {
+            Err(LinalgErrors::RankDeficient(self.rank, n))
+        } else {
+            Ok(())
         }
     }
 
     pub fn set_coeffs_and_bias(&mut self, coeffs: &[T], bias: T) {
         self.has_bias = bias.abs() > T::epsilon();
+        self.rank = 0;
+        self.cond = T::zero();
         if self.has_bias {
             self.coefficients = Mat::from_fn(coeffs.len() + 1, 1, |i, _| {
                 if i < coeffs.len() {
@@ -45,6 +152,29 @@ impl<T: RealField + Float> LR<T> {
             self.coefficients = ColRef::<T>::from_slice(coeffs).as_mat().to_owned();
         }
     }
+
+    /// Returns a copy of the model where the coefficients at the true positions of the mask
+    /// are set to 0. The bias is never masked. This is for quick what-if analysis, e.g. the
+    /// effect of dropping a predictor on the predictions, without refitting.
+    pub fn with_coefficients_masked(&self, mask: &[bool]) -> Result<Self, LinalgErrors> {
+        self.check_is_fit()?;
+        if mask.len() != self.coefficients().nrows() {
+            return Err(LinalgErrors::DimensionMismatch);
+        }
+        let mut coefficients = self.coefficients.clone();
+        for (i, _) in mask.iter().enumerate().filter(|(_, m)| **m) {
+            *coefficients.get_mut(i, 0) = T::zero();
+        }
+        Ok(LR {
+            solver: self.solver,
+            lambda: self.lambda,
+            coefficients,
+            has_bias: self.has_bias,
+            rank: self.rank,
+            cond: self.cond,
+            warn_collinearity: self.warn_collinearity,
+        })
+    }
 }
 
 impl<T: RealField + Float> LinearRegression<T> for LR<T> {
@@ -57,12 +187,35 @@ impl<T: RealField + Float> LinearRegression<T> for LR<T> {
     }
 
     fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
-        self.coefficients = if self.has_bias {
+        // Same as faer_solve_lstsq, but the rank and the condition number of the design are read off
+        // the factorization that solves it. With Ridge, that factorization is of the penalized
+        // problem, so the design is factorized once more for them.
+        let (lambda, has_bias, solver) = (self.lambda, self.has_bias, self.solver);
+        let fit = |x: MatRef<T>| match solver {
+            LRSolverMethods::QRDirect if lambda > T::zero() => {
+                let (rank, cond) = faer_design_rank_cond(x, solver);
+                (faer_qr_direct_lstsq(x, y, lambda, has_bias), rank, cond)
+            }
+            LRSolverMethods::QRDirect => qr_direct_solve_rank_cond(x, y),
+            _ => {
+                let (mut xtx, xty) = faer_gram(x, y, T::zero(), has_bias);
+                if lambda > T::zero() {
+                    let (rank, cond) = faer_gram_rank_cond(xtx.as_ref());
+                    for i in 0..x.ncols().abs_diff(has_bias as usize) {
+                        *xtx.get_mut(i, i) = *xtx.get(i, i) + lambda;
+                    }
+                    (faer_solve_gram(xtx, xty, solver), rank, cond)
+                } else {
+                    solve_gram_rank_cond(xtx, xty, solver)
+                }
+            }
+        };
+        (self.coefficients, self.rank, self.cond) = if self.has_bias {
             let ones = Mat::full(X.nrows(), 1, T::one());
             let new = faer::concat![[X, ones]];
-            faer_solve_lstsq(new.as_ref(), y, self.lambda, true, self.solver)
+            fit(new.as_ref())
         } else {
-            faer_solve_lstsq(X, y, self.lambda, false, self.solver)
+            fit(X)
         };
     }
 }
@@ -74,10 +227,24 @@ pub struct ElasticNet<T: RealField + Float> {
     pub has_bias: bool,
     pub tol: T,
     pub max_iter: usize,
+    pub stop_criterion: StopCriterion,
+    pub penalize_bias: bool, // If false, the bias is estimated as the mean of the residuals
+    pub penalty_factor: Option<Vec<T>>, // Per-feature multipliers of l1_reg. None means all 1
+    pub converged: bool,     // Whether the last fit converged
+    pub n_iter: usize,       // Number of iterations run in the last fit
 }
 
 impl<T: RealField + Float> ElasticNet<T> {
-    pub fn new(l1_reg: T, l2_reg: T, has_bias: bool, tol: T, max_iter: usize) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        l1_reg: T,
+        l2_reg: T,
+        has_bias: bool,
+        tol: T,
+        max_iter: usize,
+        stop_criterion: StopCriterion,
+        penalize_bias: bool,
+    ) -> Self {
         ElasticNet {
             l1_reg: l1_reg,
             l2_reg: l2_reg,
@@ -85,9 +252,21 @@ impl<T: RealField + Float> ElasticNet<T> {
             has_bias: has_bias,
             tol: tol,
             max_iter: max_iter,
+            stop_criterion,
+            penalize_bias,
+            penalty_factor: None,
+            converged: false,
+            n_iter: 0,
         }
     }
 
+    /// Scales the l1 penalty of the j-th feature by penalty_factor[j]. A factor of 0 leaves the feature
+    /// unpenalized by l1. Missing factors are 1. The bias is never penalized unless penalize_bias.
+    pub fn with_penalty_factor(mut self, penalty_factor: Vec<T>) -> Self {
+        self.penalty_factor = Some(penalty_factor);
+        self
+    }
+
     pub fn from_values(coeffs: &[T], bias: T) -> Self {
         let has_bias = bias.abs() > T::epsilon();
         let coefficients = if has_bias {
@@ -109,6 +288,11 @@ impl<T: RealField + Float> ElasticNet<T> {
             has_bias: has_bias,
             tol: T::from(1e-5).unwrap(),
             max_iter: 2000,
+            stop_criterion: StopCriterion::default(),
+            penalize_bias: false,
+            penalty_factor: None,
+            converged: false,
+            n_iter: 0,
         }
     }
 
@@ -142,27 +326,35 @@ impl<T: RealField + Float> LinearRegression<T> for ElasticNet<T> {
     }
 
     fn fit_unchecked(&mut self, X: MatRef<T>, y: MatRef<T>) {
-        self.coefficients = if self.has_bias {
+        // With penalize_bias, the column of ones is just another penalized feature to the solver
+        let unpenalized_bias = self.has_bias && !self.penalize_bias;
+        (self.coefficients, self.n_iter, self.converged) = if self.has_bias {
             let ones = Mat::full(X.nrows(), 1, T::one());
             let new_x = faer::concat![[X, ones]];
             faer_coordinate_descent(
                 new_x.as_ref(),
                 y,
+                None,
                 self.l1_reg,
                 self.l2_reg,
-                self.has_bias,
+                self.penalty_factor.as_deref(),
+                unpenalized_bias,
                 self.tol,
                 self.max_iter,
+                self.stop_criterion,
             )
         } else {
             faer_coordinate_descent(
                 X,
                 y,
+                None,
                 self.l1_reg,
                 self.l2_reg,
-                self.has_bias,
+                self.penalty_factor.as_deref(),
+                false,
                 self.tol,
                 self.max_iter,
+                self.stop_criterion,
             )
         };
     }
@@ -182,7 +374,7 @@ impl<T: RealField + Float> LinearRegression<T> for ElasticNet<T> {
 // --- The functions ---
 // ---------------------
 
-/// Least square that sets all singular values below threshold to 0.
+/// Least square that sets all singular values of X below rcond * (max singular value) to 0.
 /// Returns the coefficients and the singular values
 #[inline(always)]
 pub fn faer_solve_lstsq_rcond<T: RealField + Float>(
@@ -213,11 +405,16 @@ pub fn faer_solve_lstsq_rcond<T: RealField + Float>(
 
     let max_singular_value = singular_values.iter().copied().fold(T::min_value(), T::max);
     let threshold = rcond * max_singular_value;
-    // Safe, because i <= n
+    // s holds the eigenvalues of XtX, which are the squares of the singular values of X, so the
+    // cut off is applied to their square roots. Safe, because i <= n
     let mut s_inv = Mat::<T>::zeros(n, n);
     unsafe {
         for (i, v) in s.iter().copied().enumerate() {
-            *s_inv.get_mut_unchecked(i, i) = if v >= threshold { v.recip() } else { T::zero() };
+            *s_inv.get_mut_unchecked(i, i) = if singular_values[i] >= threshold {
+                v.recip()
+            } else {
+                T::zero()
+            };
         }
     }
 
@@ -235,8 +432,183 @@ pub fn faer_solve_lstsq<T: RealField + Float>(
     has_bias: bool,
     how: LRSolverMethods,
 ) -> Mat<T> {
+    if let LRSolverMethods::QRDirect = how {
+        return faer_qr_direct_lstsq(x, y, lambda, has_bias);
+    }
     // Add ridge SVD with rconditional number later.
+    let (xtx, xty) = faer_gram(x, y, lambda, has_bias);
+    faer_solve_gram(xtx, xty, how)
+}
 
+/// Least square by the QR decomposition of X itself: X = QR, and R b = Q^t y. This doesn't form XtX,
+/// whose condition number is the square of that of X, so it is more accurate on ill-conditioned
+/// designs, at about twice the cost for tall X. Ridge is solved as the least square of X stacked on
+/// sqrt(lambda) * I (0 at the bias) and y stacked on 0s. If this is rank deficient, it falls back to
+/// the pseudo-inverse.
+pub fn faer_qr_direct_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambda: T,
+    has_bias: bool,
+) -> Mat<T> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    let n1 = ncols.abs_diff(has_bias as usize);
+    if lambda > T::zero() {
+        let sqrt_lambda = lambda.sqrt();
+        let a = Mat::from_fn(nrows + n1, ncols, |i, j| {
+            if i < nrows {
+                *x.get(i, j)
+            } else if i - nrows == j {
+                sqrt_lambda
+            } else {
+                T::zero()
+            }
+        });
+        let b = Mat::from_fn(nrows + n1, y.ncols(), |i, j| {
+            if i < nrows {
+                *y.get(i, j)
+            } else {
+                T::zero()
+            }
+        });
+        qr_direct_solve(a.as_ref(), b.as_ref())
+    } else {
+        qr_direct_solve(x, y)
+    }
+}
+
+/// Least square solution of a b = y by the column pivoted QR of a, or by the pseudo-inverse of a
+/// if a is rank deficient.
+fn qr_direct_solve<T: RealField + Float>(a: MatRef<T>, y: MatRef<T>) -> Mat<T> {
+    qr_direct_solve_rank_cond(a, y).0
+}
+
+/// qr_direct_solve, which also returns the rank and the condition number estimate of a from the R
+/// factor. See qr_rank_cond.
+fn qr_direct_solve_rank_cond<T: RealField + Float>(
+    a: MatRef<T>,
+    y: MatRef<T>,
+) -> (Mat<T>, usize, T) {
+    let ncols = a.ncols();
+    let qr = a.col_piv_qr();
+    let (rank, cond) = qr_rank_cond(qr.R());
+    let coeffs = if rank < ncols {
+        faer_pinv(a) * y
+    } else {
+        let mut rhs = y.to_owned();
+        qr.solve_lstsq_in_place_with_conj(faer::Conj::No, rhs.as_mut());
+        rhs.get(..ncols, ..).to_owned()
+    };
+    (coeffs, rank, cond)
+}
+
+/// Total least squares (orthogonal regression), for when the features are measured with noise too.
+/// It minimizes the squared orthogonal distances of the points [x_i, y_i] to the fitted hyperplane,
+/// instead of the vertical distances. The coefficients come from the right singular vector v of the
+/// smallest singular value of [X | y]: b = -v[..n] / v[n]. If has bias, the last column of x is the
+/// bias column, the other columns of x and y are centered, and the bias is y_mean - x_mean * b.
+/// Y must have 1 column. This errors when the last entry of v is 0, i.e. the fitted hyperplane is
+/// parallel to y and there is no TLS solution.
+pub fn faer_total_lstsq<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    has_bias: bool,
+) -> Result<Mat<T>, LinalgErrors> {
+    if y.ncols() != 1 || x.nrows() != y.nrows() {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    let n = x.nrows();
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    if n1 == 0 || n <= n1 {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    let nf = T::from(n).unwrap();
+    let (x_mean, y_mean) = if has_bias {
+        let x_mean = (0..n1)
+            .map(|j| (0..n).fold(T::zero(), |acc, i| acc + *x.get(i, j)) / nf)
+            .collect::<Vec<_>>();
+        let y_mean = (0..n).fold(T::zero(), |acc, i| acc + *y.get(i, 0)) / nf;
+        (x_mean, y_mean)
+    } else {
+        (vec![T::zero(); n1], T::zero())
+    };
+    let z = Mat::from_fn(n, n1 + 1, |i, j| {
+        if j < n1 {
+            *x.get(i, j) - x_mean[j]
+        } else {
+            *y.get(i, 0) - y_mean
+        }
+    });
+    let svd = z
+        .thin_svd()
+        .map_err(|_| LinalgErrors::Other("SVD failed to converge.".into()))?;
+    // Singular values are sorted in non-increasing order, so the last column of V is the direction
+    // of the smallest one.
+    let v = svd.V().col(n1);
+    let v_last = *v.get(n1);
+    let max_v = v.iter().copied().fold(T::zero(), |acc, a| acc.max(a.abs()));
+    if v_last.abs() <= max_v * T::epsilon() * T::from(n1 + 1).unwrap() {
+        return Err(LinalgErrors::Other(
+            "Total least squares has no solution: the smallest singular direction of [X | y] is orthogonal to y.".into(),
+        ));
+    }
+    let b = (0..n1).map(|j| -*v.get(j) / v_last).collect::<Vec<_>>();
+    Ok(Mat::from_fn(x.ncols(), 1, |i, _| {
+        if i < n1 {
+            b[i]
+        } else {
+            y_mean - (0..n1).fold(T::zero(), |acc, j| acc + x_mean[j] * b[j])
+        }
+    }))
+}
+
+/// Returns the residuals of regressing each column of y on x, i.e. y - x * beta. This is the
+/// "partialling out" step in the Frisch-Waugh-Lovell theorem. If x has no columns, y is returned.
+pub fn faer_lstsq_residuals<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    how: LRSolverMethods,
+) -> Mat<T> {
+    if x.ncols() == 0 {
+        return y.to_owned();
+    }
+    let coeffs = faer_solve_lstsq(x, y, T::zero(), false, how);
+    y - x * coeffs
+}
+
+/// Multi-target Ridge regression with a different lambda per target. The Gram matrix XtX is
+/// formed once, and each target solves its own (XtX + lambda_j * I) b_j = XtY_j. Lambdas must have
+/// the same length as the number of columns of y. If has bias, the bias is not regularized.
+pub fn faer_solve_lstsq_per_target<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    has_bias: bool,
+    how: LRSolverMethods,
+) -> Mat<T> {
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    let (xtx, xty) = faer_gram(x, y, T::zero(), has_bias);
+    let mut coeffs = Mat::<T>::zeros(x.ncols(), y.ncols());
+    for (j, lambda) in lambdas.iter().copied().enumerate() {
+        let mut xtx_j = xtx.clone();
+        for i in 0..n1 {
+            *xtx_j.get_mut(i, i) = *xtx_j.get(i, i) + lambda;
+        }
+        let b = faer_solve_gram(xtx_j, xty.get(.., j..j + 1).to_owned(), how);
+        coeffs.col_mut(j).copy_from(b.col(0));
+    }
+    coeffs
+}
+
+/// Forms XtX + lambda * I and XtY. If has bias, the last diagonal element of XtX is not
+/// regularized.
+#[inline(always)]
+pub fn faer_gram<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambda: T,
+    has_bias: bool,
+) -> (Mat<T>, Mat<T>) {
     let n1 = x.ncols().abs_diff(has_bias as usize);
     let xt = x.transpose();
     let mut xtx = xt * x;
@@ -249,25 +621,332 @@ pub fn faer_solve_lstsq<T: RealField + Float>(
             }
         }
     }
+    (xtx, xt * y)
+}
 
+/// Solves the normal equation (XtX) b = XtY, given XtX and XtY.
+#[inline(always)]
+pub fn faer_solve_gram<T: RealField + Float>(
+    xtx: Mat<T>,
+    xty: Mat<T>,
+    how: LRSolverMethods,
+) -> Mat<T> {
     match how {
         LRSolverMethods::SVD => match xtx.thin_svd() {
-            Ok(svd) => svd.solve(xt * y),
-            _ => xtx.col_piv_qr().solve(xt * y),
+            Ok(svd) => svd.solve(xty),
+            _ => xtx.col_piv_qr().solve(xty),
+        },
+        // Only the Gram matrix is available here, so QRDirect is the same as QR.
+        LRSolverMethods::QR | LRSolverMethods::QRDirect => {
+            let qr = xtx.col_piv_qr();
+            if faer_qr_rank(qr.R()) < xtx.ncols() {
+                faer_pinv(xtx.as_ref()) * xty
+            } else {
+                qr.solve(xty)
+            }
+        }
+        // XtX is positive definite if X has full column rank, and XtX + lambda * I always is for
+        // lambda > 0 (without bias). Falls back to QR if the factorization fails.
+        LRSolverMethods::Choleskey => match xtx.llt(Side::Lower) {
+            Ok(llt) => llt.solve(xty),
+            Err(_) => faer_solve_gram(xtx, xty, LRSolverMethods::QR),
         },
-        LRSolverMethods::QR => xtx.col_piv_qr().solve(xt * y),
-        LRSolverMethods::Choleskey => todo!(),
+    }
+}
+
+/// faer_solve_gram, which also returns the rank and the condition number estimate of X. The QR and
+/// SVD solvers read them off the factorization of XtX that solves the equation. Cholesky doesn't reveal
+/// the rank, so XtX is factorized by faer_gram_rank_cond for them. xtx must not be penalized.
+fn solve_gram_rank_cond<T: RealField + Float>(
+    xtx: Mat<T>,
+    xty: Mat<T>,
+    how: LRSolverMethods,
+) -> (Mat<T>, usize, T) {
+    let n = xtx.ncols();
+    if n == 0 {
+        return (Mat::zeros(0, xty.ncols()), 0, T::zero());
+    }
+    match how {
+        LRSolverMethods::SVD => match xtx.thin_svd() {
+            Ok(svd) => {
+                // The singular values of XtX are sorted and are the squares of those of X
+                let s = svd.S().column_vector();
+                let tol = s.get(0).abs() * T::epsilon() * T::from(n).unwrap();
+                let rank = s.iter().take_while(|v| v.abs() > tol).count();
+                let cond = if rank < n {
+                    T::infinity()
+                } else {
+                    (*s.get(0) / *s.get(n - 1)).sqrt()
+                };
+                (svd.solve(xty), rank, cond)
+            }
+            _ => solve_gram_rank_cond(xtx, xty, LRSolverMethods::QR),
+        },
+        LRSolverMethods::QR | LRSolverMethods::QRDirect => {
+            let qr = xtx.col_piv_qr();
+            let (rank, cond) = qr_rank_cond(qr.R());
+            let coeffs = if rank < n {
+                faer_pinv(xtx.as_ref()) * xty
+            } else {
+                qr.solve(xty)
+            };
+            (coeffs, rank, cond.sqrt())
+        }
+        LRSolverMethods::Choleskey => {
+            let (rank, cond) = faer_gram_rank_cond(xtx.as_ref());
+            (faer_solve_gram(xtx, xty, how), rank, cond)
+        }
+    }
+}
+
+/// Numerical rank from the R factor of a column pivoted QR decomposition. The diagonal of R is
+/// non-increasing in absolute value, and entries <= max(nrows, ncols) * eps * |R_00| are treated as 0.
+pub fn faer_qr_rank<T: RealField + Float>(r: MatRef<T>) -> usize {
+    let rtol = T::epsilon() * T::from(r.nrows().max(r.ncols())).unwrap();
+    faer_qr_rank_rtol(r, rtol)
+}
+
+/// Same as faer_qr_rank, but diagonal entries of R <= rtol * |R_00| are treated as 0.
+pub fn faer_qr_rank_rtol<T: RealField + Float>(r: MatRef<T>, rtol: T) -> usize {
+    let size = r.nrows().min(r.ncols());
+    if size == 0 {
+        return 0;
+    }
+    let tol = r.get(0, 0).abs() * rtol;
+    (0..size).take_while(|&i| r.get(i, i).abs() > tol).count()
+}
+
+/// Numerical rank of x from its column pivoted QR decomposition. See faer_qr_rank_rtol. If rtol is
+/// None, it is max(nrows, ncols) * eps.
+pub fn faer_matrix_rank<T: RealField + Float>(x: MatRef<T>, rtol: Option<T>) -> usize {
+    let qr = x.col_piv_qr();
+    match rtol {
+        Some(rtol) => faer_qr_rank_rtol(qr.R(), rtol),
+        None => faer_qr_rank(qr.R()),
+    }
+}
+
+/// Residual degrees of freedom n - trace(H) of a linear smoother, where H is the hat matrix. xtwx is
+/// X^t W X (W = I if unweighted) and a_inv is the inverse of the matrix that is actually solved, e.g.
+/// (X^t W X + lambda * D)^-1 for Ridge, where D is the identity with 0 at the bias. Then
+/// trace(H) = trace(a_inv * xtwx). This is p for OLS and WLS, and decreases to #unpenalized columns as
+/// lambda grows. n is the number of rows, or the sum of weights for frequency weights.
+pub fn faer_residual_dof<T: RealField + Float>(n: T, xtwx: MatRef<T>, a_inv: MatRef<T>) -> T {
+    let p = xtwx.ncols();
+    let trace = (0..p).fold(T::zero(), |acc, i| {
+        (0..p).fold(acc, |acc, j| acc + *a_inv.get(i, j) * *xtwx.get(j, i))
+    });
+    n - trace
+}
+
+pub struct PredictionVariance<T: RealField + Float> {
+    pub coefficients: Mat<T>,
+    pub fit_var: Vec<T>,  // Variance of the fitted mean x_i b, per row
+    pub pred_var: Vec<T>, // Variance of the prediction error of a new observation at x_i, per row
+    pub dof: T,           // Residual degrees of freedom n - trace(H)
+}
+
+/// Per-row prediction variances of a Ridge fit (OLS if lambda = 0). With A = XtX + lambda * D, where D is
+/// the identity with 0 at the bias, b = A^-1 XtY has Var(b) = s^2 A^-1 XtX A^-1. The residual variance s^2
+/// is RSS / (n - trace(H)), with the effective degrees of freedom of the ridge hat matrix H = X A^-1 Xt (see
+/// faer_residual_dof). Then the variance of the fitted mean at row i is s^2 x_i A^-1 XtX A^-1 x_i^t, and a
+/// new observation adds s^2. Because of the penalty, these are smaller than the OLS ones, and the fitted
+/// values are biased towards 0, which the variances don't account for.
+pub fn faer_ridge_prediction_variance<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambda: T,
+    has_bias: bool,
+) -> Result<PredictionVariance<T>, LinalgErrors> {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    if nrows != y.nrows() {
+        return Err(LinalgErrors::DimensionMismatch);
+    }
+    if nrows <= ncols {
+        return Err(LinalgErrors::NotEnoughData);
+    }
+    let xtx = x.transpose() * x;
+    let mut penalized = xtx.clone();
+    for i in 0..ncols.abs_diff(has_bias as usize) {
+        *penalized.get_mut(i, i) = *penalized.get(i, i) + lambda;
+    }
+    let a_inv = penalized.col_piv_qr().inverse();
+    let coefficients = &a_inv * x.transpose() * y;
+    let dof = faer_residual_dof(T::from(nrows).unwrap(), xtx.as_ref(), a_inv.as_ref());
+    let residuals = y - x * &coefficients;
+    let mse = residuals.col(0).squared_norm_l2() / dof;
+    // Var(b) / s^2
+    let cov = &a_inv * &xtx * &a_inv;
+    let xc = x * &cov;
+    let fit_var = (0..nrows)
+        .map(|i| {
+            let v = (0..ncols).fold(T::zero(), |acc, j| acc + *xc.get(i, j) * *x.get(i, j));
+            mse * v
+        })
+        .collect::<Vec<_>>();
+    let pred_var = fit_var.iter().map(|v| *v + mse).collect();
+    Ok(PredictionVariance {
+        coefficients,
+        fit_var,
+        pred_var,
+        dof,
+    })
+}
+
+/// Numerical rank of a matrix and an estimate of its condition number, from the R factor of its column
+/// pivoted QR. With pivoting, |R_00| / |R_kk| (k the last index) estimates the condition number, which
+/// is infinite if the matrix is rank deficient, and 0 if it has no columns.
+fn qr_rank_cond<T: RealField + Float>(r: MatRef<T>) -> (usize, T) {
+    let n = r.ncols();
+    if n == 0 {
+        return (0, T::zero());
+    }
+    let rank = faer_qr_rank(r);
+    let cond = if rank < n {
+        T::infinity()
+    } else {
+        r.get(0, 0).abs() / r.get(n - 1, n - 1).abs()
+    };
+    (rank, cond)
+}
+
+/// Numerical rank of X and an estimate of its condition number, from the column pivoted QR of XtX.
+/// Because XtX squares the condition number, this is the rank as seen by the normal equation solvers:
+/// columns that are collinear up to ~sqrt(eps) count as dependent. See qr_rank_cond. The estimate for
+/// XtX is squared, so its square root is that of X.
+pub fn faer_gram_rank_cond<T: RealField + Float>(xtx: MatRef<T>) -> (usize, T) {
+    let (rank, cond) = qr_rank_cond(xtx.col_piv_qr().R());
+    (rank, cond.sqrt())
+}
+
+/// Numerical rank of X and an estimate of its condition number, as seen by the given solver. QRDirect
+/// factorizes X itself, so columns only count as dependent when they are collinear up to ~eps. The
+/// other solvers go through XtX. See faer_gram_rank_cond.
+pub fn faer_design_rank_cond<T: RealField + Float>(
+    x: MatRef<T>,
+    how: LRSolverMethods,
+) -> (usize, T) {
+    match how {
+        LRSolverMethods::QRDirect => qr_rank_cond(x.col_piv_qr().R()),
+        _ => faer_gram_rank_cond((x.transpose() * x).as_ref()),
+    }
+}
+
+/// The Moore-Penrose pseudo-inverse of a square matrix by SVD. For a rank deficient XtX, pinv(XtX) XtY
+/// is the minimum norm solution of the least square problem. Singular values <= n * eps * max singular
+/// value are treated as 0.
+pub fn faer_pinv<T: RealField + Float>(a: MatRef<T>) -> Mat<T> {
+    let n = a.nrows();
+    match a.thin_svd() {
+        Ok(svd) => {
+            let s = svd.S().column_vector();
+            let max_s = s.iter().copied().fold(T::zero(), T::max);
+            let threshold = max_s * T::epsilon() * T::from(n.max(1)).unwrap();
+            let s_inv = Col::<T>::from_fn(s.nrows(), |i| {
+                let v = *s.get(i);
+                if v > threshold {
+                    v.recip()
+                } else {
+                    T::zero()
+                }
+            });
+            svd.V() * s_inv.as_diagonal() * svd.U().transpose()
+        }
+        Err(_) => a.col_piv_qr().inverse(),
+    }
+}
+
+/// Timing and iteration telemetry of a single least square fit. Times are in seconds.
+pub struct LstsqProfile {
+    pub gram_time: f64,  // Time spent on forming XtX and XtY
+    pub solve_time: f64, // Time spent on solving the normal equation, or on the iterations
+    pub n_iter: usize,   // Always 1 for the direct solvers
+    pub converged: bool, // Always true for the direct solvers
+    pub solver: &'static str,
+}
+
+/// Runs the same computations as faer_solve_lstsq or faer_coordinate_descent, but times the
+/// formation of the Gram matrix and the solve step separately. This is a separate path so that
+/// the regular fits are not slowed down by the timers.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_lstsq_profile<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    l1_reg: T,
+    l2_reg: T,
+    has_bias: bool,
+    how: LRSolverMethods,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> (Mat<T>, LstsqProfile) {
+    let start = Instant::now();
+    if l1_reg > T::zero() {
+        let xtx = x.transpose() * x;
+        let xty = x.transpose() * y;
+        let yty = y.col(0).squared_norm_l2();
+        let gram_time = start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        let (coeffs, n_iter, converged) = faer_coordinate_descent_gram_with_stop(
+            xtx.as_ref(),
+            xty.as_ref(),
+            yty,
+            T::from(x.nrows()).unwrap(),
+            l1_reg,
+            l2_reg,
+            has_bias,
+            tol,
+            max_iter,
+            stop,
+        );
+        let profile = LstsqProfile {
+            gram_time,
+            solve_time: start.elapsed().as_secs_f64(),
+            n_iter,
+            converged,
+            solver: "coordinate_descent",
+        };
+        (coeffs, profile)
+    } else if let LRSolverMethods::QRDirect = how {
+        // No Gram matrix is formed
+        let coeffs = faer_qr_direct_lstsq(x, y, l2_reg, has_bias);
+        let profile = LstsqProfile {
+            gram_time: 0.,
+            solve_time: start.elapsed().as_secs_f64(),
+            n_iter: 1,
+            converged: true,
+            solver: how.as_str(),
+        };
+        (coeffs, profile)
+    } else {
+        let (xtx, xty) = faer_gram(x, y, l2_reg, has_bias);
+        let gram_time = start.elapsed().as_secs_f64();
+        let start = Instant::now();
+        let coeffs = faer_solve_gram(xtx, xty, how);
+        let profile = LstsqProfile {
+            gram_time,
+            solve_time: start.elapsed().as_secs_f64(),
+            n_iter: 1,
+            converged: true,
+            solver: how.as_str(),
+        };
+        (coeffs, profile)
     }
 }
 
 /// Solves the weighted least square with weights given by the user
 #[inline(always)]
-pub fn faer_weighted_lstsq<T: RealField>(
+pub fn faer_weighted_lstsq<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
     w: &[T],
     how: LRSolverMethods,
 ) -> Mat<T> {
+    if let LRSolverMethods::QRDirect = how {
+        // The least square of sqrt(W) X and sqrt(W) y
+        let (xw, yw) = faer_sqrt_weighted_rows(x, y, w);
+        return qr_direct_solve(xw.as_ref(), yw.as_ref());
+    }
     let weights = faer::ColRef::from_slice(w);
     let w = weights.as_diagonal();
 
@@ -280,55 +959,532 @@ pub fn faer_weighted_lstsq<T: RealField>(
             Err(_) => xtwx.col_piv_qr().solve(xtw * y),
         },
         LRSolverMethods::QR => xtwx.col_piv_qr().solve(xtw * y),
-        LRSolverMethods::Choleskey => todo!(),
+        LRSolverMethods::QRDirect => unreachable!(),
+        LRSolverMethods::Choleskey => match xtwx.llt(Side::Lower) {
+            Ok(llt) => llt.solve(xtw * y),
+            Err(_) => xtwx.col_piv_qr().solve(xtw * y),
+        },
     }
 }
 
+/// X and y with row i scaled by sqrt(w_i). The (penalized) least square on these is the weighted
+/// one on the original data, which is how weights are applied to Ridge, Lasso and Elastic Net.
+pub fn faer_sqrt_weighted_rows<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    w: &[T],
+) -> (Mat<T>, Mat<T>) {
+    let xw = Mat::from_fn(x.nrows(), x.ncols(), |i, j| *x.get(i, j) * w[i].sqrt());
+    let yw = Mat::from_fn(y.nrows(), y.ncols(), |i, j| *y.get(i, j) * w[i].sqrt());
+    (xw, yw)
+}
+
+/// The total weight of the rows, which normalizes the loss of Lasso and Elastic Net. This is the number
+/// of rows if there are no weights, so that a row with weight 2 counts the same as two copies of it.
+#[inline(always)]
+fn total_weight<T: Float>(nrows: usize, weights: Option<&[T]>) -> T {
+    weights.map_or_else(
+        || T::from(nrows).unwrap(),
+        |w| w.iter().fold(T::zero(), |acc, v| acc + *v),
+    )
+}
+
 #[inline(always)]
 fn soft_threshold_l1<T: Float>(z: T, lambda: T) -> T {
     z.signum() * (z.abs() - lambda).max(T::zero())
 }
 
+/// The l1 weights of the first n1 coefficients (all but the unpenalized bias) from the user's penalty
+/// factors. Missing factors are 1, and extra ones are ignored. So a factor given for the bias has no
+/// effect, and the bias always has a factor of 0.
+fn l1_weights_from_factors<T: Float>(penalty_factor: Option<&[T]>, n1: usize) -> Option<Vec<T>> {
+    penalty_factor.map(|f| {
+        (0..n1)
+            .map(|j| f.get(j).copied().unwrap_or(T::one()))
+            .collect()
+    })
+}
+
 /// Computes Lasso/Elastic Regression coefficients by the use of Coordinate Descent.
-/// The current stopping criterion is based on L Inf norm of the changes in the
-/// coordinates. A better alternative might be the dual gap.
+/// By default, the stopping criterion is based on L Inf norm of the changes in the
+/// coordinates. StopCriterion::DualGap stops on the duality gap instead, which bounds the
+/// distance of the objective to its minimum. See StopCriterion for the other options.
+/// Returns the coefficients, the number of iterations run, and whether the iteration converged
+/// within max_iter. Non-convergence is left to the caller to report.
+///
+/// weights, if given, are the analytic weights of the rows. The loss is then
+/// 1/(2 sum(w)) * sum(w_i (y_i - x_i b)^2), so a row with weight 2 is the same as two copies of it.
+///
+/// The intercept: if has_bias, the last column of x is the bias, and it is not penalized. It is
+/// not part of the coordinate updates, and after each sweep, it is set to the (weighted) mean of
+/// y - X b, where b are the other coefficients, which is its exact minimizer. So the bias is not shrunk
+/// towards 0. To penalize the bias like the other coefficients, e.g. for centered data, pass
+/// has_bias = false, and the column of ones is then treated as any other feature.
+///
+/// penalty_factor, if given, scales the l1 penalty per feature, like glmnet's penalty.factor: the
+/// soft threshold of the j-th coefficient is l1_reg * penalty_factor[j], and a factor of 0 means no l1
+/// shrinkage for that feature. Missing factors are 1 and extra ones are ignored, so the bias column
+/// (the last one, if has_bias) always has a factor of 0.
 ///
 /// Reference:
 /// https://xavierbourretsicotte.github.io/lasso_implementation.html
 /// https://www.stat.cmu.edu/~ryantibs/convexopt-F18/lectures/coord-desc.pdf
 /// https://github.com/minatosato/Lasso/blob/master/coordinate_descent_lasso.py
 /// https://en.wikipedia.org/wiki/Lasso_(statistics)
+#[allow(clippy::too_many_arguments)]
 #[inline(always)]
 pub fn faer_coordinate_descent<T: RealField + Float>(
     x: MatRef<T>,
     y: MatRef<T>,
+    weights: Option<&[T]>,
     l1_reg: T,
     l2_reg: T,
+    penalty_factor: Option<&[T]>,
     has_bias: bool,
     tol: T,
     max_iter: usize,
-) -> Mat<T> {
-    let m = T::from(x.nrows()).unwrap();
-    let ncols = x.ncols();
+    stop: StopCriterion,
+) -> (Mat<T>, usize, bool) {
+    let m = total_weight(x.nrows(), weights);
+    // The Gram matrix of the rows scaled by sqrt(w) is X^t W X. With the scaled bias column, the bias
+    // update below is sum(w_i r_i) / sum(w_i).
+    let scaled = weights.map(|w| faer_sqrt_weighted_rows(x, y, w));
+    let (x, y) = scaled
+        .as_ref()
+        .map_or((x, y), |(xw, yw)| (xw.as_ref(), yw.as_ref()));
+    let xty = x.transpose() * y;
+    let xtx = x.transpose() * x;
+    let yty = y.col(0).squared_norm_l2();
+    let l1_weights = l1_weights_from_factors(penalty_factor, x.ncols().abs_diff(has_bias as usize));
+
+    faer_weighted_coordinate_descent_gram(
+        xtx.as_ref(),
+        xty.as_ref(),
+        yty,
+        m,
+        l1_reg,
+        l1_weights.as_deref(),
+        l2_reg,
+        has_bias,
+        tol,
+        max_iter,
+        stop,
+    )
+}
+
+/// Computes Lasso/Elastic Net coefficients by FISTA (Beck and Teboulle, 2009), the proximal gradient
+/// method with Nesterov acceleration, on the same objective as faer_coordinate_descent. The gradient of
+/// the smooth part, X^t (Xb - y) / m + l2_reg * b, is Lipschitz with constant L = d_max / m + l2_reg,
+/// where d_max is the largest eigenvalue of XtX, and each step is a gradient step of size 1 / L followed
+/// by soft thresholding at l1_reg / L. XtX is never formed, so an iteration costs two matrix-vector
+/// products with X. The momentum is restarted whenever it points against the last step (O'Donoghue and
+/// Candes, 2015), which keeps the iterates from oscillating.
+///
+/// If has_bias, the last column of x is the bias, which is neither penalized nor thresholded.
+/// weights and penalty_factor are as in faer_coordinate_descent. With weights, the rows are scaled by
+/// sqrt(w) and m is sum(w), so the gradient of the bias is sum(w_i r_i) / sum(w_i). With
+/// StopCriterion::DualGap, the gap costs two more matrix-vector products per iteration. Returns the
+/// coefficients, the number of iterations run, and whether the iteration converged within max_iter.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_fista<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    weights: Option<&[T]>,
+    l1_reg: T,
+    l2_reg: T,
+    penalty_factor: Option<&[T]>,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> (Mat<T>, usize, bool) {
+    let (nrows, ncols) = (x.nrows(), x.ncols());
+    if nrows == 0 || ncols == 0 {
+        return (Mat::zeros(ncols, 1), 0, true);
+    }
+    let n1 = ncols.abs_diff(has_bias as usize);
+    let m = total_weight(nrows, weights);
+    let scaled = weights.map(|w| faer_sqrt_weighted_rows(x, y, w));
+    let (x, y) = scaled
+        .as_ref()
+        .map_or((x, y), |(xw, yw)| (xw.as_ref(), yw.as_ref()));
+    let d_max = faer_xtx_eigenvalues(x).map_or(T::zero(), |d| d[0]);
+    let lipschitz = d_max / m + l2_reg;
+    // X is all 0 (or the SVD failed)
+    if lipschitz <= T::zero() || lipschitz.is_nan() {
+        return (Mat::zeros(ncols, 1), 0, true);
+    }
+    let step = lipschitz.recip();
+    let l1_weights = l1_weights_from_factors(penalty_factor, n1);
+    let weight = |j: usize| l1_weights.as_ref().map_or(T::one(), |w| w[j]);
+    let thresholds = (0..n1)
+        .map(|j| l1_reg * step * weight(j))
+        .collect::<Vec<_>>();
+
+    let objective = |b: &Mat<T>| {
+        let r = x * b - y;
+        let (l1, l2) = b
+            .col(0)
+            .iter()
+            .take(n1)
+            .enumerate()
+            .fold((T::zero(), T::zero()), |(l1, l2), (j, v)| {
+                (l1 + weight(j) * v.abs(), l2 + *v * *v)
+            });
+        r.col(0).squared_norm_l2() / (m + m) + l1_reg * l1 + l2_reg * l2 / (T::one() + T::one())
+    };
+    let track_objective = stop == StopCriterion::RelativeObjective;
+
+    let mut beta = Mat::<T>::zeros(ncols, 1);
+    let mut z = beta.clone(); // The extrapolated point
+    let mut t = T::one();
+    let mut obj = if track_objective {
+        objective(&beta)
+    } else {
+        T::zero()
+    };
+    let mut converge = false;
+    let mut n_iter = 0;
+    for _ in 0..max_iter {
+        n_iter += 1;
+        let grad = x.transpose() * (x * &z - y);
+        let new_beta = Mat::from_fn(ncols, 1, |j, _| {
+            let zj = *z.get(j, 0);
+            let g = *grad.get(j, 0) / m;
+            if j < n1 {
+                soft_threshold_l1(zj - step * (g + l2_reg * zj), thresholds[j])
+            } else {
+                zj - step * g
+            }
+        });
+
+        let (max_change, momentum) = (0..ncols).fold((T::zero(), T::zero()), |(mc, mo), j| {
+            let d = *new_beta.get(j, 0) - *beta.get(j, 0);
+            (
+                mc.max(d.abs()),
+                mo + (*z.get(j, 0) - *new_beta.get(j, 0)) * d,
+            )
+        });
+        let new_obj = if track_objective {
+            objective(&new_beta)
+        } else {
+            T::zero()
+        };
+        let done = if stop == StopCriterion::DualGap {
+            let r = y - x * &new_beta;
+            let xtr = x.transpose() * &r;
+            let rtr = r.col(0).squared_norm_l2();
+            let rty = *(r.transpose() * y).get(0, 0);
+            let gap = elastic_net_dual_gap(
+                xtr.col_as_slice(0),
+                rtr,
+                rty,
+                m,
+                new_beta.as_ref(),
+                l1_reg,
+                l1_weights.as_deref(),
+                l2_reg,
+                has_bias,
+            );
+            gap < tol
+        } else {
+            stop.is_converged(max_change, obj, new_obj, tol)
+        };
+        if done {
+            beta = new_beta;
+            converge = true;
+            break;
+        }
+
+        // On restart, there is no momentum in the next step
+        let (new_t, w) = if momentum > T::zero() {
+            (T::one(), T::zero())
+        } else {
+            let four = T::from(4.).unwrap();
+            let new_t = (T::one() + (T::one() + four * t * t).sqrt()) / (T::one() + T::one());
+            (new_t, (t - T::one()) / new_t)
+        };
+        z = Mat::from_fn(ncols, 1, |j, _| {
+            *new_beta.get(j, 0) + w * (*new_beta.get(j, 0) - *beta.get(j, 0))
+        });
+        beta = new_beta;
+        t = new_t;
+        obj = new_obj;
+    }
+
+    (beta, n_iter, converge)
+}
+
+/// Lasso / Elastic Net by the given algorithm, with optional analytic weights of the rows. See
+/// faer_coordinate_descent and faer_fista. Returns the coefficients, the number of iterations run,
+/// and whether the iteration converged.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_elastic_net<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    weights: Option<&[T]>,
+    l1_reg: T,
+    l2_reg: T,
+    penalty_factor: Option<&[T]>,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+    algorithm: LassoAlgorithm,
+) -> (Mat<T>, usize, bool) {
+    match algorithm {
+        LassoAlgorithm::CoordinateDescent => faer_coordinate_descent(
+            x,
+            y,
+            weights,
+            l1_reg,
+            l2_reg,
+            penalty_factor,
+            has_bias,
+            tol,
+            max_iter,
+            stop,
+        ),
+        LassoAlgorithm::Fista => faer_fista(
+            x,
+            y,
+            weights,
+            l1_reg,
+            l2_reg,
+            penalty_factor,
+            has_bias,
+            tol,
+            max_iter,
+            stop,
+        ),
+    }
+}
+
+/// Coordinate Descent on the precomputed XtX and XtY, where m is the number of rows in X.
+/// If has_bias, the bias must be the last column of X, and the bias is estimated as the mean
+/// of the residuals. Returns the coefficients, the number of iterations run, and whether the
+/// result has converged. This stops on the change in the coefficients.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub fn faer_coordinate_descent_gram<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    m: T,
+    l1_reg: T,
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+) -> (Mat<T>, usize, bool) {
+    faer_coordinate_descent_gram_with_stop(
+        xtx,
+        xty,
+        T::zero(),
+        m,
+        l1_reg,
+        l2_reg,
+        has_bias,
+        tol,
+        max_iter,
+        StopCriterion::CoefficientChange,
+    )
+}
+
+/// The Elastic Net objective minimized by coordinate descent, computed from the Gram matrix:
+/// 1/(2m) * ||y - Xb||^2 + l1_reg * ||b||_1 + l2_reg / 2 * ||b||^2, where the bias (the last
+/// entry, if has_bias) is not penalized. yty is the squared norm of y. With l1_weights, the l1
+/// penalty of the j-th coefficient is l1_reg * l1_weights[j].
+#[allow(clippy::too_many_arguments)]
+fn weighted_elastic_net_objective<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    yty: T,
+    m: T,
+    beta: MatRef<T>,
+    l1_reg: T,
+    l1_weights: Option<&[T]>,
+    l2_reg: T,
+    has_bias: bool,
+) -> T {
+    let n1 = beta.nrows().abs_diff(has_bias as usize);
+    let btxtxb = *(beta.transpose() * xtx * beta).get(0, 0);
+    let btxty = *(beta.transpose() * xty).get(0, 0);
+    let rss = (yty - btxty - btxty + btxtxb).max(T::zero());
+    let (l1, l2) =
+        beta.col(0)
+            .iter()
+            .take(n1)
+            .enumerate()
+            .fold((T::zero(), T::zero()), |(l1, l2), (j, b)| {
+                let w = l1_weights.map_or(T::one(), |w| w[j]);
+                // A zero coefficient costs nothing, even if its weight is huge
+                let l1_j = if *b == T::zero() {
+                    T::zero()
+                } else {
+                    w * b.abs()
+                };
+                (l1 + l1_j, l2 + *b * *b)
+            });
+    rss / (m + m) + l1_reg * l1 + l2_reg * l2 / (T::one() + T::one())
+}
+
+/// The duality gap of the (weighted) Elastic Net objective at beta, i.e. the objective minus the
+/// objective of the dual at a feasible dual point, which bounds how far the objective is from its
+/// minimum. The residual r = y - X beta enters through X^t r, ||r||^2 and r^t y. As in scikit-learn, the
+/// Elastic Net is a Lasso on X stacked on sqrt(m * l2_reg) * I, and the dual point is the residual of
+/// that Lasso, scaled down until it is feasible. Features with an l1 weight of 0, and the bias, are
+/// not part of the scaling, so their part of the gap only vanishes at the minimum.
+#[allow(clippy::too_many_arguments)]
+fn elastic_net_dual_gap<T: RealField + Float>(
+    xtr: &[T],
+    rtr: T,
+    rty: T,
+    m: T,
+    beta: MatRef<T>,
+    l1_reg: T,
+    l1_weights: Option<&[T]>,
+    l2_reg: T,
+    has_bias: bool,
+) -> T {
+    let n1 = beta.nrows().abs_diff(has_bias as usize);
+    // The objective times m
+    let (alpha, l2) = (m * l1_reg, m * l2_reg);
+    let (mut dual_norm, mut l1_norm, mut b_norm2) = (T::zero(), T::zero(), T::zero());
+    for (j, r) in xtr.iter().take(n1).enumerate() {
+        let b = *beta.get(j, 0);
+        let w = l1_weights.map_or(T::one(), |w| w[j]);
+        if w > T::zero() {
+            dual_norm = dual_norm.max((*r - l2 * b).abs() / w);
+        }
+        if b != T::zero() {
+            l1_norm = l1_norm + w * b.abs();
+        }
+        b_norm2 = b_norm2 + b * b;
+    }
+    let two = T::one() + T::one();
+    let (scale, gap) = if dual_norm > alpha {
+        let scale = alpha / dual_norm;
+        (scale, rtr * (T::one() + scale * scale) / two)
+    } else {
+        (T::one(), rtr)
+    };
+    let gap = gap + alpha * l1_norm - scale * rty + l2 * (T::one() + scale * scale) * b_norm2 / two;
+    gap.max(T::zero()) / m
+}
+
+/// Same as faer_coordinate_descent_gram, but with a choice of stopping criterion. yty, the squared
+/// norm of y, is only used by StopCriterion::RelativeObjective, where the objective is given by
+/// weighted_elastic_net_objective, and by StopCriterion::DualGap, where the gap is given by
+/// elastic_net_dual_gap.
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+pub fn faer_coordinate_descent_gram_with_stop<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    yty: T,
+    m: T,
+    l1_reg: T,
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> (Mat<T>, usize, bool) {
+    faer_weighted_coordinate_descent_gram(
+        xtx, xty, yty, m, l1_reg, None, l2_reg, has_bias, tol, max_iter, stop,
+    )
+}
+
+/// Coordinate descent with a per-feature l1 penalty: the l1 penalty of the j-th coefficient is
+/// l1_reg * l1_weights[j]. l1_weights has one entry per feature, and none for the bias. If None,
+/// all weights are 1 and this is faer_coordinate_descent_gram_with_stop. A weight of infinity
+/// keeps the coefficient at 0.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_weighted_coordinate_descent_gram<T: RealField + Float>(
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    yty: T,
+    m: T,
+    l1_reg: T,
+    l1_weights: Option<&[T]>,
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> (Mat<T>, usize, bool) {
+    weighted_coordinate_descent_gram_from(
+        Mat::zeros(xtx.ncols(), 1),
+        xtx,
+        xty,
+        yty,
+        m,
+        l1_reg,
+        l1_weights,
+        l2_reg,
+        has_bias,
+        tol,
+        max_iter,
+        stop,
+    )
+}
+
+/// faer_weighted_coordinate_descent_gram, starting from beta instead of 0.
+#[allow(clippy::too_many_arguments)]
+fn weighted_coordinate_descent_gram_from<T: RealField + Float>(
+    mut beta: Mat<T>,
+    xtx: MatRef<T>,
+    xty: MatRef<T>,
+    yty: T,
+    m: T,
+    l1_reg: T,
+    l1_weights: Option<&[T]>,
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> (Mat<T>, usize, bool) {
+    let ncols = xtx.ncols();
     let n1 = ncols.abs_diff(has_bias as usize);
 
     let lambda_l1 = m * l1_reg;
+    let thresholds = (0..n1)
+        .map(|j| l1_weights.map_or(lambda_l1, |w| lambda_l1 * w[j]))
+        .collect::<Vec<_>>();
 
-    let mut beta: Mat<T> = Mat::zeros(ncols, 1);
     let mut converge = false;
+    let mut n_iter = 0;
 
-    // compute column squared l2 norms.
+    let use_objective = stop == StopCriterion::RelativeObjective;
+    let objective = |b: &Mat<T>| {
+        weighted_elastic_net_objective(
+            xtx,
+            xty,
+            yty,
+            m,
+            b.as_ref(),
+            l1_reg,
+            l1_weights,
+            l2_reg,
+            has_bias,
+        )
+    };
+    let mut obj = if use_objective {
+        objective(&beta)
+    } else {
+        T::zero()
+    };
+
+    // column squared l2 norms are the diagonal of XtX
     // (In the case of Elastic net, squared l2 norms + l2 regularization factor)
-    let norms = x
-        .col_iter()
-        .map(|c| c.squared_norm_l2() + m * l2_reg)
+    let norms = (0..ncols)
+        .map(|j| *xtx.get(j, j) + m * l2_reg)
         .collect::<Vec<_>>();
 
-    let xty = x.transpose() * y;
-    let xtx = x.transpose() * x;
-
     // Random selection often leads to faster convergence?
     for _ in 0..max_iter {
+        n_iter += 1;
         let mut max_change = T::zero();
         for j in 0..n1 {
             // temporary set beta(j, 0) to 0.
@@ -341,29 +1497,390 @@ pub fn faer_coordinate_descent<T: RealField + Float>(
             let main_update = *xty.get(j, 0) - *(xtx_j * &beta).get(0, 0);
 
             // update beta(j, 0).
-            let after = soft_threshold_l1(main_update, lambda_l1) / norms[j];
+            let after = soft_threshold_l1(main_update, thresholds[j]) / norms[j];
             *unsafe { beta.get_mut_unchecked(j, 0) } = after;
             max_change = (after - before).abs().max(max_change);
         }
         // if has_bias, n1 = last index = ncols - 1 = column of bias. If has_bias is False, n = ncols
         if has_bias {
+            // The row of XtX at the bias column contains the column sums of X, and the last
+            // element of XtY is the sum of y. So this is the mean of y - X_-bias * beta_-bias.
+            // If the rows are scaled by sqrt(w), these are the weighted sums, and m is sum(w).
             // Safe. The index is valid and the value is initialized.
-            let xx = unsafe { x.get_unchecked(.., 0..n1) };
+            let xx = unsafe { xtx.get_unchecked(n1..n1 + 1, 0..n1) };
             let bb = unsafe { beta.get_unchecked(0..n1, ..) };
-            let ss = (y - xx * bb).as_ref().sum() / m;
+            let ss = (*xty.get(n1, 0) - *(xx * bb).get(0, 0)) / m;
             *unsafe { beta.get_mut_unchecked(n1, 0) } = ss;
         }
-        converge = max_change < tol;
+        let new_obj = if use_objective {
+            objective(&beta)
+        } else {
+            T::zero()
+        };
+        converge = if stop == StopCriterion::DualGap {
+            // X^t r, ||r||^2 and r^t y from the Gram matrix
+            let xtx_b = xtx * &beta;
+            let bty = *(beta.transpose() * xty).get(0, 0);
+            let btxtxb = *(beta.transpose() * &xtx_b).get(0, 0);
+            let xtr = (0..ncols)
+                .map(|j| *xty.get(j, 0) - *xtx_b.get(j, 0))
+                .collect::<Vec<_>>();
+            let rtr = (yty - bty - bty + btxtxb).max(T::zero());
+            let gap = elastic_net_dual_gap(
+                &xtr,
+                rtr,
+                yty - bty,
+                m,
+                beta.as_ref(),
+                l1_reg,
+                l1_weights,
+                l2_reg,
+                has_bias,
+            );
+            gap < tol
+        } else {
+            stop.is_converged(max_change, obj, new_obj, tol)
+        };
+        obj = new_obj;
         if converge {
             break;
         }
     }
 
-    if !converge {
-        println!(
-            "Lasso regression: Max number of iterations have passed and result hasn't converged."
-        )
+    (beta, n_iter, converge)
+}
+
+/// The Lasso / Elastic Net regularization path: coordinate descent at each l1 penalty in lambdas, with
+/// l2_reg fixed. XtX is formed once, and the lambdas are visited from the largest to the smallest, each
+/// fit starting from the solution at the previous lambda (warm start). Nearby lambdas have nearby
+/// solutions, so this takes far fewer iterations than fitting each lambda from 0. If has_bias, the bias
+/// must be the last column of x, and it is not penalized.
+///
+/// Returns a matrix with one column of coefficients per lambda, in the order of lambdas, and whether
+/// every fit converged within max_iter.
+#[allow(clippy::too_many_arguments)]
+pub fn faer_lasso_path<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    lambdas: &[T],
+    l2_reg: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+    stop: StopCriterion,
+) -> Result<(Mat<T>, bool), LinalgErrors> {
+    if lambdas.is_empty() || lambdas.iter().any(|l| l.is_nan() || *l < T::zero()) {
+        return Err(LinalgErrors::Other(
+            "Lambdas must be non-empty and non-negative.".into(),
+        ));
+    }
+    let ncols = x.ncols();
+    let m = T::from(x.nrows()).unwrap();
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    let yty = y.col(0).squared_norm_l2();
+
+    let mut order = (0..lambdas.len()).collect::<Vec<_>>();
+    order.sort_by(|&i, &j| lambdas[j].partial_cmp(&lambdas[i]).unwrap());
+
+    let mut path = Mat::<T>::zeros(ncols, lambdas.len());
+    let mut beta = Mat::<T>::zeros(ncols, 1);
+    let mut all_converged = true;
+    for k in order {
+        let (b, _, converged) = weighted_coordinate_descent_gram_from(
+            beta,
+            xtx.as_ref(),
+            xty.as_ref(),
+            yty,
+            m,
+            lambdas[k],
+            None,
+            l2_reg,
+            has_bias,
+            tol,
+            max_iter,
+            stop,
+        );
+        all_converged &= converged;
+        path.col_mut(k).copy_from(b.col(0));
+        beta = b;
     }
+    Ok((path, all_converged))
+}
 
-    beta
+/// Adaptive Lasso (Zou, 2006). A ridge regression with l2_init gives initial estimates b_init, and
+/// then Lasso is run with the l1 penalty of the j-th coefficient weighted by 1 / |b_init_j|^gamma.
+/// Features with small initial estimates are penalized heavily, and those with large ones lightly.
+/// |b_init_j| is floored at machine epsilon, so an initial estimate of 0 gives a huge but finite
+/// weight. If has_bias, the bias must be the last column
+/// of x, and it is not penalized. Returns the coefficients, the number of coordinate descent
+/// iterations run, and whether they converged.
+///
+/// Reference:
+/// https://doi.org/10.1198/016214506000000735
+#[allow(clippy::too_many_arguments)]
+pub fn faer_adaptive_lasso<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    l1_reg: T,
+    gamma: T,
+    l2_init: T,
+    has_bias: bool,
+    tol: T,
+    max_iter: usize,
+) -> (Mat<T>, usize, bool) {
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    let init = faer_solve_lstsq(x, y, l2_init, has_bias, LRSolverMethods::QR);
+    let weights = init
+        .col_as_slice(0)
+        .iter()
+        .take(n1)
+        .map(|b| b.abs().max(T::epsilon()).powf(gamma).recip())
+        .collect::<Vec<_>>();
+
+    let m = T::from(x.nrows()).unwrap();
+    let xtx = x.transpose() * x;
+    let xty = x.transpose() * y;
+    faer_weighted_coordinate_descent_gram(
+        xtx.as_ref(),
+        xty.as_ref(),
+        T::zero(),
+        m,
+        l1_reg,
+        Some(&weights),
+        T::zero(),
+        has_bias,
+        tol,
+        max_iter,
+        StopCriterion::CoefficientChange,
+    )
+}
+
+/// Generalized (Tikhonov) ridge: minimizes ||y - Xb||^2 + ||Gamma b||^2, whose solution is
+/// b = (XtX + Gamma^t Gamma)^-1 XtY. With Gamma = sqrt(lambda) * I this is the usual ridge, and with a
+/// difference operator it penalizes the roughness of the coefficient profile. Gamma must have one column
+/// per feature. If has_bias, the bias must be the last column of x, and it is not penalized.
+pub fn faer_generalized_ridge<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    gamma: MatRef<T>,
+    has_bias: bool,
+    how: LRSolverMethods,
+) -> Result<Mat<T>, LinalgErrors> {
+    let n1 = x.ncols().abs_diff(has_bias as usize);
+    if gamma.ncols() != n1 {
+        return Err(LinalgErrors::Other(format!(
+            "The penalty matrix must have {} columns, one per feature, but it has {}.",
+            n1,
+            gamma.ncols()
+        )));
+    }
+    let (mut xtx, xty) = faer_gram(x, y, T::zero(), has_bias);
+    let gtg = gamma.transpose() * gamma;
+    let mut penalized = xtx.as_mut().submatrix_mut(0, 0, n1, n1);
+    penalized += &gtg;
+    Ok(faer_solve_gram(xtx, xty, how))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fitted_lr(has_bias: bool) -> LR<f64> {
+        let x = Mat::from_fn(20, 2, |i, j| ((i * (j + 2)) % 7) as f64 + 0.1 * i as f64);
+        let y = Mat::from_fn(20, 1, |i, _| {
+            1.5 * *x.get(i, 0) - 0.5 * *x.get(i, 1) + if has_bias { 2.0 } else { 0.0 }
+        });
+        let mut lr = LR::new("svd", 0.1, has_bias);
+        assert!(lr.fit(x.as_ref(), y.as_ref()).is_ok());
+        lr
+    }
+
+    fn assert_same_model(a: &LR<f64>, b: &LR<f64>) {
+        assert_eq!(a.coefficients.nrows(), b.coefficients.nrows());
+        assert_eq!(
+            a.coefficients.col_as_slice(0),
+            b.coefficients.col_as_slice(0)
+        );
+        assert_eq!(a.has_bias, b.has_bias);
+        assert_eq!(a.lambda, b.lambda);
+        assert_eq!(a.solver.as_str(), b.solver.as_str());
+    }
+
+    #[test]
+    fn test_to_parts_from_parts() {
+        for has_bias in [false, true] {
+            let lr = fitted_lr(has_bias);
+            let parts = lr.to_parts().ok().unwrap();
+            assert_eq!(parts.coefficients.len(), 2);
+            assert_eq!(parts.has_bias, has_bias);
+            assert_same_model(&lr, &LR::from_parts(&parts));
+        }
+    }
+
+    #[test]
+    fn test_to_parts_from_values() {
+        for has_bias in [false, true] {
+            let lr = fitted_lr(has_bias);
+            let parts = lr.to_parts().ok().unwrap();
+            let back = LR::<f64>::from_values_with_has_bias(
+                parts.coefficients,
+                parts.bias,
+                parts.has_bias,
+            );
+            assert_eq!(
+                lr.coefficients.col_as_slice(0),
+                back.coefficients.col_as_slice(0)
+            );
+            assert_eq!(back.has_bias, has_bias);
+        }
+    }
+
+    #[test]
+    fn test_to_parts_zero_bias() {
+        // A bias term that happens to be exactly 0 is kept
+        let lr = LR::<f64>::from_values_with_has_bias(&[1.0, -2.0], 0.0, true);
+        let parts = lr.to_parts().ok().unwrap();
+        assert!(parts.has_bias);
+        assert_eq!(parts.bias, 0.0);
+        assert_eq!(parts.coefficients, &[1.0, -2.0]);
+
+        let from_parts = LR::from_parts(&parts);
+        assert_same_model(&lr, &from_parts);
+        assert_eq!(from_parts.coefficients.nrows(), 3);
+        let from_values =
+            LR::<f64>::from_values_with_has_bias(parts.coefficients, parts.bias, parts.has_bias);
+        assert_same_model(&lr, &from_values);
+
+        // from_values infers has_bias from the bias, so only the predictions are the same
+        let inferred = LR::<f64>::from_values(parts.coefficients, parts.bias);
+        assert!(!inferred.has_bias);
+        let x = Mat::from_fn(5, 2, |i, j| (i + j) as f64);
+        let (p1, p2) = (
+            lr.predict(x.as_ref()).ok().unwrap(),
+            inferred.predict(x.as_ref()).ok().unwrap(),
+        );
+        assert_eq!(p1.col_as_slice(0), p2.col_as_slice(0));
+    }
+
+    #[test]
+    fn test_to_parts_not_fit() {
+        assert!(LR::<f64>::new("qr", 0.0, true).to_parts().is_err());
+    }
+
+    #[test]
+    fn test_solvers_agree_on_ridge() {
+        let x = Mat::from_fn(50, 3, |i, j| {
+            ((i * (j + 3) + j) % 11) as f64 - 5.0 + 0.01 * i as f64
+        });
+        let y = Mat::from_fn(50, 1, |i, _| {
+            1.0 + 0.5 * *x.get(i, 0) - *x.get(i, 1)
+                + 2.0 * *x.get(i, 2)
+                + ((i % 5) as f64 - 2.0) * 0.1
+        });
+        let fit = |solver: &str| {
+            let mut lr = LR::new(solver, 0.5, true);
+            assert!(lr.fit(x.as_ref(), y.as_ref()).is_ok());
+            lr.coefficients.col_as_slice(0).to_vec()
+        };
+        let qr = fit("qr");
+        for solver in ["svd", "cholesky"] {
+            let other = fit(solver);
+            assert!(qr
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn test_weighted_elastic_net_is_duplicated_rows() {
+        // x, a column of ones for the bias, and y. The first 5 rows have weight 2
+        let x = Mat::from_fn(30, 3, |i, j| match j {
+            2 => 1.0,
+            _ => (((i * (j + 3)) % 11) as f64) / 5.0 - 0.3 * j as f64,
+        });
+        let y = Mat::from_fn(30, 1, |i, _| {
+            2.0 * *x.get(i, 0) - *x.get(i, 1) + 0.5 + ((i % 4) as f64 - 1.5) * 0.1
+        });
+        let w = (0..30)
+            .map(|i| if i < 5 { 2.0 } else { 1.0 })
+            .collect::<Vec<_>>();
+        let x_dup = Mat::from_fn(35, 3, |i, j| *x.get(i % 30, j));
+        let y_dup = Mat::from_fn(35, 1, |i, _| *y.get(i % 30, 0));
+
+        for algorithm in [LassoAlgorithm::CoordinateDescent, LassoAlgorithm::Fista] {
+            for (l2_reg, has_bias) in [(0.0, true), (0.1, true), (0.1, false)] {
+                let fit = |x: MatRef<f64>, y: MatRef<f64>, w: Option<&[f64]>| {
+                    let (coeffs, _, converged) = faer_elastic_net(
+                        x,
+                        y,
+                        w,
+                        0.05,
+                        l2_reg,
+                        None,
+                        has_bias,
+                        1e-12,
+                        100_000,
+                        StopCriterion::DualGap,
+                        algorithm,
+                    );
+                    assert!(converged);
+                    coeffs
+                };
+                let weighted = fit(x.as_ref(), y.as_ref(), Some(&w));
+                let dup = fit(x_dup.as_ref(), y_dup.as_ref(), None);
+                for (a, b) in weighted.col_as_slice(0).iter().zip(dup.col_as_slice(0)) {
+                    assert!((a - b).abs() < 1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_rank_cond_from_solver() {
+        // The second column is the first one plus 1e-8 times a column orthogonal to it, so cond(X) is
+        // ~1e8 and cond(XtX) is ~1e16
+        let x = Mat::from_fn(40, 2, |i, j| {
+            let t = (i as f64 - 19.5) / 10.0;
+            let z = if i % 2 == 0 { 1.0 } else { -1.0 };
+            if j == 0 {
+                1.0 + t
+            } else {
+                1.0 + t + 1e-8 * z
+            }
+        });
+        let y = Mat::from_fn(40, 1, |i, _| *x.get(i, 0) + *x.get(i, 1));
+
+        // QRDirect factorizes X itself, and sees full rank
+        let mut lr = LR::new("qr_direct", 0.0, false);
+        lr.fit_unchecked(x.as_ref(), y.as_ref());
+        assert_eq!(lr.rank(), 2);
+        assert!(lr.condition_number() > 1e7 && lr.condition_number() < 1e10);
+        assert!(lr.check_full_rank().is_ok());
+        // Ridge gives the rank of the design too
+        let mut ridge = LR::new("qr_direct", 0.1, false);
+        ridge.fit_unchecked(x.as_ref(), y.as_ref());
+        assert_eq!(ridge.rank(), 2);
+
+        // The normal equation solvers can't tell the columns apart
+        for solver in ["qr", "svd", "cholesky"] {
+            let mut lr = LR::new(solver, 0.0, false);
+            lr.fit_unchecked(x.as_ref(), y.as_ref());
+            assert_eq!(lr.rank(), 1);
+            assert!(lr.condition_number().is_infinite());
+        }
+
+        // A well conditioned design gets the same rank and cond from every solver
+        let x = Mat::from_fn(40, 3, |i, j| (((i * (j + 2)) % 7) as f64) + 0.1 * (i * j) as f64);
+        let y = Mat::from_fn(40, 1, |i, _| *x.get(i, 0) - *x.get(i, 2));
+        let (_, expected) = faer_gram_rank_cond((x.transpose() * &x).as_ref());
+        for solver in ["qr", "svd", "cholesky", "qr_direct"] {
+            let mut lr = LR::new(solver, 0.0, false);
+            lr.fit_unchecked(x.as_ref(), y.as_ref());
+            assert_eq!(lr.rank(), 3);
+            let cond = lr.condition_number();
+            assert!(cond.is_finite() && (cond / expected - 1.0).abs() < 0.5);
+        }
+    }
 }
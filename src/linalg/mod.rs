@@ -1,5 +1,13 @@
 #![allow(non_snake_case)]
+pub mod lr_bootstrap;
+pub mod lr_cv;
+pub mod lr_diagnostics;
+pub mod lr_glm;
+pub mod lr_gls;
 pub mod lr_online_solvers;
+pub mod lr_persist;
+pub mod lr_robust;
+pub mod lr_selection;
 pub mod lr_solvers;
 
 use faer::{Mat, MatRef};
@@ -13,6 +21,7 @@ pub enum LinalgErrors {
     NotEnoughData,
     MatNotLearnedYet,
     NotContiguousOrEmpty,
+    RankDeficient(usize, usize), // (rank, number of columns)
     Other(String),
 }
 
@@ -24,6 +33,10 @@ impl LinalgErrors {
             Self::MatNotLearnedYet => "Matrix is not learned yet.".to_string(),
             Self::NotEnoughData => "Not enough rows / columns.".to_string(),
             Self::NotContiguousOrEmpty => "Input is not contiguous or is empty".to_string(),
+            Self::RankDeficient(rank, n) => format!(
+                "Design matrix is rank deficient (rank {} < {} columns). Coefficients are not identified.",
+                rank, n
+            ),
             LinalgErrors::Other(s) => s,
         }
     }
@@ -34,7 +47,19 @@ pub enum LRSolverMethods {
     SVD,
     Choleskey,
     #[default]
-    QR,
+    QR, // QR of the normal equation, i.e. of XtX
+    QRDirect, // QR of X itself. Doesn't form XtX
+}
+
+impl LRSolverMethods {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SVD => "svd",
+            Self::Choleskey => "choleskey",
+            Self::QR => "qr",
+            Self::QRDirect => "qr_direct",
+        }
+    }
 }
 
 impl From<&str> for LRSolverMethods {
@@ -42,7 +67,8 @@ impl From<&str> for LRSolverMethods {
         match value {
             "qr" => Self::QR,
             "svd" => Self::SVD,
-            "choleskey" => Self::QR, // choleskey not available
+            "qr_direct" => Self::QRDirect,
+            "cholesky" | "choleskey" => Self::Choleskey,
             _ => Self::QR,
         }
     }
@@ -100,6 +126,63 @@ impl From<(f32, f32)> for LRMethods {
     }
 }
 
+/// When an iterative solver (coordinate descent, IRLS) stops.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum StopCriterion {
+    /// Max absolute change in the coefficients < tol
+    #[default]
+    CoefficientChange,
+    /// |obj_new - obj_old| / |obj_old| < tol, where obj is the full objective incl. penalties
+    RelativeObjective,
+    /// Duality gap of the Lasso / Elastic Net objective < tol. Only coordinate descent and FISTA
+    /// compute the gap. The other solvers treat this as CoefficientChange.
+    DualGap,
+}
+
+impl StopCriterion {
+    /// Whether the solver should stop after an iteration with the given max coefficient change
+    /// and objective values before and after the iteration. Solvers that compute the duality gap
+    /// check DualGap themselves.
+    pub fn is_converged<T: Float>(&self, max_change: T, obj_old: T, obj_new: T, tol: T) -> bool {
+        match self {
+            Self::CoefficientChange | Self::DualGap => max_change < tol,
+            Self::RelativeObjective => {
+                let diff = (obj_new - obj_old).abs();
+                diff == T::zero() || diff < tol * obj_old.abs()
+            }
+        }
+    }
+}
+
+impl From<&str> for StopCriterion {
+    fn from(value: &str) -> Self {
+        match value {
+            "objective" => Self::RelativeObjective,
+            "dual_gap" | "gap" => Self::DualGap,
+            _ => Self::CoefficientChange,
+        }
+    }
+}
+
+/// The solver for Lasso and Elastic Net.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum LassoAlgorithm {
+    #[default]
+    CoordinateDescent,
+    /// Accelerated proximal gradient. Each iteration is one pass of matrix-vector products over X,
+    /// which can be faster than coordinate descent when there are many more features than rows.
+    Fista,
+}
+
+impl From<&str> for LassoAlgorithm {
+    fn from(value: &str) -> Self {
+        match value {
+            "fista" => Self::Fista,
+            _ => Self::CoordinateDescent,
+        }
+    }
+}
+
 pub trait LinearRegression<T: RealField + Float> {
     /// Typically coefficients + the bias as a single matrix (single slice)
     fn fitted_values(&self) -> MatRef<T>;
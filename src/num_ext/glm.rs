@@ -0,0 +1,330 @@
+/// Generalized Linear Models (logistic, Poisson and any family and link) using Faer and ndarray.
+use super::linear_regression::{
+    coeffs_bias_first, masked_weights, nan_for_skipped_rows, series_to_mat_for_lstsq,
+};
+use crate::linalg::{
+    lr_glm::{GlmFamily, GlmLink, GlmRegression, LogisticRegression, PoissonRegression},
+    IntoFaer, LinalgErrors, LinearRegression,
+};
+use crate::utils::NullPolicy;
+use ndarray::{s, Array2};
+use polars::prelude::*;
+use pyo3_polars::derive::polars_expr;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LogisticKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) l2_reg: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PoissonKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) l2_reg: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    // If true, inputs[1] is the offset, which is added to the linear predictor
+    #[serde(default)]
+    pub(crate) has_offset: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GlmKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    // One of gaussian, binomial, poisson and gamma
+    pub(crate) family: String,
+    // One of identity, logit, log and inverse. None means the canonical link of the family
+    #[serde(default)]
+    pub(crate) link: Option<String>,
+    pub(crate) l2_reg: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    // If true, inputs[0] is the prior weights
+    #[serde(default)]
+    pub(crate) weighted: bool,
+    // If true, the input after the target is the offset, which is added to the linear predictor
+    #[serde(default)]
+    pub(crate) has_offset: bool,
+}
+
+fn logistic_coeffs_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        "coeffs".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+fn logistic_pred_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new("prob".into(), DataType::Float64))
+}
+
+fn poisson_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let n_iter = Field::new("n_iter".into(), DataType::UInt32);
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let v: Vec<Field> = vec![coeffs, n_iter, converged];
+    Ok(Field::new("poisson".into(), DataType::Struct(v)))
+}
+
+fn poisson_pred_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new("pred".into(), DataType::Float64))
+}
+
+fn glm_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let n_iter = Field::new("n_iter".into(), DataType::UInt32);
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let v: Vec<Field> = vec![coeffs, n_iter, converged];
+    Ok(Field::new("glm".into(), DataType::Struct(v)))
+}
+
+fn check_l2_reg(l2_reg: f64) -> PolarsResult<()> {
+    if l2_reg < 0. {
+        Err(PolarsError::ComputeError(
+            "L2 regularization factor must be non-negative.".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Fits the logistic regression on the target (inputs[0]) and the features. Returns the model,
+/// the design matrix (with the bias column, if any) and the mask of the rows used.
+fn fit_logistic(
+    inputs: &[Series],
+    kwargs: &LogisticKwargs,
+) -> PolarsResult<(LogisticRegression<f64>, Array2<f64>, BooleanChunked)> {
+    let null_policy = NullPolicy::try_from(kwargs.null_policy.clone())
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    check_l2_reg(kwargs.l2_reg)?;
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, kwargs.bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let mut lr = LogisticRegression::new(
+        kwargs.solver.as_str(),
+        kwargs.l2_reg,
+        kwargs.bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    lr.fit(x, y)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    Ok((lr, mat, mask))
+}
+
+/// Fits the Poisson regression on the target (inputs[0]), the offset (inputs[1] if has_offset) and
+/// the features. The offset is a column of the design matrix, so that nulls in it are handled by the
+/// null policy like the features. Returns the model, the design matrix (target, offset if any,
+/// features, bias if any) and the mask of the rows used.
+fn fit_poisson(
+    inputs: &[Series],
+    kwargs: &PoissonKwargs,
+) -> PolarsResult<(PoissonRegression<f64>, Array2<f64>, BooleanChunked)> {
+    let null_policy = NullPolicy::try_from(kwargs.null_policy.clone())
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    check_l2_reg(kwargs.l2_reg)?;
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, kwargs.bias, null_policy)?;
+    let start = 1 + kwargs.has_offset as usize;
+    let x = mat.slice(s![.., start..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let offset = if kwargs.has_offset {
+        Some(mat.column(1).to_vec())
+    } else {
+        None
+    };
+    let mut lr = PoissonRegression::new(
+        kwargs.solver.as_str(),
+        kwargs.l2_reg,
+        kwargs.bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    lr.fit_with_offset(x, y, offset.as_deref())
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    Ok((lr, mat, mask))
+}
+
+fn glm_family_and_link(kwargs: &GlmKwargs) -> PolarsResult<(GlmFamily, Option<GlmLink>)> {
+    let to_polars = |e: LinalgErrors| PolarsError::ComputeError(e.to_string().into());
+    let family = GlmFamily::try_from(kwargs.family.as_str()).map_err(to_polars)?;
+    let link = kwargs
+        .link
+        .as_deref()
+        .map(GlmLink::try_from)
+        .transpose()
+        .map_err(to_polars)?;
+    Ok((family, link))
+}
+
+/// Fits the GLM on the weights (inputs[0] if weighted), the target, the offset (if has_offset) and
+/// the features. Like in fit_poisson, the offset is a column of the design matrix. Returns the model,
+/// the design matrix (target, offset if any, features, bias if any) and the mask of the rows used.
+fn fit_glm(
+    inputs: &[Series],
+    kwargs: &GlmKwargs,
+) -> PolarsResult<(GlmRegression<f64>, Array2<f64>, BooleanChunked)> {
+    let null_policy = NullPolicy::try_from(kwargs.null_policy.clone())
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    check_l2_reg(kwargs.l2_reg)?;
+    let (family, link) = glm_family_and_link(kwargs)?;
+    let data_for_matrix = if kwargs.weighted {
+        &inputs[1..]
+    } else {
+        inputs
+    };
+    let (mat, mask) = series_to_mat_for_lstsq(data_for_matrix, kwargs.bias, null_policy)?;
+    let start = 1 + kwargs.has_offset as usize;
+    let x = mat.slice(s![.., start..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let offset = if kwargs.has_offset {
+        Some(mat.column(1).to_vec())
+    } else {
+        None
+    };
+    let binding = if kwargs.weighted {
+        Some(masked_weights(
+            &inputs[0],
+            &mask,
+            mat.nrows(),
+            &DataType::Float64,
+        )?)
+    } else {
+        None
+    };
+    let weights = binding
+        .as_ref()
+        .map(|w| w.f64().unwrap().cont_slice().unwrap());
+    let mut lr = GlmRegression::new(
+        family,
+        link,
+        kwargs.solver.as_str(),
+        kwargs.l2_reg,
+        kwargs.bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    lr.fit_weighted(x, y, weights, offset.as_deref())
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    Ok((lr, mat, mask))
+}
+
+#[polars_expr(output_type_func=logistic_coeffs_output)]
+fn pl_logistic_coeffs(inputs: &[Series], kwargs: LogisticKwargs) -> PolarsResult<Series> {
+    let (lr, _, _) = fit_logistic(inputs, &kwargs)?;
+    let coeffs = lr.fitted_values();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        &coeffs.col(0).iter().copied().collect::<Vec<_>>(),
+        kwargs.bias,
+        kwargs.bias_first,
+    ));
+    Ok(builder.finish().into_series())
+}
+
+#[polars_expr(output_type_func=logistic_pred_output)]
+fn pl_logistic_pred(inputs: &[Series], kwargs: LogisticKwargs) -> PolarsResult<Series> {
+    let (lr, mat, mask) = fit_logistic(inputs, &kwargs)?;
+    // predict_proba adds the bias, so the bias column is not needed
+    let n_features = mat.ncols() - 1 - kwargs.bias as usize;
+    let x = mat.slice(s![.., 1..(1 + n_features)]).into_faer();
+    let prob = lr
+        .predict_proba(x)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let prob = prob.col(0).iter().copied().collect::<Vec<_>>();
+    Ok(nan_for_skipped_rows("prob", &prob, &mask))
+}
+
+#[polars_expr(output_type_func=poisson_output)]
+fn pl_poisson_coeffs(inputs: &[Series], kwargs: PoissonKwargs) -> PolarsResult<Series> {
+    let (lr, _, _) = fit_poisson(inputs, &kwargs)?;
+    let coeffs = lr.fitted_values();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        &coeffs.col(0).iter().copied().collect::<Vec<_>>(),
+        kwargs.bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+    let n_iter = Series::from_vec("n_iter".into(), vec![lr.n_iter as u32]);
+    let converged = Series::new("converged".into(), [lr.converged]);
+    let out = StructChunked::from_series(
+        "poisson".into(),
+        1,
+        [&coeffs, &n_iter, &converged].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+#[polars_expr(output_type_func=poisson_pred_output)]
+fn pl_poisson_pred(inputs: &[Series], kwargs: PoissonKwargs) -> PolarsResult<Series> {
+    let (lr, mat, mask) = fit_poisson(inputs, &kwargs)?;
+    // predict_mean adds the bias, so the bias column is not needed
+    let start = 1 + kwargs.has_offset as usize;
+    let end = mat.ncols() - kwargs.bias as usize;
+    let x = mat.slice(s![.., start..end]).into_faer();
+    let offset = if kwargs.has_offset {
+        Some(mat.column(1).to_vec())
+    } else {
+        None
+    };
+    let pred = lr
+        .predict_mean(x, offset.as_deref())
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let pred = pred.col(0).iter().copied().collect::<Vec<_>>();
+    Ok(nan_for_skipped_rows("pred", &pred, &mask))
+}
+
+#[polars_expr(output_type_func=glm_output)]
+fn pl_glm(inputs: &[Series], kwargs: GlmKwargs) -> PolarsResult<Series> {
+    let (lr, _, _) = fit_glm(inputs, &kwargs)?;
+    let coeffs = lr.fitted_values();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        &coeffs.col(0).iter().copied().collect::<Vec<_>>(),
+        kwargs.bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+    let n_iter = Series::from_vec("n_iter".into(), vec![lr.n_iter as u32]);
+    let converged = Series::new("converged".into(), [lr.converged]);
+    let out =
+        StructChunked::from_series("glm".into(), 1, [&coeffs, &n_iter, &converged].into_iter())?;
+    Ok(out.into_series())
+}
+
+#[polars_expr(output_type_func=poisson_pred_output)]
+fn pl_glm_pred(inputs: &[Series], kwargs: GlmKwargs) -> PolarsResult<Series> {
+    let (lr, mat, mask) = fit_glm(inputs, &kwargs)?;
+    // predict_mean adds the bias, so the bias column is not needed
+    let start = 1 + kwargs.has_offset as usize;
+    let end = mat.ncols() - kwargs.bias as usize;
+    let x = mat.slice(s![.., start..end]).into_faer();
+    let offset = if kwargs.has_offset {
+        Some(mat.column(1).to_vec())
+    } else {
+        None
+    };
+    let pred = lr
+        .predict_mean(x, offset.as_deref())
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let pred = pred.col(0).iter().copied().collect::<Vec<_>>();
+    Ok(nan_for_skipped_rows("pred", &pred, &mask))
+}
@@ -1,23 +1,53 @@
+use super::linear_regression_f32::{lstsq_f32, lstsq_pred_f32};
 use crate::linalg::{
-    lr_online_solvers::{faer_recursive_lstsq, faer_rolling_lstsq, faer_rolling_skipping_lstsq},
+    lr_bootstrap::{faer_bootstrap_lstsq, percentile_interval, sign_agreement},
+    lr_cv::{faer_lstsq_fold_cv, faer_lstsq_kfold_cv, faer_ridge_loocv},
+    lr_diagnostics::{
+        breusch_pagan_test, durbin_watson, faer_chow_test, faer_conditioning_report,
+        faer_driscoll_kraay, faer_row_influence, faer_vif, faer_xtx_eigenvalues,
+        newey_west_default_lags, newey_west_long_run, pacf_durbin_levinson, recursive_cusum,
+        residual_acf, standardized_coefficients, white_test,
+    },
+    lr_gls::faer_cochrane_orcutt,
+    lr_online_solvers::{
+        faer_ewls, faer_expanding_predictions, faer_recursive_lstsq, faer_recursive_lstsq_with_se,
+        faer_recursive_residuals, faer_rolling_lstsq, faer_rolling_skipping_lstsq,
+    },
+    lr_persist::coeffs_and_bias_from_bytes,
+    lr_robust::{
+        faer_mm_lstsq, faer_robust_lstsq, ransac_fit, theil_sen, HuberRegression,
+        QuantileRegression, RobustLoss,
+    },
+    lr_selection::{
+        faer_best_subset, faer_forward_stepwise, faer_relative_importance, SubsetCriterion,
+    },
     lr_solvers::{
-        faer_coordinate_descent, faer_solve_lstsq, faer_solve_lstsq_rcond, faer_weighted_lstsq,
+        faer_adaptive_lasso, faer_coordinate_descent, faer_design_rank_cond, faer_elastic_net,
+        faer_generalized_ridge, faer_lasso_path, faer_lstsq_profile, faer_lstsq_residuals,
+        faer_matrix_rank, faer_residual_dof, faer_ridge_prediction_variance, faer_solve_lstsq,
+        faer_solve_lstsq_per_target, faer_solve_lstsq_rcond, faer_sqrt_weighted_rows,
+        faer_total_lstsq, faer_weighted_lstsq, PredictionVariance, DEFAULT_COND_THRESHOLD, LR,
     },
-    IntoFaer, LRMethods,
+    IntoFaer, LRMethods, LRSolverMethods, LassoAlgorithm, LinalgErrors, LinearRegression,
+    StopCriterion,
 };
-use crate::utils::{to_frame, NullPolicy};
+use crate::stats_utils::{self, gamma};
+use crate::utils::NullPolicy;
 /// Least Squares using Faer and ndarray.
 use core::f64;
 use faer::{
     linalg::solvers::{DenseSolveCore, Solve},
-    Col,
+    Col, ColRef, Mat, MatRef,
 };
+use faer_traits::RealField;
 use itertools::Itertools;
-use ndarray::{s, Array2};
+use ndarray::{s, Array2, ShapeBuilder};
+use num::Float;
 use polars::prelude as pl;
 use polars::prelude::*;
 use pyo3_polars::derive::polars_expr;
 use serde::Deserialize;
+use std::borrow::Cow;
 
 #[derive(Deserialize, Debug)]
 pub(crate) struct LstsqKwargs {
@@ -31,6 +61,70 @@ pub(crate) struct LstsqKwargs {
     pub(crate) weighted: bool,
     #[serde(default)]
     pub(crate) std_err: String,
+    // Number of lags of the Newey-West standard errors. None means the rule of thumb
+    #[serde(default)]
+    pub(crate) hac_lags: Option<usize>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    #[serde(default)]
+    pub(crate) clip: Option<(f64, f64)>,
+    #[serde(default)]
+    pub(crate) weight_type: String,
+    #[serde(default = "_cond_threshold")]
+    pub(crate) warn_collinearity: f64,
+    #[serde(default)]
+    pub(crate) stop_criterion: String,
+    // Penalize the bias like the other coefficients in Ridge / Lasso / Elastic Net
+    #[serde(default)]
+    pub(crate) penalize_bias: bool,
+    // "cd" or "fista", for Lasso / Elastic Net
+    #[serde(default)]
+    pub(crate) algorithm: String,
+    // Significance level of the confidence intervals in the report, and of the prediction intervals
+    #[serde(default = "_ci_alpha")]
+    pub(crate) alpha: f64,
+    // Add prediction interval bounds to the predictions
+    #[serde(default)]
+    pub(crate) pred_interval: bool,
+    // Add the standard errors of the fitted means to the predictions
+    #[serde(default)]
+    pub(crate) pred_se: bool,
+    // Max number of iterations for Lasso / Elastic Net
+    #[serde(default = "_max_iter")]
+    pub(crate) max_iter: usize,
+    // Per-feature multipliers of l1_reg for Lasso / Elastic Net. The bias always has a factor of 0
+    #[serde(default)]
+    pub(crate) penalty_factor: Option<Vec<f64>>,
+    // Error if Lasso / Elastic Net doesn't converge within max_iter
+    #[serde(default)]
+    pub(crate) strict: bool,
+    // Treat NaN and +/-inf in the target and the features as nulls under the skip null policy,
+    // and raise under the other policies
+    #[serde(default)]
+    pub(crate) drop_nonfinite: bool,
+    // If true, the input after the target is an offset, a known term of the linear predictor
+    #[serde(default)]
+    pub(crate) has_offset: bool,
+}
+
+impl LstsqKwargs {
+    /// The has_bias passed to the regularized solvers. They leave the last column unpenalized only if
+    /// has_bias, so with penalize_bias, the bias column is treated as any other feature.
+    pub(crate) fn unpenalized_bias(&self) -> bool {
+        self.bias && !self.penalize_bias
+    }
+}
+
+fn _cond_threshold() -> f64 {
+    DEFAULT_COND_THRESHOLD
+}
+
+fn _ci_alpha() -> f64 {
+    0.05
+}
+
+fn _max_iter() -> usize {
+    2000
 }
 
 #[derive(Deserialize, Debug)]
@@ -40,6 +134,29 @@ pub(crate) struct MultiLstsqKwargs {
     pub(crate) solver: String,
     pub(crate) last_target_idx: usize,
     pub(crate) l2_reg: f64,
+    // One l2_reg per target. If empty, l2_reg is used for all targets.
+    #[serde(default)]
+    pub(crate) l2_regs: Vec<f64>,
+    #[serde(default)]
+    pub(crate) clip: Option<(f64, f64)>,
+}
+
+impl MultiLstsqKwargs {
+    pub(crate) fn per_target_l2(&self, n_targets: usize) -> PolarsResult<Option<Vec<f64>>> {
+        if self.l2_regs.is_empty() {
+            Ok(None)
+        } else if self.l2_regs.len() != n_targets {
+            Err(PolarsError::ComputeError(
+                "The number of l2 penalties must match the number of targets.".into(),
+            ))
+        } else if self.l2_regs.iter().any(|l| l.is_nan() || *l < 0.) {
+            Err(PolarsError::ComputeError(
+                "L2 penalties must be non-negative.".into(),
+            ))
+        } else {
+            Ok(Some(self.l2_regs.clone()))
+        }
+    }
 }
 
 // Sherman-William-Woodbury (Update, online versions) LstsqKwargs
@@ -50,298 +167,2992 @@ pub(crate) struct SWWLstsqKwargs {
     pub(crate) bias: bool,
     pub(crate) lambda: f64,
     pub(crate) min_size: usize,
+    #[serde(default)]
+    pub(crate) clip: Option<(f64, f64)>,
+    #[serde(default)]
+    pub(crate) horizon: usize,
+    // Forgetting factor in (0, 1] of pl_ewls. None means 1, i.e. no forgetting
+    #[serde(default)]
+    pub(crate) forget: Option<f64>,
 }
 
-fn report_output(_: &[Field]) -> PolarsResult<Field> {
-    let features = Field::new("features".into(), DataType::String); // index of feature
-    let beta = Field::new("beta".into(), DataType::Float64); // estimated value for this coefficient
-    let stderr = Field::new("std_err".into(), DataType::Float64); // Std Err for this coefficient
-    let t = Field::new("t".into(), DataType::Float64); // t value for this coefficient
-    let p = Field::new("p>|t|".into(), DataType::Float64); // p value for this coefficient
-    let ci_lower = Field::new("0.025".into(), DataType::Float64); // CI lower bound at 0.025
-    let ci_upper = Field::new("0.975".into(), DataType::Float64); // CI upper bound at 0.975
-    let r2 = Field::new("r2".into(), DataType::Float64); // Coefficient of determination
-    let adj_r2 = Field::new("adj_r2".into(), DataType::Float64); // adjusted
-    let v: Vec<Field> = vec![features, beta, stderr, t, p, ci_lower, ci_upper, r2, adj_r2];
-    Ok(Field::new("lin_reg_report".into(), DataType::Struct(v)))
+#[derive(Deserialize, Debug)]
+pub(crate) struct BootstrapKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) l2_reg: f64,
+    pub(crate) n_boot: usize,
+    pub(crate) confidence: f64,
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
 }
 
-fn pred_residue_output(_: &[Field]) -> PolarsResult<Field> {
-    let pred = Field::new("pred".into(), DataType::Float64);
-    let residue = Field::new("resid".into(), DataType::Float64);
-    let v = vec![pred, residue];
-    Ok(Field::new("pred".into(), DataType::Struct(v)))
+#[derive(Deserialize, Debug)]
+pub(crate) struct DriscollKraayKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) lags: Option<usize>,
 }
 
-fn coeff_pred_output(_: &[Field]) -> PolarsResult<Field> {
-    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
-    let pred = Field::new("prediction".into(), DataType::Float64);
-    let v: Vec<Field> = vec![coeffs, pred];
-    Ok(Field::new("".into(), DataType::Struct(v)))
+#[derive(Deserialize, Debug)]
+pub(crate) struct SignStabilityKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) l2_reg: f64,
+    pub(crate) n_boot: usize,
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
 }
 
-fn coeff_singular_values_output(_: &[Field]) -> PolarsResult<Field> {
-    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
-    let singular_values = Field::new(
-        "singular_values".into(),
-        DataType::List(Box::new(DataType::Float64)),
-    );
-    let v: Vec<Field> = vec![coeffs, singular_values];
-    Ok(Field::new("".into(), DataType::Struct(v)))
+#[derive(Deserialize, Debug)]
+pub(crate) struct RobustLstsqKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) loss: String,
+    pub(crate) c: Option<f64>,
+    pub(crate) estimate_scale: bool,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    #[serde(default)]
+    pub(crate) stop_criterion: String,
 }
 
-fn coeff_output(_: &[Field]) -> PolarsResult<Field> {
-    Ok(Field::new(
-        "coeffs".into(),
-        DataType::List(Box::new(DataType::Float64)),
-    ))
+#[derive(Deserialize, Debug)]
+pub(crate) struct HuberRegKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) delta: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
 }
 
-#[derive(PartialEq)]
-pub enum StandardError {
-    SE,
-    HC0,
-    HC1,
-    HC2,
-    HC3,
+#[derive(Deserialize, Debug)]
+pub(crate) struct QuantileRegKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) tau: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
 }
 
-impl From<String> for StandardError {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "hc0" => Self::HC0,
-            "hc1" => Self::HC1,
-            "hc2" => Self::HC2,
-            "hc3" => Self::HC3,
-            _ => Self::SE,
-        }
-    }
+#[derive(Deserialize, Debug)]
+pub(crate) struct MMLstsqKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) c: f64,
+    pub(crate) n_subsamples: usize,
+    pub(crate) seed: Option<u64>,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    #[serde(default)]
+    pub(crate) stop_criterion: String,
 }
 
-impl Into<PlSmallStr> for StandardError {
-    fn into(self) -> PlSmallStr {
-        match self {
-            StandardError::SE => "std_err".into(),
-            StandardError::HC0 => "hc0_se".into(),
-            StandardError::HC1 => "hc1_se".into(),
-            StandardError::HC2 => "hc2_se".into(),
-            StandardError::HC3 => "hc3_se".into(),
-        }
-    }
+#[derive(Deserialize, Debug)]
+pub(crate) struct RansacKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) n_trials: usize,
+    pub(crate) min_samples: Option<usize>,
+    pub(crate) residual_threshold: Option<f64>,
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TheilSenKwargs {
+    pub(crate) null_policy: String,
+    pub(crate) max_pairs: Option<usize>,
+    pub(crate) seed: Option<u64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct Ar1LstsqKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) prais_winsten: bool,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct FixedEffectsKwargs {
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GroupSlopesKwargs {
+    pub(crate) null_policy: String,
+    pub(crate) confidence: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct BestSubsetKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) max_size: Option<usize>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct StepwiseKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) criterion: String,
+    pub(crate) max_size: Option<usize>,
+    pub(crate) max_vif: Option<f64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RelativeImportanceKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct TrainTestKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    pub(crate) l1_reg: f64,
+    pub(crate) l2_reg: f64,
+    pub(crate) tol: f64,
+    #[serde(default)]
+    pub(crate) penalize_bias: bool,
+    pub(crate) score: String, // Rows to predict: "test", "train" or "all"
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PredIntervalKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) l2_reg: f64,
+    pub(crate) confidence: f64,
+    // "prediction" for a new observation, or "confidence" for the mean
+    pub(crate) interval: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct AdaptiveLassoKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) l1_reg: f64,
+    pub(crate) gamma: f64,
+    pub(crate) l2_init: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    #[serde(default)]
+    pub(crate) strict: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GeneralizedRidgeKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    // The penalty matrix Gamma, flattened in row major order, with gamma_ncols columns
+    pub(crate) gamma: Vec<f64>,
+    pub(crate) gamma_ncols: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LassoPathKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) lambdas: Vec<f64>,
+    #[serde(default)]
+    pub(crate) l2_reg: f64,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) stop_criterion: String,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    #[serde(default)]
+    pub(crate) strict: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct RidgeLoocvKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) lambdas: Vec<f64>,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct LstsqCvKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) lambdas: Vec<f64>,
+    pub(crate) l1_ratio: f64,
+    pub(crate) n_folds: usize,
+    pub(crate) seed: Option<u64>,
+    pub(crate) tol: f64,
+    pub(crate) max_iter: usize,
+    #[serde(default)]
+    pub(crate) bias_first: bool,
+    // If true, the first input is an integer column with the fold of each row
+    #[serde(default)]
+    pub(crate) custom_folds: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PredictPathKwargs {
+    pub(crate) coefficients: Vec<Vec<f64>>,
+    pub(crate) bias: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PredictWithCoeffsKwargs {
+    pub(crate) coefficients: Vec<f64>,
+    pub(crate) bias: f64,
+    pub(crate) model: Option<Vec<u8>>, // A model from to_bytes. If given, coefficients and bias are ignored
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PredictFromCoeffsKwargs {
+    pub(crate) bias: bool, // If true, the last value of each coefficient list is the bias
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct GroupedPredictKwargs {
+    pub(crate) coefficients: Vec<f64>,
+    pub(crate) bias: bool,
+    pub(crate) weighted: bool, // If true, the second input is the weights
+    pub(crate) how: String,    // "sum" or "mean"
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PartialRegKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
 }
 
-// -----------------------------------------------------------------------------------------------------
+#[derive(Deserialize, Debug)]
+pub(crate) struct ResidualDiagKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    pub(crate) solver: String,
+    #[serde(default)]
+    pub(crate) lag: usize,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct XtxEigenKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct VifKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct MatrixRankKwargs {
+    pub(crate) bias: bool,
+    pub(crate) null_policy: String,
+    // Relative tolerance on the diagonal of R. If None, it is max(nrows, ncols) * eps.
+    #[serde(default)]
+    pub(crate) rtol: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct ConditioningKwargs {
+    pub(crate) null_policy: String,
+}
+
+fn xtx_eigenvalues_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        "eigenvalues".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+fn conditioning_output(_: &[Field]) -> PolarsResult<Field> {
+    let without_bias = Field::new("cond_without_bias".into(), DataType::Float64);
+    let with_bias = Field::new("cond_with_bias".into(), DataType::Float64);
+    let centered = Field::new("cond_centered".into(), DataType::Float64); // features centered first
+    let v: Vec<Field> = vec![without_bias, with_bias, centered];
+    Ok(Field::new("conditioning".into(), DataType::Struct(v)))
+}
+
+fn influence_output(_: &[Field]) -> PolarsResult<Field> {
+    let leverage = Field::new("leverage".into(), DataType::Float64);
+    let studentized_resid = Field::new("studentized_resid".into(), DataType::Float64);
+    let cooks_distance = Field::new("cooks_distance".into(), DataType::Float64);
+    let v: Vec<Field> = vec![leverage, studentized_resid, cooks_distance];
+    Ok(Field::new("influence".into(), DataType::Struct(v)))
+}
+
+fn matrix_rank_output(_: &[Field]) -> PolarsResult<Field> {
+    let rank = Field::new("rank".into(), DataType::UInt32);
+    let n_cols = Field::new("n_cols".into(), DataType::UInt32); // bias included
+    let note = Field::new("note".into(), DataType::String); // null unless rank deficient
+    let v: Vec<Field> = vec![rank, n_cols, note];
+    Ok(Field::new("matrix_rank".into(), DataType::Struct(v)))
+}
+
+fn group_influence_output(fields: &[Field]) -> PolarsResult<Field> {
+    let group = Field::new("group".into(), fields[0].dtype().clone());
+    let n = Field::new("n".into(), DataType::UInt32); // number of rows in the group
+    let leverage = Field::new("leverage".into(), DataType::Float64); // sum of the leverages
+    let dffits_abs_sum = Field::new("dffits_abs_sum".into(), DataType::Float64);
+    let dffits_norm = Field::new("dffits_norm".into(), DataType::Float64); // l2 norm of the DFFITS
+    let dffits_max_abs = Field::new("dffits_max_abs".into(), DataType::Float64);
+    let v: Vec<Field> = vec![
+        group,
+        n,
+        leverage,
+        dffits_abs_sum,
+        dffits_norm,
+        dffits_max_abs,
+    ];
+    Ok(Field::new("group_influence".into(), DataType::Struct(v)))
+}
+
+fn report_output(_: &[Field]) -> PolarsResult<Field> {
+    let features = Field::new("features".into(), DataType::String); // index of feature
+    let beta = Field::new("beta".into(), DataType::Float64); // estimated value for this coefficient
+    let std_beta = Field::new("std_beta".into(), DataType::Float64); // beta * std(x) / std(y), NaN for the bias
+    let stderr = Field::new("std_err".into(), DataType::Float64); // Std Err for this coefficient
+    let t = Field::new("t".into(), DataType::Float64); // t value for this coefficient
+    let p = Field::new("p>|t|".into(), DataType::Float64); // p value for this coefficient
+    let ci_lower_95 = Field::new("0.025".into(), DataType::Float64); // Deprecated, 95% CI lower bound
+    let ci_upper_95 = Field::new("0.975".into(), DataType::Float64); // Deprecated, 95% CI upper bound
+    let ci_lower = Field::new("ci_lower".into(), DataType::Float64); // CI lower bound at alpha / 2
+    let ci_upper = Field::new("ci_upper".into(), DataType::Float64); // CI upper bound at 1 - alpha / 2
+    let r2 = Field::new("r2".into(), DataType::Float64); // Coefficient of determination
+    let adj_r2 = Field::new("adj_r2".into(), DataType::Float64); // adjusted
+    let resid_se = Field::new("resid_se".into(), DataType::Float64); // sqrt(RSS / df_resid)
+    let f_stat = Field::new("f_stat".into(), DataType::Float64); // all coefficients but the bias are 0
+    let f_pvalue = Field::new("f_pvalue".into(), DataType::Float64);
+    let df_resid = Field::new("df_resid".into(), DataType::Float64); // n - trace of the hat matrix
+    let n_obs = Field::new("n_obs".into(), DataType::UInt32); // rows used, after dropping nulls
+    let rank = Field::new("rank".into(), DataType::UInt32); // numerical rank of X, bias included
+                                                            // Of the residuals in row order
+    let durbin_watson = Field::new("durbin_watson".into(), DataType::Float64);
+    // Null unless X is rank deficient
+    let note = Field::new("note".into(), DataType::String);
+    let v: Vec<Field> = vec![
+        features,
+        beta,
+        std_beta,
+        stderr,
+        t,
+        p,
+        ci_lower_95,
+        ci_upper_95,
+        ci_lower,
+        ci_upper,
+        r2,
+        adj_r2,
+        resid_se,
+        f_stat,
+        f_pvalue,
+        df_resid,
+        n_obs,
+        rank,
+        durbin_watson,
+        note,
+    ];
+    Ok(Field::new("lin_reg_report".into(), DataType::Struct(v)))
+}
+
+fn sign_stability_output(_: &[Field]) -> PolarsResult<Field> {
+    let features = Field::new("features".into(), DataType::String);
+    let beta = Field::new("beta".into(), DataType::Float64); // estimated on the full sample
+                                                             // fraction of the resamples with the same sign as beta
+    let sign_agreement = Field::new("sign_agreement".into(), DataType::Float64);
+    let v: Vec<Field> = vec![features, beta, sign_agreement];
+    Ok(Field::new("sign_stability".into(), DataType::Struct(v)))
+}
+
+fn driscoll_kraay_output(_: &[Field]) -> PolarsResult<Field> {
+    let features = Field::new("features".into(), DataType::String);
+    let beta = Field::new("beta".into(), DataType::Float64);
+    let dk_se = Field::new("dk_se".into(), DataType::Float64);
+    let t = Field::new("t".into(), DataType::Float64);
+    let p = Field::new("p>|t|".into(), DataType::Float64); // t distribution with #periods - 1 dof
+                                                           // row of the covariance matrix, in the same order as features
+    let cov = Field::new("cov".into(), DataType::List(Box::new(DataType::Float64)));
+    let v: Vec<Field> = vec![features, beta, dk_se, t, p, cov];
+    Ok(Field::new("driscoll_kraay".into(), DataType::Struct(v)))
+}
+
+fn bootstrap_output(_: &[Field]) -> PolarsResult<Field> {
+    let features = Field::new("features".into(), DataType::String);
+    let beta = Field::new("beta".into(), DataType::Float64); // estimated on the full sample
+    let boot_se = Field::new("boot_se".into(), DataType::Float64); // std of the bootstrap betas
+    let lower = Field::new("lower".into(), DataType::Float64);
+    let upper = Field::new("upper".into(), DataType::Float64);
+    let v: Vec<Field> = vec![features, beta, boot_se, lower, upper];
+    Ok(Field::new("bootstrap_ci".into(), DataType::Struct(v)))
+}
+
+fn pred_residue_output(_: &[Field]) -> PolarsResult<Field> {
+    let pred = Field::new("pred".into(), DataType::Float64);
+    let residue = Field::new("resid".into(), DataType::Float64);
+    let v = vec![pred, residue];
+    Ok(Field::new("pred".into(), DataType::Struct(v)))
+}
+
+fn lstsq_pred_output(fields: &[Field], kwargs: LstsqKwargs) -> PolarsResult<Field> {
+    let dtype = lstsq_dtype(fields, kwargs.weighted);
+    let pred = Field::new("pred".into(), dtype.clone());
+    let residue = Field::new("resid".into(), dtype.clone());
+    let mut v = vec![pred, residue];
+    if kwargs.pred_interval {
+        v.push(Field::new("pred_lower".into(), dtype.clone()));
+        v.push(Field::new("pred_upper".into(), dtype.clone()));
+    }
+    if kwargs.pred_se {
+        v.push(Field::new("pred_se".into(), dtype));
+    }
+    Ok(Field::new("pred".into(), DataType::Struct(v)))
+}
+
+fn pred_interval_output(_: &[Field]) -> PolarsResult<Field> {
+    let pred = Field::new("prediction".into(), DataType::Float64);
+    let fit_var = Field::new("fit_var".into(), DataType::Float64); // variance of the fitted mean
+    let pred_var = Field::new("pred_var".into(), DataType::Float64); // fit_var + residual variance
+    let lower = Field::new("lower".into(), DataType::Float64);
+    let upper = Field::new("upper".into(), DataType::Float64);
+    let v: Vec<Field> = vec![pred, fit_var, pred_var, lower, upper];
+    Ok(Field::new("pred_interval".into(), DataType::Struct(v)))
+}
+
+fn coeff_pred_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let pred = Field::new("prediction".into(), DataType::Float64);
+    let v: Vec<Field> = vec![coeffs, pred];
+    Ok(Field::new("".into(), DataType::Struct(v)))
+}
+
+fn recursive_residuals_output(_: &[Field]) -> PolarsResult<Field> {
+    let resid = Field::new("recursive_resid".into(), DataType::Float64);
+    let cusum = Field::new("cusum".into(), DataType::Float64); // scaled by the residual std
+    let bound = Field::new("cusum_bound".into(), DataType::Float64); // 5% significance
+    let v: Vec<Field> = vec![resid, cusum, bound];
+    Ok(Field::new(
+        "recursive_residuals".into(),
+        DataType::Struct(v),
+    ))
+}
+
+fn expanding_output(_: &[Field]) -> PolarsResult<Field> {
+    let pred = Field::new("prediction".into(), DataType::Float64);
+    let resid = Field::new("resid".into(), DataType::Float64); // target - prediction
+    let v: Vec<Field> = vec![pred, resid];
+    Ok(Field::new("expanding".into(), DataType::Struct(v)))
+}
+
+fn recursive_inference_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    // Aligned with coeffs
+    let stderr = Field::new(
+        "std_err".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let t = Field::new("t".into(), DataType::List(Box::new(DataType::Float64)));
+    let pred = Field::new("prediction".into(), DataType::Float64);
+    let v: Vec<Field> = vec![coeffs, stderr, t, pred];
+    Ok(Field::new("".into(), DataType::Struct(v)))
+}
+
+fn predict_path_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        "predictions".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+fn grouped_predict_output(fields: &[Field]) -> PolarsResult<Field> {
+    let group = Field::new("group".into(), fields[0].dtype().clone());
+    let prediction = Field::new("prediction".into(), DataType::Float64);
+    let weight = Field::new("weight".into(), DataType::Float64); // total weight of the group
+    let v: Vec<Field> = vec![group, prediction, weight];
+    Ok(Field::new("grouped_prediction".into(), DataType::Struct(v)))
+}
+
+fn coeff_singular_values_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let singular_values = Field::new(
+        "singular_values".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let v: Vec<Field> = vec![coeffs, singular_values];
+    Ok(Field::new("".into(), DataType::Struct(v)))
+}
+
+fn profile_output(_: &[Field]) -> PolarsResult<Field> {
+    let gram_time = Field::new("gram_time".into(), DataType::Float64); // seconds on XtX and XtY
+    let solve_time = Field::new("solve_time".into(), DataType::Float64); // seconds on solving
+    let n_iter = Field::new("n_iter".into(), DataType::UInt32);
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let solver = Field::new("solver".into(), DataType::String);
+    let rank = Field::new("rank".into(), DataType::UInt32); // numerical rank of the design
+    let cond = Field::new("cond".into(), DataType::Float64); // estimated condition number
+    let ill_conditioned = Field::new("ill_conditioned".into(), DataType::Boolean);
+    let n_obs = Field::new("n_obs".into(), DataType::UInt32); // rows used, after dropping nulls
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let v: Vec<Field> = vec![
+        gram_time,
+        solve_time,
+        n_iter,
+        converged,
+        solver,
+        rank,
+        cond,
+        ill_conditioned,
+        n_obs,
+        coeffs,
+    ];
+    Ok(Field::new("profile".into(), DataType::Struct(v)))
+}
+
+fn robust_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let scale = Field::new("scale".into(), DataType::Float64); // final residual scale estimate
+    let n_iter = Field::new("n_iter".into(), DataType::UInt32);
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let v: Vec<Field> = vec![coeffs, scale, n_iter, converged];
+    Ok(Field::new("robust".into(), DataType::Struct(v)))
+}
+
+fn mm_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let scale = Field::new("scale".into(), DataType::Float64); // S-estimate of the residual scale
+                                                               // Final weight of each row used in the fit
+    let weights = Field::new(
+        "weights".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let v: Vec<Field> = vec![coeffs, scale, weights, converged];
+    Ok(Field::new("mm".into(), DataType::Struct(v)))
+}
+
+fn ransac_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    // Whether each input row is an inlier. Rows dropped by the null policy are not.
+    let inliers = Field::new(
+        "inliers".into(),
+        DataType::List(Box::new(DataType::Boolean)),
+    );
+    let n_inliers = Field::new("n_inliers".into(), DataType::UInt32);
+    let v: Vec<Field> = vec![coeffs, inliers, n_inliers];
+    Ok(Field::new("ransac".into(), DataType::Struct(v)))
+}
+
+fn ar1_output(_: &[Field]) -> PolarsResult<Field> {
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let rho = Field::new("rho".into(), DataType::Float64); // AR(1) coefficient of the errors
+    let n_iter = Field::new("n_iter".into(), DataType::UInt32);
+    let converged = Field::new("converged".into(), DataType::Boolean);
+    let v: Vec<Field> = vec![coeffs, rho, n_iter, converged];
+    Ok(Field::new("ar1".into(), DataType::Struct(v)))
+}
+
+fn fixed_effects_output(_: &[Field]) -> PolarsResult<Field> {
+    let features = Field::new("features".into(), DataType::String);
+    let beta = Field::new("beta".into(), DataType::Float64);
+    let stderr = Field::new("std_err".into(), DataType::Float64); // dof adjusted by #groups
+    let t = Field::new("t".into(), DataType::Float64);
+    let p = Field::new("p>|t|".into(), DataType::Float64);
+    let within_r2 = Field::new("within_r2".into(), DataType::Float64); // r2 of the demeaned model
+    let n_groups = Field::new("n_groups".into(), DataType::UInt32);
+    let v: Vec<Field> = vec![features, beta, stderr, t, p, within_r2, n_groups];
+    Ok(Field::new("fixed_effects".into(), DataType::Struct(v)))
+}
+
+fn group_slopes_output(_: &[Field]) -> PolarsResult<Field> {
+    let group_a = Field::new("group_a".into(), DataType::String);
+    let group_b = Field::new("group_b".into(), DataType::String);
+    let diff = Field::new("diff".into(), DataType::Float64); // slope of group_b - slope of group_a
+    let stderr = Field::new("std_err".into(), DataType::Float64);
+    // Tukey-Kramer simultaneous confidence interval of diff
+    let lower = Field::new("lower".into(), DataType::Float64);
+    let upper = Field::new("upper".into(), DataType::Float64);
+    let p = Field::new("p_adj".into(), DataType::Float64);
+    let v: Vec<Field> = vec![group_a, group_b, diff, stderr, lower, upper, p];
+    Ok(Field::new("group_slopes".into(), DataType::Struct(v)))
+}
+
+fn vif_output(_: &[Field]) -> PolarsResult<Field> {
+    let feature = Field::new("feature".into(), DataType::String);
+    let vif = Field::new("vif".into(), DataType::Float64); // inf if perfectly collinear
+    let v: Vec<Field> = vec![feature, vif];
+    Ok(Field::new("vif".into(), DataType::Struct(v)))
+}
+
+fn relative_importance_output(_: &[Field]) -> PolarsResult<Field> {
+    let feature = Field::new("feature".into(), DataType::String);
+    let importance = Field::new("importance".into(), DataType::Float64); // sums to R2
+    let share = Field::new("share".into(), DataType::Float64); // importance / R2, sums to 1
+    let v: Vec<Field> = vec![feature, importance, share];
+    Ok(Field::new(
+        "relative_importance".into(),
+        DataType::Struct(v),
+    ))
+}
+
+fn best_subset_output(_: &[Field]) -> PolarsResult<Field> {
+    let criterion = Field::new("criterion".into(), DataType::String); // aic, bic or adj_r2
+    let features = Field::new(
+        "features".into(),
+        DataType::List(Box::new(DataType::String)),
+    );
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let value = Field::new("value".into(), DataType::Float64); // value of the criterion
+    let v: Vec<Field> = vec![criterion, features, coeffs, value];
+    Ok(Field::new("best_subset".into(), DataType::Struct(v)))
+}
+
+fn stepwise_output(_: &[Field]) -> PolarsResult<Field> {
+    // selected features, in the order they were added, and the bias
+    let features = Field::new(
+        "features".into(),
+        DataType::List(Box::new(DataType::String)),
+    );
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let value = Field::new("value".into(), DataType::Float64); // value of the criterion
+                                                               // candidates refused because their VIF was above max_vif
+    let rejected = Field::new(
+        "rejected".into(),
+        DataType::List(Box::new(DataType::String)),
+    );
+    let v: Vec<Field> = vec![features, coeffs, value, rejected];
+    Ok(Field::new("stepwise".into(), DataType::Struct(v)))
+}
+
+fn ridge_loocv_output(_: &[Field]) -> PolarsResult<Field> {
+    let best_lambda = Field::new("best_lambda".into(), DataType::Float64);
+    let lambdas = Field::new(
+        "lambdas".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    // LOO mean squared error for each lambda
+    let errors = Field::new(
+        "loo_mse".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    // Coefficients at the best lambda
+    let coeffs = Field::new("coeffs".into(), DataType::List(Box::new(DataType::Float64)));
+    let v: Vec<Field> = vec![best_lambda, lambdas, errors, coeffs];
+    Ok(Field::new("ridge_loocv".into(), DataType::Struct(v)))
+}
+
+fn lstsq_cv_output(_: &[Field]) -> PolarsResult<Field> {
+    let lambda_min = Field::new("lambda_min".into(), DataType::Float64);
+    let lambda_1se = Field::new("lambda_1se".into(), DataType::Float64);
+    let lambdas = Field::new(
+        "lambdas".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    // mean, std and standard error of the fold MSEs for each lambda
+    let cv_mse = Field::new("cv_mse".into(), DataType::List(Box::new(DataType::Float64)));
+    let cv_std = Field::new("cv_std".into(), DataType::List(Box::new(DataType::Float64)));
+    let cv_se = Field::new("cv_se".into(), DataType::List(Box::new(DataType::Float64)));
+    // fold ids and the test MSE of each fold at lambda_min
+    let folds = Field::new("folds".into(), DataType::List(Box::new(DataType::Int64)));
+    let fold_mse = Field::new(
+        "fold_mse".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let coeffs_min = Field::new(
+        "coeffs_min".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let coeffs_1se = Field::new(
+        "coeffs_1se".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    );
+    let v: Vec<Field> = vec![
+        lambda_min, lambda_1se, lambdas, cv_mse, cv_std, cv_se, folds, fold_mse, coeffs_min,
+        coeffs_1se,
+    ];
+    Ok(Field::new("lstsq_cv".into(), DataType::Struct(v)))
+}
+
+fn partial_regression_output(_: &[Field]) -> PolarsResult<Field> {
+    let beta = Field::new("beta".into(), DataType::Float64); // slope of the partialled residuals
+    let stderr = Field::new("std_err".into(), DataType::Float64);
+    let partial_corr = Field::new("partial_corr".into(), DataType::Float64);
+    let v: Vec<Field> = vec![beta, stderr, partial_corr];
+    Ok(Field::new("partial_regression".into(), DataType::Struct(v)))
+}
+
+fn acf_output(_: &[Field]) -> PolarsResult<Field> {
+    let acf = Field::new("acf".into(), DataType::List(Box::new(DataType::Float64))); // lags 1..=h
+    let bound = Field::new("bound".into(), DataType::Float64); // approx. 95% bound, 2 / sqrt(n)
+    let v: Vec<Field> = vec![acf, bound];
+    Ok(Field::new("residual_acf".into(), DataType::Struct(v)))
+}
+
+fn pacf_output(_: &[Field]) -> PolarsResult<Field> {
+    let pacf = Field::new("pacf".into(), DataType::List(Box::new(DataType::Float64))); // lags 1..=h
+    let bound = Field::new("bound".into(), DataType::Float64); // approx. 95% bound, 2 / sqrt(n)
+    let v: Vec<Field> = vec![pacf, bound];
+    Ok(Field::new("residual_pacf".into(), DataType::Struct(v)))
+}
+
+fn chow_test_output(_: &[Field]) -> PolarsResult<Field> {
+    let s = Field::new("statistic".into(), DataType::Float64);
+    let df1 = Field::new("df1".into(), DataType::UInt32); // number of coefficients
+    let df2 = Field::new("df2".into(), DataType::UInt32); // n - 2 * number of coefficients
+    let p = Field::new("pvalue".into(), DataType::Float64);
+    let v: Vec<Field> = vec![s, df1, df2, p];
+    Ok(Field::new("chow_test".into(), DataType::Struct(v)))
+}
+
+fn white_test_output(_: &[Field]) -> PolarsResult<Field> {
+    let s = Field::new("statistic".into(), DataType::Float64); // n * R^2 of the auxiliary regression
+    let dof = Field::new("dof".into(), DataType::UInt32);
+    let p = Field::new("pvalue".into(), DataType::Float64);
+    let v: Vec<Field> = vec![s, dof, p];
+    Ok(Field::new("white_test".into(), DataType::Struct(v)))
+}
+
+fn breusch_pagan_output(_: &[Field]) -> PolarsResult<Field> {
+    let s = Field::new("statistic".into(), DataType::Float64); // n * R^2 of the auxiliary regression
+    let dof = Field::new("dof".into(), DataType::UInt32);
+    let p = Field::new("pvalue".into(), DataType::Float64);
+    let v: Vec<Field> = vec![s, dof, p];
+    Ok(Field::new("breusch_pagan".into(), DataType::Struct(v)))
+}
+
+fn coeff_output(_: &[Field]) -> PolarsResult<Field> {
+    Ok(Field::new(
+        "coeffs".into(),
+        DataType::List(Box::new(DataType::Float64)),
+    ))
+}
+
+/// True if the target and all features are Float32. pl_lstsq and pl_lstsq_pred then run in f32 end
+/// to end. Weights don't count, because they are cast anyway.
+#[inline]
+fn all_f32<'a>(mut dtypes: impl Iterator<Item = &'a DataType>) -> bool {
+    dtypes.all(|dt| dt == &DataType::Float32)
+}
+
+fn lstsq_dtype(fields: &[Field], weighted: bool) -> DataType {
+    let data = if weighted { &fields[1..] } else { fields };
+    if !data.is_empty() && all_f32(data.iter().map(|f| f.dtype())) {
+        DataType::Float32
+    } else {
+        DataType::Float64
+    }
+}
+
+fn lstsq_coeff_output(fields: &[Field], kwargs: LstsqKwargs) -> PolarsResult<Field> {
+    Ok(Field::new(
+        "coeffs".into(),
+        DataType::List(Box::new(lstsq_dtype(fields, kwargs.weighted))),
+    ))
+}
+
+fn lasso_path_output(_: &[Field]) -> PolarsResult<Field> {
+    // One list of coefficients per lambda
+    Ok(Field::new(
+        "lasso_path".into(),
+        DataType::List(Box::new(DataType::List(Box::new(DataType::Float64)))),
+    ))
+}
+
+#[derive(PartialEq)]
+pub enum StandardError {
+    SE,
+    HC0,
+    HC1,
+    HC2,
+    HC3,
+    NeweyWest,
+}
+
+impl From<String> for StandardError {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "hc0" => Self::HC0,
+            "hc1" => Self::HC1,
+            "hc2" => Self::HC2,
+            "hc3" => Self::HC3,
+            "hac" => Self::NeweyWest,
+            _ => Self::SE,
+        }
+    }
+}
+
+impl Into<PlSmallStr> for StandardError {
+    fn into(self) -> PlSmallStr {
+        match self {
+            StandardError::SE => "std_err".into(),
+            StandardError::HC0 => "hc0_se".into(),
+            StandardError::HC1 => "hc1_se".into(),
+            StandardError::HC2 => "hc2_se".into(),
+            StandardError::HC3 => "hc3_se".into(),
+            StandardError::NeweyWest => "hac_se".into(),
+        }
+    }
+}
+
+/// The bias is always the last column in the underlying computations. For interop with tools that
+/// assume intercept-first ordering, this copies the coefficients and moves the bias to index 0.
+#[inline(always)]
+pub(crate) fn coeffs_bias_first<T: Copy>(coeffs: &[T], has_bias: bool, bias_first: bool) -> Vec<T> {
+    let mut v = coeffs.to_vec();
+    if has_bias && bias_first {
+        v.rotate_right(1);
+    }
+    v
+}
+
+/// Total sum of squares of y, weighted if w is given. If centered, this is around the (weighted) mean
+/// of y, which is what R2 is measured against with a bias. Without a bias, the model has no reason to
+/// fit the mean, so R2 is measured against 0, i.e. the uncentered sum of squares.
+pub(crate) fn total_sum_of_squares<T: RealField + Float>(
+    y: ColRef<T>,
+    w: Option<&[T]>,
+    centered: bool,
+) -> T {
+    let weight = |i: usize| w.map_or(T::one(), |w| w[i]);
+    let mean = if centered {
+        let (sum_wy, sum_w) = y
+            .iter()
+            .enumerate()
+            .fold((T::zero(), T::zero()), |acc, (i, v)| {
+                (acc.0 + weight(i) * *v, acc.1 + weight(i))
+            });
+        sum_wy / sum_w
+    } else {
+        T::zero()
+    };
+    y.iter().enumerate().fold(T::zero(), |acc, (i, v)| {
+        acc + weight(i) * (*v - mean).powi(2)
+    })
+}
+
+/// The overall F test that all coefficients but the bias are 0, from the total and residual sums of
+/// squares. The statistic is ((TSS - RSS) / k) / (RSS / dof), where k is the number of coefficients
+/// without the bias, and dof is the residual degrees of freedom. Returns (statistic, p value).
+pub(crate) fn overall_f_test<T: Float>(tss: T, rss: T, k: usize, dof: T) -> (T, T) {
+    let k_t = T::from(k).unwrap();
+    let f_stat = ((tss - rss) / k_t) / (rss / dof);
+    let f_pvalue = if k > 0 && f_stat.is_finite() {
+        let x = f_stat.max(T::zero()).to_f64().unwrap();
+        stats_utils::beta::fisher_snedecor_sf(x, k as f64, dof.to_f64().unwrap())
+            .map_or(T::nan(), |p| T::from(p).unwrap())
+    } else {
+        T::nan()
+    };
+    (f_stat, f_pvalue)
+}
+
+/// The per-row variances of the predictions of an OLS or Ridge fit (see faer_ridge_prediction_variance),
+/// for the prediction intervals and the standard errors of pl_lstsq_pred. None if neither is asked for.
+/// Errors for Lasso / Elastic Net and weighted fits.
+pub(crate) fn lstsq_prediction_variance<T: RealField + Float>(
+    x: MatRef<T>,
+    y: MatRef<T>,
+    kwargs: &LstsqKwargs,
+) -> PolarsResult<Option<PredictionVariance<T>>> {
+    if !(kwargs.pred_interval || kwargs.pred_se) {
+        return Ok(None);
+    }
+    if kwargs.weighted || kwargs.l1_reg > 0. {
+        return Err(PolarsError::ComputeError(
+            "Prediction intervals and standard errors are only supported for unweighted OLS and Ridge."
+                .into(),
+        ));
+    }
+    let l2_reg = T::from(kwargs.l2_reg).unwrap();
+    faer_ridge_prediction_variance(x, y, l2_reg, kwargs.unpenalized_bias())
+        .map(Some)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))
+}
+
+/// Prediction interval bounds pred -/+ t * sqrt(pred_var), where pred_var is the variance of the
+/// prediction error of a new observation (see lstsq_prediction_variance), and t is the 1 - alpha / 2
+/// quantile of the t distribution with the effective degrees of freedom. The bounds are clipped like
+/// the predictions.
+pub(crate) fn prediction_bounds<T: RealField + Float>(
+    pv: &PredictionVariance<T>,
+    pred: MatRef<T>,
+    kwargs: &LstsqKwargs,
+) -> (Vec<T>, Vec<T>) {
+    let dof = pv.dof.to_f64().unwrap();
+    let q = T::from(stats_utils::beta::student_t_ppf(
+        1.0 - kwargs.alpha / 2.0,
+        dof,
+    ))
+    .unwrap();
+    (0..pred.nrows())
+        .map(|i| {
+            let half_width = q * pv.pred_var[i].sqrt();
+            let p = *pred.get(i, 0);
+            (
+                clip_prediction(p - half_width, kwargs.clip),
+                clip_prediction(p + half_width, kwargs.clip),
+            )
+        })
+        .unzip()
+}
+
+/// The standard errors s * sqrt(h_i) of the fitted means, where s is the residual standard error and h_i
+/// is the leverage of row i (see lstsq_prediction_variance). Unlike the prediction intervals, they don't
+/// include the variance of a new observation, so they shrink to 0 as the number of rows grows.
+pub(crate) fn prediction_std_errors<T: RealField + Float>(pv: &PredictionVariance<T>) -> Vec<T> {
+    pv.fit_var.iter().map(|v| v.max(T::zero()).sqrt()).collect()
+}
+
+/// If has_offset, the column after the target in mat (target, offset, features, bias if any) is an
+/// offset, a known term of the linear predictor with coefficient 1. The offset is subtracted from the
+/// target and its column is dropped, so that the returned matrix is the usual design of
+/// series_to_mat_for_lstsq. The offset is returned so that it can be added back to the predictions.
+/// It is a column of the design, so nulls in it are handled by the null policy like the features.
+pub(crate) fn split_offset<T: Float + 'static>(
+    mat: Array2<T>,
+    has_offset: bool,
+) -> (Array2<T>, Option<Vec<T>>) {
+    if !has_offset {
+        return (mat, None);
+    }
+    let offset = mat.column(1).to_vec();
+    let out = Array2::from_shape_fn((mat.nrows(), mat.ncols() - 1).f(), |(i, j)| {
+        if j == 0 {
+            mat[[i, 0]] - mat[[i, 1]]
+        } else {
+            mat[[i, j + 1]]
+        }
+    });
+    (out, Some(offset))
+}
+
+/// Adds the offset (see split_offset), if any, to the predictions.
+pub(crate) fn add_offset<T: RealField + Float>(pred: &mut Mat<T>, offset: Option<&[T]>) {
+    if let Some(o) = offset {
+        pred.col_mut(0)
+            .iter_mut()
+            .zip(o.iter())
+            .for_each(|(p, oi)| *p = *p + *oi);
+    }
+}
+
+/// The residuals of the predictions, which include the offset, if any. y is the target with the
+/// offset subtracted (see split_offset).
+pub(crate) fn residuals_with_offset<T: RealField + Float>(
+    y: MatRef<T>,
+    pred: MatRef<T>,
+    offset: Option<&[T]>,
+) -> Mat<T> {
+    match offset {
+        Some(o) => Mat::from_fn(y.nrows(), 1, |i, _| *y.get(i, 0) + o[i] - *pred.get(i, 0)),
+        None => y - pred,
+    }
+}
+
+/// The weights of the rows that went into the design matrix, cast to dtype. `mask` is the one from
+/// series_to_mat_for_lstsq, so the weights of the rows skipped because of nulls are dropped too.
+pub(crate) fn masked_weights(
+    weights: &Series,
+    mask: &BooleanChunked,
+    nrows: usize,
+    dtype: &DataType,
+) -> PolarsResult<Series> {
+    let w = if weights.len() != nrows && mask.len() == weights.len() {
+        weights.filter(mask)?
+    } else {
+        weights.clone()
+    };
+    if w.len() != nrows {
+        return Err(PolarsError::ShapeMismatch(
+            "Length of weights and data in X must be the same.".into(),
+        ));
+    }
+    if w.has_nulls() {
+        return Err(PolarsError::ComputeError(
+            "Weights cannot have nulls.".into(),
+        ));
+    }
+    Ok(w.cast(dtype)?.rechunk())
+}
+
+/// The confidence interval columns of the report: `0.025` and `0.975`, the 95% bounds, which are kept
+/// for backward compatibility and are deprecated, and `ci_lower` and `ci_upper`, the bounds
+/// beta -/+ t_(1 - alpha / 2) * std_err, where t is the quantile of the t distribution with dof degrees
+/// of freedom.
+pub(crate) fn report_ci_series<T: Float>(
+    betas: &[T],
+    std_err: &[T],
+    dof: f64,
+    alpha: f64,
+) -> [Series; 4]
+where
+    Series: NamedFromOwned<Vec<T>>,
+{
+    let bounds = |lower_name: &str, upper_name: &str, q: f64| {
+        let t = T::from(crate::stats_utils::beta::student_t_ppf(q, dof)).unwrap();
+        let (lower, upper): (Vec<T>, Vec<T>) = betas
+            .iter()
+            .zip(std_err)
+            .map(|(b, se)| (*b - t * *se, *b + t * *se))
+            .unzip();
+        (
+            Series::from_vec(lower_name.into(), lower),
+            Series::from_vec(upper_name.into(), upper),
+        )
+    };
+    let (lower_95, upper_95) = bounds("0.025", "0.975", 0.975);
+    let (lower, upper) = bounds("ci_lower", "ci_upper", 1.0 - alpha / 2.0);
+    [lower_95, upper_95, lower, upper]
+}
+
+/// Puts the values of the rows that went into the design matrix back in place, and NaN for the rows
+/// that were skipped because of nulls.
+pub(crate) fn nan_for_skipped_rows<T: Float>(
+    name: &str,
+    values: &[T],
+    mask: &BooleanChunked,
+) -> Series
+where
+    Series: NamedFromOwned<Vec<T>>,
+{
+    let v = if (!mask).any() {
+        let mut values = values.iter();
+        mask.into_no_null_iter()
+            .map(|m| if m { *values.next().unwrap() } else { T::nan() })
+            .collect_vec()
+    } else {
+        values.to_vec()
+    };
+    Series::from_vec(name.into(), v)
+}
+
+/// A Series of length 1, with the values as its only list element.
+fn single_list_series(name: &str, values: &[f64]) -> Series {
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new(name.into(), 1, values.len(), DataType::Float64);
+    builder.append_slice(values);
+    builder.finish().into_series()
+}
+
+/// For each row of the input, the row of the design matrix it went into, or None if the row was
+/// dropped. The mask is the one returned by series_to_mat_for_lstsq, which has length 1 when no row
+/// is dropped.
+pub(crate) fn design_rows(mask: &BooleanChunked, height: usize) -> Vec<Option<usize>> {
+    if mask.len() == height {
+        let mut k = 0;
+        mask.into_no_null_iter()
+            .map(|keep| {
+                k += keep as usize;
+                keep.then_some(k - 1)
+            })
+            .collect()
+    } else {
+        (0..height).map(Some).collect()
+    }
+}
+
+/// In strict mode, an iterative solver that runs out of iterations is an error. Otherwise, the
+/// last iterate is used.
+#[inline(always)]
+pub(crate) fn check_converged(converged: bool, strict: bool) -> PolarsResult<()> {
+    if strict && !converged {
+        Err(PolarsError::ComputeError(
+            "The solver did not converge within `max_iter` iterations.".into(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// A note for reports if the design with ncols columns has numerical rank < ncols. None otherwise.
+pub(crate) fn rank_note(rank: usize, ncols: usize) -> Option<String> {
+    (rank < ncols).then(|| LinalgErrors::RankDeficient(rank, ncols).to_string())
+}
+
+/// Moves the last row of a report (the bias row) to the front.
+#[inline(always)]
+pub(crate) fn report_bias_first(report: Series) -> PolarsResult<Series> {
+    let n = report.len() as IdxSize;
+    if n == 0 {
+        return Ok(report);
+    }
+    let idx = IdxCa::from_vec("".into(), std::iter::once(n - 1).chain(0..n - 1).collect());
+    report.take(&idx)
+}
+
+// -----------------------------------------------------------------------------------------------------
+
+/// Clamps a prediction into [lower, upper]. NaN predictions are passed through unclamped.
+/// Clipping is applied to the final prediction, i.e. after the bias is added.
+#[inline(always)]
+pub(crate) fn clip_prediction<T: Float>(pred: T, clip: Option<(f64, f64)>) -> T {
+    match clip {
+        Some((lower, upper)) if !pred.is_nan() => pred
+            .max(T::from(lower).unwrap())
+            .min(T::from(upper).unwrap()),
+        _ => pred,
+    }
+}
+
+/// Clamps all predictions in place. See clip_prediction.
+#[inline(always)]
+pub(crate) fn clip_predictions<T: RealField + Float>(pred: &mut Mat<T>, clip: Option<(f64, f64)>) {
+    if clip.is_some() {
+        for j in 0..pred.ncols() {
+            for v in pred.col_mut(j).iter_mut() {
+                *v = clip_prediction(*v, clip);
+            }
+        }
+    }
+}
+
+/// Assembles the design for a linear regression. The returned matrix has the target as its first
+/// column, then the features in the given order, then a column of 1s if add_bias. If skip_null, rows
+/// with a null in the target or in any feature are dropped. Otherwise, nulls raise an error. The mask
+/// is true for the rows that are kept. If no row is dropped, the mask may have length 1.
+pub fn build_design(
+    target: &Series,
+    features: &[Series],
+    add_bias: bool,
+    skip_null: bool,
+) -> PolarsResult<(Array2<f64>, BooleanChunked)> {
+    let null_policy = if skip_null {
+        NullPolicy::SKIP
+    } else {
+        NullPolicy::RAISE
+    };
+    build_design_with_null_policy(target, features, add_bias, null_policy)
+}
+
+/// Expressions get the target at index 0 of inputs, followed by the features.
+#[inline(always)]
+pub(crate) fn series_to_mat_for_lstsq(
+    inputs: &[Series],
+    has_bias: bool,
+    null_policy: NullPolicy<f64>,
+) -> PolarsResult<(Array2<f64>, BooleanChunked)> {
+    build_design_with_null_policy(&inputs[0], &inputs[1..], has_bias, null_policy)
+}
+
+/// Replaces the non-finite values (NaN and +/-inf) in the float columns of inputs[offset..] by nulls
+/// if skip, so that their rows are dropped like the rows with nulls. Otherwise, non-finite values are
+/// an error, since they would silently turn XtX into garbage. inputs[..offset] are kept as is.
+pub(crate) fn nonfinite_as_null(
+    inputs: &[Series],
+    offset: usize,
+    skip: bool,
+) -> PolarsResult<Vec<Series>> {
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, s)| match s.dtype() {
+            DataType::Float64 if i >= offset => finite_or_null(s.f64()?, skip),
+            DataType::Float32 if i >= offset => finite_or_null(s.f32()?, skip),
+            _ => Ok(s.clone()),
+        })
+        .collect()
+}
+
+/// The inputs of pl_lstsq and pl_lstsq_pred, after nonfinite_as_null if drop_nonfinite.
+pub(crate) fn lstsq_inputs<'a>(
+    inputs: &'a [Series],
+    kwargs: &LstsqKwargs,
+) -> PolarsResult<Cow<'a, [Series]>> {
+    if kwargs.drop_nonfinite {
+        let skip = matches!(
+            NullPolicy::<f64>::try_from(kwargs.null_policy.clone()),
+            Ok(NullPolicy::SKIP)
+        );
+        Ok(Cow::Owned(nonfinite_as_null(
+            inputs,
+            kwargs.weighted as usize,
+            skip,
+        )?))
+    } else {
+        Ok(Cow::Borrowed(inputs))
+    }
+}
+
+fn finite_or_null<T>(ca: &ChunkedArray<T>, skip: bool) -> PolarsResult<Series>
+where
+    T: PolarsFloatType,
+    T::Native: Float,
+    ChunkedArray<T>: IntoSeries,
+{
+    if !ca.into_iter().any(|v| v.is_some_and(|x| !x.is_finite())) {
+        Ok(ca.clone().into_series())
+    } else if skip {
+        let out: ChunkedArray<T> = ca
+            .into_iter()
+            .map(|v| v.filter(|x| x.is_finite()))
+            .collect();
+        Ok(out.with_name(ca.name().clone()).into_series())
+    } else {
+        Err(PolarsError::ComputeError(
+            format!(
+                "Non-finite values (NaN or inf) found in column `{}`. Use the skip null policy to drop their rows.",
+                ca.name()
+            )
+            .into(),
+        ))
+    }
+}
+
+/// Same as build_design, but nulls are handled by the given null policy.
+fn build_design_with_null_policy(
+    target: &Series,
+    features: &[Series],
+    has_bias: bool,
+    null_policy: NullPolicy<f64>,
+) -> PolarsResult<(Array2<f64>, BooleanChunked)> {
+    let n_features = features.len();
+    let height = target.len();
+    if height == 0 {
+        return Err(PolarsError::ComputeError("Empty data".into()));
+    }
+    if features.iter().any(|s| s.len() != height) {
+        return Err(PolarsError::ShapeMismatch(
+            "Input columns must have the same length.".into(),
+        ));
+    }
+
+    let y_has_null = target.has_nulls();
+    let has_null = features.iter().any(|s| s.has_nulls()) | y_has_null;
+
+    // In mask, true means not null. The fill value is only applied to non-target columns.
+    // `keep` are the rows that go into the matrix. None means all rows.
+    let (mask, keep, fill) = if has_null {
+        match null_policy {
+            // Like ignore, skip_window takes the raw data. The actual skip is done in the underlying function in linalg.
+            NullPolicy::IGNORE | NullPolicy::SKIP_WINDOW => {
+                // false, because it has nulls
+                Ok((BooleanChunked::from_slice("".into(), &[false]), None, None))
+            }
+            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
+            NullPolicy::SKIP => {
+                let init_mask = target.is_not_null();
+                let mask = features
+                    .iter()
+                    .fold(init_mask, |acc, s| acc & s.is_not_null());
+                Ok((mask.clone(), Some(mask), None))
+            }
+            NullPolicy::FILL(x) => {
+                if y_has_null {
+                    let init_mask = target.is_not_null();
+                    Ok((init_mask.clone(), Some(init_mask), Some(x)))
+                } else {
+                    // all filled, no nulls
+                    Ok((
+                        BooleanChunked::from_slice("".into(), &[true]),
+                        None,
+                        Some(x),
+                    ))
+                }
+            }
+            NullPolicy::FILL_WINDOW(x) => {
+                if y_has_null {
+                    // Unlike fill, this doesn't drop y's nulls
+                    Ok((
+                        BooleanChunked::from_slice("".into(), &[false]),
+                        None,
+                        Some(x),
+                    ))
+                } else {
+                    // all filled, no nulls
+                    Ok((
+                        BooleanChunked::from_slice("".into(), &[true]),
+                        None,
+                        Some(x),
+                    ))
+                }
+            }
+        }
+    } else {
+        // In this case, the (!mask).any() is never true, which means there is no null.
+        Ok((BooleanChunked::from_slice("".into(), &[true]), None, None))
+    }?;
+
+    // The masks here have no nulls, so only the values bitmap matters.
+    let keep_idx: Option<Vec<usize>> = keep.map(|m| {
+        m.downcast_iter()
+            .flat_map(|arr| arr.values().iter())
+            .enumerate()
+            .filter_map(|(i, b)| b.then_some(i))
+            .collect()
+    });
+    let nrows = keep_idx.as_ref().map(|idx| idx.len()).unwrap_or(height);
+    if nrows == 0 {
+        return Err(PolarsError::ComputeError(
+            "No complete rows left after dropping rows with nulls.".into(),
+        ));
+    }
+    if nrows < n_features {
+        return Err(PolarsError::ComputeError(
+            "#Data < #features. No conclusive result.".into(),
+        ));
+    }
+
+    // Write each column directly into a preallocated column-major matrix, with the bias
+    // column (if any) written in place as the last column. Nulls become NaN unless filled.
+    let ncols = 1 + n_features + has_bias as usize;
+    let mut mat = Array2::<f64>::uninit((nrows, ncols).f());
+    for (j, s) in std::iter::once(target).chain(features.iter()).enumerate() {
+        let s = s.cast(&DataType::Float64)?.rechunk();
+        let ca = s.f64()?;
+        // Safe. Height > 0 and it is rechunked, so there is exactly one array.
+        let arr = ca.downcast_iter().next().unwrap();
+        let values = arr.values().as_slice();
+        let validity = arr.validity().filter(|v| v.unset_bits() > 0);
+        let null_value = if j == 0 {
+            f64::NAN
+        } else {
+            fill.unwrap_or(f64::NAN)
+        };
+        let mut col = mat.column_mut(j);
+        // Safe. A column of a Fortran ordered array is contiguous.
+        let col = col.as_slice_mut().unwrap();
+        match (&keep_idx, validity) {
+            (None, None) => {
+                for (v, x) in col.iter_mut().zip(values.iter()) {
+                    v.write(*x);
+                }
+            }
+            (None, Some(bitmap)) => {
+                for (v, x) in col.iter_mut().zip(values.iter()) {
+                    v.write(*x);
+                }
+                for (v, is_valid) in col.iter_mut().zip(bitmap.iter()) {
+                    if !is_valid {
+                        v.write(null_value);
+                    }
+                }
+            }
+            (Some(idx), None) => {
+                for (v, i) in col.iter_mut().zip(idx.iter()) {
+                    v.write(values[*i]);
+                }
+            }
+            (Some(idx), Some(bitmap)) => {
+                for (v, i) in col.iter_mut().zip(idx.iter()) {
+                    v.write(if bitmap.get_bit(*i) {
+                        values[*i]
+                    } else {
+                        null_value
+                    });
+                }
+            }
+        }
+    }
+    if has_bias {
+        for v in mat.column_mut(ncols - 1).as_slice_mut().unwrap() {
+            v.write(1f64);
+        }
+    }
+    // Safe. Every column has been written with exactly nrows values above.
+    let mat = unsafe { mat.assume_init() };
+    Ok((mat, mask))
+}
+
+fn series_to_mat_for_multi_lstsq(
+    inputs: &[Series],
+    last_target_idx: usize,
+    has_bias: bool,
+    null_policy: NullPolicy<f64>,
+) -> PolarsResult<Array2<f64>> {
+    let y_has_null = inputs[..last_target_idx]
+        .iter()
+        .fold(false, |acc, s| s.has_nulls() | acc);
+    let has_null = inputs[last_target_idx..].iter().any(|s| s.has_nulls()) | y_has_null;
+
+    let mut df = if has_null {
+        match null_policy {
+            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
+
+            NullPolicy::FILL(x) => {
+                let df = DataFrame::new(inputs.iter().map(|s| s.clone().into_column()).collect())?;
+                if y_has_null {
+                    // This is because for predictions, it becomes too complicated when different targets have nulls.
+                    // There will be too many masks we need to keep track of.
+                    // This can be dealt with but I won't implement it for now.
+                    Err(PolarsError::ComputeError(
+                        "Filling null doesn't work for multi-target lstsq when there are nulls in any of the targets.".into(),
+                    ))
+                } else {
+                    let y_names = inputs[..last_target_idx]
+                        .iter()
+                        .map(|s| s.name().clone())
+                        .collect::<Vec<_>>();
+
+                    df.lazy()
+                        .with_columns([pl::col("*")
+                            .exclude(y_names)
+                            .cast(DataType::Float64)
+                            .fill_null(lit(x))])
+                        .collect()
+                }
+            }
+            _ => Err(PolarsError::ComputeError(
+                "The null policy is not supported by multi-target linear regression.".into(),
+            )),
+        }
+    } else {
+        DataFrame::new(inputs.iter().map(|s| s.clone().into_column()).collect())
+    }?;
+
+    if has_bias {
+        df = df.lazy().with_column(lit(1f64)).collect()?;
+    }
+
+    if df.is_empty() {
+        Err(PolarsError::ComputeError("Empty data".into()))
+    } else {
+        df.to_ndarray::<Float64Type>(IndexOrder::Fortran)
+    }
+}
+
+// -----------------------------------------------------------------------------------------------------
+
+#[polars_expr(output_type_func_with_kwargs=lstsq_coeff_output)]
+fn pl_lstsq(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let weighted = kwargs.weighted;
+    let binding = lstsq_inputs(inputs, &kwargs)?;
+    let inputs = binding.as_ref();
+    let data_for_matrix = if weighted { &inputs[1..] } else { inputs };
+    if all_f32(data_for_matrix.iter().map(|s| s.dtype())) {
+        return lstsq_f32(inputs, kwargs);
+    }
+
+    let has_bias = kwargs.bias;
+    let unpenalized_bias = kwargs.unpenalized_bias();
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let solver = kwargs.solver.as_str().into();
+
+    match series_to_mat_for_lstsq(data_for_matrix, has_bias, null_policy) {
+        Ok((mat, mask)) => {
+            let (mat, _) = split_offset(mat, kwargs.has_offset);
+            // Solving Least Square
+            let x = mat.slice(s![.., 1..]).into_faer();
+            let y = mat.slice(s![.., 0..1]).into_faer();
+            let binding = if weighted {
+                Some(masked_weights(
+                    &inputs[0],
+                    &mask,
+                    mat.nrows(),
+                    &DataType::Float64,
+                )?)
+            } else {
+                None
+            };
+            let weights = binding
+                .as_ref()
+                .map(|w| w.f64().unwrap().cont_slice().unwrap());
+            let method = LRMethods::from((kwargs.l1_reg, kwargs.l2_reg));
+            // Ridge takes the weights by scaling the rows by sqrt(w). Lasso and Elastic Net take them
+            // directly, since their loss is normalized by the total weight.
+            let scaled = match (weights, method) {
+                (Some(w), LRMethods::L2) => Some(faer_sqrt_weighted_rows(x, y, w)),
+                _ => None,
+            };
+            let (xs, ys) = match &scaled {
+                Some((xw, yw)) => (xw.as_ref(), yw.as_ref()),
+                None => (x, y),
+            };
+            let coeffs = match (weights, method) {
+                (Some(w), LRMethods::Normal) => faer_weighted_lstsq(x, y, w, solver),
+                (_, LRMethods::Normal | LRMethods::L2) => {
+                    faer_solve_lstsq(xs, ys, kwargs.l2_reg, unpenalized_bias, solver)
+                }
+                (_, LRMethods::L1 | LRMethods::ElasticNet) => {
+                    let (coeffs, _, converged) = faer_elastic_net(
+                        x,
+                        y,
+                        weights,
+                        kwargs.l1_reg,
+                        kwargs.l2_reg,
+                        kwargs.penalty_factor.as_deref(),
+                        unpenalized_bias,
+                        kwargs.tol,
+                        kwargs.max_iter,
+                        StopCriterion::from(kwargs.stop_criterion.as_str()),
+                        LassoAlgorithm::from(kwargs.algorithm.as_str()),
+                    );
+                    check_converged(converged, kwargs.strict)?;
+                    coeffs
+                }
+            };
+            let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+                ListPrimitiveChunkedBuilder::new(
+                    "coeffs".into(),
+                    1,
+                    coeffs.nrows(),
+                    DataType::Float64,
+                );
+
+            builder.append_slice(&coeffs_bias_first(
+                coeffs.col_as_slice(0),
+                has_bias,
+                kwargs.bias_first,
+            ));
+            let out = builder.finish();
+            Ok(out.into_series())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Adaptive Lasso. See faer_adaptive_lasso.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_adaptive_lasso(inputs: &[Series], kwargs: AdaptiveLassoKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let (coeffs, _, converged) = faer_adaptive_lasso(
+        x,
+        y,
+        kwargs.l1_reg,
+        kwargs.gamma,
+        kwargs.l2_init,
+        has_bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    check_converged(converged, kwargs.strict)?;
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        coeffs.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    Ok(builder.finish().into_series())
+}
+
+/// Generalized (Tikhonov) ridge with a full penalty matrix. See faer_generalized_ridge.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_generalized_ridge(inputs: &[Series], kwargs: GeneralizedRidgeKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let ncols = kwargs.gamma_ncols;
+    if ncols == 0 || kwargs.gamma.len() % ncols != 0 {
+        return Err(PolarsError::ComputeError(
+            "The flattened penalty matrix doesn't have a whole number of rows.".into(),
+        ));
+    }
+    let gamma = MatRef::from_row_major_slice(&kwargs.gamma, kwargs.gamma.len() / ncols, ncols);
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_generalized_ridge(x, y, gamma, has_bias, solver)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        coeffs.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    Ok(builder.finish().into_series())
+}
+
+/// The Lasso / Elastic Net path over a grid of lambdas. See faer_lasso_path. The output has a single row,
+/// a list with one list of coefficients per lambda, in the order of the lambdas.
+#[polars_expr(output_type_func=lasso_path_output)]
+fn pl_lasso_path(inputs: &[Series], kwargs: LassoPathKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let (path, converged) = faer_lasso_path(
+        x,
+        y,
+        &kwargs.lambdas,
+        kwargs.l2_reg,
+        has_bias,
+        kwargs.tol,
+        kwargs.max_iter,
+        StopCriterion::from(kwargs.stop_criterion.as_str()),
+    )
+    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    check_converged(converged, kwargs.strict)?;
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "".into(),
+        path.ncols(),
+        path.nrows() * path.ncols(),
+        DataType::Float64,
+    );
+    for j in 0..path.ncols() {
+        builder.append_slice(&coeffs_bias_first(
+            path.col_as_slice(j),
+            has_bias,
+            kwargs.bias_first,
+        ));
+    }
+    let out = Series::new("lasso_path".into(), [builder.finish().into_series()]);
+    Ok(out)
+}
+
+#[polars_expr(output_type_func=profile_output)]
+fn pl_lstsq_profile(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let unpenalized_bias = kwargs.unpenalized_bias();
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let (coeffs, profile) = faer_lstsq_profile(
+        x,
+        y,
+        kwargs.l1_reg,
+        kwargs.l2_reg,
+        unpenalized_bias,
+        solver,
+        kwargs.tol,
+        kwargs.max_iter,
+        StopCriterion::from(kwargs.stop_criterion.as_str()),
+    );
+    // Not part of the timed fit
+    let (rank, cond) = faer_design_rank_cond(x, solver);
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        coeffs.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+
+    let gram_time = Series::from_vec("gram_time".into(), vec![profile.gram_time]);
+    let solve_time = Series::from_vec("solve_time".into(), vec![profile.solve_time]);
+    let n_iter = Series::from_vec("n_iter".into(), vec![profile.n_iter as u32]);
+    let converged = Series::new("converged".into(), [profile.converged]);
+    let solver = Series::new("solver".into(), [profile.solver]);
+    let rank = Series::from_vec("rank".into(), vec![rank as u32]);
+    let ill_conditioned = Series::new("ill_conditioned".into(), [cond > kwargs.warn_collinearity]);
+    let cond = Series::from_vec("cond".into(), vec![cond]);
+    let n_obs = Series::from_vec("n_obs".into(), vec![x.nrows() as u32]);
+    let out = StructChunked::from_series(
+        "profile".into(),
+        1,
+        [
+            &gram_time,
+            &solve_time,
+            &n_iter,
+            &converged,
+            &solver,
+            &rank,
+            &cond,
+            &ill_conditioned,
+            &n_obs,
+            &coeffs,
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+#[polars_expr(output_type_func=robust_output)]
+fn pl_robust_lstsq(inputs: &[Series], kwargs: RobustLstsqKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+    let loss = RobustLoss::from(kwargs.loss.as_str());
+    let c = kwargs.c.unwrap_or(loss.default_c());
+    if c <= 0. {
+        return Err(PolarsError::ComputeError(
+            "Tuning constant `c` must be positive.".into(),
+        ));
+    }
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let fit = faer_robust_lstsq(
+        x,
+        y,
+        loss,
+        c,
+        kwargs.estimate_scale,
+        solver,
+        kwargs.tol,
+        kwargs.max_iter,
+        StopCriterion::from(kwargs.stop_criterion.as_str()),
+    );
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "coeffs".into(),
+        1,
+        fit.coefficients.nrows(),
+        DataType::Float64,
+    );
+    builder.append_slice(&coeffs_bias_first(
+        fit.coefficients.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+    let scale = Series::from_vec("scale".into(), vec![fit.scale]);
+    let n_iter = Series::from_vec("n_iter".into(), vec![fit.n_iter as u32]);
+    let converged = Series::new("converged".into(), [fit.converged]);
+    let out = StructChunked::from_series(
+        "robust".into(),
+        1,
+        [&coeffs, &scale, &n_iter, &converged].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Huber regression with the scale re-estimated in each iteration. See HuberRegression.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_huber_reg(inputs: &[Series], kwargs: HuberRegKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let mut huber = HuberRegression::new(
+        kwargs.delta,
+        kwargs.solver.as_str(),
+        has_bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    huber
+        .fit(x, y)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "coeffs".into(),
+        1,
+        huber.coefficients.nrows(),
+        DataType::Float64,
+    );
+    builder.append_slice(&coeffs_bias_first(
+        huber.coefficients.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    Ok(builder.finish().into_series())
+}
+
+/// Quantile regression, which minimizes the pinball loss. See faer_quantile_lstsq.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_quantile_reg(inputs: &[Series], kwargs: QuantileRegKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let mut qr = QuantileRegression::new(
+        kwargs.tau,
+        kwargs.solver.as_str(),
+        has_bias,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+    qr.fit(x, y)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let coeffs = qr.fitted_values();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        qr.coefficients.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    Ok(builder.finish().into_series())
+}
+
+/// MM-estimation of a linear regression. See faer_mm_lstsq.
+#[polars_expr(output_type_func=mm_output)]
+fn pl_mm_regression(inputs: &[Series], kwargs: MMLstsqKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+    if kwargs.c <= 0. || kwargs.n_subsamples == 0 {
+        return Err(PolarsError::ComputeError(
+            "Tuning constant `c` and the number of subsamples must be positive.".into(),
+        ));
+    }
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= x.ncols() {
+        return Err(PolarsError::ComputeError(
+            "#Data <= #features. No conclusive result.".into(),
+        ));
+    }
+    let fit = faer_mm_lstsq(
+        x,
+        y,
+        kwargs.c,
+        kwargs.n_subsamples,
+        kwargs.seed,
+        solver,
+        kwargs.tol,
+        kwargs.max_iter,
+        StopCriterion::from(kwargs.stop_criterion.as_str()),
+    );
+
+    let coeffs = single_list_series(
+        "coeffs",
+        &coeffs_bias_first(
+            fit.coefficients.col_as_slice(0),
+            has_bias,
+            kwargs.bias_first,
+        ),
+    );
+    let scale = Series::from_vec("scale".into(), vec![fit.scale]);
+    let weights = single_list_series("weights", &fit.weights);
+    let converged = Series::new("converged".into(), [fit.converged]);
+    let out = StructChunked::from_series(
+        "mm".into(),
+        1,
+        [&coeffs, &scale, &weights, &converged].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// RANSAC consensus fit. See ransac_fit.
+#[polars_expr(output_type_func=ransac_output)]
+fn pl_ransac_coeffs(inputs: &[Series], kwargs: RansacKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    // LR adds the bias itself, so the design matrix doesn't get a bias column
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, false, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let fit = ransac_fit(
+        x,
+        y,
+        has_bias,
+        kwargs.solver.as_str(),
+        kwargs.n_trials,
+        kwargs.min_samples,
+        kwargs.residual_threshold,
+        kwargs.seed,
+    )
+    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let coeffs = single_list_series(
+        "coeffs",
+        &coeffs_bias_first(
+            fit.lr.coefficients.col_as_slice(0),
+            has_bias,
+            kwargs.bias_first,
+        ),
+    );
+    let inliers = design_rows(&mask, inputs[0].len())
+        .into_iter()
+        .map(|row| row.is_some_and(|i| fit.inliers[i]))
+        .collect::<BooleanChunked>()
+        .into_series();
+    let inliers = Series::new("inliers".into(), [inliers]);
+    let n_inliers = Series::from_vec("n_inliers".into(), vec![fit.n_inliers as u32]);
+    let out = StructChunked::from_series(
+        "ransac".into(),
+        1,
+        [&coeffs, &inliers, &n_inliers].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Theil-Sen slope and intercept of a simple regression. Inputs are [target, x]. See theil_sen.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_theil_sen(inputs: &[Series], kwargs: TheilSenKwargs) -> PolarsResult<Series> {
+    if inputs.len() != 2 {
+        return Err(PolarsError::ComputeError(
+            "Theil-Sen only supports a single feature.".into(),
+        ));
+    }
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, false, null_policy)?;
+    let y = mat.column(0).to_vec();
+    let x = mat.column(1).to_vec();
+    let (slope, intercept) = theil_sen(&x, &y, kwargs.max_pairs, kwargs.seed)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    Ok(single_list_series(
+        "coeffs",
+        &coeffs_bias_first(&[slope, intercept], true, kwargs.bias_first),
+    ))
+}
+
+/// Cochrane-Orcutt / Prais-Winsten regression for AR(1) errors. Rows are assumed to be in time order.
+#[polars_expr(output_type_func=ar1_output)]
+fn pl_cochrane_orcutt(inputs: &[Series], kwargs: Ar1LstsqKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= x.ncols() {
+        return Err(PolarsError::ComputeError(
+            "#Data <= #features. Not enough data to estimate rho.".into(),
+        ));
+    }
+    let fit = faer_cochrane_orcutt(
+        x,
+        y,
+        kwargs.prais_winsten,
+        solver,
+        kwargs.tol,
+        kwargs.max_iter,
+    );
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "coeffs".into(),
+        1,
+        fit.coefficients.nrows(),
+        DataType::Float64,
+    );
+    builder.append_slice(&coeffs_bias_first(
+        fit.coefficients.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+    let rho = Series::from_vec("rho".into(), vec![fit.rho]);
+    let n_iter = Series::from_vec("n_iter".into(), vec![fit.n_iter as u32]);
+    let converged = Series::new("converged".into(), [fit.converged]);
+    let out = StructChunked::from_series(
+        "ar1".into(),
+        1,
+        [&coeffs, &rho, &n_iter, &converged].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Entity fixed effects regression by the within transform. Inputs are [group, target, features...].
+/// The target and the features are demeaned within each group, and OLS (without bias) is run on the
+/// demeaned data. This gives the same coefficients as adding one dummy per group, without building
+/// the dummies. Since the group means are estimated, the residual degrees of freedom is
+/// n - #groups - #features.
+#[polars_expr(output_type_func=fixed_effects_output)]
+fn pl_fixed_effects_lstsq(inputs: &[Series], kwargs: FixedEffectsKwargs) -> PolarsResult<Series> {
+    let null_policy: NullPolicy<f64> = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let mut name_builder = StringChunkedBuilder::new("features".into(), inputs.len().abs_diff(2));
+    for s in inputs[2..].iter().map(|s| s.name()) {
+        name_builder.append_value(s);
+    }
+
+    // Use a df for the group means. Column "0" is the group and column "1" is the target.
+    let v = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, s)| Column::new(i.to_string().into(), s))
+        .collect_vec();
+    let mut df = DataFrame::new(v)?.lazy();
+    // Nulls must be dealt with before the demeaning, so that the group means are computed on the
+    // rows that are actually used.
+    if inputs.iter().any(|s| s.has_nulls()) {
+        df = match null_policy {
+            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
+            NullPolicy::SKIP => Ok(df.drop_nulls(None)),
+            NullPolicy::FILL(x) => Ok(df
+                .with_columns(
+                    (2..inputs.len())
+                        .map(|i| col(i.to_string()).fill_null(lit(x)))
+                        .collect_vec(),
+                )
+                .drop_nulls(Some(vec![col("0"), col("1")]))),
+            _ => Err(PolarsError::ComputeError(
+                "Fixed effects regression only supports 'raise', 'skip' or a fill value as null policy."
+                    .into(),
+            )),
+        }?;
+    }
+    let mut within = Vec::with_capacity(inputs.len());
+    within.push(col("0"));
+    for i in 1..inputs.len() {
+        let c = col(i.to_string()).cast(DataType::Float64);
+        within.push(c.clone() - c.mean().over([col("0")]));
+    }
+    let df = df.select(within).collect()?;
+    let n_groups = df.column("0")?.n_unique()?;
+    let demeaned = df.get_columns()[1..]
+        .iter()
+        .map(|c| c.as_materialized_series().clone())
+        .collect_vec();
+
+    let (mat, _) = build_design(&demeaned[0], &demeaned[1..], false, false)?;
+    let nrows = mat.nrows();
+    let ncols = mat.ncols() - 1;
+    let dof = nrows as f64 - n_groups as f64 - ncols as f64;
+    if dof <= 0. {
+        return Err(PolarsError::ComputeError(
+            "#Data <= #groups + #features. No conclusive result.".into(),
+        ));
+    }
+
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_solve_lstsq(x, y, 0., false, solver);
+    let betas = coeffs.col_as_slice(0);
+    let xtx_inv = (x.transpose() * x).col_piv_qr().inverse();
+
+    let res = y - x * &coeffs;
+    let ssr = res.col(0).squared_norm_l2();
+    let within_r2 = 1.0 - ssr / y.col(0).squared_norm_l2();
+    let sigma2 = ssr / dof;
+
+    let std_err = (0..ncols)
+        .map(|i| (sigma2 * xtx_inv.get(i, i)).sqrt())
+        .collect_vec();
+    let t_values = betas
+        .iter()
+        .zip(std_err.iter())
+        .map(|(b, se)| b / se)
+        .collect_vec();
+    let p_values = t_values
+        .iter()
+        .map(
+            |t| match crate::stats_utils::beta::student_t_sf(t.abs(), dof) {
+                Ok(p) => 2.0 * p,
+                Err(_) => f64::NAN,
+            },
+        )
+        .collect_vec();
+
+    let names_series = name_builder.finish().into_series();
+    let coeffs_series = Float64Chunked::from_slice("beta".into(), betas).into_series();
+    let stderr_series = Float64Chunked::from_vec("std_err".into(), std_err).into_series();
+    let t_series = Float64Chunked::from_vec("t".into(), t_values).into_series();
+    let p_series = Float64Chunked::from_vec("p>|t|".into(), p_values).into_series();
+    let r2_series = Float64Chunked::from_vec("within_r2".into(), vec![within_r2]).into_series();
+    let n_groups_series = Series::from_vec("n_groups".into(), vec![n_groups as u32]);
+    let out = StructChunked::from_series(
+        "fixed_effects".into(),
+        names_series.len(),
+        [
+            &names_series,
+            &coeffs_series,
+            &stderr_series,
+            &t_series,
+            &p_series,
+            &r2_series,
+            &n_groups_series,
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Pairwise comparisons of group slopes. Inputs are [group, target, x]. Each group gets its own bias
+/// and slope, which is the same as regressing on the group dummies and their interactions with x, and
+/// the error variance is pooled over the groups, with n - 2 * #groups degrees of freedom. Every pair
+/// of slopes is compared by the Tukey-Kramer method, so the p-values and the confidence intervals hold
+/// simultaneously for all pairs. Groups are ordered by their values.
+#[polars_expr(output_type_func=group_slopes_output)]
+fn pl_group_slopes(inputs: &[Series], kwargs: GroupSlopesKwargs) -> PolarsResult<Series> {
+    let null_policy: NullPolicy<f64> = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    if !(kwargs.confidence > 0. && kwargs.confidence < 1.) {
+        return Err(PolarsError::ComputeError(
+            "Confidence must be in (0, 1).".into(),
+        ));
+    }
+
+    let v = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, s)| Column::new(i.to_string().into(), s))
+        .collect_vec();
+    let mut df = DataFrame::new(v)?.lazy();
+    if inputs.iter().any(|s| s.has_nulls()) {
+        df = match null_policy {
+            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
+            NullPolicy::SKIP => Ok(df.drop_nulls(None)),
+            _ => Err(PolarsError::ComputeError(
+                "Group slopes only supports 'raise' or 'skip' as null policy.".into(),
+            )),
+        }?;
+    }
+    // Within group sums of squares and cross products, on the demeaned data
+    let x = col("2").cast(DataType::Float64);
+    let y = col("1").cast(DataType::Float64);
+    let xc = x.clone() - x.mean().over([col("0")]);
+    let yc = y.clone() - y.mean().over([col("0")]);
+    let sums = df
+        .select([col("0"), xc.alias("x"), yc.alias("y")])
+        .group_by([col("0")])
+        .agg([
+            len().alias("n"),
+            (col("x") * col("x")).sum().alias("sxx"),
+            (col("x") * col("y")).sum().alias("sxy"),
+            (col("y") * col("y")).sum().alias("syy"),
+        ])
+        .sort(["0"], Default::default())
+        .collect()?;
+
+    let groups = sums.column("0")?.cast(&DataType::String)?;
+    let groups = groups.str()?;
+    let n = sums.column("n")?.cast(&DataType::Float64)?;
+    let n = n.f64()?;
+    let sxx = sums.column("sxx")?.f64()?;
+    let sxy = sums.column("sxy")?.f64()?;
+    let syy = sums.column("syy")?.f64()?;
+
+    let n_groups = sums.height();
+    if n_groups < 2 {
+        return Err(PolarsError::ComputeError(
+            "At least 2 groups are needed to compare slopes.".into(),
+        ));
+    }
+    let mut slopes = Vec::with_capacity(n_groups);
+    let mut ssr = 0.;
+    let mut total = 0.;
+    for (((n_g, sxx_g), sxy_g), syy_g) in n
+        .into_no_null_iter()
+        .zip(sxx.into_no_null_iter())
+        .zip(sxy.into_no_null_iter())
+        .zip(syy.into_no_null_iter())
+    {
+        if n_g < 2. || sxx_g <= 0. {
+            return Err(PolarsError::ComputeError(
+                "Each group must have at least 2 distinct values of x to estimate its slope."
+                    .into(),
+            ));
+        }
+        let b = sxy_g / sxx_g;
+        slopes.push((b, sxx_g));
+        ssr += (syy_g - b * sxy_g).max(0.);
+        total += n_g;
+    }
+    let dof = total - 2. * n_groups as f64;
+    if dof <= 0. {
+        return Err(PolarsError::ComputeError(
+            "#Data <= 2 * #groups. No conclusive result.".into(),
+        ));
+    }
+    let sigma2 = ssr / dof;
+    let k = n_groups as f64;
+    let q_crit = stats_utils::tukey::qtukey(kwargs.confidence, k, dof) / f64::consts::SQRT_2;
+
+    let n_pairs = n_groups * (n_groups - 1) / 2;
+    let mut group_a = StringChunkedBuilder::new("group_a".into(), n_pairs);
+    let mut group_b = StringChunkedBuilder::new("group_b".into(), n_pairs);
+    let mut diffs = Vec::with_capacity(n_pairs);
+    let mut std_errs = Vec::with_capacity(n_pairs);
+    let mut lower = Vec::with_capacity(n_pairs);
+    let mut upper = Vec::with_capacity(n_pairs);
+    let mut p_values = Vec::with_capacity(n_pairs);
+    for (a, b) in (0..n_groups).tuple_combinations() {
+        let d = slopes[b].0 - slopes[a].0;
+        let se = (sigma2 * (slopes[a].1.recip() + slopes[b].1.recip())).sqrt();
+        let q = d.abs() / se * f64::consts::SQRT_2;
+        group_a.append_option(groups.get(a));
+        group_b.append_option(groups.get(b));
+        diffs.push(d);
+        std_errs.push(se);
+        lower.push(d - q_crit * se);
+        upper.push(d + q_crit * se);
+        p_values.push((1. - stats_utils::tukey::ptukey(q, k, dof)).max(0.));
+    }
+
+    let a_series = group_a.finish().into_series();
+    let b_series = group_b.finish().into_series();
+    let diff_series = Float64Chunked::from_vec("diff".into(), diffs).into_series();
+    let stderr_series = Float64Chunked::from_vec("std_err".into(), std_errs).into_series();
+    let lower_series = Float64Chunked::from_vec("lower".into(), lower).into_series();
+    let upper_series = Float64Chunked::from_vec("upper".into(), upper).into_series();
+    let p_series = Float64Chunked::from_vec("p_adj".into(), p_values).into_series();
+    let out = StructChunked::from_series(
+        "group_slopes".into(),
+        n_pairs,
+        [
+            &a_series,
+            &b_series,
+            &diff_series,
+            &stderr_series,
+            &lower_series,
+            &upper_series,
+            &p_series,
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Exhaustive best subset selection by AIC, BIC and adjusted R2. One row per criterion, with the
+/// selected features and their coefficients. See faer_best_subset.
+#[polars_expr(output_type_func=best_subset_output)]
+fn pl_best_subset(inputs: &[Series], kwargs: BestSubsetKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let n_features = inputs.len() - 1;
+    let fits = faer_best_subset(x, y, has_bias, kwargs.max_size.unwrap_or(n_features))
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let mut criterion = StringChunkedBuilder::new("criterion".into(), fits.len());
+    let mut features: Vec<Series> = Vec::with_capacity(fits.len());
+    let mut coeffs: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "coeffs".into(),
+        fits.len(),
+        n_features + has_bias as usize,
+        DataType::Float64,
+    );
+    for fit in fits.iter() {
+        criterion.append_value(fit.criterion.as_str());
+        let mut names = fit
+            .features
+            .iter()
+            .map(|j| inputs[j + 1].name().as_str())
+            .collect_vec();
+        if has_bias {
+            if kwargs.bias_first {
+                names.insert(0, "__bias__");
+            } else {
+                names.push("__bias__");
+            }
+        }
+        features.push(Series::new("".into(), names));
+        coeffs.append_slice(&coeffs_bias_first(
+            fit.coefficients.col_as_slice(0),
+            has_bias,
+            kwargs.bias_first,
+        ));
+    }
+    let criterion = criterion.finish().into_series();
+    let features = Series::new("features".into(), features);
+    let coeffs = coeffs.finish().into_series();
+    let value = Series::from_vec("value".into(), fits.iter().map(|f| f.value).collect_vec());
+    let out = StructChunked::from_series(
+        "best_subset".into(),
+        fits.len(),
+        [&criterion, &features, &coeffs, &value].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Forward stepwise selection, optionally refusing candidates whose VIF against the selected features is
+/// above max_vif. See faer_forward_stepwise.
+#[polars_expr(output_type_func=stepwise_output)]
+fn pl_stepwise(inputs: &[Series], kwargs: StepwiseKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let criterion = SubsetCriterion::from(kwargs.criterion.as_str());
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let n_features = inputs.len() - 1;
+    let fit = faer_forward_stepwise(
+        x,
+        y,
+        has_bias,
+        criterion,
+        kwargs.max_size.unwrap_or(n_features),
+        kwargs.max_vif,
+    )
+    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let name = |j: &usize| inputs[j + 1].name().as_str();
+    let mut names = fit.features.iter().map(name).collect_vec();
+    if has_bias {
+        if kwargs.bias_first {
+            names.insert(0, "__bias__");
+        } else {
+            names.push("__bias__");
+        }
+    }
+    let features = Series::new("features".into(), [Series::new("".into(), names)]);
+    let coeffs = single_list_series(
+        "coeffs",
+        &coeffs_bias_first(
+            fit.coefficients.col_as_slice(0),
+            has_bias,
+            kwargs.bias_first,
+        ),
+    );
+    let value = Series::from_vec("value".into(), vec![fit.value]);
+    let rejected = Series::new(
+        "rejected".into(),
+        [Series::new(
+            "".into(),
+            fit.rejected.iter().map(name).collect_vec(),
+        )],
+    );
+    let out = StructChunked::from_series(
+        "stepwise".into(),
+        1,
+        [&features, &coeffs, &value, &rejected].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Variance inflation factors. One row per feature, and none for the bias. Inputs are [target, features...],
+/// and the target is only used to pick the rows. See faer_vif.
+#[polars_expr(output_type_func=vif_output)]
+fn pl_vif(inputs: &[Series], kwargs: VifKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    if x.nrows() == 0 {
+        return Err(PolarsError::ComputeError(
+            "Not enough data to compute VIFs.".into(),
+        ));
+    }
+    let vif = faer_vif(x, has_bias);
+
+    let n_features = vif.len();
+    let feature = Series::new(
+        "feature".into(),
+        inputs[1..].iter().map(|s| s.name().as_str()).collect_vec(),
+    );
+    let vif = Series::from_vec("vif".into(), vif);
+    let out = StructChunked::from_series("vif".into(), n_features, [&feature, &vif].into_iter())?;
+    Ok(out.into_series())
+}
+
+/// The LMG / Shapley decomposition of R2 by feature. One row per feature. See faer_relative_importance.
+#[polars_expr(output_type_func=relative_importance_output)]
+fn pl_relative_importance(
+    inputs: &[Series],
+    kwargs: RelativeImportanceKwargs,
+) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let importance = faer_relative_importance(x, y, has_bias)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let r2 = importance.iter().sum::<f64>();
+
+    let n_features = importance.len();
+    let feature = Series::new(
+        "feature".into(),
+        inputs[1..].iter().map(|s| s.name().as_str()).collect_vec(),
+    );
+    let share = importance.iter().map(|v| v / r2).collect_vec();
+    let importance = Series::from_vec("importance".into(), importance);
+    let share = Series::from_vec("share".into(), share);
+    let out = StructChunked::from_series(
+        "relative_importance".into(),
+        n_features,
+        [&feature, &importance, &share].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+#[polars_expr(output_type_func=ridge_loocv_output)]
+fn pl_ridge_loocv(inputs: &[Series], kwargs: RidgeLoocvKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let res = faer_ridge_loocv(x, y, &kwargs.lambdas, has_bias)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let best_lambda = Series::from_vec("best_lambda".into(), vec![res.best_lambda]);
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "lambdas".into(),
+        1,
+        kwargs.lambdas.len(),
+        DataType::Float64,
+    );
+    builder.append_slice(&kwargs.lambdas);
+    let lambdas = builder.finish().into_series();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("loo_mse".into(), 1, res.errors.len(), DataType::Float64);
+    builder.append_slice(&res.errors);
+    let errors = builder.finish().into_series();
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "coeffs".into(),
+        1,
+        res.coefficients.nrows(),
+        DataType::Float64,
+    );
+    builder.append_slice(&coeffs_bias_first(
+        res.coefficients.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    let coeffs = builder.finish().into_series();
+    let out = StructChunked::from_series(
+        "ridge_loocv".into(),
+        1,
+        [&best_lambda, &lambdas, &errors, &coeffs].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// K-fold CV over a grid of lambdas. Inputs are [target, features...], or [folds, target, features...]
+/// if custom_folds, where folds is an integer column. Folds are ordered by their ids.
+#[polars_expr(output_type_func=lstsq_cv_output)]
+fn pl_lstsq_cv(inputs: &[Series], kwargs: LstsqCvKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let offset = kwargs.custom_folds as usize;
+    let (mat, mask) = series_to_mat_for_lstsq(&inputs[offset..], has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let (fold_ids, res) = if kwargs.custom_folds {
+        let folds = if mask.len() == inputs[0].len() {
+            inputs[0].filter(&mask)?
+        } else if mask.get(0).unwrap_or(false) {
+            inputs[0].clone()
+        } else {
+            return Err(PolarsError::ComputeError(
+                "Null policy 'ignore' is not supported with custom folds.".into(),
+            ));
+        };
+        if !folds.dtype().is_integer() || folds.has_nulls() {
+            return Err(PolarsError::ComputeError(
+                "The fold column must be an integer column without nulls.".into(),
+            ));
+        }
+        let folds = folds.cast(&DataType::Int64)?;
+        let folds = folds.i64()?.into_no_null_iter().collect_vec();
+        let ids = folds
+            .iter()
+            .copied()
+            .sorted_unstable()
+            .dedup()
+            .collect_vec();
+        if ids.len() < 2 {
+            return Err(PolarsError::ComputeError(
+                "The fold column must have at least 2 distinct values.".into(),
+            ));
+        }
+        let fold_of = folds
+            .iter()
+            .map(|f| ids.binary_search(f).unwrap())
+            .collect_vec();
+        let res = faer_lstsq_fold_cv(
+            x,
+            y,
+            &kwargs.lambdas,
+            kwargs.l1_ratio,
+            has_bias,
+            &fold_of,
+            ids.len(),
+            kwargs.tol,
+            kwargs.max_iter,
+        );
+        (ids, res)
+    } else {
+        let res = faer_lstsq_kfold_cv(
+            x,
+            y,
+            &kwargs.lambdas,
+            kwargs.l1_ratio,
+            has_bias,
+            kwargs.n_folds,
+            kwargs.seed,
+            kwargs.tol,
+            kwargs.max_iter,
+        );
+        ((0..kwargs.n_folds as i64).collect_vec(), res)
+    };
+    let res = res.map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let lambda_min = Series::from_vec("lambda_min".into(), vec![res.lambda_min]);
+    let lambda_1se = Series::from_vec("lambda_1se".into(), vec![res.lambda_1se]);
+    let lambdas = single_list_series("lambdas", &kwargs.lambdas);
+    let cv_mse = single_list_series("cv_mse", &res.cv_mean);
+    let cv_std = single_list_series("cv_std", &res.cv_std);
+    let cv_se = single_list_series("cv_se", &res.cv_se);
+    let mut folds: ListPrimitiveChunkedBuilder<Int64Type> =
+        ListPrimitiveChunkedBuilder::new("folds".into(), 1, fold_ids.len(), DataType::Int64);
+    folds.append_slice(&fold_ids);
+    let folds = folds.finish().into_series();
+    let fold_mse = single_list_series("fold_mse", &res.fold_mse);
+    let coeffs_min = single_list_series(
+        "coeffs_min",
+        &coeffs_bias_first(res.coeffs_min.col_as_slice(0), has_bias, kwargs.bias_first),
+    );
+    let coeffs_1se = single_list_series(
+        "coeffs_1se",
+        &coeffs_bias_first(res.coeffs_1se.col_as_slice(0), has_bias, kwargs.bias_first),
+    );
+    let out = StructChunked::from_series(
+        "lstsq_cv".into(),
+        1,
+        [
+            &lambda_min,
+            &lambda_1se,
+            &lambdas,
+            &cv_mse,
+            &cv_std,
+            &cv_se,
+            &folds,
+            &fold_mse,
+            &coeffs_min,
+            &coeffs_1se,
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Frisch-Waugh-Lovell. Inputs are [target, focal, controls...]. The controls (and the bias) are
+/// partialled out of both the target and the focal predictor, and the slope of the residuals is
+/// the multiple regression coefficient of the focal predictor.
+#[polars_expr(output_type_func=partial_regression_output)]
+fn pl_partial_regression(inputs: &[Series], kwargs: PartialRegKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let nrows = mat.nrows();
+    // Target and focal predictor, as a 2-target regression on the controls
+    let yx = mat.slice(s![.., 0..2]).into_faer();
+    let z = mat.slice(s![.., 2..]).into_faer();
+    let dof = nrows as f64 - (z.ncols() + 1) as f64;
+
+    let (beta, std_err, partial_corr) = if dof > 0. {
+        let res = faer_lstsq_residuals(z, yx, solver);
+        let e_y = res.col(0);
+        let e_x = res.col(1);
+        let sxy = e_x.transpose() * e_y;
+        let sxx = e_x.squared_norm_l2();
+        let syy = e_y.squared_norm_l2();
+        let beta = sxy / sxx;
+        let ssr = (e_y - beta * e_x).squared_norm_l2();
+        let std_err = (ssr / dof / sxx).sqrt();
+        (beta, std_err, sxy / (sxx * syy).sqrt())
+    } else {
+        (f64::NAN, f64::NAN, f64::NAN)
+    };
+
+    let beta = Series::from_vec("beta".into(), vec![beta]);
+    let std_err = Series::from_vec("std_err".into(), vec![std_err]);
+    let partial_corr = Series::from_vec("partial_corr".into(), vec![partial_corr]);
+    let out = StructChunked::from_series(
+        "partial_regression".into(),
+        1,
+        [&beta, &std_err, &partial_corr].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// The residual maker applied to a column. Inputs are [column, controls...]. Returns
+/// column - Z (ZtZ)^-1 Zt column, where Z are the controls (and the bias), aligned to the input rows.
+/// Rows dropped because of nulls are null.
+#[polars_expr(output_type=Float64)]
+fn pl_residualize(inputs: &[Series], kwargs: PartialRegKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let z = mat.slice(s![.., 1..]).into_faer();
+    let res = faer_lstsq_residuals(z, y, solver);
+
+    let height = inputs[0].len();
+    let mut builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new(inputs[0].name().clone(), height);
+    for row in design_rows(&mask, height) {
+        match row {
+            Some(k) => builder.append_value(*res.get(k, 0)),
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish().into_series())
+}
+
+/// Autocorrelation of the OLS residuals at lags 1..=lag, in the order of the (non-null) rows.
+#[polars_expr(output_type_func=acf_output)]
+fn pl_residual_acf(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_solve_lstsq(x, y, 0., has_bias, solver);
+    let res = y - x * coeffs;
+
+    let acf = residual_acf(res.col_as_slice(0), kwargs.lag);
+    let bound = 2.0 / (mat.nrows() as f64).sqrt();
+
+    let acf = single_list_series("acf", &acf[1..]);
+    let bound = Series::from_vec("bound".into(), vec![bound]);
+    let out = StructChunked::from_series("residual_acf".into(), 1, [&acf, &bound].into_iter())?;
+    Ok(out.into_series())
+}
+
+/// Partial autocorrelation of the OLS residuals, in the order of the (non-null) rows.
+#[polars_expr(output_type_func=pacf_output)]
+fn pl_residual_pacf(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_solve_lstsq(x, y, 0., has_bias, solver);
+    let res = y - x * coeffs;
 
-/// Returns a Array2 ready for linear regression, and a mask, where true means the row doesn't contain null
-#[inline(always)]
-fn series_to_mat_for_lstsq(
-    inputs: &[Series],
-    has_bias: bool,
-    null_policy: NullPolicy<f64>,
-) -> PolarsResult<(Array2<f64>, BooleanChunked)> {
-    let n_features = inputs.len().abs_diff(1);
+    let acf = residual_acf(res.col_as_slice(0), kwargs.lag);
+    let pacf = pacf_durbin_levinson(&acf);
+    let bound = 2.0 / (mat.nrows() as f64).sqrt();
 
-    // minus 1 because target is also in inputs. Target is at position 0.
-    let y_has_null = inputs[0].has_nulls();
-    let has_null = inputs[1..].iter().any(|s| s.has_nulls()) | y_has_null;
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("pacf".into(), 1, pacf.len(), DataType::Float64);
+    builder.append_slice(&pacf);
+    let pacf = builder.finish().into_series();
+    let bound = Series::from_vec("bound".into(), vec![bound]);
+    let out = StructChunked::from_series("residual_pacf".into(), 1, [&pacf, &bound].into_iter())?;
+    Ok(out.into_series())
+}
 
-    let mut df = to_frame(inputs)?;
-    if df.is_empty() {
-        return Err(PolarsError::ComputeError("Empty data".into()));
-    }
-    // Add a constant column if has_bias
-    if has_bias {
-        df = df.lazy().with_column(lit(1f64)).collect()?;
-    }
+/// White's test for heteroskedasticity on the OLS residuals. The auxiliary regression always has a
+/// constant, regardless of whether the original regression has a bias.
+#[polars_expr(output_type_func=white_test_output)]
+fn pl_white_test(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
 
-    // In mask, true means not null.
-    let y_name = inputs[0].name();
-    let init_mask = inputs[0].is_not_null();
-    let (df, mask) = if has_null {
-        match null_policy {
-            // Like ignore, skip_window takes the raw data. The actual skip is done in the underlying function in linalg.
-            NullPolicy::IGNORE | NullPolicy::SKIP_WINDOW => {
-                // false, because it has nulls
-                Ok((df, BooleanChunked::from_slice("".into(), &[false])))
-            }
-            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
-            NullPolicy::SKIP => {
-                let init_mask = inputs[0].is_not_null(); //0 always exist
-                let mask = inputs[1..]
-                    .iter()
-                    .fold(init_mask, |acc, s| acc & s.is_not_null());
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_solve_lstsq(x, y, 0., has_bias, solver);
+    let res = y - x * coeffs;
 
-                df = df.filter(&mask).unwrap();
-                Ok((df, mask))
-            }
-            NullPolicy::FILL(x) => {
-                df = df
-                    .lazy()
-                    .with_columns([pl::col("*")
-                        .exclude([y_name.clone()])
-                        .cast(DataType::Float64)
-                        .fill_null(lit(x))])
-                    .collect()?;
+    let n_features = x.ncols() - has_bias as usize;
+    let (stat, dof) = white_test(x.get(.., ..n_features), res.col_as_slice(0));
+    let p = if dof > 0 && stat.is_finite() {
+        gamma::sf(stat, dof as f64 / 2., 0.5).map_err(|e| PolarsError::ComputeError(e.into()))?
+    } else {
+        f64::NAN
+    };
 
-                if y_has_null {
-                    df = df.filter(&init_mask).unwrap();
-                    Ok((df, init_mask))
-                } else {
-                    // all filled, no nulls
-                    let mask = BooleanChunked::from_slice("".into(), &[true]);
-                    Ok((df, mask))
-                }
-            }
-            NullPolicy::FILL_WINDOW(x) => {
-                df = df
-                    .lazy()
-                    .with_columns([pl::col("*")
-                        .exclude([y_name.clone()])
-                        .cast(DataType::Float64)
-                        .fill_null(lit(x))])
-                    .collect()?;
+    let s = Series::from_vec("statistic".into(), vec![stat]);
+    let dof = Series::from_vec("dof".into(), vec![dof as u32]);
+    let p = Series::from_vec("pvalue".into(), vec![p]);
+    let out = StructChunked::from_series("white_test".into(), 1, [&s, &dof, &p].into_iter())?;
+    Ok(out.into_series())
+}
 
-                if y_has_null {
-                    // Unlike fill, this doesn't drop y's nulls
-                    Ok((df, BooleanChunked::from_slice("".into(), &[false])))
-                } else {
-                    // all filled, no nulls
-                    let mask = BooleanChunked::from_slice("".into(), &[true]);
-                    Ok((df, mask))
-                }
-            }
-        }
+/// Breusch-Pagan test for heteroskedasticity on the OLS residuals. Like in pl_white_test, the auxiliary
+/// regression always has a constant. See breusch_pagan_test.
+#[polars_expr(output_type_func=breusch_pagan_output)]
+fn pl_breusch_pagan(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_solve_lstsq(x, y, 0., has_bias, solver);
+    let res = y - x * coeffs;
+
+    let n_features = x.ncols() - has_bias as usize;
+    let (stat, dof) = breusch_pagan_test(x.get(.., ..n_features), res.col_as_slice(0));
+    let p = if dof > 0 && stat.is_finite() {
+        gamma::sf(stat, dof as f64 / 2., 0.5).map_err(|e| PolarsError::ComputeError(e.into()))?
     } else {
-        // In this case, the (!mask).any() is never true, which means there is no null.
-        let mask = BooleanChunked::from_slice("".into(), &[true]);
-        Ok((df, mask))
-    }?;
+        f64::NAN
+    };
 
-    if df.height() < n_features {
-        Err(PolarsError::ComputeError(
-            "#Data < #features. No conclusive result.".into(),
-        ))
+    let s = Series::from_vec("statistic".into(), vec![stat]);
+    let dof = Series::from_vec("dof".into(), vec![dof as u32]);
+    let p = Series::from_vec("pvalue".into(), vec![p]);
+    let out = StructChunked::from_series("breusch_pagan".into(), 1, [&s, &dof, &p].into_iter())?;
+    Ok(out.into_series())
+}
+
+/// Chow test for a structural break. Inputs are [split, target, features...], where split is true on the
+/// rows after the break. See faer_chow_test.
+#[polars_expr(output_type_func=chow_test_output)]
+fn pl_chow_test(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, mask) = series_to_mat_for_lstsq(&inputs[1..], has_bias, null_policy)?;
+    let split = if mask.len() == inputs[0].len() {
+        inputs[0].filter(&mask)?
+    } else if mask.get(0).unwrap_or(false) {
+        inputs[0].clone()
     } else {
-        let mat = df.to_ndarray::<Float64Type>(IndexOrder::Fortran)?;
-        Ok((mat, mask))
+        return Err(PolarsError::ComputeError(
+            "Null policy 'ignore' is not supported for the Chow test.".into(),
+        ));
+    };
+    if split.has_nulls() {
+        return Err(PolarsError::ComputeError(
+            "The split condition must not have nulls.".into(),
+        ));
     }
-}
+    let after = split.bool()?.into_no_null_iter().collect_vec();
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let (stat, df1, df2) = faer_chow_test(x, y, &after, solver)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let p = if stat.is_finite() {
+        stats_utils::beta::fisher_snedecor_sf(stat, df1 as f64, df2 as f64)
+            .map_err(|e| PolarsError::ComputeError(e.into()))?
+    } else {
+        f64::NAN
+    };
 
-fn series_to_mat_for_multi_lstsq(
-    inputs: &[Series],
-    last_target_idx: usize,
-    has_bias: bool,
-    null_policy: NullPolicy<f64>,
-) -> PolarsResult<Array2<f64>> {
-    let y_has_null = inputs[..last_target_idx]
-        .iter()
-        .fold(false, |acc, s| s.has_nulls() | acc);
-    let has_null = inputs[last_target_idx..].iter().any(|s| s.has_nulls()) | y_has_null;
+    let s = Series::from_vec("statistic".into(), vec![stat]);
+    let df1 = Series::from_vec("df1".into(), vec![df1 as u32]);
+    let df2 = Series::from_vec("df2".into(), vec![df2 as u32]);
+    let p = Series::from_vec("pvalue".into(), vec![p]);
+    let out = StructChunked::from_series("chow_test".into(), 1, [&s, &df1, &df2, &p].into_iter())?;
+    Ok(out.into_series())
+}
 
-    let mut df = if has_null {
-        match null_policy {
-            NullPolicy::RAISE => Err(PolarsError::ComputeError("Nulls found in data".into())),
+/// Row level influence of the OLS fit: the leverage, the internally studentized residual and Cook's
+/// distance, aligned to the input rows. Rows dropped because of nulls are null.
+#[polars_expr(output_type_func=influence_output)]
+fn pl_influence(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
 
-            NullPolicy::FILL(x) => {
-                let df = DataFrame::new(inputs.iter().map(|s| s.clone().into_column()).collect())?;
-                if y_has_null {
-                    // This is because for predictions, it becomes too complicated when different targets have nulls.
-                    // There will be too many masks we need to keep track of.
-                    // This can be dealt with but I won't implement it for now.
-                    Err(PolarsError::ComputeError(
-                        "Filling null doesn't work for multi-target lstsq when there are nulls in any of the targets.".into(),
-                    ))
-                } else {
-                    let y_names = inputs[..last_target_idx]
-                        .iter()
-                        .map(|s| s.name().clone())
-                        .collect::<Vec<_>>();
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= x.ncols() {
+        return Err(PolarsError::ComputeError(
+            "#Data <= #features. No conclusive result.".into(),
+        ));
+    }
+    let influence = faer_row_influence(x, y, has_bias, solver);
 
-                    df.lazy()
-                        .with_columns([pl::col("*")
-                            .exclude(y_names)
-                            .cast(DataType::Float64)
-                            .fill_null(lit(x))])
-                        .collect()
-                }
+    let height = inputs[0].len();
+    let mut leverage: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("leverage".into(), height);
+    let mut student: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("studentized_resid".into(), height);
+    let mut cooks: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("cooks_distance".into(), height);
+    for row in design_rows(&mask, height) {
+        match row {
+            Some(k) => {
+                leverage.append_value(influence.leverage[k]);
+                student.append_value(influence.internal[k]);
+                cooks.append_value(influence.cooks[k]);
+            }
+            None => {
+                leverage.append_null();
+                student.append_null();
+                cooks.append_null();
             }
-            _ => Err(PolarsError::ComputeError(
-                "The null policy is not supported by multi-target linear regression.".into(),
-            )),
         }
-    } else {
-        DataFrame::new(inputs.iter().map(|s| s.clone().into_column()).collect())
-    }?;
-
-    if has_bias {
-        df = df.lazy().with_column(lit(1f64)).collect()?;
     }
+    let leverage = leverage.finish().into_series();
+    let student = student.finish().into_series();
+    let cooks = cooks.finish().into_series();
+    let out = StructChunked::from_series(
+        "influence".into(),
+        height,
+        [&leverage, &student, &cooks].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
 
-    if df.is_empty() {
-        Err(PolarsError::ComputeError("Empty data".into()))
+/// Influence of groups of rows. Inputs are [group, target, features...]. The row level leverage and
+/// DFFITS of the OLS fit are aggregated by group. Groups are ordered by their values.
+#[polars_expr(output_type_func=group_influence_output)]
+fn pl_group_influence(inputs: &[Series], kwargs: ResidualDiagKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let (mat, mask) = series_to_mat_for_lstsq(&inputs[1..], has_bias, null_policy)?;
+    let groups = if mask.len() == inputs[0].len() {
+        inputs[0].filter(&mask)?
+    } else if mask.get(0).unwrap_or(false) {
+        inputs[0].clone()
     } else {
-        df.to_ndarray::<Float64Type>(IndexOrder::Fortran)
+        return Err(PolarsError::ComputeError(
+            "Null policy 'ignore' is not supported for group influence.".into(),
+        ));
+    };
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= x.ncols() {
+        return Err(PolarsError::ComputeError(
+            "#Data <= #features. No conclusive result.".into(),
+        ));
     }
+    let influence = faer_row_influence(x, y, has_bias, solver);
+
+    let df = df!(
+        "group" => groups.with_name("group".into()),
+        "leverage" => influence.leverage,
+        "dffits" => influence.dffits,
+    )?
+    .lazy()
+    .group_by([col("group")])
+    .agg([
+        len().cast(DataType::UInt32).alias("n"),
+        col("leverage").sum(),
+        col("dffits").abs().sum().alias("dffits_abs_sum"),
+        (col("dffits") * col("dffits"))
+            .sum()
+            .sqrt()
+            .alias("dffits_norm"),
+        col("dffits").abs().max().alias("dffits_max_abs"),
+    ])
+    .sort(["group"], Default::default())
+    .collect()?;
+
+    let out = df.into_struct("group_influence".into());
+    Ok(out.into_series())
 }
 
-// -----------------------------------------------------------------------------------------------------
+/// Condition numbers of the features before and after appending the bias column, and after centering
+/// the features. Inputs are [target, features...], and the target is only used to pick the rows.
+#[polars_expr(output_type_func=conditioning_output)]
+fn pl_conditioning_report(inputs: &[Series], kwargs: ConditioningKwargs) -> PolarsResult<Series> {
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
-#[polars_expr(output_type_func=coeff_output)]
-fn pl_lstsq(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
-    let has_bias = kwargs.bias;
+    let (mat, _) = series_to_mat_for_lstsq(inputs, false, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let report = faer_conditioning_report(x);
+
+    let without_bias = Series::from_vec("cond_without_bias".into(), vec![report.without_bias]);
+    let with_bias = Series::from_vec("cond_with_bias".into(), vec![report.with_bias]);
+    let centered = Series::from_vec("cond_centered".into(), vec![report.centered]);
+    let out = StructChunked::from_series(
+        "conditioning".into(),
+        1,
+        [&without_bias, &with_bias, &centered].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Numerical rank of the design from its column pivoted QR decomposition. Inputs are
+/// [target, features...], and the target is only used to pick the rows.
+#[polars_expr(output_type_func=matrix_rank_output)]
+fn pl_matrix_rank(inputs: &[Series], kwargs: MatrixRankKwargs) -> PolarsResult<Series> {
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
-    let solver = kwargs.solver.as_str().into();
-    let weighted = kwargs.weighted;
-    let data_for_matrix = if weighted { &inputs[1..] } else { inputs };
+    let (mat, _) = series_to_mat_for_lstsq(inputs, kwargs.bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let rank = faer_matrix_rank(x, kwargs.rtol);
 
-    match series_to_mat_for_lstsq(data_for_matrix, has_bias, null_policy) {
-        Ok((mat, _)) => {
-            // Solving Least Square
-            let x = mat.slice(s![.., 1..]).into_faer();
-            let y = mat.slice(s![.., 0..1]).into_faer();
-            let coeffs = if weighted {
-                let binding = inputs[0].cast(&DataType::Float64)?;
-                let weights = binding.f64().unwrap();
-                let weights = weights.cont_slice().unwrap();
-                if weights.len() != mat.nrows() {
-                    return Err(PolarsError::ComputeError(
-                        "Shape of weights is not the same as the data.".into(),
-                    ));
-                }
-                faer_weighted_lstsq(x, y, weights, solver)
-            } else {
-                match LRMethods::from((kwargs.l1_reg, kwargs.l2_reg)) {
-                    LRMethods::Normal | LRMethods::L2 => {
-                        faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver)
-                    }
+    let note = Series::new("note".into(), [rank_note(rank, x.ncols())]);
+    let rank = Series::from_vec("rank".into(), vec![rank as u32]);
+    let n_cols = Series::from_vec("n_cols".into(), vec![x.ncols() as u32]);
+    let out =
+        StructChunked::from_series("matrix_rank".into(), 1, [&rank, &n_cols, &note].into_iter())?;
+    Ok(out.into_series())
+}
 
-                    LRMethods::L1 | LRMethods::ElasticNet => faer_coordinate_descent(
-                        x,
-                        y,
-                        kwargs.l1_reg,
-                        kwargs.l2_reg,
-                        has_bias,
-                        kwargs.tol,
-                        2000,
-                    ),
-                }
-            };
-            let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
-                ListPrimitiveChunkedBuilder::new(
-                    "coeffs".into(),
-                    1,
-                    coeffs.nrows(),
-                    DataType::Float64,
-                );
+/// Eigenvalues of XtX in decreasing order. Inputs are [target, features...], and the target is only
+/// used to pick the rows.
+#[polars_expr(output_type_func=xtx_eigenvalues_output)]
+fn pl_xtx_eigenvalues(inputs: &[Series], kwargs: XtxEigenKwargs) -> PolarsResult<Series> {
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
-            builder.append_slice(coeffs.col_as_slice(0));
-            let out = builder.finish();
-            Ok(out.into_series())
-        }
-        Err(e) => Err(e),
-    }
+    let (mat, _) = series_to_mat_for_lstsq(inputs, kwargs.bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let eigenvalues =
+        faer_xtx_eigenvalues(x).map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    Ok(single_list_series("eigenvalues", &eigenvalues))
 }
 
 // Strictly speaking, this output type is not correct. Should be struct of last_target_idx many
@@ -351,6 +3162,7 @@ fn pl_lstsq_multi(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResult<S
     let has_bias = kwargs.bias;
     let solver = kwargs.solver.as_str().into();
     let last_target_idx = kwargs.last_target_idx;
+    let l2_regs = kwargs.per_target_l2(last_target_idx)?;
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
@@ -363,14 +3175,18 @@ fn pl_lstsq_multi(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResult<S
     let y = mat.slice(s![.., 0..last_target_idx]).into_faer();
     let x = mat.slice(s![.., last_target_idx..]).into_faer();
 
-    let coeffs = match LRMethods::from((0., kwargs.l2_reg)) {
-        LRMethods::Normal | LRMethods::L2 => {
-            Ok(faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver))
-        }
-        _ => Err(PolarsError::ComputeError(
-            "The method is not supported.".into(),
-        )),
-    }?;
+    let coeffs = if let Some(lambdas) = l2_regs {
+        faer_solve_lstsq_per_target(x, y, &lambdas, has_bias, solver)
+    } else {
+        match LRMethods::from((0., kwargs.l2_reg)) {
+            LRMethods::Normal | LRMethods::L2 => {
+                Ok(faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver))
+            }
+            _ => Err(PolarsError::ComputeError(
+                "The method is not supported.".into(),
+            )),
+        }?
+    };
 
     let df_out = DataFrame::new(
         y_names
@@ -398,8 +3214,10 @@ fn pl_lstsq_multi(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResult<S
 #[polars_expr(output_type_func=pred_residue_output)]
 fn pl_lstsq_multi_pred(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
     let solver = kwargs.solver.as_str().into();
     let last_target_idx = kwargs.last_target_idx;
+    let l2_regs = kwargs.per_target_l2(last_target_idx)?;
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
@@ -412,16 +3230,21 @@ fn pl_lstsq_multi_pred(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsRes
     let y = mat.slice(s![.., 0..last_target_idx]).into_faer();
     let x = mat.slice(s![.., last_target_idx..]).into_faer();
 
-    let coeffs = match LRMethods::from((0., kwargs.l2_reg)) {
-        LRMethods::Normal | LRMethods::L2 => {
-            Ok(faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver))
-        }
-        _ => Err(PolarsError::ComputeError(
-            "The method is not supported.".into(),
-        )),
-    }?;
+    let coeffs = if let Some(lambdas) = l2_regs {
+        faer_solve_lstsq_per_target(x, y, &lambdas, has_bias, solver)
+    } else {
+        match LRMethods::from((0., kwargs.l2_reg)) {
+            LRMethods::Normal | LRMethods::L2 => {
+                Ok(faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver))
+            }
+            _ => Err(PolarsError::ComputeError(
+                "The method is not supported.".into(),
+            )),
+        }?
+    };
 
-    let pred = x * &coeffs;
+    let mut pred = x * &coeffs;
+    clip_predictions(&mut pred, clip);
     let resid = y - &pred;
 
     let mut s = Vec::with_capacity(y_names.len() * 2);
@@ -449,7 +3272,7 @@ fn pl_lstsq_w_rcond(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Seri
             // rcond will be passed as tol
             let rcond = kwargs
                 .tol
-                .max(f64::EPSILON * (inputs.len().max(mat.len())) as f64);
+                .max(f64::EPSILON * (mat.nrows().max(mat.ncols() - 1)) as f64);
 
             // Solving Least Square
             let x = mat.slice(s![.., 1..]).into_faer();
@@ -467,7 +3290,11 @@ fn pl_lstsq_w_rcond(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Seri
                     DataType::Float64,
                 );
 
-            builder.append_slice(coeffs.col_as_slice(0));
+            builder.append_slice(&coeffs_bias_first(
+                coeffs.col_as_slice(0),
+                has_bias,
+                kwargs.bias_first,
+            ));
             let coeffs_ca = builder.finish();
 
             let mut sv_builder: ListPrimitiveChunkedBuilder<Float64Type> =
@@ -488,53 +3315,411 @@ fn pl_lstsq_w_rcond(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Seri
             )?;
             Ok(ca.into_series())
         }
-        Err(e) => Err(e),
+        Err(e) => Err(e),
+    }
+}
+
+/// Total least squares (orthogonal regression) coefficients. Inputs are [target, features...].
+/// See faer_total_lstsq.
+#[polars_expr(output_type_func=coeff_output)]
+fn pl_total_lstsq(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = faer_total_lstsq(x, y, has_bias)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), 1, coeffs.nrows(), DataType::Float64);
+    builder.append_slice(&coeffs_bias_first(
+        coeffs.col_as_slice(0),
+        has_bias,
+        kwargs.bias_first,
+    ));
+    let out = builder.finish();
+    Ok(out.into_series())
+}
+
+/// Weighted sum or weighted mean of the predictions per group. Inputs are [group, weights, features...],
+/// or [group, features...] if not weighted. Since the prediction is linear, sum_i w_i * (x_i b + bias) is
+/// (sum_i w_i x_i) b + bias * sum_i w_i, so the features are aggregated first and the per row predictions
+/// are never computed. Rows with a null feature are skipped. Groups are ordered by their values.
+#[polars_expr(output_type_func=grouped_predict_output)]
+fn pl_predict_grouped(inputs: &[Series], kwargs: GroupedPredictKwargs) -> PolarsResult<Series> {
+    let offset = 1 + kwargs.weighted as usize;
+    let n_features = inputs.len() - offset;
+    if kwargs.coefficients.len() != n_features + kwargs.bias as usize {
+        return Err(PolarsError::ShapeMismatch(
+            format!(
+                "The coefficients must have {} values (#features + bias).",
+                n_features + kwargs.bias as usize
+            )
+            .into(),
+        ));
+    }
+    let mean = match kwargs.how.as_str() {
+        "sum" => false,
+        "mean" => true,
+        _ => {
+            return Err(PolarsError::ComputeError(
+                "Aggregation must be 'sum' or 'mean'.".into(),
+            ))
+        }
+    };
+    let weights = if kwargs.weighted {
+        let w = inputs[1].cast(&DataType::Float64)?;
+        if w.has_nulls()
+            || !w
+                .f64()?
+                .into_no_null_iter()
+                .all(|v| v.is_finite() && v >= 0.)
+        {
+            return Err(PolarsError::ComputeError(
+                "Weights must be finite, non-negative and not null.".into(),
+            ));
+        }
+        w
+    } else {
+        Series::from_vec("".into(), vec![1f64; inputs[0].len()])
+    };
+
+    let mut columns = Vec::with_capacity(inputs.len() + 1);
+    columns.push(Column::new("group".into(), &inputs[0]));
+    columns.push(Column::new("w".into(), weights));
+    for (j, s) in inputs[offset..].iter().enumerate() {
+        columns.push(Column::new(j.to_string().into(), s));
+    }
+    let features = (0..n_features).map(|j| col(j.to_string())).collect_vec();
+    // The linear predictor of the weighted sums of the features
+    let pred = features.iter().zip(kwargs.coefficients.iter()).fold(
+        if kwargs.bias {
+            lit(kwargs.coefficients[n_features]) * col("w").sum()
+        } else {
+            lit(0f64)
+        },
+        |acc, (c, b)| acc + lit(*b) * (col("w") * c.clone().cast(DataType::Float64)).sum(),
+    );
+    let pred = if mean { pred / col("w").sum() } else { pred };
+
+    let df = DataFrame::new(columns)?
+        .lazy()
+        .drop_nulls(Some(features))
+        .group_by([col("group")])
+        .agg([pred.alias("prediction"), col("w").sum().alias("weight")])
+        .sort(["group"], Default::default())
+        .collect()?;
+
+    let out = df.into_struct("grouped_prediction".into());
+    Ok(out.into_series())
+}
+
+/// Predictions of many coefficient sets on the same features, computed as one product X * B, where
+/// each column of B is a coefficient set. If bias, the last value of each set is the bias. The output
+/// is a list per row, with one prediction per coefficient set. Rows with any null feature are null.
+#[polars_expr(output_type_func=predict_path_output)]
+fn pl_predict_path(inputs: &[Series], kwargs: PredictPathKwargs) -> PolarsResult<Series> {
+    let n_coeffs = inputs.len() + kwargs.bias as usize;
+    let n_sets = kwargs.coefficients.len();
+    if n_sets == 0 {
+        return Err(PolarsError::ComputeError(
+            "At least one coefficient set is required.".into(),
+        ));
+    }
+    if kwargs.coefficients.iter().any(|c| c.len() != n_coeffs) {
+        return Err(PolarsError::ShapeMismatch(
+            format!(
+                "Every coefficient set must have {} values (#features + bias).",
+                n_coeffs
+            )
+            .into(),
+        ));
+    }
+    let height = inputs[0].len();
+    if inputs.iter().any(|s| s.len() != height) {
+        return Err(PolarsError::ShapeMismatch(
+            "Input columns must have the same length.".into(),
+        ));
+    }
+
+    let mut x = Mat::<f64>::from_fn(height, n_coeffs, |_, _| 1.0);
+    let mut valid = vec![true; height];
+    for (j, s) in inputs.iter().enumerate() {
+        let s = s.cast(&DataType::Float64)?;
+        let ca = s.f64()?;
+        for (i, v) in ca.iter().enumerate() {
+            match v {
+                Some(v) => *x.get_mut(i, j) = v,
+                None => valid[i] = false,
+            }
+        }
+    }
+    let b = Mat::<f64>::from_fn(n_coeffs, n_sets, |i, j| kwargs.coefficients[j][i]);
+    let pred = &x * &b;
+
+    let mut builder: ListPrimitiveChunkedBuilder<Float64Type> = ListPrimitiveChunkedBuilder::new(
+        "predictions".into(),
+        height,
+        height * n_sets,
+        DataType::Float64,
+    );
+    let mut row = vec![0f64; n_sets];
+    for (i, is_valid) in valid.into_iter().enumerate() {
+        if is_valid {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = *pred.get(i, j);
+            }
+            builder.append_slice(&row);
+        } else {
+            builder.append_null();
+        }
+    }
+    Ok(builder.finish().into_series())
+}
+
+/// Predictions of a linear model fit elsewhere, given by its coefficients and bias, or by the bytes of
+/// a serialized LR, ElasticNet or OnlineLR. Rows with any null feature are null.
+#[polars_expr(output_type=Float64)]
+fn pl_lstsq_predict_with_coeffs(
+    inputs: &[Series],
+    kwargs: PredictWithCoeffsKwargs,
+) -> PolarsResult<Series> {
+    let (coeffs, bias) = match kwargs.model.as_deref() {
+        Some(bytes) => coeffs_and_bias_from_bytes::<f64>(bytes)
+            .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?,
+        None => (kwargs.coefficients, kwargs.bias),
+    };
+    if coeffs.len() != inputs.len() {
+        return Err(PolarsError::ShapeMismatch(
+            format!(
+                "The model has {} coefficients, but {} features are given.",
+                coeffs.len(),
+                inputs.len()
+            )
+            .into(),
+        ));
+    }
+    let height = inputs[0].len();
+    if inputs.iter().any(|s| s.len() != height) {
+        return Err(PolarsError::ShapeMismatch(
+            "Input columns must have the same length.".into(),
+        ));
+    }
+
+    let mut x = Mat::<f64>::zeros(height, inputs.len());
+    let mut valid = vec![true; height];
+    for (j, s) in inputs.iter().enumerate() {
+        let s = s.cast(&DataType::Float64)?;
+        let ca = s.f64()?;
+        for (i, v) in ca.iter().enumerate() {
+            match v {
+                Some(v) => *x.get_mut(i, j) = v,
+                None => valid[i] = false,
+            }
+        }
+    }
+    let pred = LR::from_values(&coeffs, bias)
+        .predict(x.as_ref())
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let out = Float64Chunked::from_iter_options(
+        "prediction".into(),
+        valid
+            .into_iter()
+            .zip(pred.col_as_slice(0).iter())
+            .map(|(is_valid, p)| is_valid.then_some(*p)),
+    );
+    Ok(out.into_series())
+}
+
+/// Splits a coefficient list into the feature coefficients and the bias (0 if there is none).
+#[inline(always)]
+fn split(c: &[f64], n_features: usize, bias: bool) -> (&[f64], f64) {
+    let b = if bias { c[n_features] } else { 0. };
+    (&c[..n_features], b)
+}
+
+/// Predictions with coefficients from a list column, e.g. fit on one group and joined onto another.
+/// Inputs are [coefficients, features...]. If bias, the last value of each list is the bias, as in the
+/// output of pl_lstsq. A coefficient column of length 1 applies to all rows. Otherwise, each row uses
+/// its own list. Rows with any null feature or null coefficients are null.
+#[polars_expr(output_type=Float64)]
+fn pl_predict_from_coeffs(
+    inputs: &[Series],
+    kwargs: PredictFromCoeffsKwargs,
+) -> PolarsResult<Series> {
+    let n_features = inputs.len() - 1;
+    let n_coeffs = n_features + kwargs.bias as usize;
+    let coeffs = inputs[0].cast(&DataType::List(Box::new(DataType::Float64)))?;
+    let coeffs = coeffs.list()?;
+    let height = inputs[1].len();
+    if inputs[1..].iter().any(|s| s.len() != height) {
+        return Err(PolarsError::ShapeMismatch(
+            "Input columns must have the same length.".into(),
+        ));
+    }
+    if coeffs.len() != 1 && coeffs.len() != height {
+        return Err(PolarsError::ShapeMismatch(
+            "The coefficients must be a single list, or one list per row.".into(),
+        ));
+    }
+    let to_vec = |c: Series| -> PolarsResult<Vec<f64>> {
+        if c.len() != n_coeffs {
+            return Err(PolarsError::ShapeMismatch(
+                format!(
+                    "The coefficients have {} values, but {} are expected (#features + bias).",
+                    c.len(),
+                    n_coeffs
+                )
+                .into(),
+            ));
+        }
+        if c.has_nulls() {
+            return Err(PolarsError::ComputeError(
+                "The coefficients cannot have nulls.".into(),
+            ));
+        }
+        Ok(c.f64()?.into_no_null_iter().collect())
+    };
+
+    let mut x = Mat::<f64>::zeros(height, n_features);
+    let mut valid = vec![true; height];
+    for (j, s) in inputs[1..].iter().enumerate() {
+        let s = s.cast(&DataType::Float64)?;
+        let ca = s.f64()?;
+        for (i, v) in ca.iter().enumerate() {
+            match v {
+                Some(v) => *x.get_mut(i, j) = v,
+                None => valid[i] = false,
+            }
+        }
     }
+
+    let pred: Vec<Option<f64>> = if coeffs.len() == 1 {
+        match coeffs.get_as_series(0) {
+            Some(c) => {
+                let c = to_vec(c)?;
+                let (b, bias) = split(&c, n_features, kwargs.bias);
+                let pred = LR::from_values(b, bias)
+                    .predict(x.as_ref())
+                    .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+                valid
+                    .into_iter()
+                    .zip(pred.col_as_slice(0).iter())
+                    .map(|(is_valid, p)| is_valid.then_some(*p))
+                    .collect()
+            }
+            None => vec![None; height],
+        }
+    } else {
+        let mut pred = Vec::with_capacity(height);
+        for (i, c) in coeffs.into_iter().enumerate() {
+            match c {
+                Some(c) if valid[i] => {
+                    let c = to_vec(c)?;
+                    let (b, bias) = split(&c, n_features, kwargs.bias);
+                    pred.push(Some(
+                        b.iter()
+                            .enumerate()
+                            .fold(bias, |acc, (j, v)| acc + v * *x.get(i, j)),
+                    ));
+                }
+                _ => pred.push(None),
+            }
+        }
+        pred
+    };
+    Ok(Float64Chunked::from_iter_options("prediction".into(), pred.into_iter()).into_series())
 }
 
-#[polars_expr(output_type_func=pred_residue_output)]
+#[polars_expr(output_type_func_with_kwargs=lstsq_pred_output)]
 fn pl_lstsq_pred(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let weighted = kwargs.weighted;
+    let binding = lstsq_inputs(inputs, &kwargs)?;
+    let inputs = binding.as_ref();
+    let data_for_matrix = if weighted { &inputs[1..] } else { inputs };
+    if all_f32(data_for_matrix.iter().map(|s| s.dtype())) {
+        return lstsq_pred_f32(inputs, kwargs);
+    }
+
     let has_bias = kwargs.bias;
-    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+    let unpenalized_bias = kwargs.unpenalized_bias();
+    let clip = kwargs.clip;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy.clone())
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
     let solver = kwargs.solver.as_str().into();
-    let weighted = kwargs.weighted;
-    let data_for_matrix = if weighted { &inputs[1..] } else { inputs };
 
     match series_to_mat_for_lstsq(data_for_matrix, has_bias, null_policy.clone()) {
         Ok((mat, mask)) => {
+            let (mat, offset) = split_offset(mat, kwargs.has_offset);
             let y = mat.slice(s![.., 0..1]).into_faer();
             let x = mat.slice(s![.., 1..]).into_faer();
-            let coeffs = if weighted {
-                let binding = inputs[0].cast(&DataType::Float64)?;
-                let weights = binding.f64().unwrap();
-                let weights = weights.cont_slice().unwrap();
-                if weights.len() != mat.nrows() {
-                    return Err(PolarsError::ComputeError(
-                        "Length of weights and data in X must be the same.".into(),
-                    ));
-                }
-                faer_weighted_lstsq(x, y, weights, solver)
+            let binding = if weighted {
+                Some(masked_weights(
+                    &inputs[0],
+                    &mask,
+                    mat.nrows(),
+                    &DataType::Float64,
+                )?)
             } else {
-                match LRMethods::from((kwargs.l1_reg, kwargs.l2_reg)) {
-                    LRMethods::Normal | LRMethods::L2 => {
-                        faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, solver)
-                    }
-                    LRMethods::L1 | LRMethods::ElasticNet => faer_coordinate_descent(
+                None
+            };
+            let weights = binding
+                .as_ref()
+                .map(|w| w.f64().unwrap().cont_slice().unwrap());
+            let method = LRMethods::from((kwargs.l1_reg, kwargs.l2_reg));
+            // Ridge takes the weights by scaling the rows by sqrt(w). Lasso and Elastic Net take them
+            // directly, since their loss is normalized by the total weight.
+            let scaled = match (weights, method) {
+                (Some(w), LRMethods::L2) => Some(faer_sqrt_weighted_rows(x, y, w)),
+                _ => None,
+            };
+            let (xs, ys) = match &scaled {
+                Some((xw, yw)) => (xw.as_ref(), yw.as_ref()),
+                None => (x, y),
+            };
+            let coeffs = match (weights, method) {
+                (Some(w), LRMethods::Normal) => faer_weighted_lstsq(x, y, w, solver),
+                (_, LRMethods::Normal | LRMethods::L2) => {
+                    faer_solve_lstsq(xs, ys, kwargs.l2_reg, unpenalized_bias, solver)
+                }
+                (_, LRMethods::L1 | LRMethods::ElasticNet) => {
+                    let (coeffs, _, converged) = faer_elastic_net(
                         x,
                         y,
+                        weights,
                         kwargs.l1_reg,
                         kwargs.l2_reg,
-                        has_bias,
+                        kwargs.penalty_factor.as_deref(),
+                        unpenalized_bias,
                         kwargs.tol,
-                        2000,
-                    ),
+                        kwargs.max_iter,
+                        StopCriterion::from(kwargs.stop_criterion.as_str()),
+                        LassoAlgorithm::from(kwargs.algorithm.as_str()),
+                    );
+                    check_converged(converged, kwargs.strict)?;
+                    coeffs
                 }
             };
 
-            let pred = x * &coeffs;
-            let resid = y - &pred;
+            let mut pred = x * &coeffs;
+            add_offset(&mut pred, offset.as_deref());
+            let pv = lstsq_prediction_variance(x, y, &kwargs)?;
+            let bounds = match &pv {
+                Some(pv) if kwargs.pred_interval => {
+                    Some(prediction_bounds(pv, pred.as_ref(), &kwargs))
+                }
+                _ => None,
+            };
+            let pred_se = match &pv {
+                Some(pv) if kwargs.pred_se => Some(prediction_std_errors(pv)),
+                _ => None,
+            };
+            clip_predictions(&mut pred, clip);
+            let resid = residuals_with_offset(y, pred.as_ref(), offset.as_deref());
             let pred = pred.col_as_slice(0);
             let resid = resid.col_as_slice(0);
             // If null policy is raise and we have nulls, we won't reach here
@@ -564,30 +3749,200 @@ fn pl_lstsq_pred(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series>
                 let residue = Float64Chunked::from_slice("resid".into(), resid);
                 (pred, residue)
             };
-            let p = p.into_series();
-            let r = r.into_series();
-            let out = StructChunked::from_series("".into(), p.len(), [&p, &r].into_iter())?;
+            let mut fields = vec![p.into_series(), r.into_series()];
+            if let Some((lower, upper)) = bounds {
+                fields.push(nan_for_skipped_rows("pred_lower", &lower, &mask));
+                fields.push(nan_for_skipped_rows("pred_upper", &upper, &mask));
+            }
+            if let Some(se) = pred_se {
+                fields.push(nan_for_skipped_rows("pred_se", &se, &mask));
+            }
+            let out = StructChunked::from_series("".into(), fields[0].len(), fields.iter())?;
             Ok(out.into_series())
         }
         Err(e) => Err(e),
     }
 }
 
+/// Fits on the train rows and predicts the scored rows. Inputs are [train, target, features...], where
+/// train is a boolean mask. The null policy applies to the train rows. Rows outside of the scored set get
+/// nulls, and so do scored rows with a null feature, unless the null policy is a fill value. The residual
+/// is null where the target is null, e.g. for test rows whose target is unknown.
+#[polars_expr(output_type_func=pred_residue_output)]
+fn pl_lstsq_train_test(inputs: &[Series], kwargs: TrainTestKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let unpenalized_bias = has_bias && !kwargs.penalize_bias;
+    let null_policy: NullPolicy<f64> = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+    let (score_train, score_test) = match kwargs.score.as_str() {
+        "test" => (false, true),
+        "train" => (true, false),
+        "all" => (true, true),
+        _ => {
+            return Err(PolarsError::ComputeError(
+                "Scored rows must be one of 'test', 'train' and 'all'.".into(),
+            ))
+        }
+    };
+
+    let train = inputs[0].bool()?;
+    if train.has_nulls() {
+        return Err(PolarsError::ComputeError(
+            "The train mask must not have nulls.".into(),
+        ));
+    }
+    if !train.any() {
+        return Err(PolarsError::ComputeError("There are no train rows.".into()));
+    }
+    let fill = match null_policy {
+        NullPolicy::FILL(v) => Some(v),
+        _ => None,
+    };
+    let train_data = inputs[1..]
+        .iter()
+        .map(|s| s.filter(train))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let (mat, _) = series_to_mat_for_lstsq(&train_data, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let coeffs = match LRMethods::from((kwargs.l1_reg, kwargs.l2_reg)) {
+        LRMethods::Normal | LRMethods::L2 => {
+            faer_solve_lstsq(x, y, kwargs.l2_reg, unpenalized_bias, solver)
+        }
+        LRMethods::L1 | LRMethods::ElasticNet => {
+            faer_coordinate_descent(
+                x,
+                y,
+                None,
+                kwargs.l1_reg,
+                kwargs.l2_reg,
+                None,
+                unpenalized_bias,
+                kwargs.tol,
+                2000,
+                StopCriterion::default(),
+            )
+            .0
+        }
+    };
+    let coeffs = coeffs.col_as_slice(0);
+
+    let target = inputs[1].cast(&DataType::Float64)?;
+    let target = target.f64()?;
+    let features = inputs[2..]
+        .iter()
+        .map(|s| s.cast(&DataType::Float64))
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let features = features
+        .iter()
+        .map(|s| s.f64())
+        .collect::<PolarsResult<Vec<_>>>()?;
+    let bias = if has_bias { coeffs[features.len()] } else { 0. };
+
+    let height = inputs[0].len();
+    let mut p_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("pred".into(), height);
+    let mut r_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("resid".into(), height);
+    for (i, is_train) in train.into_no_null_iter().enumerate() {
+        let pred = if (is_train && score_train) || (!is_train && score_test) {
+            features
+                .iter()
+                .zip(coeffs.iter())
+                .try_fold(bias, |acc, (ca, b)| ca.get(i).or(fill).map(|v| acc + v * b))
+        } else {
+            None
+        };
+        p_builder.append_option(pred);
+        r_builder.append_option(pred.zip(target.get(i)).map(|(p, t)| t - p));
+    }
+    let p = p_builder.finish().into_series();
+    let r = r_builder.finish().into_series();
+    let out = StructChunked::from_series("".into(), height, [&p, &r].into_iter())?;
+    Ok(out.into_series())
+}
+
+/// Predictions with per-row variances and intervals, for OLS or Ridge. See faer_ridge_prediction_variance.
+/// Rows dropped because of nulls get nulls.
+#[polars_expr(output_type_func=pred_interval_output)]
+fn pl_lstsq_pred_interval(inputs: &[Series], kwargs: PredIntervalKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let pv = faer_ridge_prediction_variance(x, y, kwargs.l2_reg, has_bias)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+    let pred = x * &pv.coefficients;
+    // The intervals use a t distribution with the effective degrees of freedom
+    let q = stats_utils::beta::student_t_ppf(0.5 + kwargs.confidence / 2., pv.dof);
+    let interval_var = if kwargs.interval == "confidence" {
+        &pv.fit_var
+    } else {
+        &pv.pred_var
+    };
+
+    let height = inputs[0].len();
+    let mut pred_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("prediction".into(), height);
+    let mut fit_var_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("fit_var".into(), height);
+    let mut pred_var_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("pred_var".into(), height);
+    let mut lower_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("lower".into(), height);
+    let mut upper_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("upper".into(), height);
+    for row in design_rows(&mask, height) {
+        match row {
+            Some(k) => {
+                let p = *pred.get(k, 0);
+                let half_width = q * interval_var[k].sqrt();
+                pred_builder.append_value(p);
+                fit_var_builder.append_value(pv.fit_var[k]);
+                pred_var_builder.append_value(pv.pred_var[k]);
+                lower_builder.append_value(p - half_width);
+                upper_builder.append_value(p + half_width);
+            }
+            None => {
+                pred_builder.append_null();
+                fit_var_builder.append_null();
+                pred_var_builder.append_null();
+                lower_builder.append_null();
+                upper_builder.append_null();
+            }
+        }
+    }
+    let out = StructChunked::from_series(
+        "pred_interval".into(),
+        height,
+        [
+            &pred_builder.finish().into_series(),
+            &fit_var_builder.finish().into_series(),
+            &pred_var_builder.finish().into_series(),
+            &lower_builder.finish().into_series(),
+            &upper_builder.finish().into_series(),
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
 #[polars_expr(output_type_func=report_output)]
 fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
     let se_type = StandardError::from(kwargs.std_err);
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
-    // index 0 is y_var, 1 is target y. Skip
-    let binding = inputs[0].cast(&DataType::Float64)?;
-    let y_var = binding.f64().unwrap();
-    let y_var = y_var.get(0).unwrap_or(f64::NAN);
+    // index 0 is target y. Skip
     let mut name_builder = StringChunkedBuilder::new(
         "features".into(),
-        inputs.len().abs_diff(2) + (has_bias) as usize,
+        inputs.len().abs_diff(1) + (has_bias) as usize,
     );
-    for s in inputs[2..].iter().map(|s| s.name()) {
+    for s in inputs[1..].iter().map(|s| s.name()) {
         name_builder.append_value(s);
     }
     if has_bias {
@@ -595,38 +3950,47 @@ fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
     }
     // Copy data
     // Target y is at index 0
-    match series_to_mat_for_lstsq(&inputs[1..], has_bias, null_policy) {
+    match series_to_mat_for_lstsq(inputs, has_bias, null_policy) {
         Ok((mat, _)) => {
             let ncols = mat.ncols() - 1;
             let nrows = mat.nrows();
 
             let x = mat.slice(s![0..nrows, 1..]).into_faer();
             let y = mat.slice(s![0..nrows, 0..1]).into_faer();
-            // Solving Least Square
+            // Solving Least Square. For Ridge, xtx_inv is the inverse of the penalized XtX.
             let xtx = x.transpose() * &x;
-            let xtx_qr = xtx.col_piv_qr();
-            let xtx_inv = xtx_qr.inverse();
+            let mut penalized = xtx.clone();
+            for i in 0..ncols.abs_diff(has_bias as usize) {
+                *penalized.get_mut(i, i) = *penalized.get(i, i) + kwargs.l2_reg;
+            }
+            let xtx_inv = penalized.col_piv_qr().inverse();
             let xtx_inv_xt = &xtx_inv * x.transpose();
             let coeffs = &xtx_inv_xt * y;
             let betas = coeffs.col_as_slice(0);
-            // Degree of Freedom
-            let dof = nrows as f64 - ncols as f64;
+            // Degree of Freedom, n - trace of the hat matrix. This is n - p without Ridge.
+            let dof = faer_residual_dof(nrows as f64, xtx.as_ref(), xtx_inv.as_ref());
             // Residue
             let res = y - x * &coeffs;
 
-            // r2, adj_r2
-            let nf64 = nrows as f64;
-            let ratio = res.col(0).squared_norm_l2() / (y_var * nf64);
+            // r2, adj_r2 and the residual standard error. Same as statsmodels: the total sum of
+            // squares is uncentered without a bias, and adj_r2 = 1 - (1 - r2) * (n - has_bias) / dof.
+            let rss = res.col(0).squared_norm_l2();
+            let tss = total_sum_of_squares(y.col(0), None, has_bias);
+            let ratio = rss / tss;
             let r2 = 1.0 - ratio;
-            let adj_r2 = 1.0 - ratio * ((nrows - 1) as f64 / (dof - 1.0));
+            let adj_r2 = 1.0 - ratio * ((nrows - has_bias as usize) as f64 / dof);
+            let resid_se = (rss / dof).sqrt();
+            let (f_stat, f_pvalue) = overall_f_test(tss, rss, ncols - has_bias as usize, dof);
 
             // std err
             let std_err = match se_type {
                 StandardError::SE => {
                     // total residue, sum of squares
                     let mse = (res.transpose() * &res).get(0, 0) / dof;
+                    // Var(b) = mse * A^-1 XtX A^-1, which is mse * (XtX)^-1 without Ridge
+                    let cov = &xtx_inv * &xtx * &xtx_inv;
                     (0..ncols)
-                        .map(|i| (mse * xtx_inv.get(i, i)).sqrt())
+                        .map(|i| (mse * cov.get(i, i)).sqrt())
                         .collect_vec()
                 }
                 StandardError::HC0 | StandardError::HC1 => {
@@ -634,7 +3998,7 @@ fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
                     let diag = temp_diag * temp_diag;
                     let var_hc = &xtx_inv_xt * diag * xtx_inv_xt.transpose();
                     let factor = if se_type == StandardError::HC1 {
-                        (nrows as f64) / ((nrows - ncols) as f64)
+                        (nrows as f64) / dof
                     } else {
                         1f64
                     };
@@ -659,6 +4023,16 @@ fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
                     let var_hc = &xtx_inv_xt * diag * xtx_inv_xt.transpose();
                     (0..ncols).map(|i| (var_hc.get(i, i)).sqrt()).collect_vec()
                 }
+                StandardError::NeweyWest => {
+                    // Newey-West: the scores x_i * r_i are autocorrelated, and the rows are in time order
+                    let lags = kwargs
+                        .hac_lags
+                        .unwrap_or_else(|| newey_west_default_lags(nrows));
+                    let scores = Mat::from_fn(nrows, ncols, |i, j| *x.get(i, j) * *res.get(i, 0));
+                    let long_run = newey_west_long_run(scores.as_ref(), lags);
+                    let var_hac = &xtx_inv * long_run * &xtx_inv;
+                    (0..ncols).map(|i| (var_hac.get(i, i)).sqrt()).collect_vec()
+                }
             };
 
             // T values
@@ -678,54 +4052,72 @@ fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
                 )
                 .collect_vec();
 
-            let t_alpha = crate::stats_utils::beta::student_t_ppf(0.975, dof);
-            let ci_lower = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b - t_alpha * se)
-                .collect_vec();
-            let ci_upper = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b + t_alpha * se)
-                .collect_vec();
+            let [lower_95, upper_95, lower, upper] =
+                report_ci_series(betas, &std_err, dof, kwargs.alpha);
 
             // Finalize
             let names_ca = name_builder.finish();
             let names_series = names_ca.into_series();
             let coeffs_series = Float64Chunked::from_slice("beta".into(), betas);
             let coeffs_series = coeffs_series.into_series();
+            let std_beta = standardized_coefficients(x, y, betas, None, has_bias);
+            let std_beta_series =
+                Float64Chunked::from_vec("std_beta".into(), std_beta).into_series();
             let stderr_series = Float64Chunked::from_vec(se_type.into(), std_err);
             let stderr_series = stderr_series.into_series();
             let t_series = Float64Chunked::from_vec("t".into(), t_values);
             let t_series = t_series.into_series();
             let p_series = Float64Chunked::from_vec("p>|t|".into(), p_values);
             let p_series = p_series.into_series();
-            let lower = Float64Chunked::from_vec("0.025".into(), ci_lower);
-            let lower = lower.into_series();
-            let upper = Float64Chunked::from_vec("0.975".into(), ci_upper);
-            let upper = upper.into_series();
             let r2 = Float64Chunked::from_vec("r2".into(), vec![r2]);
             let r2_series = r2.into_series();
             let adj_r2 = Float64Chunked::from_vec("adj_r2".into(), vec![adj_r2]);
             let adj_r2_series = adj_r2.into_series();
+            let resid_se_series = Series::from_vec("resid_se".into(), vec![resid_se]);
+            let f_stat_series = Series::from_vec("f_stat".into(), vec![f_stat]);
+            let f_pvalue_series = Series::from_vec("f_pvalue".into(), vec![f_pvalue]);
+            let df_resid = Float64Chunked::from_vec("df_resid".into(), vec![dof]);
+            let df_resid_series = df_resid.into_series();
+            let n_obs_series = Series::from_vec("n_obs".into(), vec![nrows as u32]);
+            let rank = faer_matrix_rank(x, None);
+            let rank_series = Series::from_vec("rank".into(), vec![rank as u32]);
+            let note_series = Series::new("note".into(), [rank_note(rank, ncols)]);
+            let dw_series = Series::from_vec(
+                "durbin_watson".into(),
+                vec![durbin_watson(res.col_as_slice(0))],
+            );
             let out = StructChunked::from_series(
                 "lin_reg_report".into(),
                 names_series.len(),
                 [
                     &names_series,
                     &coeffs_series,
+                    &std_beta_series,
                     &stderr_series,
                     &t_series,
                     &p_series,
+                    &lower_95,
+                    &upper_95,
                     &lower,
                     &upper,
                     &r2_series,
                     &adj_r2_series,
+                    &resid_se_series,
+                    &f_stat_series,
+                    &f_pvalue_series,
+                    &df_resid_series,
+                    &n_obs_series,
+                    &rank_series,
+                    &dw_series,
+                    &note_series,
                 ]
                 .into_iter(),
             )?;
-            Ok(out.into_series())
+            if kwargs.bias_first && has_bias {
+                report_bias_first(out.into_series())
+            } else {
+                Ok(out.into_series())
+            }
         }
         Err(e) => Err(e),
     }
@@ -734,30 +4126,28 @@ fn pl_lin_reg_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
 #[polars_expr(output_type_func=report_output)]
 fn pl_wls_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
+    let frequency = kwargs.weight_type == "frequency";
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
-    let binding = inputs[0].cast(&DataType::Float64)?;
-    let weights = binding.f64().unwrap();
-    let weights = weights.cont_slice().unwrap();
-    let binding2 = inputs[1].cast(&DataType::Float64)?;
-    let y_var = binding2.f64().unwrap();
-    let y_var = y_var.get(0).unwrap_or(f64::NAN);
-    // index 0 is weights, 1 is y_var, 2 is target y. Skip them
+    // index 0 is weights, 1 is target y. Skip them
     let mut name_builder = StringChunkedBuilder::new(
         "features".into(),
-        inputs.len().abs_diff(3) + (has_bias) as usize,
+        inputs.len().abs_diff(2) + (has_bias) as usize,
     );
-    for s in inputs[3..].iter().map(|s| s.name()) {
+    for s in inputs[2..].iter().map(|s| s.name()) {
         name_builder.append_value(s);
     }
     if has_bias {
         name_builder.append_value("__bias__");
     }
     // Copy data
-    // Target y is at index 2, weights at index 0
-    match series_to_mat_for_lstsq(&inputs[2..], has_bias, null_policy) {
-        Ok((mat, _)) => {
+    // Target y is at index 1, weights at index 0
+    match series_to_mat_for_lstsq(&inputs[1..], has_bias, null_policy) {
+        Ok((mat, mask)) => {
+            // The weights of the skipped rows are dropped too
+            let binding = masked_weights(&inputs[0], &mask, mat.nrows(), &DataType::Float64)?;
+            let weights = binding.f64().unwrap().cont_slice().unwrap();
             let ncols = mat.ncols() - 1;
             let nrows = mat.nrows();
 
@@ -771,27 +4161,44 @@ fn pl_wls_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series>
 
             let xtwx = xt * w * x;
             let xtwy = xt * w * y;
-            let qr = xtwx.col_piv_qr();
+            // For Ridge, xtwx_inv is the inverse of the penalized XtWX
+            let mut penalized = xtwx.clone();
+            for i in 0..ncols.abs_diff(has_bias as usize) {
+                *penalized.get_mut(i, i) = *penalized.get(i, i) + kwargs.l2_reg;
+            }
+            let qr = penalized.col_piv_qr();
             let xtwx_inv = qr.inverse();
             let coeffs = qr.solve(xtwy);
             let betas = coeffs.col_as_slice(0);
 
-            // Degree of Freedom
-            let dof = nrows as f64 - ncols as f64;
             // Residue
             let res = y - x * &coeffs;
-            let mse =
-                (0..y.nrows()).fold(0., |acc, i| acc + weights[i] * res.get(i, 0).powi(2)) / dof;
+            let wssr = (0..y.nrows()).fold(0., |acc, i| acc + weights[i] * res.get(i, 0).powi(2));
+            // Analytic weights: n = #rows, mse = sum(w * r^2) / (n - p)
+            // Frequency weights: each row stands for w rows, so n = sum(w), mse = sum(w * r^2) / (sum(w) - p),
+            // and r2 is computed on the expanded data.
+            let nf64 = if frequency {
+                weights[..nrows].iter().sum::<f64>()
+            } else {
+                nrows as f64
+            };
+            // Same as statsmodels WLS, r2 is measured against the weighted total sum of squares
+            let wsst = total_sum_of_squares(y.col(0), Some(&weights[..nrows]), has_bias);
+            let ratio = wssr / wsst;
+            // Degree of Freedom, n - trace of the hat matrix. This is n - p without Ridge.
+            let dof = faer_residual_dof(nf64, xtwx.as_ref(), xtwx_inv.as_ref());
+            let mse = wssr / dof;
+            let cov = &xtwx_inv * &xtwx * &xtwx_inv;
 
-            // r2, adj_r2
-            let nf64 = nrows as f64;
-            let ratio = res.col(0).squared_norm_l2() / (y_var * nf64);
+            // r2, adj_r2 and the residual standard error
             let r2 = 1.0 - ratio;
-            let adj_r2 = 1.0 - ratio * ((nrows - 1) as f64 / (dof - 1.0));
+            let adj_r2 = 1.0 - ratio * ((nf64 - has_bias as usize as f64) / dof);
+            let resid_se = mse.sqrt();
+            let (f_stat, f_pvalue) = overall_f_test(wsst, wssr, ncols - has_bias as usize, dof);
 
             // std err
             let std_err = (0..ncols)
-                .map(|i| (mse * xtwx_inv.get(i, i)).sqrt())
+                .map(|i| (mse * cov.get(i, i)).sqrt())
                 .collect_vec();
             // T values
             let t_values = betas
@@ -810,64 +4217,332 @@ fn pl_wls_report(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series>
                 )
                 .collect_vec();
 
-            let t_alpha = crate::stats_utils::beta::student_t_ppf(0.975, dof);
-            let ci_lower = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b - t_alpha * se)
-                .collect_vec();
-            let ci_upper = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b + t_alpha * se)
-                .collect_vec();
+            let [lower_95, upper_95, lower, upper] =
+                report_ci_series(betas, &std_err, dof, kwargs.alpha);
             // Finalize
             let names_ca = name_builder.finish();
             let names_series = names_ca.into_series();
             let coeffs_series = Float64Chunked::from_slice("beta".into(), betas);
             let coeffs_series = coeffs_series.into_series();
+            let std_beta =
+                standardized_coefficients(x, y, betas, Some(&weights[..nrows]), has_bias);
+            let std_beta_series =
+                Float64Chunked::from_vec("std_beta".into(), std_beta).into_series();
             let stderr_series = Float64Chunked::from_vec("std_err".into(), std_err);
             let stderr_series = stderr_series.into_series();
             let t_series = Float64Chunked::from_vec("t".into(), t_values);
             let t_series = t_series.into_series();
             let p_series = Float64Chunked::from_vec("p>|t|".into(), p_values);
             let p_series = p_series.into_series();
-            let lower = Float64Chunked::from_vec("0.025".into(), ci_lower);
-            let lower = lower.into_series();
-            let upper = Float64Chunked::from_vec("0.975".into(), ci_upper);
-            let upper = upper.into_series();
             let r2 = Float64Chunked::from_vec("r2".into(), vec![r2]);
             let r2_series = r2.into_series();
             let adj_r2 = Float64Chunked::from_vec("adj_r2".into(), vec![adj_r2]);
             let adj_r2_series = adj_r2.into_series();
+            let resid_se_series = Series::from_vec("resid_se".into(), vec![resid_se]);
+            let f_stat_series = Series::from_vec("f_stat".into(), vec![f_stat]);
+            let f_pvalue_series = Series::from_vec("f_pvalue".into(), vec![f_pvalue]);
+            let df_resid = Float64Chunked::from_vec("df_resid".into(), vec![dof]);
+            let df_resid_series = df_resid.into_series();
+            let n_obs_series = Series::from_vec("n_obs".into(), vec![nrows as u32]);
+            let rank = faer_matrix_rank(x, None);
+            let rank_series = Series::from_vec("rank".into(), vec![rank as u32]);
+            let note_series = Series::new("note".into(), [rank_note(rank, ncols)]);
+            // On the weighted residuals sqrt(w) * r
+            let weighted_res = (0..nrows)
+                .map(|i| weights[i].sqrt() * *res.get(i, 0))
+                .collect_vec();
+            let dw_series =
+                Series::from_vec("durbin_watson".into(), vec![durbin_watson(&weighted_res)]);
             let out = StructChunked::from_series(
                 "lin_reg_report".into(),
                 names_series.len(),
                 [
                     &names_series,
                     &coeffs_series,
+                    &std_beta_series,
                     &stderr_series,
                     &t_series,
                     &p_series,
+                    &lower_95,
+                    &upper_95,
                     &lower,
                     &upper,
                     &r2_series,
                     &adj_r2_series,
+                    &resid_se_series,
+                    &f_stat_series,
+                    &f_pvalue_series,
+                    &df_resid_series,
+                    &n_obs_series,
+                    &rank_series,
+                    &dw_series,
+                    &note_series,
                 ]
                 .into_iter(),
             )?;
-            Ok(out.into_series())
+            if kwargs.bias_first && has_bias {
+                report_bias_first(out.into_series())
+            } else {
+                Ok(out.into_series())
+            }
         }
         Err(e) => Err(e),
     }
 }
 
+#[polars_expr(output_type_func=bootstrap_output)]
+fn pl_lstsq_bootstrap_ci(inputs: &[Series], kwargs: BootstrapKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    if kwargs.n_boot < 2 {
+        return Err(PolarsError::ComputeError(
+            "Number of bootstrap samples must be >= 2.".into(),
+        ));
+    }
+    if !(kwargs.confidence > 0. && kwargs.confidence < 1.) {
+        return Err(PolarsError::ComputeError(
+            "Confidence level must be in (0, 1).".into(),
+        ));
+    }
+
+    let mut name_builder = StringChunkedBuilder::new(
+        "features".into(),
+        inputs.len().abs_diff(1) + (has_bias) as usize,
+    );
+    for s in inputs[1..].iter().map(|s| s.name()) {
+        name_builder.append_value(s);
+    }
+    if has_bias {
+        name_builder.append_value("__bias__");
+    }
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+
+    let coeffs = faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, LRSolverMethods::QR);
+    let boot = faer_bootstrap_lstsq(x, y, kwargs.l2_reg, has_bias, kwargs.n_boot, kwargs.seed);
+
+    let n_boot = kwargs.n_boot as f64;
+    let mut boot_se = Vec::with_capacity(boot.nrows());
+    let mut lower = Vec::with_capacity(boot.nrows());
+    let mut upper = Vec::with_capacity(boot.nrows());
+    for i in 0..boot.nrows() {
+        let mut samples = boot.row(i).iter().copied().collect_vec();
+        let mean = samples.iter().sum::<f64>() / n_boot;
+        let var = samples.iter().fold(0., |acc, b| acc + (b - mean).powi(2)) / (n_boot - 1.);
+        boot_se.push(var.sqrt());
+        let (lo, hi) = percentile_interval(&mut samples, kwargs.confidence);
+        lower.push(lo);
+        upper.push(hi);
+    }
+
+    let names_series = name_builder.finish().into_series();
+    let beta = Series::from_vec("beta".into(), coeffs.col_as_slice(0).to_vec());
+    let boot_se = Series::from_vec("boot_se".into(), boot_se);
+    let lower = Series::from_vec("lower".into(), lower);
+    let upper = Series::from_vec("upper".into(), upper);
+    let out = StructChunked::from_series(
+        "bootstrap_ci".into(),
+        names_series.len(),
+        [&names_series, &beta, &boot_se, &lower, &upper].into_iter(),
+    )?;
+    if kwargs.bias_first && has_bias {
+        report_bias_first(out.into_series())
+    } else {
+        Ok(out.into_series())
+    }
+}
+
+/// OLS with Driscoll-Kraay standard errors. Inputs are [time, panel, target, features...]. Time periods
+/// are ordered by their values and are assumed to be evenly spaced. The panel column is only used to
+/// check that each (panel, time) pair appears at most once.
+#[polars_expr(output_type_func=driscoll_kraay_output)]
+fn pl_driscoll_kraay(inputs: &[Series], kwargs: DriscollKraayKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    let solver = kwargs.solver.as_str().into();
+
+    let mut name_builder = StringChunkedBuilder::new(
+        "features".into(),
+        inputs.len().abs_diff(3) + (has_bias) as usize,
+    );
+    for s in inputs[3..].iter().map(|s| s.name()) {
+        name_builder.append_value(s);
+    }
+    if has_bias {
+        name_builder.append_value("__bias__");
+    }
+
+    let (mat, mask) = series_to_mat_for_lstsq(&inputs[2..], has_bias, null_policy)?;
+    let (time, panel) = if mask.len() == inputs[0].len() {
+        (inputs[0].filter(&mask)?, inputs[1].filter(&mask)?)
+    } else if mask.get(0).unwrap_or(false) {
+        (inputs[0].clone(), inputs[1].clone())
+    } else {
+        return Err(PolarsError::ComputeError(
+            "Null policy 'ignore' is not supported for Driscoll-Kraay standard errors.".into(),
+        ));
+    };
+    if time.has_nulls() || panel.has_nulls() {
+        return Err(PolarsError::ComputeError(
+            "The time and panel columns must not have nulls.".into(),
+        ));
+    }
+
+    let df = df!(
+        "time" => time.with_name("time".into()),
+        "panel" => panel.with_name("panel".into()),
+    )?;
+    let n_pairs = df
+        .clone()
+        .lazy()
+        .group_by([col("time"), col("panel")])
+        .agg([len()])
+        .collect()?
+        .height();
+    if n_pairs != df.height() {
+        return Err(PolarsError::ComputeError(
+            "Each (panel, time) pair must appear at most once.".into(),
+        ));
+    }
+    let period = df
+        .lazy()
+        .select([col("time")
+            .rank(
+                RankOptions {
+                    method: RankMethod::Dense,
+                    descending: false,
+                },
+                None,
+            )
+            .cast(DataType::UInt64)])
+        .collect()?;
+    let time_of = period.get_columns()[0]
+        .u64()?
+        .into_no_null_iter()
+        .map(|t| t as usize - 1)
+        .collect_vec();
+    let n_periods = time_of.iter().max().map_or(0, |t| t + 1);
+    let lags = kwargs
+        .lags
+        .unwrap_or_else(|| newey_west_default_lags(n_periods));
+
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    let res = faer_driscoll_kraay(x, y, &time_of, n_periods, lags, solver)
+        .map_err(|e| PolarsError::ComputeError(e.to_string().into()))?;
+
+    let k = x.ncols();
+    let dof = (n_periods - 1) as f64;
+    let betas = res.coefficients.col_as_slice(0);
+    let std_err = (0..k).map(|i| res.cov.get(i, i).sqrt()).collect_vec();
+    let t_values = betas
+        .iter()
+        .zip(std_err.iter())
+        .map(|(b, se)| b / se)
+        .collect_vec();
+    let p_values = t_values
+        .iter()
+        .map(
+            |t| match crate::stats_utils::beta::student_t_sf(t.abs(), dof) {
+                Ok(p) => 2.0 * p,
+                Err(_) => f64::NAN,
+            },
+        )
+        .collect_vec();
+    let mut cov: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("cov".into(), k, k * k, DataType::Float64);
+    for i in 0..k {
+        cov.append_slice(&(0..k).map(|j| *res.cov.get(i, j)).collect_vec());
+    }
+
+    let names_series = name_builder.finish().into_series();
+    let beta = Series::new("beta".into(), betas);
+    let dk_se = Series::from_vec("dk_se".into(), std_err);
+    let t = Series::from_vec("t".into(), t_values);
+    let p = Series::from_vec("p>|t|".into(), p_values);
+    let cov = cov.finish().into_series();
+    let out = StructChunked::from_series(
+        "driscoll_kraay".into(),
+        names_series.len(),
+        [&names_series, &beta, &dk_se, &t, &p, &cov].into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+#[polars_expr(output_type_func=sign_stability_output)]
+fn pl_coef_sign_stability(inputs: &[Series], kwargs: SignStabilityKwargs) -> PolarsResult<Series> {
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+    if kwargs.n_boot < 1 {
+        return Err(PolarsError::ComputeError(
+            "Number of bootstrap samples must be >= 1.".into(),
+        ));
+    }
+
+    let mut name_builder = StringChunkedBuilder::new(
+        "features".into(),
+        inputs.len().abs_diff(1) + (has_bias) as usize,
+    );
+    for s in inputs[1..].iter().map(|s| s.name()) {
+        name_builder.append_value(s);
+    }
+    if has_bias {
+        name_builder.append_value("__bias__");
+    }
+
+    let (mat, _) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+
+    let coeffs = faer_solve_lstsq(x, y, kwargs.l2_reg, has_bias, LRSolverMethods::QR);
+    let boot = faer_bootstrap_lstsq(x, y, kwargs.l2_reg, has_bias, kwargs.n_boot, kwargs.seed);
+    let agreement = sign_agreement(boot.as_ref(), coeffs.col_as_slice(0));
+
+    let names_series = name_builder.finish().into_series();
+    let beta = Series::from_vec("beta".into(), coeffs.col_as_slice(0).to_vec());
+    let agreement = Series::from_vec("sign_agreement".into(), agreement);
+    let out = StructChunked::from_series(
+        "sign_stability".into(),
+        names_series.len(),
+        [&names_series, &beta, &agreement].into_iter(),
+    )?;
+    if kwargs.bias_first && has_bias {
+        report_bias_first(out.into_series())
+    } else {
+        Ok(out.into_series())
+    }
+}
+
 // --- Rolling and Recursive
 
 #[polars_expr(output_type_func=coeff_pred_output)]
 fn pl_recursive_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
+    recursive_lstsq_output(inputs, kwargs)
+}
+
+/// Exponentially weighted recursive least squares. Same output as pl_recursive_lstsq. See faer_ewls.
+#[polars_expr(output_type_func=coeff_pred_output)]
+fn pl_ewls(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
+    let forget = kwargs.forget.unwrap_or(1.0);
+    if !(forget > 0. && forget <= 1.) {
+        return Err(PolarsError::ComputeError(
+            "Forgetting factor must be in (0, 1].".into(),
+        ));
+    }
+    recursive_lstsq_output(inputs, kwargs)
+}
+
+/// The coefficients and predictions of pl_recursive_lstsq and pl_ewls, which forgets if kwargs.forget
+/// is < 1.
+fn recursive_lstsq_output(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
     let n = kwargs.n; // Gauranteed n >= 1
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
 
     // Gauranteed in Python that this won't be SKIP. SKIP doesn't work now.
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
@@ -880,65 +4555,44 @@ fn pl_recursive_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult
             let x = mat.slice(s![.., 1..]).into_faer();
             let y = mat.slice(s![.., 0..1]).into_faer();
 
-            let coeffs = faer_recursive_lstsq(x, y, n, kwargs.lambda);
+            let coeffs = match kwargs.forget {
+                Some(forget) if forget < 1. => faer_ewls(x, y, n, kwargs.lambda, forget, has_bias),
+                _ => faer_recursive_lstsq(x, y, n, kwargs.lambda, has_bias),
+            };
+            let height = inputs[0].len();
             let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
                 ListPrimitiveChunkedBuilder::new(
                     "coeffs".into(),
-                    mat.nrows(),
+                    height,
                     mat.ncols(),
                     DataType::Float64,
                 );
             let mut pred_builder: PrimitiveChunkedBuilder<Float64Type> =
-                PrimitiveChunkedBuilder::new("pred".into(), mat.nrows());
+                PrimitiveChunkedBuilder::new("prediction".into(), height);
 
-            // Fill or Skip strategy can drop nulls. Fill will drop null when y has nulls.
-            // Skip will drop nulls whenever there is a null in the row.
-            // Mask true means the row doesn't have nulls
-            let has_nulls = (!&mask).any();
-            if has_nulls {
-                // Find the first index where we get n non-nulls.
-                let mut new_n = 0;
-                let mut m = 0;
-                for v in mask.into_no_null_iter() {
-                    new_n += v as usize;
-                    if new_n >= n {
-                        break;
+            // Fill strategy will drop the rows where y is null. Step i is the fit on the first
+            // n + i rows of x, so it belongs to row n - 1 + i of x. Its prediction is made on the
+            // features of the input row horizon rows later.
+            let rows = design_rows(&mask, height);
+            for (r, row) in rows.iter().enumerate() {
+                match row {
+                    Some(k) if k + 1 >= n => {
+                        let coefficients = &coeffs[k + 1 - n];
+                        builder.append_slice(coefficients.col_as_slice(0));
+                        match rows.get(r + kwargs.horizon).copied().flatten() {
+                            Some(j) => {
+                                let features = x.get(j..j + 1, ..);
+                                let pred = *(features * coefficients).get(0, 0);
+                                pred_builder.append_value(clip_prediction(pred, clip));
+                            }
+                            None => pred_builder.append_null(),
+                        }
                     }
-                    m += 1;
-                }
-                for _ in 0..m {
-                    builder.append_null();
-                    pred_builder.append_null();
-                }
-                let mut i = 0;
-                for should_keep in mask.into_no_null_iter().skip(m) {
-                    if should_keep {
-                        let coefficients = &coeffs[i];
-                        let row = x.get(i..i + 1, ..);
-                        let pred = *(row * coefficients).get(0, 0);
-                        let coef = coefficients.col_as_slice(0);
-                        builder.append_slice(coef);
-                        pred_builder.append_value(pred);
-                        i += 1;
-                    } else {
+                    _ => {
                         builder.append_null();
                         pred_builder.append_null();
                     }
                 }
-            } else {
-                // n always > 1, guaranteed in Python
-                let m = n.abs_diff(1);
-                for _ in 0..m {
-                    builder.append_null();
-                    pred_builder.append_null();
-                }
-                for (i, coefficients) in coeffs.into_iter().enumerate() {
-                    let row = x.get(m + i..m + i + 1, ..);
-                    let pred = *(row * &coefficients).get(0, 0);
-                    let coef = coefficients.col_as_slice(0);
-                    builder.append_slice(coef);
-                    pred_builder.append_value(pred);
-                }
             }
 
             let coef_out = builder.finish();
@@ -954,10 +4608,191 @@ fn pl_recursive_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult
     }
 }
 
+/// Same as pl_recursive_lstsq, but also returns the standard errors and t values of the coefficients
+/// at every step. See faer_recursive_lstsq_with_se for how the residual variance is tracked.
+#[polars_expr(output_type_func=recursive_inference_output)]
+fn pl_recursive_lstsq_inference(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
+    let n = kwargs.n; // Gauranteed n >= 1
+    let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() < n {
+        return Err(PolarsError::ComputeError(
+            "Not enough rows for the initial fit.".into(),
+        ));
+    }
+    let steps = faer_recursive_lstsq_with_se(x, y, n, kwargs.lambda, has_bias);
+
+    let height = inputs[0].len();
+    let rows = design_rows(&mask, height);
+    let mut coeff_builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("coeffs".into(), height, x.ncols(), DataType::Float64);
+    let mut se_builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("std_err".into(), height, x.ncols(), DataType::Float64);
+    let mut t_builder: ListPrimitiveChunkedBuilder<Float64Type> =
+        ListPrimitiveChunkedBuilder::new("t".into(), height, x.ncols(), DataType::Float64);
+    let mut pred_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("prediction".into(), height);
+
+    // Rows of x are the rows of the input where mask is true. Step i is the fit after the first
+    // n + i of them, so it belongs to row n - 1 + i of x. Its prediction is made on the features of
+    // the input row horizon rows later.
+    for (r, row) in rows.iter().enumerate() {
+        match row {
+            Some(k) if k + 1 >= n => {
+                let (coefficients, std_err) = &steps[k + 1 - n];
+                let coef = coefficients.col_as_slice(0);
+                let t_values = coef
+                    .iter()
+                    .zip(std_err.iter())
+                    .map(|(b, se)| b / se)
+                    .collect_vec();
+                coeff_builder.append_slice(coef);
+                se_builder.append_slice(std_err);
+                t_builder.append_slice(&t_values);
+                match rows.get(r + kwargs.horizon).copied().flatten() {
+                    Some(j) => {
+                        let pred = *(x.get(j..j + 1, ..) * coefficients).get(0, 0);
+                        pred_builder.append_value(clip_prediction(pred, clip));
+                    }
+                    None => pred_builder.append_null(),
+                }
+            }
+            _ => {
+                coeff_builder.append_null();
+                se_builder.append_null();
+                t_builder.append_null();
+                pred_builder.append_null();
+            }
+        }
+    }
+
+    let coef_out = coeff_builder.finish().into_series();
+    let se_out = se_builder.finish().into_series();
+    let t_out = t_builder.finish().into_series();
+    let pred_out = pred_builder.finish().into_series();
+    let ca = StructChunked::from_series(
+        "".into(),
+        height,
+        [&coef_out, &se_out, &t_out, &pred_out].into_iter(),
+    )?;
+    Ok(ca.into_series())
+}
+
+/// Expanding window out of sample predictions and residuals. The first n rows of the design only fit
+/// the initial model, and are null. See faer_expanding_predictions.
+#[polars_expr(output_type_func=expanding_output)]
+fn pl_expanding_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
+    let n = kwargs.n;
+    let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= n {
+        return Err(PolarsError::ComputeError(
+            "Not enough data for expanding window predictions.".into(),
+        ));
+    }
+    let preds = faer_expanding_predictions(x, y, n, kwargs.lambda, has_bias);
+
+    let height = inputs[0].len();
+    let mut pred_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("prediction".into(), height);
+    let mut resid_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("resid".into(), height);
+    for row in design_rows(&mask, height) {
+        match row {
+            Some(k) if k >= n => {
+                let pred = clip_prediction(preds[k - n], clip);
+                pred_builder.append_value(pred);
+                resid_builder.append_value(*y.get(k, 0) - pred);
+            }
+            _ => {
+                pred_builder.append_null();
+                resid_builder.append_null();
+            }
+        }
+    }
+    let out = StructChunked::from_series(
+        "expanding".into(),
+        height,
+        [
+            &pred_builder.finish().into_series(),
+            &resid_builder.finish().into_series(),
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
+/// Recursive residuals and their CUSUM, for the Brown-Durbin-Evans test of parameter stability. The first
+/// n rows of the design only fit the initial model, and are null. See faer_recursive_residuals.
+#[polars_expr(output_type_func=recursive_residuals_output)]
+fn pl_recursive_residuals(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
+    let n = kwargs.n;
+    let has_bias = kwargs.bias;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+        .map_err(|e| PolarsError::ComputeError(e.into()))?;
+
+    let (mat, mask) = series_to_mat_for_lstsq(inputs, has_bias, null_policy)?;
+    let x = mat.slice(s![.., 1..]).into_faer();
+    let y = mat.slice(s![.., 0..1]).into_faer();
+    if x.nrows() <= n {
+        return Err(PolarsError::ComputeError(
+            "Not enough data for recursive residuals.".into(),
+        ));
+    }
+    let w = faer_recursive_residuals(x, y, n);
+    let (cusum, bound) = recursive_cusum(&w);
+
+    let height = inputs[0].len();
+    let mut resid_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("recursive_resid".into(), height);
+    let mut cusum_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("cusum".into(), height);
+    let mut bound_builder: PrimitiveChunkedBuilder<Float64Type> =
+        PrimitiveChunkedBuilder::new("cusum_bound".into(), height);
+    for row in design_rows(&mask, height) {
+        match row {
+            Some(k) if k >= n => {
+                resid_builder.append_value(w[k - n]);
+                cusum_builder.append_value(cusum[k - n]);
+                bound_builder.append_value(bound[k - n]);
+            }
+            _ => {
+                resid_builder.append_null();
+                cusum_builder.append_null();
+                bound_builder.append_null();
+            }
+        }
+    }
+    let out = StructChunked::from_series(
+        "recursive_residuals".into(),
+        height,
+        [
+            &resid_builder.finish().into_series(),
+            &cusum_builder.finish().into_series(),
+            &bound_builder.finish().into_series(),
+        ]
+        .into_iter(),
+    )?;
+    Ok(out.into_series())
+}
+
 #[polars_expr(output_type_func=coeff_pred_output)] // They share the same output type
 fn pl_rolling_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
     let n = kwargs.n; // Gauranteed n >= 2
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
 
     let mut null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
@@ -979,9 +4814,9 @@ fn pl_rolling_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<S
             let x = mat.slice(s![.., 1..]).into_faer();
             let y = mat.slice(s![.., 0..1]).into_faer();
             let coeffs = if should_skip {
-                faer_rolling_skipping_lstsq(x, y, n, kwargs.min_size, kwargs.lambda)
+                faer_rolling_skipping_lstsq(x, y, n, kwargs.min_size, kwargs.lambda, has_bias)
             } else {
-                faer_rolling_lstsq(x, y, n, kwargs.lambda)
+                faer_rolling_lstsq(x, y, n, kwargs.lambda, has_bias)
             };
 
             let mut builder: ListPrimitiveChunkedBuilder<Float64Type> =
@@ -1008,7 +4843,7 @@ fn pl_rolling_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<S
                         pred_builder.append_null();
                     } else {
                         let row = x.get(m + i..m + i + 1, ..);
-                        let pred = *(row * &coefficients).get(0, 0);
+                        let pred = clip_prediction(*(row * &coefficients).get(0, 0), clip);
                         let coef = coefficients.col_as_slice(0);
                         builder.append_slice(coef);
                         pred_builder.append_value(pred);
@@ -1018,7 +4853,7 @@ fn pl_rolling_lstsq(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<S
                 // nothing is skipped. All coeffs must be valid.
                 for (i, coefficients) in coeffs.into_iter().enumerate() {
                     let row = x.get(m + i..m + i + 1, ..);
-                    let pred = *(row * &coefficients).get(0, 0);
+                    let pred = clip_prediction(*(row * &coefficients).get(0, 0), clip);
                     let coef = coefficients.col_as_slice(0);
                     builder.append_slice(coef);
                     pred_builder.append_value(pred);
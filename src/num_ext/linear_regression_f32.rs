@@ -1,4 +1,10 @@
-use super::linear_regression::{LstsqKwargs, MultiLstsqKwargs, SWWLstsqKwargs, StandardError};
+use super::linear_regression::{
+    add_offset, check_converged, clip_prediction, clip_predictions, coeffs_bias_first, design_rows,
+    lstsq_inputs, lstsq_prediction_variance, masked_weights, nan_for_skipped_rows, overall_f_test,
+    prediction_bounds, prediction_std_errors, rank_note, report_bias_first, report_ci_series,
+    residuals_with_offset, split_offset, total_sum_of_squares, LstsqKwargs, MultiLstsqKwargs,
+    SWWLstsqKwargs, StandardError,
+};
 /// A copy of linear_regression, but with f32.
 /// Unfortunately, it is not so easily to write generic functions. If I do so,
 /// there would be too many functions that have purpose that is not obvious by first sight.
@@ -8,18 +14,23 @@ use super::linear_regression::{LstsqKwargs, MultiLstsqKwargs, SWWLstsqKwargs, St
 /// but there are some exceptions, mainly from functions with weights. In those cases,
 /// a manual cast need to be used before calling .f64() or .f32() on the series.
 use crate::linalg::{
+    lr_diagnostics::{
+        durbin_watson, newey_west_default_lags, newey_west_long_run, standardized_coefficients,
+    },
     lr_online_solvers::{faer_recursive_lstsq, faer_rolling_lstsq, faer_rolling_skipping_lstsq},
     lr_solvers::{
-        faer_coordinate_descent, faer_solve_lstsq, faer_solve_lstsq_rcond, faer_weighted_lstsq,
+        faer_elastic_net, faer_matrix_rank, faer_residual_dof, faer_solve_lstsq,
+        faer_solve_lstsq_per_target, faer_solve_lstsq_rcond, faer_sqrt_weighted_rows,
+        faer_weighted_lstsq,
     },
-    IntoFaer, LRMethods,
+    IntoFaer, LRMethods, LassoAlgorithm, StopCriterion,
 };
 use crate::utils::{to_frame, NullPolicy};
 /// Least Squares using Faer and ndarray.
 use core::f32;
 use faer::{
     linalg::solvers::{DenseSolveCore, Solve},
-    Col,
+    Col, Mat,
 };
 use itertools::Itertools;
 use ndarray::{s, Array2};
@@ -30,17 +41,65 @@ use pyo3_polars::derive::polars_expr;
 fn report_output(_: &[Field]) -> PolarsResult<Field> {
     let features = Field::new("features".into(), DataType::String); // index of feature
     let beta = Field::new("beta".into(), DataType::Float32); // estimated value for this coefficient
+    let std_beta = Field::new("std_beta".into(), DataType::Float32); // beta * std(x) / std(y), NaN for the bias
     let stderr = Field::new("std_err".into(), DataType::Float32); // Std Err for this coefficient
     let t = Field::new("t".into(), DataType::Float32); // t value for this coefficient
     let p = Field::new("p>|t|".into(), DataType::Float32); // p value for this coefficient
-    let ci_lower = Field::new("0.025".into(), DataType::Float32); // CI lower bound at 0.025
-    let ci_upper = Field::new("0.975".into(), DataType::Float32); // CI upper bound at 0.975
+    let ci_lower_95 = Field::new("0.025".into(), DataType::Float32); // Deprecated, 95% CI lower bound
+    let ci_upper_95 = Field::new("0.975".into(), DataType::Float32); // Deprecated, 95% CI upper bound
+    let ci_lower = Field::new("ci_lower".into(), DataType::Float32); // CI lower bound at alpha / 2
+    let ci_upper = Field::new("ci_upper".into(), DataType::Float32); // CI upper bound at 1 - alpha / 2
     let r2 = Field::new("r2".into(), DataType::Float32); // Coefficient of determination
     let adj_r2 = Field::new("adj_r2".into(), DataType::Float32); // adjusted
-    let v: Vec<Field> = vec![features, beta, stderr, t, p, ci_lower, ci_upper, r2, adj_r2];
+    let resid_se = Field::new("resid_se".into(), DataType::Float32); // sqrt(RSS / df_resid)
+    let f_stat = Field::new("f_stat".into(), DataType::Float32); // all coefficients but the bias are 0
+    let f_pvalue = Field::new("f_pvalue".into(), DataType::Float32);
+    let df_resid = Field::new("df_resid".into(), DataType::Float32); // n - trace of the hat matrix
+    let n_obs = Field::new("n_obs".into(), DataType::UInt32); // rows used, after dropping nulls
+    let rank = Field::new("rank".into(), DataType::UInt32); // numerical rank of X, bias included
+                                                            // Of the residuals in row order
+    let durbin_watson = Field::new("durbin_watson".into(), DataType::Float32);
+    // Null unless X is rank deficient
+    let note = Field::new("note".into(), DataType::String);
+    let v: Vec<Field> = vec![
+        features,
+        beta,
+        std_beta,
+        stderr,
+        t,
+        p,
+        ci_lower_95,
+        ci_upper_95,
+        ci_lower,
+        ci_upper,
+        r2,
+        adj_r2,
+        resid_se,
+        f_stat,
+        f_pvalue,
+        df_resid,
+        n_obs,
+        rank,
+        durbin_watson,
+        note,
+    ];
     Ok(Field::new("lin_reg_report".into(), DataType::Struct(v)))
 }
 
+fn lstsq_pred_output(_: &[Field], kwargs: LstsqKwargs) -> PolarsResult<Field> {
+    let pred = Field::new("pred".into(), DataType::Float32);
+    let residue = Field::new("resid".into(), DataType::Float32);
+    let mut v = vec![pred, residue];
+    if kwargs.pred_interval {
+        v.push(Field::new("pred_lower".into(), DataType::Float32));
+        v.push(Field::new("pred_upper".into(), DataType::Float32));
+    }
+    if kwargs.pred_se {
+        v.push(Field::new("pred_se".into(), DataType::Float32));
+    }
+    Ok(Field::new("pred".into(), DataType::Struct(v)))
+}
+
 fn pred_residue_output(_: &[Field]) -> PolarsResult<Field> {
     let pred = Field::new("pred".into(), DataType::Float32);
     let residue = Field::new("resid".into(), DataType::Float32);
@@ -159,6 +218,11 @@ fn series_to_mat_for_lstsq_f32(
         Ok((df, mask))
     }?;
 
+    if df.height() == 0 {
+        return Err(PolarsError::ComputeError(
+            "No complete rows left after dropping rows with nulls.".into(),
+        ));
+    }
     if df.height() < n_features {
         Err(PolarsError::ComputeError(
             "#Data < #features. No conclusive result.".into(),
@@ -230,7 +294,18 @@ fn series_to_mat_for_multi_lstsq_f32(
 
 #[polars_expr(output_type_func=coeff_output)]
 fn pl_lstsq_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let binding = lstsq_inputs(inputs, &kwargs)?;
+    lstsq_f32(binding.as_ref(), kwargs)
+}
+
+/// pl_lstsq in f32. This is also where pl_lstsq goes when all of its data columns are Float32.
+pub(crate) fn lstsq_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
+    let unpenalized_bias = kwargs.unpenalized_bias();
+    let penalty_factor = kwargs
+        .penalty_factor
+        .as_ref()
+        .map(|f| f.iter().map(|v| *v as f32).collect::<Vec<_>>());
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
@@ -239,35 +314,56 @@ fn pl_lstsq_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series>
     let data_for_matrix = if weighted { &inputs[1..] } else { inputs };
 
     match series_to_mat_for_lstsq_f32(data_for_matrix, has_bias, null_policy) {
-        Ok((mat, _)) => {
+        Ok((mat, mask)) => {
+            let (mat, _) = split_offset(mat, kwargs.has_offset);
             // Solving Least Square
             let x = mat.slice(s![.., 1..]).into_faer();
             let y = mat.slice(s![.., 0..1]).into_faer();
-            let coeffs = if weighted {
-                let binding = inputs[0].cast(&DataType::Float32)?;
-                let weights = binding.f32().unwrap();
-                let weights = weights.cont_slice().unwrap();
-                if weights.len() != mat.nrows() {
-                    return Err(PolarsError::ComputeError(
-                        "Shape of weights is not the same as the data.".into(),
-                    ));
-                }
-                faer_weighted_lstsq(x, y, weights, solver)
+            let binding = if weighted {
+                Some(masked_weights(
+                    &inputs[0],
+                    &mask,
+                    mat.nrows(),
+                    &DataType::Float32,
+                )?)
             } else {
-                match LRMethods::from((kwargs.l1_reg, kwargs.l2_reg)) {
-                    LRMethods::Normal | LRMethods::L2 => {
-                        faer_solve_lstsq(x, y, kwargs.l2_reg as f32, has_bias, solver)
-                    }
-
-                    LRMethods::L1 | LRMethods::ElasticNet => faer_coordinate_descent(
+                None
+            };
+            let weights = binding
+                .as_ref()
+                .map(|w| w.f32().unwrap().cont_slice().unwrap());
+            let method = LRMethods::from((kwargs.l1_reg, kwargs.l2_reg));
+            // Ridge takes the weights by scaling the rows by sqrt(w). Lasso and Elastic Net take them
+            // directly, since their loss is normalized by the total weight.
+            let scaled = match (weights, method) {
+                (Some(w), LRMethods::L2) => Some(faer_sqrt_weighted_rows(x, y, w)),
+                _ => None,
+            };
+            let (xs, ys) = match &scaled {
+                Some((xw, yw)) => (xw.as_ref(), yw.as_ref()),
+                None => (x, y),
+            };
+            let coeffs = match (weights, method) {
+                (Some(w), LRMethods::Normal) => faer_weighted_lstsq(x, y, w, solver),
+                (_, LRMethods::Normal | LRMethods::L2) => {
+                    faer_solve_lstsq(xs, ys, kwargs.l2_reg as f32, unpenalized_bias, solver)
+                }
+                (_, LRMethods::L1 | LRMethods::ElasticNet) => {
+                    let (coeffs, _, converged) = faer_elastic_net(
                         x,
                         y,
+                        weights,
                         kwargs.l1_reg as f32,
                         kwargs.l2_reg as f32,
-                        has_bias,
+                        penalty_factor.as_deref(),
+                        unpenalized_bias,
                         kwargs.tol as f32,
-                        2000,
-                    ),
+                        kwargs.max_iter,
+                        StopCriterion::from(kwargs.stop_criterion.as_str()),
+                        LassoAlgorithm::from(kwargs.algorithm.as_str()),
+                    );
+                    check_converged(converged, kwargs.strict)?;
+                    coeffs
                 }
             };
             let mut builder: ListPrimitiveChunkedBuilder<Float32Type> =
@@ -278,7 +374,11 @@ fn pl_lstsq_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series>
                     DataType::Float32,
                 );
 
-            builder.append_slice(coeffs.col_as_slice(0));
+            builder.append_slice(&coeffs_bias_first(
+                coeffs.col_as_slice(0),
+                has_bias,
+                kwargs.bias_first,
+            ));
             let out = builder.finish();
             Ok(out.into_series())
         }
@@ -293,6 +393,7 @@ fn pl_lstsq_multi_f32(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResu
     let has_bias = kwargs.bias;
     let solver = kwargs.solver.as_str().into();
     let last_target_idx = kwargs.last_target_idx;
+    let l2_regs = kwargs.per_target_l2(last_target_idx)?;
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
@@ -305,18 +406,23 @@ fn pl_lstsq_multi_f32(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResu
     let y = mat.slice(s![.., 0..last_target_idx]).into_faer();
     let x = mat.slice(s![.., last_target_idx..]).into_faer();
 
-    let coeffs = match LRMethods::from((0., kwargs.l2_reg)) {
-        LRMethods::Normal | LRMethods::L2 => Ok(faer_solve_lstsq(
-            x,
-            y,
-            kwargs.l2_reg as f32,
-            has_bias,
-            solver,
-        )),
-        _ => Err(PolarsError::ComputeError(
-            "The method is not supported.".into(),
-        )),
-    }?;
+    let coeffs = if let Some(lambdas) = l2_regs {
+        let lambdas = lambdas.into_iter().map(|l| l as f32).collect_vec();
+        faer_solve_lstsq_per_target(x, y, &lambdas, has_bias, solver)
+    } else {
+        match LRMethods::from((0., kwargs.l2_reg)) {
+            LRMethods::Normal | LRMethods::L2 => Ok(faer_solve_lstsq(
+                x,
+                y,
+                kwargs.l2_reg as f32,
+                has_bias,
+                solver,
+            )),
+            _ => Err(PolarsError::ComputeError(
+                "The method is not supported.".into(),
+            )),
+        }?
+    };
 
     let df_out = DataFrame::new(
         y_names
@@ -344,8 +450,10 @@ fn pl_lstsq_multi_f32(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResu
 #[polars_expr(output_type_func=pred_residue_output)]
 fn pl_lstsq_multi_pred_f32(inputs: &[Series], kwargs: MultiLstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
     let solver = kwargs.solver.as_str().into();
     let last_target_idx = kwargs.last_target_idx;
+    let l2_regs = kwargs.per_target_l2(last_target_idx)?;
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
@@ -358,20 +466,26 @@ fn pl_lstsq_multi_pred_f32(inputs: &[Series], kwargs: MultiLstsqKwargs) -> Polar
     let y = mat.slice(s![.., 0..last_target_idx]).into_faer();
     let x = mat.slice(s![.., last_target_idx..]).into_faer();
 
-    let coeffs = match LRMethods::from((0., kwargs.l2_reg)) {
-        LRMethods::Normal | LRMethods::L2 => Ok(faer_solve_lstsq(
-            x,
-            y,
-            kwargs.l2_reg as f32,
-            has_bias,
-            solver,
-        )),
-        _ => Err(PolarsError::ComputeError(
-            "The method is not supported.".into(),
-        )),
-    }?;
+    let coeffs = if let Some(lambdas) = l2_regs {
+        let lambdas = lambdas.into_iter().map(|l| l as f32).collect_vec();
+        faer_solve_lstsq_per_target(x, y, &lambdas, has_bias, solver)
+    } else {
+        match LRMethods::from((0., kwargs.l2_reg)) {
+            LRMethods::Normal | LRMethods::L2 => Ok(faer_solve_lstsq(
+                x,
+                y,
+                kwargs.l2_reg as f32,
+                has_bias,
+                solver,
+            )),
+            _ => Err(PolarsError::ComputeError(
+                "The method is not supported.".into(),
+            )),
+        }?
+    };
 
-    let pred = x * &coeffs;
+    let mut pred = x * &coeffs;
+    clip_predictions(&mut pred, clip);
     let resid = y - &pred;
 
     let mut s = Vec::with_capacity(y_names.len() * 2);
@@ -398,7 +512,7 @@ fn pl_lstsq_w_rcond_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<
         Ok((mat, _)) => {
             // rcond will be passed as tol
             let rcond =
-                (kwargs.tol as f32).max(f32::EPSILON * (inputs.len().max(mat.len())) as f32);
+                (kwargs.tol as f32).max(f32::EPSILON * (mat.nrows().max(mat.ncols() - 1)) as f32);
 
             // Solving Least Square
             let x = mat.slice(s![.., 1..]).into_faer();
@@ -416,7 +530,11 @@ fn pl_lstsq_w_rcond_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<
                     DataType::Float32,
                 );
 
-            builder.append_slice(coeffs.col_as_slice(0));
+            builder.append_slice(&coeffs_bias_first(
+                coeffs.col_as_slice(0),
+                has_bias,
+                kwargs.bias_first,
+            ));
             let coeffs_ca = builder.finish();
 
             let mut sv_builder: ListPrimitiveChunkedBuilder<Float32Type> =
@@ -441,10 +559,22 @@ fn pl_lstsq_w_rcond_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<
     }
 }
 
-#[polars_expr(output_type_func=pred_residue_output)]
+#[polars_expr(output_type_func_with_kwargs=lstsq_pred_output)]
 fn pl_lstsq_pred_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
+    let binding = lstsq_inputs(inputs, &kwargs)?;
+    lstsq_pred_f32(binding.as_ref(), kwargs)
+}
+
+/// pl_lstsq_pred in f32. This is also where pl_lstsq_pred goes when all of its data columns are Float32.
+pub(crate) fn lstsq_pred_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
-    let null_policy = NullPolicy::try_from(kwargs.null_policy)
+    let unpenalized_bias = kwargs.unpenalized_bias();
+    let penalty_factor = kwargs
+        .penalty_factor
+        .as_ref()
+        .map(|f| f.iter().map(|v| *v as f32).collect::<Vec<_>>());
+    let clip = kwargs.clip;
+    let null_policy = NullPolicy::try_from(kwargs.null_policy.clone())
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
     let solver = kwargs.solver.as_str().into();
@@ -453,37 +583,72 @@ fn pl_lstsq_pred_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
 
     match series_to_mat_for_lstsq_f32(data_for_matrix, has_bias, null_policy.clone()) {
         Ok((mat, mask)) => {
+            let (mat, offset) = split_offset(mat, kwargs.has_offset);
             let y = mat.slice(s![.., 0..1]).into_faer();
             let x = mat.slice(s![.., 1..]).into_faer();
-            let coeffs = if weighted {
-                let binding = inputs[0].cast(&DataType::Float32)?;
-                let weights = binding.f32().unwrap();
-                let weights = weights.cont_slice().unwrap();
-                if weights.len() != mat.nrows() {
-                    return Err(PolarsError::ComputeError(
-                        "Length of weights and data in X must be the same.".into(),
-                    ));
-                }
-                faer_weighted_lstsq(x, y, weights, solver)
+            let binding = if weighted {
+                Some(masked_weights(
+                    &inputs[0],
+                    &mask,
+                    mat.nrows(),
+                    &DataType::Float32,
+                )?)
             } else {
-                match LRMethods::from((kwargs.l1_reg, kwargs.l2_reg)) {
-                    LRMethods::Normal | LRMethods::L2 => {
-                        faer_solve_lstsq(x, y, kwargs.l2_reg as f32, has_bias, solver)
-                    }
-                    LRMethods::L1 | LRMethods::ElasticNet => faer_coordinate_descent(
+                None
+            };
+            let weights = binding
+                .as_ref()
+                .map(|w| w.f32().unwrap().cont_slice().unwrap());
+            let method = LRMethods::from((kwargs.l1_reg, kwargs.l2_reg));
+            // Ridge takes the weights by scaling the rows by sqrt(w). Lasso and Elastic Net take them
+            // directly, since their loss is normalized by the total weight.
+            let scaled = match (weights, method) {
+                (Some(w), LRMethods::L2) => Some(faer_sqrt_weighted_rows(x, y, w)),
+                _ => None,
+            };
+            let (xs, ys) = match &scaled {
+                Some((xw, yw)) => (xw.as_ref(), yw.as_ref()),
+                None => (x, y),
+            };
+            let coeffs = match (weights, method) {
+                (Some(w), LRMethods::Normal) => faer_weighted_lstsq(x, y, w, solver),
+                (_, LRMethods::Normal | LRMethods::L2) => {
+                    faer_solve_lstsq(xs, ys, kwargs.l2_reg as f32, unpenalized_bias, solver)
+                }
+                (_, LRMethods::L1 | LRMethods::ElasticNet) => {
+                    let (coeffs, _, converged) = faer_elastic_net(
                         x,
                         y,
+                        weights,
                         kwargs.l1_reg as f32,
                         kwargs.l2_reg as f32,
-                        has_bias,
+                        penalty_factor.as_deref(),
+                        unpenalized_bias,
                         kwargs.tol as f32,
-                        2000,
-                    ),
+                        kwargs.max_iter,
+                        StopCriterion::from(kwargs.stop_criterion.as_str()),
+                        LassoAlgorithm::from(kwargs.algorithm.as_str()),
+                    );
+                    check_converged(converged, kwargs.strict)?;
+                    coeffs
                 }
             };
 
-            let pred = x * &coeffs;
-            let resid = y - &pred;
+            let mut pred = x * &coeffs;
+            add_offset(&mut pred, offset.as_deref());
+            let pv = lstsq_prediction_variance(x, y, &kwargs)?;
+            let bounds = match &pv {
+                Some(pv) if kwargs.pred_interval => {
+                    Some(prediction_bounds(pv, pred.as_ref(), &kwargs))
+                }
+                _ => None,
+            };
+            let pred_se = match &pv {
+                Some(pv) if kwargs.pred_se => Some(prediction_std_errors(pv)),
+                _ => None,
+            };
+            clip_predictions(&mut pred, clip);
+            let resid = residuals_with_offset(y, pred.as_ref(), offset.as_deref());
             let pred = pred.col_as_slice(0);
             let resid = resid.col_as_slice(0);
             // If null policy is raise and we have nulls, we won't reach here
@@ -513,9 +678,15 @@ fn pl_lstsq_pred_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
                 let residue = Float32Chunked::from_slice("resid".into(), resid);
                 (pred, residue)
             };
-            let p = p.into_series();
-            let r = r.into_series();
-            let out = StructChunked::from_series("".into(), p.len(), [&p, &r].into_iter())?;
+            let mut fields = vec![p.into_series(), r.into_series()];
+            if let Some((lower, upper)) = bounds {
+                fields.push(nan_for_skipped_rows("pred_lower", &lower, &mask));
+                fields.push(nan_for_skipped_rows("pred_upper", &upper, &mask));
+            }
+            if let Some(se) = pred_se {
+                fields.push(nan_for_skipped_rows("pred_se", &se, &mask));
+            }
+            let out = StructChunked::from_series("".into(), fields[0].len(), fields.iter())?;
             Ok(out.into_series())
         }
         Err(e) => Err(e),
@@ -528,15 +699,12 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
     let se_type = StandardError::from(kwargs.std_err);
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
-    // index 0 is y_var, 1 is target y. Skip
-    let binding = inputs[0].cast(&DataType::Float32)?;
-    let y_var = binding.f32().unwrap();
-    let y_var = y_var.get(0).unwrap_or(f32::NAN);
+    // index 0 is target y. Skip
     let mut name_builder = StringChunkedBuilder::new(
         "features".into(),
-        inputs.len().abs_diff(2) + (has_bias) as usize,
+        inputs.len().abs_diff(1) + (has_bias) as usize,
     );
-    for s in inputs[2..].iter().map(|s| s.name()) {
+    for s in inputs[1..].iter().map(|s| s.name()) {
         name_builder.append_value(s);
     }
     if has_bias {
@@ -544,38 +712,47 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
     }
     // Copy data
     // Target y is at index 0
-    match series_to_mat_for_lstsq_f32(&inputs[1..], has_bias, null_policy) {
+    match series_to_mat_for_lstsq_f32(inputs, has_bias, null_policy) {
         Ok((mat, _)) => {
             let ncols = mat.ncols() - 1;
             let nrows = mat.nrows();
 
             let x = mat.slice(s![0..nrows, 1..]).into_faer();
             let y = mat.slice(s![0..nrows, 0..1]).into_faer();
-            // Solving Least Square
+            // Solving Least Square. For Ridge, xtx_inv is the inverse of the penalized XtX.
             let xtx = x.transpose() * &x;
-            let xtx_qr = xtx.col_piv_qr();
-            let xtx_inv = xtx_qr.inverse();
+            let mut penalized = xtx.clone();
+            for i in 0..ncols.abs_diff(has_bias as usize) {
+                *penalized.get_mut(i, i) = *penalized.get(i, i) + kwargs.l2_reg as f32;
+            }
+            let xtx_inv = penalized.col_piv_qr().inverse();
             let xtx_inv_xt = &xtx_inv * x.transpose();
             let coeffs = &xtx_inv_xt * y;
             let betas = coeffs.col_as_slice(0);
-            // Degree of Freedom
-            let dof = nrows as f32 - ncols as f32;
+            // Degree of Freedom, n - trace of the hat matrix. This is n - p without Ridge.
+            let dof = faer_residual_dof(nrows as f32, xtx.as_ref(), xtx_inv.as_ref());
             // Residue
             let res = y - x * &coeffs;
 
-            // r2, adj_r2
-            let nf32 = nrows as f32;
-            let ratio = res.col(0).squared_norm_l2() / (y_var * nf32);
+            // r2, adj_r2 and the residual standard error. Same as statsmodels: the total sum of
+            // squares is uncentered without a bias, and adj_r2 = 1 - (1 - r2) * (n - has_bias) / dof.
+            let rss = res.col(0).squared_norm_l2();
+            let tss = total_sum_of_squares(y.col(0), None, has_bias);
+            let ratio = rss / tss;
             let r2 = 1.0 - ratio;
-            let adj_r2 = 1.0 - ratio * ((nrows - 1) as f32 / (dof - 1.0));
+            let adj_r2 = 1.0 - ratio * ((nrows - has_bias as usize) as f32 / dof);
+            let resid_se = (rss / dof).sqrt();
+            let (f_stat, f_pvalue) = overall_f_test(tss, rss, ncols - has_bias as usize, dof);
 
             // std err
             let std_err = match se_type {
                 StandardError::SE => {
                     // total residue, sum of squares
                     let mse = (res.transpose() * &res).get(0, 0) / dof;
+                    // Var(b) = mse * A^-1 XtX A^-1, which is mse * (XtX)^-1 without Ridge
+                    let cov = &xtx_inv * &xtx * &xtx_inv;
                     (0..ncols)
-                        .map(|i| (mse * xtx_inv.get(i, i)).sqrt())
+                        .map(|i| (mse * cov.get(i, i)).sqrt())
                         .collect_vec()
                 }
                 StandardError::HC0 | StandardError::HC1 => {
@@ -583,7 +760,7 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
                     let diag = temp_diag * temp_diag;
                     let var_hc = &xtx_inv_xt * diag * xtx_inv_xt.transpose();
                     let factor = if se_type == StandardError::HC1 {
-                        (nrows as f32) / ((nrows - ncols) as f32)
+                        (nrows as f32) / dof
                     } else {
                         1f32
                     };
@@ -608,6 +785,16 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
                     let var_hc = &xtx_inv_xt * diag * xtx_inv_xt.transpose();
                     (0..ncols).map(|i| (var_hc.get(i, i)).sqrt()).collect_vec()
                 }
+                StandardError::NeweyWest => {
+                    // Newey-West: the scores x_i * r_i are autocorrelated, and the rows are in time order
+                    let lags = kwargs
+                        .hac_lags
+                        .unwrap_or_else(|| newey_west_default_lags(nrows));
+                    let scores = Mat::from_fn(nrows, ncols, |i, j| *x.get(i, j) * *res.get(i, 0));
+                    let long_run = newey_west_long_run(scores.as_ref(), lags);
+                    let var_hac = &xtx_inv * long_run * &xtx_inv;
+                    (0..ncols).map(|i| (var_hac.get(i, i)).sqrt()).collect_vec()
+                }
             };
             // T values
             let t_values = betas
@@ -626,54 +813,72 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
                 )
                 .collect_vec();
 
-            let t_alpha = crate::stats_utils::beta::student_t_ppf(0.975, dof.into()) as f32;
-            let ci_lower = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b - t_alpha * se)
-                .collect_vec();
-            let ci_upper = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b + t_alpha * se)
-                .collect_vec();
+            let [lower_95, upper_95, lower, upper] =
+                report_ci_series(betas, &std_err, dof.into(), kwargs.alpha);
 
             // Finalize
             let names_ca = name_builder.finish();
             let names_series = names_ca.into_series();
             let coeffs_series = Float32Chunked::from_slice("beta".into(), betas);
             let coeffs_series = coeffs_series.into_series();
+            let std_beta = standardized_coefficients(x, y, betas, None, has_bias);
+            let std_beta_series =
+                Float32Chunked::from_vec("std_beta".into(), std_beta).into_series();
             let stderr_series = Float32Chunked::from_vec(se_type.into(), std_err);
             let stderr_series = stderr_series.into_series();
             let t_series = Float32Chunked::from_vec("t".into(), t_values);
             let t_series = t_series.into_series();
             let p_series = Float32Chunked::from_vec("p>|t|".into(), p_values);
             let p_series = p_series.into_series();
-            let lower = Float32Chunked::from_vec("0.025".into(), ci_lower);
-            let lower = lower.into_series();
-            let upper = Float32Chunked::from_vec("0.975".into(), ci_upper);
-            let upper = upper.into_series();
             let r2 = Float32Chunked::from_vec("r2".into(), vec![r2]);
             let r2_series = r2.into_series();
             let adj_r2 = Float32Chunked::from_vec("adj_r2".into(), vec![adj_r2]);
             let adj_r2_series = adj_r2.into_series();
+            let resid_se_series = Series::from_vec("resid_se".into(), vec![resid_se]);
+            let f_stat_series = Series::from_vec("f_stat".into(), vec![f_stat]);
+            let f_pvalue_series = Series::from_vec("f_pvalue".into(), vec![f_pvalue]);
+            let df_resid = Float32Chunked::from_vec("df_resid".into(), vec![dof]);
+            let df_resid_series = df_resid.into_series();
+            let n_obs_series = Series::from_vec("n_obs".into(), vec![nrows as u32]);
+            let rank = faer_matrix_rank(x, None);
+            let rank_series = Series::from_vec("rank".into(), vec![rank as u32]);
+            let note_series = Series::new("note".into(), [rank_note(rank, ncols)]);
+            let dw_series = Series::from_vec(
+                "durbin_watson".into(),
+                vec![durbin_watson(res.col_as_slice(0))],
+            );
             let out = StructChunked::from_series(
                 "lin_reg_report".into(),
                 names_series.len(),
                 [
                     &names_series,
                     &coeffs_series,
+                    &std_beta_series,
                     &stderr_series,
                     &t_series,
                     &p_series,
+                    &lower_95,
+                    &upper_95,
                     &lower,
                     &upper,
                     &r2_series,
                     &adj_r2_series,
+                    &resid_se_series,
+                    &f_stat_series,
+                    &f_pvalue_series,
+                    &df_resid_series,
+                    &n_obs_series,
+                    &rank_series,
+                    &dw_series,
+                    &note_series,
                 ]
                 .into_iter(),
             )?;
-            Ok(out.into_series())
+            if kwargs.bias_first && has_bias {
+                report_bias_first(out.into_series())
+            } else {
+                Ok(out.into_series())
+            }
         }
         Err(e) => Err(e),
     }
@@ -682,30 +887,28 @@ fn pl_lin_reg_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult
 #[polars_expr(output_type_func=report_output)]
 fn pl_wls_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Series> {
     let has_bias = kwargs.bias;
+    let frequency = kwargs.weight_type == "frequency";
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
 
-    let binding = inputs[0].cast(&DataType::Float32)?;
-    let weights = binding.f32().unwrap();
-    let weights = weights.cont_slice().unwrap();
-    let binding2 = inputs[1].cast(&DataType::Float32)?;
-    let y_var = binding2.f32().unwrap();
-    let y_var = y_var.get(0).unwrap_or(f32::NAN);
-    // index 0 is weights, 1 is y_var, 2 is target y. Skip them
+    // index 0 is weights, 1 is target y. Skip them
     let mut name_builder = StringChunkedBuilder::new(
         "features".into(),
-        inputs.len().abs_diff(3) + (has_bias) as usize,
+        inputs.len().abs_diff(2) + (has_bias) as usize,
     );
-    for s in inputs[3..].iter().map(|s| s.name()) {
+    for s in inputs[2..].iter().map(|s| s.name()) {
         name_builder.append_value(s);
     }
     if has_bias {
         name_builder.append_value("__bias__");
     }
     // Copy data
-    // Target y is at index 2, weights at index 0
-    match series_to_mat_for_lstsq_f32(&inputs[2..], has_bias, null_policy) {
-        Ok((mat, _)) => {
+    // Target y is at index 1, weights at index 0
+    match series_to_mat_for_lstsq_f32(&inputs[1..], has_bias, null_policy) {
+        Ok((mat, mask)) => {
+            // The weights of the skipped rows are dropped too
+            let binding = masked_weights(&inputs[0], &mask, mat.nrows(), &DataType::Float32)?;
+            let weights = binding.f32().unwrap().cont_slice().unwrap();
             let ncols = mat.ncols() - 1;
             let nrows = mat.nrows();
 
@@ -719,27 +922,44 @@ fn pl_wls_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
 
             let xtwx = xt * w * x;
             let xtwy = xt * w * y;
-            let qr = xtwx.col_piv_qr();
+            // For Ridge, xtwx_inv is the inverse of the penalized XtWX
+            let mut penalized = xtwx.clone();
+            for i in 0..ncols.abs_diff(has_bias as usize) {
+                *penalized.get_mut(i, i) = *penalized.get(i, i) + kwargs.l2_reg as f32;
+            }
+            let qr = penalized.col_piv_qr();
             let xtwx_inv = qr.inverse();
             let coeffs = qr.solve(xtwy);
             let betas = coeffs.col_as_slice(0);
 
-            // Degree of Freedom
-            let dof = nrows as f32 - ncols as f32;
             // Residue
             let res = y - x * &coeffs;
-            let mse =
-                (0..y.nrows()).fold(0., |acc, i| acc + weights[i] * res.get(i, 0).powi(2)) / dof;
-
-            // r2, adj_r2
-            let nf32 = nrows as f32;
-            let ratio = res.col(0).squared_norm_l2() / (y_var * nf32);
+            let wssr = (0..y.nrows()).fold(0., |acc, i| acc + weights[i] * res.get(i, 0).powi(2));
+            // Analytic weights: n = #rows, mse = sum(w * r^2) / (n - p)
+            // Frequency weights: each row stands for w rows, so n = sum(w), mse = sum(w * r^2) / (sum(w) - p),
+            // and r2 is computed on the expanded data.
+            let nf32 = if frequency {
+                weights[..nrows].iter().sum::<f32>()
+            } else {
+                nrows as f32
+            };
+            // Same as statsmodels WLS, r2 is measured against the weighted total sum of squares
+            let wsst = total_sum_of_squares(y.col(0), Some(&weights[..nrows]), has_bias);
+            let ratio = wssr / wsst;
+            // Degree of Freedom, n - trace of the hat matrix. This is n - p without Ridge.
+            let dof = faer_residual_dof(nf32, xtwx.as_ref(), xtwx_inv.as_ref());
+            let mse = wssr / dof;
+            let cov = &xtwx_inv * &xtwx * &xtwx_inv;
+
+            // r2, adj_r2 and the residual standard error
             let r2 = 1.0 - ratio;
-            let adj_r2 = 1.0 - ratio * ((nrows - 1) as f32 / (dof - 1.0));
+            let adj_r2 = 1.0 - ratio * ((nf32 - has_bias as usize as f32) / dof);
+            let resid_se = mse.sqrt();
+            let (f_stat, f_pvalue) = overall_f_test(wsst, wssr, ncols - has_bias as usize, dof);
 
             // std err
             let std_err = (0..ncols)
-                .map(|i| (mse * xtwx_inv.get(i, i)).sqrt())
+                .map(|i| (mse * cov.get(i, i)).sqrt())
                 .collect_vec();
             // T values
             let t_values = betas
@@ -758,53 +978,74 @@ fn pl_wls_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
                 )
                 .collect_vec();
 
-            let t_alpha = crate::stats_utils::beta::student_t_ppf(0.975, dof.into()) as f32;
-            let ci_lower = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b - t_alpha * se)
-                .collect_vec();
-            let ci_upper = betas
-                .iter()
-                .zip(std_err.iter())
-                .map(|(b, se)| b + t_alpha * se)
-                .collect_vec();
+            let [lower_95, upper_95, lower, upper] =
+                report_ci_series(betas, &std_err, dof.into(), kwargs.alpha);
             // Finalize
             let names_ca = name_builder.finish();
             let names_series = names_ca.into_series();
             let coeffs_series = Float32Chunked::from_slice("beta".into(), betas);
             let coeffs_series = coeffs_series.into_series();
+            let std_beta =
+                standardized_coefficients(x, y, betas, Some(&weights[..nrows]), has_bias);
+            let std_beta_series =
+                Float32Chunked::from_vec("std_beta".into(), std_beta).into_series();
             let stderr_series = Float32Chunked::from_vec("std_err".into(), std_err);
             let stderr_series = stderr_series.into_series();
             let t_series = Float32Chunked::from_vec("t".into(), t_values);
             let t_series = t_series.into_series();
             let p_series = Float32Chunked::from_vec("p>|t|".into(), p_values);
             let p_series = p_series.into_series();
-            let lower = Float32Chunked::from_vec("0.025".into(), ci_lower);
-            let lower = lower.into_series();
-            let upper = Float32Chunked::from_vec("0.975".into(), ci_upper);
-            let upper = upper.into_series();
             let r2 = Float32Chunked::from_vec("r2".into(), vec![r2]);
             let r2_series = r2.into_series();
             let adj_r2 = Float32Chunked::from_vec("adj_r2".into(), vec![adj_r2]);
             let adj_r2_series = adj_r2.into_series();
+            let resid_se_series = Series::from_vec("resid_se".into(), vec![resid_se]);
+            let f_stat_series = Series::from_vec("f_stat".into(), vec![f_stat]);
+            let f_pvalue_series = Series::from_vec("f_pvalue".into(), vec![f_pvalue]);
+            let df_resid = Float32Chunked::from_vec("df_resid".into(), vec![dof]);
+            let df_resid_series = df_resid.into_series();
+            let n_obs_series = Series::from_vec("n_obs".into(), vec![nrows as u32]);
+            let rank = faer_matrix_rank(x, None);
+            let rank_series = Series::from_vec("rank".into(), vec![rank as u32]);
+            let note_series = Series::new("note".into(), [rank_note(rank, ncols)]);
+            // On the weighted residuals sqrt(w) * r
+            let weighted_res = (0..nrows)
+                .map(|i| weights[i].sqrt() * *res.get(i, 0))
+                .collect_vec();
+            let dw_series =
+                Series::from_vec("durbin_watson".into(), vec![durbin_watson(&weighted_res)]);
             let out = StructChunked::from_series(
                 "lin_reg_report".into(),
                 names_series.len(),
                 [
                     &names_series,
                     &coeffs_series,
+                    &std_beta_series,
                     &stderr_series,
                     &t_series,
                     &p_series,
+                    &lower_95,
+                    &upper_95,
                     &lower,
                     &upper,
                     &r2_series,
                     &adj_r2_series,
+                    &resid_se_series,
+                    &f_stat_series,
+                    &f_pvalue_series,
+                    &df_resid_series,
+                    &n_obs_series,
+                    &rank_series,
+                    &dw_series,
+                    &note_series,
                 ]
                 .into_iter(),
             )?;
-            Ok(out.into_series())
+            if kwargs.bias_first && has_bias {
+                report_bias_first(out.into_series())
+            } else {
+                Ok(out.into_series())
+            }
         }
         Err(e) => Err(e),
     }
@@ -816,6 +1057,7 @@ fn pl_wls_report_f32(inputs: &[Series], kwargs: LstsqKwargs) -> PolarsResult<Ser
 fn pl_recursive_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
     let n = kwargs.n; // Gauranteed n >= 1
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
 
     // Gauranteed in Python that this won't be SKIP. SKIP doesn't work now.
     let null_policy = NullPolicy::try_from(kwargs.null_policy)
@@ -828,65 +1070,41 @@ fn pl_recursive_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsRe
             let x = mat.slice(s![.., 1..]).into_faer();
             let y = mat.slice(s![.., 0..1]).into_faer();
 
-            let coeffs = faer_recursive_lstsq(x, y, n, kwargs.lambda as f32);
+            let coeffs = faer_recursive_lstsq(x, y, n, kwargs.lambda as f32, has_bias);
+            let height = inputs[0].len();
             let mut builder: ListPrimitiveChunkedBuilder<Float32Type> =
                 ListPrimitiveChunkedBuilder::new(
                     "coeffs".into(),
-                    mat.nrows(),
+                    height,
                     mat.ncols(),
                     DataType::Float32,
                 );
             let mut pred_builder: PrimitiveChunkedBuilder<Float32Type> =
-                PrimitiveChunkedBuilder::new("pred".into(), mat.nrows());
-
-            // Fill or Skip strategy can drop nulls. Fill will drop null when y has nulls.
-            // Skip will drop nulls whenever there is a null in the row.
-            // Mask true means the row doesn't have nulls
-            let has_nulls = (!&mask).any();
-            if has_nulls {
-                // Find the first index where we get n non-nulls.
-                let mut new_n = 0;
-                let mut m = 0;
-                for v in mask.into_no_null_iter() {
-                    new_n += v as usize;
-                    if new_n >= n {
-                        break;
+                PrimitiveChunkedBuilder::new("prediction".into(), height);
+
+            // Fill strategy will drop the rows where y is null. Step i is the fit on the first
+            // n + i rows of x, so it belongs to row n - 1 + i of x. Its prediction is made on the
+            // features of the input row horizon rows later.
+            let rows = design_rows(&mask, height);
+            for (r, row) in rows.iter().enumerate() {
+                match row {
+                    Some(k) if k + 1 >= n => {
+                        let coefficients = &coeffs[k + 1 - n];
+                        builder.append_slice(coefficients.col_as_slice(0));
+                        match rows.get(r + kwargs.horizon).copied().flatten() {
+                            Some(j) => {
+                                let features = x.get(j..j + 1, ..);
+                                let pred = *(features * coefficients).get(0, 0);
+                                pred_builder.append_value(clip_prediction(pred, clip));
+                            }
+                            None => pred_builder.append_null(),
+                        }
                     }
-                    m += 1;
-                }
-                for _ in 0..m {
-                    builder.append_null();
-                    pred_builder.append_null();
-                }
-                let mut i = 0;
-                for should_keep in mask.into_no_null_iter().skip(m) {
-                    if should_keep {
-                        let coefficients = &coeffs[i];
-                        let row = x.get(i..i + 1, ..);
-                        let pred = *(row * coefficients).get(0, 0);
-                        let coef = coefficients.col_as_slice(0);
-                        builder.append_slice(coef);
-                        pred_builder.append_value(pred);
-                        i += 1;
-                    } else {
+                    _ => {
                         builder.append_null();
                         pred_builder.append_null();
                     }
                 }
-            } else {
-                // n always > 1, guaranteed in Python
-                let m = n.abs_diff(1);
-                for _ in 0..m {
-                    builder.append_null();
-                    pred_builder.append_null();
-                }
-                for (i, coefficients) in coeffs.into_iter().enumerate() {
-                    let row = x.get(m + i..m + i + 1, ..);
-                    let pred = *(row * &coefficients).get(0, 0);
-                    let coef = coefficients.col_as_slice(0);
-                    builder.append_slice(coef);
-                    pred_builder.append_value(pred);
-                }
             }
 
             let coef_out = builder.finish();
@@ -906,6 +1124,7 @@ fn pl_recursive_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsRe
 fn pl_rolling_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResult<Series> {
     let n = kwargs.n; // Gauranteed n >= 2
     let has_bias = kwargs.bias;
+    let clip = kwargs.clip;
 
     let mut null_policy = NullPolicy::try_from(kwargs.null_policy)
         .map_err(|e| PolarsError::ComputeError(e.into()))?;
@@ -927,9 +1146,16 @@ fn pl_rolling_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResu
             let x = mat.slice(s![.., 1..]).into_faer();
             let y = mat.slice(s![.., 0..1]).into_faer();
             let coeffs = if should_skip {
-                faer_rolling_skipping_lstsq(x, y, n, kwargs.min_size, kwargs.lambda as f32)
+                faer_rolling_skipping_lstsq(
+                    x,
+                    y,
+                    n,
+                    kwargs.min_size,
+                    kwargs.lambda as f32,
+                    has_bias,
+                )
             } else {
-                faer_rolling_lstsq(x, y, n, kwargs.lambda as f32)
+                faer_rolling_lstsq(x, y, n, kwargs.lambda as f32, has_bias)
             };
 
             let mut builder: ListPrimitiveChunkedBuilder<Float32Type> =
@@ -956,7 +1182,7 @@ fn pl_rolling_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResu
                         pred_builder.append_null();
                     } else {
                         let row = x.get(m + i..m + i + 1, ..);
-                        let pred = *(row * &coefficients).get(0, 0);
+                        let pred = clip_prediction(*(row * &coefficients).get(0, 0), clip);
                         let coef = coefficients.col_as_slice(0);
                         builder.append_slice(coef);
                         pred_builder.append_value(pred);
@@ -966,7 +1192,7 @@ fn pl_rolling_lstsq_f32(inputs: &[Series], kwargs: SWWLstsqKwargs) -> PolarsResu
                 // nothing is skipped. All coeffs must be valid.
                 for (i, coefficients) in coeffs.into_iter().enumerate() {
                     let row = x.get(m + i..m + i + 1, ..);
-                    let pred = *(row * &coefficients).get(0, 0);
+                    let pred = clip_prediction(*(row * &coefficients).get(0, 0), clip);
                     let coef = coefficients.col_as_slice(0);
                     builder.append_slice(coef);
                     pred_builder.append_value(pred);
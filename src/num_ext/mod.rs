@@ -5,6 +5,7 @@ mod entrophies;
 mod fft;
 mod float_extras;
 mod gcd_lcm;
+mod glm;
 mod haversine;
 mod isotonic_regression;
 mod iterations;
@@ -1,14 +1,17 @@
 #![allow(non_snake_case)]
 /// Linear Regression Interop with Python
 use crate::linalg::{
+    lr_cv::cross_val_score,
     lr_online_solvers::OnlineLR,
-    lr_solvers::{ElasticNet, LR},
-    IntoFaer, IntoNdarray, LinalgErrors, LinearRegression,
+    lr_solvers::{ElasticNet, LRParts, DEFAULT_COND_THRESHOLD, LR},
+    IntoFaer, IntoNdarray, LRMethods, LRSolverMethods, LinalgErrors, LinearRegression,
+    StopCriterion,
 };
 
 use numpy::{IntoPyArray, PyArray1, PyArray2, PyReadonlyArray1, PyReadonlyArray2};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 
 impl From<LinalgErrors> for PyErr {
     fn from(value: LinalgErrors) -> Self {
@@ -29,17 +32,30 @@ impl PyLR {
         solver = "qr",
         lambda_ = 0.,
         has_bias = false,
+        warn_collinearity = DEFAULT_COND_THRESHOLD,
     ))]
-    pub fn new(solver: &str, lambda_: f64, has_bias: bool) -> Self {
-        PyLR {
-            lr: LR::new(solver, lambda_, has_bias),
-        }
+    pub fn new(solver: &str, lambda_: f64, has_bias: bool, warn_collinearity: f64) -> Self {
+        let mut lr = LR::new(solver, lambda_, has_bias);
+        lr.warn_collinearity = warn_collinearity;
+        PyLR { lr }
     }
 
     pub fn is_fit(&self) -> bool {
         self.lr.is_fit()
     }
 
+    /// Serializes the model. See LR::to_bytes.
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.lr.to_bytes())
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyLR {
+            lr: LR::from_bytes(bytes)?,
+        })
+    }
+
     pub fn fit(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>) -> PyResult<()> {
         let x = X.as_array().into_faer();
         let y = y.as_array().into_faer();
@@ -60,6 +76,42 @@ impl PyLR {
         }
     }
 
+    /// The model as (coefficients, bias, has_bias, lambda, solver). See LR::to_parts.
+    #[allow(clippy::type_complexity)]
+    pub fn to_parts<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyArray1<f64>>, f64, bool, f64, &'static str)> {
+        let parts = self.lr.to_parts()?;
+        Ok((
+            parts.coefficients.to_vec().into_pyarray(py),
+            parts.bias,
+            parts.has_bias,
+            parts.lambda,
+            parts.solver,
+        ))
+    }
+
+    #[staticmethod]
+    pub fn from_parts(
+        coeffs: PyReadonlyArray1<f64>,
+        bias: f64,
+        has_bias: bool,
+        lambda_: f64,
+        solver: &str,
+    ) -> PyResult<Self> {
+        let parts = LRParts {
+            coefficients: coeffs.as_slice()?,
+            bias,
+            has_bias,
+            lambda: lambda_,
+            solver: LRSolverMethods::from(solver).as_str(),
+        };
+        Ok(PyLR {
+            lr: LR::from_parts(&parts),
+        })
+    }
+
     pub fn predict<'py>(
         &self,
         py: Python<'py>,
@@ -94,6 +146,28 @@ impl PyLR {
     pub fn lambda_(&self) -> f64 {
         self.lr.lambda
     }
+
+    pub fn rank(&self) -> usize {
+        self.lr.rank()
+    }
+
+    pub fn condition_number(&self) -> f64 {
+        self.lr.condition_number()
+    }
+
+    pub fn collinearity_warning(&self) -> Option<String> {
+        self.lr.collinearity_warning()
+    }
+
+    pub fn check_full_rank(&self) -> PyResult<()> {
+        Ok(self.lr.check_full_rank()?)
+    }
+
+    pub fn with_coefficients_masked(&self, mask: Vec<bool>) -> PyResult<Self> {
+        Ok(PyLR {
+            lr: self.lr.with_coefficients_masked(&mask)?,
+        })
+    }
 }
 
 #[pyclass(subclass)]
@@ -111,10 +185,34 @@ impl PyElasticNet {
         has_bias = false,
         tol = 1e-5,
         max_iter = 2000,
+        stop_criterion = "coefficient",
+        penalize_bias = false,
+        penalty_factor = None,
     ))]
-    pub fn new(l1_reg: f64, l2_reg: f64, has_bias: bool, tol: f64, max_iter: usize) -> Self {
+    pub fn new(
+        l1_reg: f64,
+        l2_reg: f64,
+        has_bias: bool,
+        tol: f64,
+        max_iter: usize,
+        stop_criterion: &str,
+        penalize_bias: bool,
+        penalty_factor: Option<Vec<f64>>,
+    ) -> Self {
+        let lr = ElasticNet::new(
+            l1_reg,
+            l2_reg,
+            has_bias,
+            tol,
+            max_iter,
+            StopCriterion::from(stop_criterion),
+            penalize_bias,
+        );
         PyElasticNet {
-            lr: ElasticNet::new(l1_reg, l2_reg, has_bias, tol, max_iter),
+            lr: match penalty_factor {
+                Some(f) => lr.with_penalty_factor(f),
+                None => lr,
+            },
         }
     }
 
@@ -133,6 +231,18 @@ impl PyElasticNet {
         self.lr.is_fit()
     }
 
+    /// Serializes the model. See ElasticNet::to_bytes.
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.lr.to_bytes())
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyElasticNet {
+            lr: ElasticNet::from_bytes(bytes)?,
+        })
+    }
+
     pub fn fit(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>) -> PyResult<()> {
         let x = X.as_array().into_faer();
         let y = y.as_array().into_faer();
@@ -180,6 +290,16 @@ impl PyElasticNet {
     pub fn regularizers(&self) -> (f64, f64) {
         self.lr.regularizers()
     }
+
+    #[getter]
+    pub fn converged(&self) -> bool {
+        self.lr.converged
+    }
+
+    #[getter]
+    pub fn n_iter(&self) -> usize {
+        self.lr.n_iter
+    }
 }
 
 #[pyclass(subclass)]
@@ -191,10 +311,10 @@ pub struct PyOnlineLR {
 impl PyOnlineLR {
     #[new]
     #[allow(clippy::too_many_arguments)]
-    #[pyo3(signature=(lambda_=0., has_bias=false))]
-    pub fn new(lambda_: f64, has_bias: bool) -> Self {
+    #[pyo3(signature=(lambda_=0., has_bias=false, forget=1.))]
+    pub fn new(lambda_: f64, has_bias: bool, forget: f64) -> Self {
         PyOnlineLR {
-            lr: OnlineLR::new(lambda_, has_bias),
+            lr: OnlineLR::new(lambda_, has_bias).with_forget(forget),
         }
     }
 
@@ -202,6 +322,18 @@ impl PyOnlineLR {
         self.lr.is_fit()
     }
 
+    /// Serializes the model. See OnlineLR::to_bytes.
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.lr.to_bytes())
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(bytes: &[u8]) -> PyResult<Self> {
+        Ok(PyOnlineLR {
+            lr: OnlineLR::from_bytes(bytes)?,
+        })
+    }
+
     pub fn fit(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>) -> PyResult<()> {
         let x = X.as_array().into_faer();
         let y = y.as_array().into_faer();
@@ -211,12 +343,41 @@ impl PyOnlineLR {
         }
     }
 
+    pub fn reset(&mut self) {
+        self.lr.reset();
+    }
+
+    pub fn refit(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>) -> PyResult<()> {
+        let x = X.as_array().into_faer();
+        let y = y.as_array().into_faer();
+        match self.lr.refit(x, y) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub fn update(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>, c: f64) {
         let x = X.as_array().into_faer();
         let y = y.as_array().into_faer();
         self.lr.update(x, y, c);
     }
 
+    pub fn update_batch(&mut self, X: PyReadonlyArray2<f64>, y: PyReadonlyArray2<f64>, c: f64) {
+        let x = X.as_array().into_faer();
+        let y = y.as_array().into_faer();
+        self.lr.update_batch(x, y, c);
+    }
+
+    pub fn update_with_forget(
+        &mut self,
+        X: PyReadonlyArray2<f64>,
+        y: PyReadonlyArray2<f64>,
+    ) -> bool {
+        let x = X.as_array().into_faer();
+        let y = y.as_array().into_faer();
+        self.lr.update_with_forget(x, y)
+    }
+
     pub fn set_coeffs_bias_inverse(
         &mut self,
         coeffs: PyReadonlyArray1<f64>,
@@ -280,4 +441,54 @@ impl PyOnlineLR {
     pub fn lambda_(&self) -> f64 {
         self.lr.lambda
     }
+
+    #[getter]
+    pub fn forget(&self) -> f64 {
+        self.lr.forget
+    }
+}
+
+/// K-fold cross validation over a grid of lambdas. Returns the mean and the sample std of the
+/// validation MSEs of the folds, one per lambda. See cross_val_score.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)]
+#[pyo3(signature=(
+    X,
+    y,
+    lambdas,
+    n_folds = 5,
+    method = "l2",
+    l1_ratio = 0.5,
+    has_bias = false,
+    seed = None,
+    tol = 1e-5,
+    max_iter = 2000,
+))]
+pub fn py_cross_val_score(
+    X: PyReadonlyArray2<f64>,
+    y: PyReadonlyArray2<f64>,
+    lambdas: Vec<f64>,
+    n_folds: usize,
+    method: &str,
+    l1_ratio: f64,
+    has_bias: bool,
+    seed: Option<u64>,
+    tol: f64,
+    max_iter: usize,
+) -> PyResult<(Vec<f64>, Vec<f64>)> {
+    let x = X.as_array().into_faer();
+    let y = y.as_array().into_faer();
+    let score = cross_val_score(
+        x,
+        y,
+        &lambdas,
+        n_folds,
+        LRMethods::from(method),
+        l1_ratio,
+        has_bias,
+        seed,
+        tol,
+        max_iter,
+    )?;
+    Ok((score.mean_mse, score.std_mse))
 }
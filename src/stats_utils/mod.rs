@@ -17,6 +17,7 @@
 pub mod beta;
 pub mod gamma;
 pub mod normal;
+pub mod tukey;
 
 pub const PREC_ACC: f64 = 0.0000000000000011102230246251565;
 pub const LN_PI: f64 = 1.1447298858494001741434273513530587116472948129153;
@@ -2,7 +2,7 @@ import polars as pl
 import polars_ds as pds
 import pytest
 import numpy as np
-from polars_ds.linear_models import OnlineLR, ElasticNet
+from polars_ds.linear_models import LR, OnlineLR, ElasticNet, cross_val_score
 
 
 def test_lr_null_policies_for_np():
@@ -89,6 +89,112 @@ def test_online_lr():
         assert np.all(np.abs(coeffs - sklearn_coeffs) < 1e-6)
 
 
+def test_online_lr_forget():
+    # The coefficients change in the middle of the series
+    rng = np.random.default_rng(47)
+    X = rng.normal(size=(400, 2))
+    beta_before, beta_after = np.array([1.0, -1.0]), np.array([3.0, 0.5])
+    y = np.where(np.arange(400) < 200, X @ beta_before, X @ beta_after) + rng.normal(size=400) * 0.1
+
+    plain = OnlineLR().fit(X[:20], y[:20].reshape(-1, 1))
+    forgetting = OnlineLR(forget=0.9).fit(X[:20], y[:20].reshape(-1, 1))
+    no_forgetting = OnlineLR(forget=1.0).fit(X[:20], y[:20].reshape(-1, 1))
+    for i in range(20, 250):
+        plain.update(X[i], y[i])
+        forgetting.update(X[i], y[i])
+        no_forgetting.update(X[i], y[i])
+
+    # 50 rows after the change, the forgetting model has adapted, and the plain one has not
+    err_plain = np.abs(plain.coeffs() - beta_after).max()
+    err_forgetting = np.abs(forgetting.coeffs() - beta_after).max()
+    assert err_forgetting < 0.1
+    assert err_forgetting < err_plain / 5
+    # forget = 1 is the same as the plain update
+    assert np.allclose(no_forgetting.coeffs(), plain.coeffs())
+
+    # The forgetting model is the weighted least squares with weights forget^(t - k)
+    w = 0.9 ** (249 - np.arange(250))
+    w[:20] = 0.9 ** (249 - 19)  # the initial fit is one batch, which decays as a whole
+    expected = np.linalg.solve(X[:250].T @ (w[:, None] * X[:250]), X[:250].T @ (w * y[:250]))
+    assert np.allclose(forgetting.coeffs(), expected)
+
+    with pytest.raises(ValueError):
+        OnlineLR(forget=0.0)
+    with pytest.raises(ValueError):
+        forgetting.update(X[0], y[0], c=-1.0)
+
+
+def test_online_lr_update_batch():
+    rng = np.random.default_rng(11)
+    X = rng.normal(size=(40, 3))
+    y = (X @ np.array([1.0, -2.0, 0.5]) + 0.3 + rng.normal(size=40)).reshape(-1, 1)
+
+    for fit_bias in [False, True]:
+        by_row = OnlineLR(lambda_=0.1, fit_bias=fit_bias).fit(X[:20], y[:20])
+        by_batch = OnlineLR(lambda_=0.1, fit_bias=fit_bias).fit(X[:20], y[:20])
+        for i in range(20, 25):
+            by_row.update(X[i], y[i])
+        by_batch.update_batch(X[20:25], y[20:25])
+        assert np.allclose(by_row.coeffs(), by_batch.coeffs(), rtol=0.0, atol=1e-8)
+        assert np.allclose(by_row.inv(), by_batch.inv(), rtol=0.0, atol=1e-8)
+
+        # Removal of a block
+        for i in range(5, 10):
+            by_row.update(X[i], y[i], c=-1.0)
+        by_batch.update_batch(X[5:10], y[5:10], c=-1.0)
+        assert np.allclose(by_row.coeffs(), by_batch.coeffs(), rtol=0.0, atol=1e-8)
+
+    # Rows with NaN are skipped, as in update
+    by_row = OnlineLR().fit(X[:20], y[:20])
+    by_batch = OnlineLR().fit(X[:20], y[:20])
+    X_nan = X[20:25].copy()
+    X_nan[2, 1] = np.nan
+    for i in range(5):
+        by_row.update(X_nan[i], y[20 + i])
+    by_batch.update_batch(X_nan, y[20:25])
+    assert np.allclose(by_row.coeffs(), by_batch.coeffs(), rtol=0.0, atol=1e-8)
+
+    # Removing the only two rows where the last feature is non-zero makes XtX singular. The block is
+    # then applied row by row, and the second removal is skipped
+    X_sparse = X[:20].copy()
+    X_sparse[2:, 2] = 0.0
+    by_row = OnlineLR().fit(X_sparse, y[:20])
+    by_batch = OnlineLR().fit(X_sparse, y[:20])
+    for i in range(2):
+        by_row.update(X_sparse[i], y[i], c=-1.0)
+    by_batch.update_batch(X_sparse[:2], y[:2], c=-1.0)
+    assert np.all(np.isfinite(by_batch.inv()))
+    assert np.allclose(by_row.coeffs(), by_batch.coeffs(), rtol=0.0, atol=1e-8)
+
+
+def test_online_lr_reset():
+    rng = np.random.default_rng(13)
+    X = rng.normal(size=(60, 3))
+    y = (X @ np.array([1.0, -2.0, 0.5]) + 0.3 + rng.normal(size=60)).reshape(-1, 1)
+
+    for fit_bias in [False, True]:
+        olr = OnlineLR(lambda_=0.1, fit_bias=fit_bias).fit(X[:20], y[:20])
+        for i in range(20, 30):
+            olr.update(X[i], y[i])
+
+        olr.reset()
+        assert not olr.is_fit()
+        with pytest.raises(ValueError):
+            olr.update(X[30], y[30])
+
+        # After a reset, nothing from the old data is left
+        olr.fit(X[30:50], y[30:50])
+        fresh = OnlineLR(lambda_=0.1, fit_bias=fit_bias).fit(X[30:50], y[30:50])
+        assert np.allclose(olr.coeffs(), fresh.coeffs())
+        assert np.allclose(olr.predict(X[50:]), fresh.predict(X[50:]))
+        assert np.allclose(olr.inv(), fresh.inv())
+
+        olr.update(X[50], y[50])
+        olr.refit(X[30:50], y[30:50])
+        assert np.allclose(olr.coeffs(), fresh.coeffs())
+        assert np.allclose(olr.inv(), fresh.inv())
+
+
 def _test_elastic_net(add_bias: bool = False):
     import sklearn.linear_model as lm
 
@@ -131,3 +237,282 @@ def _test_elastic_net(add_bias: bool = False):
 def test_elastic_net():
     _test_elastic_net(add_bias=False)
     _test_elastic_net(add_bias=True)
+
+
+def test_elastic_net_bias_not_shrunk():
+    rng = np.random.default_rng(17)
+    X = rng.normal(size=(500, 3))
+    y = 10.0 + X @ np.array([1.0, 0.2, -0.3]) + rng.normal(size=500) * 0.1
+
+    # By default, the bias is the mean of the residuals of the other coefficients, so it is not shrunk
+    en = ElasticNet(l1_reg=0.5, l2_reg=0.1, fit_bias=True, tol=1e-10)
+    en.fit(X, y)
+    assert np.isclose(en.bias(), np.mean(y - X @ en.coeffs()))
+    assert abs(en.bias() - 10.0) < 0.1
+
+    # A penalized bias is the same as a column of ones without a bias
+    en_penalized = ElasticNet(l1_reg=0.5, l2_reg=0.1, fit_bias=True, tol=1e-10, penalize_bias=True)
+    en_penalized.fit(X, y)
+    en_ones = ElasticNet(l1_reg=0.5, l2_reg=0.1, fit_bias=False, tol=1e-10)
+    en_ones.fit(np.column_stack([X, np.ones(500)]), y)
+    assert np.allclose(en_penalized.coeffs(), en_ones.coeffs()[:3])
+    assert np.isclose(en_penalized.bias(), en_ones.coeffs()[3])
+    assert en_penalized.bias() < en.bias()
+
+
+def test_elastic_net_penalty_factor():
+    rng = np.random.default_rng(21)
+    X = rng.normal(size=(500, 3))
+    y = 1.0 + X @ np.array([1.0, 0.2, -0.3]) + rng.normal(size=500) * 0.1
+
+    # A factor of 0 leaves the feature unshrunk. Only it is non-zero under a large l1_reg.
+    en = ElasticNet(l1_reg=2.0, l2_reg=0.0, fit_bias=True, tol=1e-10, penalty_factor=[0.0, 1.0, 1.0])
+    en.fit(X, y)
+    coeffs = en.coeffs()
+    assert coeffs[1] == 0.0 and coeffs[2] == 0.0
+    assert abs(coeffs[0] - 1.0) < 0.05
+
+    # No l1 shrinkage at all is OLS with a Ridge penalty of 0
+    en_none = ElasticNet(l1_reg=2.0, l2_reg=0.0, fit_bias=True, tol=1e-12, penalty_factor=[0.0] * 3)
+    en_none.fit(X, y)
+    ols = np.linalg.lstsq(np.column_stack([X, np.ones(500)]), y, rcond=None)[0]
+    assert np.allclose(en_none.coeffs(), ols[:3], atol=1e-8)
+    assert np.isclose(en_none.bias(), ols[3], atol=1e-8)
+
+    with pytest.raises(ValueError):
+        ElasticNet(l1_reg=0.1, l2_reg=0.0, penalty_factor=[1.0, -1.0])
+
+
+def test_elastic_net_dual_gap():
+    rng = np.random.default_rng(22)
+    X = rng.normal(size=(500, 3))
+    y = 1.0 + X @ np.array([1.0, 0.2, -0.3]) + rng.normal(size=500) * 0.1
+
+    en_gap = ElasticNet(l1_reg=0.05, l2_reg=0.1, fit_bias=True, tol=1e-12, stop_criterion="dual_gap")
+    en_gap.fit(X, y)
+    assert en_gap.converged()
+    en = ElasticNet(l1_reg=0.05, l2_reg=0.1, fit_bias=True, tol=1e-12)
+    en.fit(X, y)
+    assert np.allclose(en_gap.coeffs(), en.coeffs(), atol=1e-8)
+    assert np.isclose(en_gap.bias(), en.bias(), atol=1e-8)
+
+    en_short = ElasticNet(
+        l1_reg=0.05, l2_reg=0.1, fit_bias=True, tol=1e-12, max_iter=1, stop_criterion="dual_gap"
+    )
+    en_short.fit(X, y)
+    assert not en_short.converged()
+    assert en_short.n_iter() == 1
+    assert 1 < en_gap.n_iter() <= 2000
+
+
+def test_lin_reg_profile():
+    rng = np.random.default_rng(3)
+    size = 100
+    x = rng.normal(size=(size, 2))
+    y = 1.0 + 2.0 * x[:, 0] - 3.0 * x[:, 1]  # exact, so the coefficients are known
+    df = (
+        pl.DataFrame(x, schema=["x1", "x2"])
+        .with_columns(y=pl.Series(y))
+        .with_columns(x1=pl.when(pl.int_range(pl.len()) < 10).then(None).otherwise(pl.col("x1")))
+    )
+
+    for solver in ["qr", "svd"]:
+        profile = (
+            df.select(pds.lin_reg_profile("x1", "x2", target="y", add_bias=True, solver=solver))
+            .unnest("profile")
+            .row(0, named=True)
+        )
+        assert profile["solver"] == solver
+        assert profile["n_iter"] == 1
+        assert profile["converged"]
+        assert profile["n_obs"] == size - 10  # rows with a null are skipped
+        assert profile["rank"] == 3  # x1, x2 and the bias
+        assert np.isfinite(profile["cond"]) and profile["cond"] >= 1.0
+        assert not profile["ill_conditioned"]
+        assert profile["gram_time"] >= 0.0
+        assert profile["solve_time"] >= 0.0
+        assert np.allclose(profile["coeffs"], [2.0, -3.0, 1.0], atol=1e-10)
+
+    # The same coefficients as lin_reg, with the bias first if asked
+    profile = (
+        df.select(pds.lin_reg_profile("x1", "x2", target="y", add_bias=True, bias_first=True))
+        .unnest("profile")
+        .row(0, named=True)
+    )
+    assert np.allclose(profile["coeffs"], [1.0, 2.0, -3.0], atol=1e-10)
+
+    # Lasso is iterative
+    profile = (
+        df.select(pds.lin_reg_profile("x1", "x2", target="y", add_bias=True, l1_reg=0.01))
+        .unnest("profile")
+        .row(0, named=True)
+    )
+    assert profile["solver"] == "coordinate_descent"
+    assert profile["converged"]
+    assert profile["n_iter"] > 1
+
+
+def test_lr_solvers_agree_on_ridge():
+    rng = np.random.default_rng(5)
+    X = rng.normal(size=(200, 4))
+    y = 1.0 + X @ np.array([0.5, -1.0, 2.0, 0.0]) + rng.normal(size=200) * 0.1
+
+    # XtX + lambda * I is positive definite, so cholesky doesn't fall back to qr
+    results = []
+    for solver in ["qr", "svd", "cholesky"]:
+        lr = LR(fit_bias=True, lambda_=0.5, solver=solver)
+        lr.fit(X, y)
+        results.append(np.append(lr.coeffs(), lr.bias()))
+
+    assert np.allclose(results[0], results[1], atol=1e-9, rtol=0.0)
+    assert np.allclose(results[0], results[2], atol=1e-9, rtol=0.0)
+
+
+def test_lr_parts_round_trip():
+    rng = np.random.default_rng(23)
+    X = rng.normal(size=(100, 3))
+    y = 1.0 + X @ np.array([0.5, -1.0, 2.0]) + rng.normal(size=100) * 0.1
+
+    lr = LR(fit_bias=True, lambda_=0.1, solver="svd").fit(X, y)
+    parts = lr.to_parts()
+    assert parts["fit_bias"] and parts["lambda_"] == 0.1 and parts["solver"] == "svd"
+    loaded = LR.from_parts(**parts)
+    assert np.array_equal(loaded.coeffs(), lr.coeffs())
+    assert loaded.bias() == lr.bias()
+    assert np.array_equal(loaded.predict(X), lr.predict(X))
+
+    # A bias of exactly 0 is kept, unlike from_values
+    zero_bias = LR.from_parts([1.0, 2.0], 0.0, fit_bias=True)
+    assert zero_bias.to_parts()["fit_bias"]
+    assert not LR.from_values([1.0, 2.0], 0.0).to_parts()["fit_bias"]
+
+    with pytest.raises(ValueError):
+        LR().to_parts()
+
+
+def test_lr_check_full_rank():
+    rng = np.random.default_rng(21)
+    X = rng.normal(size=(100, 3))
+    y = 1.0 + X @ np.array([0.5, -1.0, 2.0]) + rng.normal(size=100) * 0.1
+
+    lr = LR(fit_bias=True).fit(X, y)
+    assert lr.rank() == 4
+    lr.check_full_rank()
+
+    # The third column is the sum of the first two
+    X_collinear = X.copy()
+    X_collinear[:, 2] = X[:, 0] + X[:, 1]
+    for lambda_ in [0.0, 0.1]:
+        lr = LR(fit_bias=True, lambda_=lambda_).fit(X_collinear, y)
+        assert lr.rank() == 3
+        assert lr.condition_number() == float("inf")
+        with pytest.raises(ValueError, match="rank deficient"):
+            lr.check_full_rank()
+
+    with pytest.raises(ValueError):
+        LR().check_full_rank()
+
+
+def test_lr_with_coefficients_masked():
+    rng = np.random.default_rng(9)
+    X = rng.normal(size=(200, 4))
+    y = 1.0 + X @ np.array([0.5, -1.0, 2.0, 0.3]) + rng.normal(size=200) * 0.1
+    mask = np.array([False, True, False, True])
+
+    lr = LR(fit_bias=True).fit(X, y)
+    masked = lr.with_coefficients_masked(list(mask))
+    assert np.all(masked.coeffs()[mask] == 0.0)
+    assert np.array_equal(masked.coeffs()[~mask], lr.coeffs()[~mask])
+    assert masked.bias() == lr.bias()
+    assert np.allclose(masked.predict(X).flatten(), X @ masked.coeffs() + masked.bias())
+    # The original model is not changed
+    assert np.all(lr.coeffs()[mask] != 0.0)
+
+    # Masking doesn't refit. A refit on the other columns is the same as a direct fit on them.
+    refit = LR(fit_bias=True).fit(X[:, ~mask], y)
+    design = np.column_stack([X[:, ~mask], np.ones(len(y))])
+    expected, *_ = np.linalg.lstsq(design, y, rcond=None)
+    assert np.allclose(np.append(refit.coeffs(), refit.bias()), expected, atol=1e-9, rtol=0.0)
+
+    with pytest.raises(ValueError):
+        lr.with_coefficients_masked([True, False])
+    with pytest.raises(ValueError):
+        LR().with_coefficients_masked([True, False, False, False])
+
+
+def test_cross_val_score():
+    rng = np.random.default_rng(25)
+    X = rng.normal(size=(300, 3))
+    y = 1.0 + X @ np.array([0.5, -1.0, 0.0]) + rng.normal(size=300) * 0.5
+    df = pl.DataFrame(X, schema=["x1", "x2", "x3"]).with_columns(y=pl.Series(y))
+    lambdas = [0.001, 0.01, 0.1, 1.0]
+
+    # Same folds and the same models as lin_reg_cv
+    for method, l1_ratio in [("l2", 0.0), ("l1", 1.0)]:
+        mean_mse, std_mse = cross_val_score(
+            X, y, lambdas, n_folds=5, method=method, fit_bias=True, seed=7, tol=1e-10
+        )
+        res = df.select(
+            pds.lin_reg_cv(
+                "x1",
+                "x2",
+                "x3",
+                target="y",
+                lambdas=lambdas,
+                l1_ratio=l1_ratio,
+                add_bias=True,
+                seed=7,
+                tol=1e-10,
+            )
+        ).unnest("lstsq_cv")
+        assert np.allclose(mean_mse, res["cv_mse"][0].to_numpy(), atol=1e-8)
+        assert np.allclose(std_mse, res["cv_std"][0].to_numpy(), atol=1e-8)
+
+    # Deterministic given the seed
+    a, _ = cross_val_score(X, y, lambdas, n_folds=4, method="elastic", fit_bias=True, seed=1)
+    b, _ = cross_val_score(X, y, lambdas, n_folds=4, method="elastic", fit_bias=True, seed=1)
+    assert np.array_equal(a, b)
+
+    # Folds of 2 rows can't be scored with 4 coefficients
+    with pytest.raises(ValueError):
+        cross_val_score(X[:10], y[:10], lambdas, n_folds=5, method="normal", fit_bias=True)
+
+
+def test_linear_models_round_trip():
+    import pickle
+
+    rng = np.random.default_rng(11)
+    X = rng.normal(size=(200, 3))
+    y = 0.5 + X @ np.array([1.0, -2.0, 0.3]) + rng.normal(size=200) * 0.1
+    X_new = rng.normal(size=(50, 3))
+
+    models = [
+        LR(fit_bias=True, lambda_=0.1),
+        ElasticNet(l1_reg=0.01, l2_reg=0.1, fit_bias=True, penalty_factor=[1.0, 0.5, 2.0]),
+        OnlineLR(lambda_=0.1, fit_bias=True),
+    ]
+    for model in models:
+        model.fit(X[:150], y[:150])
+        for loaded in [type(model).from_bytes(model.to_bytes()), pickle.loads(pickle.dumps(model))]:
+            assert np.array_equal(loaded.coeffs(), model.coeffs())
+            assert np.array_equal(loaded.predict(X_new), model.predict(X_new))
+
+        # The same predictions by the expression, from the model or its bytes
+        df = pl.DataFrame(X_new, schema=["x1", "x2", "x3"])
+        for m in [model, model.to_bytes()]:
+            pred = df.select(pds.lin_reg_predict_with_coeffs("x1", "x2", "x3", model=m))
+            assert np.allclose(pred["prediction"].to_numpy(), model.predict(X_new).flatten())
+
+    # The streaming state survives, so updates after loading match updates on the original
+    online = models[2]
+    loaded = OnlineLR.from_bytes(online.to_bytes())
+    assert np.array_equal(loaded.inv(), online.inv())
+    for i in range(150, 200):
+        online.update(X[i], y[i])
+        loaded.update(X[i], y[i])
+    assert np.allclose(loaded.coeffs(), online.coeffs())
+
+    with pytest.raises(ValueError):
+        LR.from_bytes(models[1].to_bytes())
+    with pytest.raises(ValueError):
+        LR.from_bytes(models[0].to_bytes()[:-3])
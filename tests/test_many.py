@@ -647,6 +647,282 @@ def test_elastic_net_regression():
         assert np.all(np.abs(res_sklearn - res) < 1e-4)
 
 
+def test_lin_reg_stop_criterion():
+    from sklearn import linear_model
+
+    df = (
+        pds.frame(size=5_000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(0.0, 1.0).alias("x3"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 0.5 + pl.col("x2") * 0.25 - pl.col("x3") * 0.15 + pds.random() * 0.0001
+        )
+    )
+
+    x = df.select("x1", "x2", "x3").to_numpy()
+    y = df["y"].to_numpy()
+
+    for l1_reg, l2_reg in [(0.01, 0.0), (0.05, 0.05)]:
+        res = (
+            df.select(
+                pds.lin_reg(
+                    "x1",
+                    "x2",
+                    "x3",
+                    target="y",
+                    l1_reg=l1_reg,
+                    l2_reg=l2_reg,
+                    add_bias=True,
+                    tol=1e-12,
+                    stop_criterion="objective",
+                ).alias("coeffs")
+            )
+            .explode("coeffs")["coeffs"]
+            .to_numpy()
+        )
+
+        alpha = l1_reg + l2_reg
+        sklearn = linear_model.ElasticNet(alpha=alpha, l1_ratio=l1_reg / alpha, fit_intercept=True)
+        sklearn.fit(x, y)
+        assert np.all(np.abs(res[:3] - sklearn.coef_) < 1e-4)
+        assert abs(res[-1] - sklearn.intercept_) < 1e-4
+
+    robust = df.select(
+        pds.lin_reg_robust(
+            "x1", "x2", "x3", target="y", add_bias=True, tol=1e-10, stop_criterion="coefficient"
+        ).struct.field("coeffs")
+    ).explode("coeffs")["coeffs"]
+    robust_obj = df.select(
+        pds.lin_reg_robust(
+            "x1", "x2", "x3", target="y", add_bias=True, tol=1e-10, stop_criterion="objective"
+        ).struct.field("coeffs")
+    ).explode("coeffs")["coeffs"]
+    assert np.all(np.abs(robust.to_numpy() - robust_obj.to_numpy()) < 1e-4)
+
+
+def test_lin_reg_dual_gap():
+    from sklearn import linear_model
+
+    rng = np.random.default_rng(20)
+    size = 2000
+    x = rng.normal(size=(size, 4))
+    y = 1.0 + x @ np.array([0.5, 0.25, -0.15, 0.0]) + rng.normal(size=size) * 0.1
+    df = pl.DataFrame(x, schema=["x1", "x2", "x3", "x4"]).with_columns(y=pl.Series(y))
+
+    for l1_reg, l2_reg in [(0.01, 0.0), (0.05, 0.05)]:
+        alpha = l1_reg + l2_reg
+        sklearn = linear_model.ElasticNet(
+            alpha=alpha, l1_ratio=l1_reg / alpha, fit_intercept=True, tol=1e-12, max_iter=100_000
+        ).fit(x, y)
+        expected = [*sklearn.coef_, sklearn.intercept_]
+        for algorithm in ["cd", "fista"]:
+            res = (
+                df.select(
+                    pds.lin_reg(
+                        "x1",
+                        "x2",
+                        "x3",
+                        "x4",
+                        target="y",
+                        l1_reg=l1_reg,
+                        l2_reg=l2_reg,
+                        add_bias=True,
+                        tol=1e-12,
+                        max_iter=100_000,
+                        stop_criterion="dual_gap",
+                        algorithm=algorithm,
+                    )
+                )
+                .item()
+                .to_numpy()
+            )
+            assert np.allclose(res, expected, atol=1e-5)
+
+
+def test_lin_reg_strict():
+    rng = np.random.default_rng(23)
+    size = 500
+    x = rng.normal(size=(size, 3))
+    y = 1.0 + x @ np.array([0.5, 0.25, -0.15]) + rng.normal(size=size) * 0.1
+    df = pl.DataFrame(x, schema=["x1", "x2", "x3"]).with_columns(y=pl.Series(y))
+
+    for algorithm in ["cd", "fista"]:
+        # Not converged, but the last iterate is returned
+        res = df.select(
+            pds.lin_reg(
+                "x1", "x2", "x3", target="y", l1_reg=0.01, add_bias=True, max_iter=1, algorithm=algorithm
+            )
+        ).item()
+        assert len(res) == 4
+
+        with pytest.raises(pl.exceptions.ComputeError):
+            df.select(
+                pds.lin_reg(
+                    "x1",
+                    "x2",
+                    "x3",
+                    target="y",
+                    l1_reg=0.01,
+                    add_bias=True,
+                    max_iter=1,
+                    algorithm=algorithm,
+                    strict=True,
+                )
+            )
+
+    profile = df.select(
+        pds.lin_reg_profile("x1", "x2", "x3", target="y", l1_reg=0.01, add_bias=True)
+    ).unnest("profile")
+    assert profile["converged"].item()
+    assert 1 < profile["n_iter"].item() < 2000
+
+
+def test_lin_reg_fista():
+    rng = np.random.default_rng(16)
+    size, n_features = 60, 200  # wide
+    x = rng.normal(size=(size, n_features))
+    y = 0.5 + 3.0 * x[:, 0] - 2.0 * x[:, 5] + x[:, 7] + 0.1 * rng.normal(size=size)
+    names = [f"x{i}" for i in range(n_features)]
+    df = pl.DataFrame(x, schema=names).with_columns(y=pl.Series(y))
+
+    for l1_reg, l2_reg in [(0.05, 0.0), (0.02, 0.1)]:
+        cd, fista = (
+            df.select(
+                pds.lin_reg(
+                    *names,
+                    target="y",
+                    add_bias=True,
+                    l1_reg=l1_reg,
+                    l2_reg=l2_reg,
+                    tol=1e-10,
+                    algorithm=algorithm,
+                )
+            )
+            .item()
+            .to_numpy()
+            for algorithm in ["cd", "fista"]
+        )
+        assert np.allclose(cd, fista, atol=1e-6)
+
+    pred = df.select(
+        pds.lin_reg(*names, target="y", l1_reg=0.05, return_pred=True, algorithm="fista")
+    ).unnest("lr_pred")
+    assert pred.shape == (size, 2)
+
+
+def test_lin_reg_penalize_bias():
+    rng = np.random.default_rng(18)
+    size = 300
+    x = rng.normal(size=(size, 2))
+    y = 5.0 + x @ np.array([1.0, -0.5]) + rng.normal(size=size) * 0.1
+    df = pl.DataFrame(x, schema=["x1", "x2"]).with_columns(y=pl.Series(y), ones=pl.lit(1.0))
+
+    for l1_reg, l2_reg in [(0.2, 0.0), (0.2, 0.3), (0.0, 50.0)]:
+        default = df.select(
+            pds.lin_reg("x1", "x2", target="y", add_bias=True, l1_reg=l1_reg, l2_reg=l2_reg, tol=1e-10)
+        )["coeffs"][0].to_numpy()
+        # The bias is not shrunk towards 0
+        assert abs(default[2] - np.mean(y - x @ default[:2])) < 1e-6
+
+        penalized = df.select(
+            pds.lin_reg(
+                "x1",
+                "x2",
+                target="y",
+                add_bias=True,
+                l1_reg=l1_reg,
+                l2_reg=l2_reg,
+                tol=1e-10,
+                penalize_bias=True,
+            )
+        )["coeffs"][0].to_numpy()
+        ones = df.select(
+            pds.lin_reg("x1", "x2", "ones", target="y", l1_reg=l1_reg, l2_reg=l2_reg, tol=1e-10)
+        )["coeffs"][0].to_numpy()
+        assert np.allclose(penalized, ones)
+        assert penalized[2] < default[2]
+
+
+def test_lin_reg_penalty_factor():
+    rng = np.random.default_rng(19)
+    size = 300
+    x = rng.normal(size=(size, 3))
+    y = 2.0 + x @ np.array([1.5, -0.5, 0.2]) + rng.normal(size=size) * 0.1
+    df = pl.DataFrame(x, schema=["x1", "x2", "x3"]).with_columns(
+        y=pl.Series(y), x1_half=pl.Series(x[:, 0] / 2.0)
+    )
+
+    def coeffs(*names, **kwargs):
+        return (
+            df.select(pds.lin_reg(*names, target="y", add_bias=True, tol=1e-12, **kwargs))
+            .item()
+            .to_numpy()
+        )
+
+    # All factors 0 is OLS. An extra factor for the bias is ignored.
+    ols = coeffs("x1", "x2", "x3")
+    assert np.allclose(coeffs("x1", "x2", "x3", l1_reg=0.3, penalty_factor=[0, 0, 0]), ols, atol=1e-8)
+    assert np.allclose(
+        coeffs("x1", "x2", "x3", l1_reg=0.3, penalty_factor=[0, 0, 0, 5]), ols, atol=1e-8
+    )
+
+    # A factor of f on a feature is the same as plain Lasso on the feature divided by f
+    for algorithm in ["cd", "fista"]:
+        weighted = coeffs(
+            "x1", "x2", "x3", l1_reg=0.2, penalty_factor=[2.0, 1.0, 1.0], algorithm=algorithm
+        )
+        scaled = coeffs("x1_half", "x2", "x3", l1_reg=0.2, algorithm=algorithm)
+        assert np.allclose(weighted, [scaled[0] / 2.0, *scaled[1:]], atol=1e-6)
+
+    # With a large l1_reg, only the unpenalized feature is non-zero
+    res = coeffs("x1", "x2", "x3", l1_reg=5.0, penalty_factor=[1.0, 0.0, 1.0])
+    assert res[0] == 0.0 and res[2] == 0.0
+    assert abs(res[1]) > 0.1
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x1", target="y", l1_reg=0.1, penalty_factor=[-1.0])
+
+
+def test_lin_reg_ewls():
+    rng = np.random.default_rng(41)
+    size = 400
+    x = rng.normal(size=size)
+    # The slope changes from 1 to -2 in the middle of the series
+    slope = np.where(np.arange(size) < size // 2, 1.0, -2.0)
+    df = pl.DataFrame({"x": x, "y": slope * x + 0.5 + rng.normal(size=size) * 0.05})
+
+    res = df.select(
+        pds.recursive_lin_reg("x", target="y", start_with=5, add_bias=True).alias("recursive"),
+        pds.lin_reg_ewls(
+            "x", target="y", start_with=5, forget=1.0 - 1e-12, add_bias=True
+        ).alias("ewls_1"),
+        pds.lin_reg_ewls("x", target="y", start_with=5, halflife=10.0, add_bias=True).alias("ewls"),
+    )
+    # With forget ~ 1, this is the plain recursive least squares
+    recursive = np.vstack(res["recursive"].struct.field("coeffs").to_list()[4:])
+    ewls_1 = np.vstack(res["ewls_1"].struct.field("coeffs").to_list()[4:])
+    assert np.allclose(recursive, ewls_1, atol=1e-6)
+    assert np.allclose(
+        res["recursive"].struct.field("prediction").to_numpy()[4:],
+        res["ewls_1"].struct.field("prediction").to_numpy()[4:],
+        atol=1e-6,
+    )
+
+    # With a short memory, the slope follows the regime change. The plain one is stuck in between.
+    ewls = np.vstack(res["ewls"].struct.field("coeffs").to_list()[4:])
+    assert abs(ewls[size // 2 - 10, 0] - 1.0) < 0.1
+    assert abs(ewls[-1, 0] + 2.0) < 0.1
+    assert abs(recursive[-1, 0] + 0.5) < 0.2
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_ewls("x", target="y", start_with=5)
+    with pytest.raises(ValueError):
+        pds.lin_reg_ewls("x", target="y", start_with=5, forget=1.5)
+
+
 def test_recursive_lin_reg():
     # Test against the lstsq method with a fit whenver a new row is in the data
     size = 1_000
@@ -694,6 +970,200 @@ def test_recursive_lin_reg():
         assert np.all(np.abs(normal_result - recursive_result) < 1e-5)
 
 
+def test_lin_reg_expanding():
+    rng = np.random.default_rng(23)
+    size = 300
+    x1 = rng.normal(size=size)
+    x2 = rng.normal(size=size)
+    y = 0.5 * x1 - 0.25 * x2 + 1.0 + rng.normal(size=size) * 0.1
+    df = pl.DataFrame({"x1": x1, "x2": x2, "y": y})
+
+    start_with = 10
+    res = df.select(
+        pds.lin_reg_expanding("x1", "x2", target="y", start_with=start_with, add_bias=True)
+    ).unnest("expanding")
+    assert res.height == size
+    assert res["prediction"][:start_with].is_null().all()
+    assert res["prediction"][start_with:].is_not_null().all()
+    pred = res["prediction"].to_numpy()
+    assert np.allclose(res["resid"].to_numpy()[start_with:], (y - pred)[start_with:])
+
+    # The prediction of row t is from the fit on the rows before t
+    for t in [start_with, 100, size - 1]:
+        coeffs = (
+            df.limit(t)
+            .select(pds.lin_reg("x1", "x2", target="y", add_bias=True))["coeffs"][0]
+            .to_numpy()
+        )
+        assert np.isclose(res["prediction"][t], x1[t] * coeffs[0] + x2[t] * coeffs[1] + coeffs[2])
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_expanding("x1", target="y", start_with=0)
+
+
+def test_recursive_lin_reg_with_std_err():
+    # Standard errors at each row match the report of a fit on the rows so far
+    size = 200
+    df = (
+        pds.frame(size=size)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 0.5 - pl.col("x2") * 0.25 + pds.random() * 0.1,
+        )
+    )
+
+    start_with = 5
+    res = df.select(
+        pds.recursive_lin_reg(
+            "x1", "x2", target="y", start_with=start_with, add_bias=True, with_std_err=True
+        ).alias("result"),
+    ).unnest("result")
+    assert res["std_err"][: start_with - 1].is_null().all()
+
+    for i in [start_with, 20, 100, size]:
+        report = df.limit(i).select(
+            pds.lin_reg_report("x1", "x2", target="y", add_bias=True).alias("report")
+        ).unnest("report")
+        assert np.allclose(res["coeffs"][i - 1].to_numpy(), report["beta"].to_numpy())
+        assert np.allclose(res["std_err"][i - 1].to_numpy(), report["std_err"].to_numpy())
+        assert np.allclose(res["t"][i - 1].to_numpy(), report["t"].to_numpy())
+
+
+def test_recursive_lin_reg_ridge():
+    rng = np.random.default_rng(41)
+    x = rng.normal(size=(60, 2))
+    y = x @ np.array([2.0, -1.0]) + rng.normal(size=60) * 0.1
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "y": y})
+
+    start_with = 5
+
+    def coeffs(l2_reg):
+        res = df.select(
+            pds.recursive_lin_reg(
+                "x1", "x2", target="y", start_with=start_with, l2_reg=l2_reg
+            ).alias("result")
+        ).unnest("result")
+        return np.stack(res["coeffs"][start_with - 1 :].to_list())
+
+    ols = coeffs(0.0)
+    ridge = coeffs(50.0)
+    # Every step is the Ridge solution on the rows so far, so the first fit and the updates are
+    # regularized the same way
+    for i in [start_with, 20, 60]:
+        xi, yi = x[:i], y[:i]
+        expected = np.linalg.solve(xi.T @ xi + 50.0 * np.eye(2), xi.T @ yi)
+        assert np.allclose(ridge[i - start_with], expected)
+    # and the coefficients are shrunk towards 0
+    assert np.all(np.linalg.norm(ridge, axis=1) < np.linalg.norm(ols, axis=1))
+
+
+def test_recursive_rolling_lin_reg_bias():
+    # With a bias, each step matches a batch fit with bias, also for Ridge where the bias is not penalized
+    rng = np.random.default_rng(43)
+    x = rng.normal(size=(80, 2))
+    y = 5.0 + x @ np.array([2.0, -1.0]) + rng.normal(size=80) * 0.1
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "y": y})
+
+    start_with = 10
+    for l2_reg in [0.0, 5.0]:
+        res = df.select(
+            pds.recursive_lin_reg(
+                "x1", "x2", target="y", start_with=start_with, add_bias=True, l2_reg=l2_reg
+            ).alias("recursive"),
+            pds.rolling_lin_reg(
+                "x1", "x2", target="y", window_size=start_with, add_bias=True, l2_reg=l2_reg
+            ).alias("rolling"),
+        )
+        recursive = res["recursive"].struct.field("coeffs")
+        rolling = res["rolling"].struct.field("coeffs")
+        for i in [start_with, 40, 80]:
+            batch = df.limit(i).select(
+                pds.lin_reg("x1", "x2", target="y", add_bias=True, l2_reg=l2_reg)
+            )
+            assert np.allclose(recursive[i - 1].to_numpy(), batch.item().to_numpy())
+            window = df.slice(i - start_with, start_with).select(
+                pds.lin_reg("x1", "x2", target="y", add_bias=True, l2_reg=l2_reg)
+            )
+            assert np.allclose(rolling[i - 1].to_numpy(), window.item().to_numpy())
+        # The intercept is the last coefficient, and the prediction includes it
+        last = recursive[-1].to_numpy()
+        pred = res["recursive"].struct.field("prediction")[-1]
+        assert np.isclose(pred, x[-1] @ last[:2] + last[2])
+
+
+def test_recursive_lin_reg_horizon():
+    # The prediction at row t uses the coefficients fit on rows up to t and the features at t + h
+    size = 50
+    df = (
+        pds.frame(size=size)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 0.5 - pl.col("x2") * 0.25 + pds.random() * 0.1,
+        )
+    )
+
+    start_with = 4
+    x = df.select("x1", "x2").to_numpy()
+    y = df["y"].to_numpy()
+    for horizon in [0, 1, 3]:
+        for with_std_err in [False, True]:
+            res = df.select(
+                pds.recursive_lin_reg(
+                    "x1",
+                    "x2",
+                    target="y",
+                    start_with=start_with,
+                    horizon=horizon,
+                    with_std_err=with_std_err,
+                ).alias("result"),
+            ).unnest("result")
+            pred = res["prediction"]
+            assert pred[: start_with - 1].is_null().all()
+            assert pred[size - horizon :].is_null().all()
+            for t in range(start_with - 1, size - horizon):
+                beta = np.linalg.lstsq(x[: t + 1], y[: t + 1], rcond=None)[0]
+                assert abs(pred[t] - x[t + horizon] @ beta) < 1e-6
+
+    with pytest.raises(ValueError):
+        pds.recursive_lin_reg("x1", "x2", target="y", start_with=start_with, horizon=-1)
+
+
+def test_lin_reg_recursive_residuals():
+    rng = np.random.default_rng(16)
+    size = 120
+    x = rng.normal(size=size)
+    y = 1.0 + 0.5 * x + rng.normal(size=size) * 0.3
+    df = pl.DataFrame({"x": x, "y": y})
+    res = df.select(
+        pds.lin_reg_recursive_residuals("x", target="y", add_bias=True)
+    ).unnest("recursive_residuals")
+
+    k = 2
+    xb = np.column_stack([x, np.ones(size)])
+    assert res["recursive_resid"][:k].is_null().all()
+    w = []
+    for t in range(k, size):
+        a = xb[:t]
+        beta = np.linalg.lstsq(a, y[:t], rcond=None)[0]
+        h = xb[t] @ np.linalg.inv(a.T @ a) @ xb[t]
+        w.append((y[t] - xb[t] @ beta) / np.sqrt(1 + h))
+    w = np.array(w)
+    assert np.allclose(res["recursive_resid"][k:].to_numpy(), w)
+    # The sum of squared recursive residuals is the RSS of the full fit
+    rss = np.sum((y - xb @ np.linalg.lstsq(xb, y, rcond=None)[0]) ** 2)
+    assert np.isclose(np.sum(w**2), rss)
+    s = np.sqrt(np.mean(w**2))
+    assert np.allclose(res["cusum"][k:].to_numpy(), np.cumsum(w) / s)
+    # A stable model stays within the bounds
+    assert (res["cusum"].abs() < res["cusum_bound"]).all()
+
+
 def test_recursive_ridge():
     # Test against the lstsq method with a fit whenver a new row is in the data
     size = 1_000
@@ -786,6 +1256,28 @@ def test_rolling_lin_reg():
         assert_frame_equal(df_to_test, df_answer)
 
 
+def test_rolling_lin_reg_duplicate_rows():
+    # Removing the first row of [2, 5, 5] leaves the duplicate pair [5, 5], whose XtX is singular
+    x = np.array([0.0, 1.0, 2.0, 5.0, 5.0, 6.0, 8.0, 8.0, 9.0, 10.0, 11.0, 11.0, 12.0, 13.0])
+    rng = np.random.default_rng(3)
+    y = 2.0 * x + 1.0 + rng.normal(size=len(x)) * 0.01
+    df = pl.DataFrame({"x": x, "y": y})
+
+    window_size = 3
+    res = df.select(
+        pds.rolling_lin_reg("x", target="y", window_size=window_size, add_bias=True).alias("result")
+    ).unnest("result")
+    for i in range(window_size - 1, len(x)):
+        coeffs = res["coeffs"][i].to_numpy()
+        assert np.all(np.isfinite(coeffs))
+        expected = (
+            df.slice(i + 1 - window_size, window_size)
+            .select(pds.lin_reg("x", target="y", add_bias=True))["coeffs"][0]
+            .to_numpy()
+        )
+        assert np.allclose(coeffs, expected, atol=1e-6)
+
+
 # This only tests that nulls are correctly skipped.
 def test_rolling_null_skips():
     size = 1000
@@ -918,7 +1410,371 @@ def test_hc_lin_reg_report():
             pds_se = pds_result[f"{se_type}_se"].to_numpy()
             sm_se = getattr(results, f"{se_type.upper()}_se")
 
-        assert np.all((pds_se - sm_se) < 1e-7)
+        assert np.all(np.abs(pds_se - sm_se) < 1e-7)
+
+
+def test_lin_reg_report_hc3():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(27)
+    size = 200
+    X = rng.normal(size=(size, 2))
+    # The noise grows with |x1|, so the classical standard errors are wrong
+    y = 1.0 + X @ np.array([0.5, -0.25]) + rng.normal(size=size) * (0.2 + np.abs(X[:, 0]))
+    df = pl.DataFrame({"x1": X[:, 0], "x2": X[:, 1], "y": y})
+
+    res = df.select(
+        pds.lin_reg_report("x1", "x2", target="y", add_bias=True, std_err="hc3").alias("report")
+    ).unnest("report")
+    sm_res = sm.OLS(y, sm.add_constant(X, prepend=False)).fit(cov_type="HC3", use_t=True)
+
+    assert np.allclose(res["beta"].to_numpy(), sm_res.params)
+    assert np.allclose(res["hc3_se"].to_numpy(), sm_res.bse)
+    assert np.allclose(res["t"].to_numpy(), sm_res.tvalues)
+    assert np.allclose(res["p>|t|"].to_numpy(), sm_res.pvalues)
+
+    classical = df.select(
+        pds.lin_reg_report("x1", "x2", target="y", add_bias=True, std_err="classical").alias("report")
+    ).unnest("report")
+    assert np.allclose(classical["std_err"].to_numpy(), sm.OLS(y, sm.add_constant(X, prepend=False)).fit().bse)
+
+
+def test_lin_reg_report_hac():
+    import statsmodels.api as sm
+
+    # A regression with AR(1) errors and a persistent regressor
+    rng = np.random.default_rng(28)
+    size = 300
+    e = np.zeros(size)
+    x = np.zeros(size)
+    for t in range(1, size):
+        e[t] = 0.7 * e[t - 1] + rng.normal()
+        x[t] = 0.5 * x[t - 1] + rng.normal()
+    y = 1.0 + 0.5 * x + e
+    df = pl.DataFrame({"x": x, "y": y})
+    design = sm.add_constant(x, prepend=False)
+
+    # 300 rows give 5 lags by the rule of thumb
+    for lags, sm_lags in [(3, 3), (None, 5)]:
+        res = df.select(
+            pds.lin_reg_report("x", target="y", add_bias=True, std_err="hac", hac_lags=lags).alias(
+                "report"
+            )
+        ).unnest("report")
+        sm_res = sm.OLS(y, design).fit(cov_type="HAC", cov_kwds={"maxlags": sm_lags}, use_t=True)
+        assert np.allclose(res["hac_se"].to_numpy(), sm_res.bse)
+        assert np.allclose(res["t"].to_numpy(), sm_res.tvalues)
+        assert np.allclose(res["p>|t|"].to_numpy(), sm_res.pvalues)
+
+    # With positively autocorrelated errors and regressor, the classical standard errors are too small
+    classical = df.select(
+        pds.lin_reg_report("x", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    assert classical["std_err"][0] < res["hac_se"][0]
+
+
+def test_lin_reg_matrix_rank():
+    rng = np.random.default_rng(41)
+    size = 100
+    x = rng.normal(size=(size, 3))
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "x3": x[:, 2], "y": rng.normal(size=size)})
+    df = df.with_columns(x4=pl.col("x2"))  # duplicated column
+    names = ["x1", "x2", "x3", "x4"]
+
+    res = df.select(pds.lin_reg_matrix_rank(*names, target="y")).unnest("matrix_rank")
+    assert res["rank"][0] == len(names) - 1
+    assert res["n_cols"][0] == len(names)
+    assert "rank deficient" in res["note"][0]
+
+    res = df.select(pds.lin_reg_matrix_rank(*names[:3], target="y", add_bias=True)).unnest(
+        "matrix_rank"
+    )
+    assert res["rank"][0] == 4
+    assert res["note"][0] is None
+
+    report = df.select(pds.lin_reg_report(*names, target="y").alias("report")).unnest("report")
+    assert (report["rank"] == len(names) - 1).all()
+    assert report["note"].null_count() == 0
+
+    report = df.select(pds.lin_reg_report(*names[:3], target="y").alias("report")).unnest("report")
+    assert (report["rank"] == 3).all()
+    assert report["note"].null_count() == len(report)
+
+
+def test_lin_reg_report_std_beta():
+    rng = np.random.default_rng(37)
+    size = 300
+    x = rng.normal(size=(size, 3)) * np.array([1.0, 10.0, 0.1])
+    y = 2.0 + x @ np.array([0.5, 0.05, 5.0]) + rng.normal(size=size)
+    w = rng.uniform(0.5, 2.0, size=size)
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "x3": x[:, 2], "y": y, "w": w})
+    names = ["x1", "x2", "x3"]
+
+    report = df.select(
+        pds.lin_reg_report(*names, target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    expected = report["beta"].to_numpy()[:3] * x.std(axis=0) / y.std()
+    assert np.allclose(report["std_beta"].to_numpy()[:3], expected)
+    assert np.isnan(report["std_beta"][3])
+
+    # When the features and the target are standardized, std_beta is the raw beta
+    df_std = df.select((pl.col(c) - pl.col(c).mean()) / pl.col(c).std() for c in names + ["y"])
+    report = df_std.select(
+        pds.lin_reg_report(*names, target="y", add_bias=True, bias_first=True).alias("report")
+    ).unnest("report")
+    assert np.isnan(report["std_beta"][0])
+    assert np.allclose(report["std_beta"].to_numpy()[1:], report["beta"].to_numpy()[1:])
+
+    # Weighted standard deviations with weights
+    report = df.select(
+        pds.lin_reg_report(*names, target="y", weights="w", add_bias=True).alias("report")
+    ).unnest("report")
+
+    def wstd(v):
+        m = np.average(v, weights=w)
+        return np.sqrt(np.average((v - m) ** 2, weights=w))
+
+    expected = report["beta"].to_numpy()[:3] * np.array([wstd(x[:, j]) for j in range(3)]) / wstd(y)
+    assert np.allclose(report["std_beta"].to_numpy()[:3], expected)
+
+
+def test_lin_reg_durbin_watson_and_acf():
+    import statsmodels.api as sm
+    from statsmodels.stats.stattools import durbin_watson
+    from statsmodels.tsa.stattools import acf
+
+    rng = np.random.default_rng(29)
+    size = 300
+    e = np.zeros(size)
+    for t in range(1, size):
+        e[t] = 0.6 * e[t - 1] + rng.normal()
+    x = rng.normal(size=size)
+    y = 1.0 + 0.5 * x + e
+    w = rng.uniform(0.5, 2.0, size=size)
+    df = pl.DataFrame({"x": x, "y": y, "w": w})
+    design = sm.add_constant(x, prepend=False)
+
+    ols = sm.OLS(y, design).fit()
+    report = df.select(pds.lin_reg_report("x", target="y", add_bias=True).alias("report")).unnest(
+        "report"
+    )
+    # The same value on every row of the report
+    assert report["durbin_watson"].n_unique() == 1
+    assert np.isclose(report["durbin_watson"][0], durbin_watson(ols.resid))
+    assert report["durbin_watson"][0] < 1.5  # positively autocorrelated
+
+    wls = sm.WLS(y, design, weights=w).fit()
+    report = df.select(
+        pds.lin_reg_report("x", target="y", weights="w", add_bias=True).alias("report")
+    ).unnest("report")
+    assert np.isclose(report["durbin_watson"][0], durbin_watson(wls.wresid))
+
+    res = df.select(pds.lin_reg_residual_acf("x", target="y", add_bias=True, lag=5)).unnest(
+        "residual_acf"
+    )
+    assert np.allclose(res["acf"][0].to_numpy(), acf(ols.resid, nlags=5)[1:])
+    assert np.isclose(res["bound"][0], 2.0 / np.sqrt(size))
+
+
+def test_lin_reg_report_goodness_of_fit():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(11)
+    X = rng.normal(size=(300, 3))
+    y = 2.0 + X @ np.array([0.5, -1.0, 0.25]) + rng.normal(size=300)
+    w = rng.uniform(0.5, 2.0, size=300)
+    df = pl.DataFrame({"x1": X[:, 0], "x2": X[:, 1], "x3": X[:, 2], "y": y, "w": w})
+
+    cols = ["r2", "adj_r2", "resid_se"]
+    for add_bias in [True, False]:
+        design = sm.add_constant(X, prepend=False) if add_bias else X
+        for weights in [None, "w"]:
+            if weights is None:
+                res = sm.OLS(y, design).fit()
+            else:
+                res = sm.WLS(y, design, weights=w).fit()
+            report = df.select(
+                pds.lin_reg_report(
+                    "x1", "x2", "x3", target="y", weights=weights, add_bias=add_bias
+                ).alias("report")
+            ).unnest("report")
+            actual = report.select(cols).row(0)
+            expected = (res.rsquared, res.rsquared_adj, np.sqrt(res.scale))
+            assert np.allclose(actual, expected, rtol=1e-10)
+
+
+def test_lin_reg_report_f_test():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(23)
+    X = rng.normal(size=(120, 3))
+    # The last feature is irrelevant, and the signal is weak so the p-value is not 0
+    y = 1.0 + X @ np.array([0.15, -0.1, 0.0]) + rng.normal(size=120)
+    df = pl.DataFrame({"x1": X[:, 0], "x2": X[:, 1], "x3": X[:, 2], "y": y})
+
+    for add_bias in [True, False]:
+        design = sm.add_constant(X, prepend=False) if add_bias else X
+        res = sm.OLS(y, design).fit()
+        report = df.select(
+            pds.lin_reg_report("x1", "x2", "x3", target="y", add_bias=add_bias).alias("report")
+        ).unnest("report")
+        assert np.isclose(report["f_stat"][0], res.fvalue, rtol=1e-10)
+        assert np.isclose(report["f_pvalue"][0], res.f_pvalue, rtol=1e-6)
+
+
+def test_lin_reg_report_confidence_intervals():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(29)
+    X = rng.normal(size=(80, 2))
+    y = 0.5 + X @ np.array([1.0, -0.5]) + rng.normal(size=80)
+    df = pl.DataFrame({"x1": X[:, 0], "x2": X[:, 1], "y": y})
+    res = sm.OLS(y, sm.add_constant(X, prepend=False)).fit()
+
+    for alpha in [0.05, 0.1, 0.01]:
+        report = df.select(
+            pds.lin_reg_report("x1", "x2", target="y", add_bias=True, alpha=alpha).alias("report")
+        ).unnest("report")
+        # One interval per coefficient, including the bias
+        assert report["features"].to_list() == ["x1", "x2", "__bias__"]
+        ci = res.conf_int(alpha=alpha)
+        assert np.allclose(report["ci_lower"].to_numpy(), ci[:, 0], rtol=1e-8)
+        assert np.allclose(report["ci_upper"].to_numpy(), ci[:, 1], rtol=1e-8)
+        # The deprecated columns are always the 95% bounds
+        ci_95 = res.conf_int(alpha=0.05)
+        assert np.allclose(report["0.025"].to_numpy(), ci_95[:, 0], rtol=1e-8)
+        assert np.allclose(report["0.975"].to_numpy(), ci_95[:, 1], rtol=1e-8)
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_report("x1", "x2", target="y", alpha=1.5)
+
+
+def test_lin_reg_pred_with_interval():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(31)
+    X = rng.normal(size=(100, 2))
+    y = 1.0 + X @ np.array([0.5, -0.25]) + rng.normal(size=100) * 0.5
+    x1 = [None if i % 10 == 0 else v for i, v in enumerate(X[:, 0])]
+    df = pl.DataFrame({"x1": x1, "x2": X[:, 1], "y": y})
+    keep = df["x1"].is_not_null().to_numpy()
+
+    # The default output is unchanged
+    pred = df.select(
+        pds.lin_reg("x1", "x2", target="y", add_bias=True, return_pred=True)
+    ).unnest("lr_pred")
+    assert pred.columns == ["pred", "resid"]
+
+    pred = df.select(
+        pds.lin_reg(
+            "x1", "x2", target="y", add_bias=True, return_pred=True, pred_interval=True, alpha=0.1
+        )
+    ).unnest("lr_pred")
+    assert pred.columns == ["pred", "resid", "pred_lower", "pred_upper"]
+    # Rows skipped because of nulls get NaN
+    assert pred["pred_lower"].filter(~keep).is_nan().all()
+    assert pred["pred_upper"].filter(~keep).is_nan().all()
+
+    design = sm.add_constant(X[keep], prepend=False)
+    res = sm.OLS(y[keep], design).fit()
+    frame = res.get_prediction(design).summary_frame(alpha=0.1)
+    assert np.allclose(pred["pred_lower"].filter(keep).to_numpy(), frame["obs_ci_lower"].to_numpy())
+    assert np.allclose(pred["pred_upper"].filter(keep).to_numpy(), frame["obs_ci_upper"].to_numpy())
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x1", target="y", l1_reg=0.1, return_pred=True, pred_interval=True)
+
+
+def test_lin_reg_pred_se():
+    # A small example by hand: x = [0, 1, 2, 3], y = [1, 3, 2, 5] with a bias
+    df = pl.DataFrame({"x": [0.0, 1.0, 2.0, 3.0], "y": [1.0, 3.0, 2.0, 5.0]})
+    X = np.column_stack([df["x"].to_numpy(), np.ones(4)])
+    y = df["y"].to_numpy()
+    xtx_inv = np.linalg.inv(X.T @ X)
+    b = xtx_inv @ X.T @ y
+    s2 = np.sum((y - X @ b) ** 2) / (4 - 2)
+    leverage = np.einsum("ij,jk,ik->i", X, xtx_inv, X)
+    assert np.allclose(leverage, [0.7, 0.3, 0.3, 0.7])
+
+    pred = df.select(
+        pds.lin_reg(
+            "x", target="y", add_bias=True, return_pred=True, pred_interval=True, pred_se=True
+        )
+    ).unnest("lr_pred")
+    assert pred.columns == ["pred", "resid", "pred_lower", "pred_upper", "pred_se"]
+    se = pred["pred_se"].to_numpy()
+    assert np.allclose(se, np.sqrt(s2 * leverage))
+    # The prediction interval has the extra variance of a new observation
+    t = (pred["pred_upper"] - pred["pred"]).to_numpy() / np.sqrt(s2 * (1.0 + leverage))
+    assert np.allclose(t, t[0])
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x", target="y", weights="x", return_pred=True, pred_se=True)
+
+
+def test_lin_reg_elastic_net():
+    from polars_ds.linear_models import ElasticNet
+
+    rng = np.random.default_rng(37)
+    X = rng.normal(size=(300, 4))
+    y = 0.5 + X @ np.array([1.0, 0.0, -0.5, 0.2]) + rng.normal(size=300) * 0.1
+    df = pl.DataFrame({"x1": X[:, 0], "x2": X[:, 1], "x3": X[:, 2], "x4": X[:, 3], "y": y})
+
+    def coeffs(**kwargs):
+        return (
+            df.select(
+                pds.lin_reg("x1", "x2", "x3", "x4", target="y", add_bias=True, tol=1e-10, **kwargs)
+            )
+            .explode("coeffs")["coeffs"]
+            .to_numpy()
+        )
+
+    # Elastic net with l2_reg = 0 is Lasso, and both match the ElasticNet model
+    for l2_reg in [0.0, 0.2]:
+        en = ElasticNet(l1_reg=0.05, l2_reg=l2_reg, fit_bias=True, tol=1e-10)
+        en.fit(X, y)
+        expected = np.append(en.coeffs(), en.bias())
+        assert np.allclose(coeffs(l1_reg=0.05, l2_reg=l2_reg), expected, atol=1e-8)
+
+    # The l2 penalty shrinks the coefficients further
+    assert np.abs(coeffs(l1_reg=0.05, l2_reg=0.2)[:4]).sum() < np.abs(coeffs(l1_reg=0.05)[:4]).sum()
+    # max_iter is respected
+    assert not np.allclose(coeffs(l1_reg=0.05, l2_reg=0.2, max_iter=1), expected, atol=1e-8)
+    with pytest.raises(ValueError):
+        pds.lin_reg("x1", target="y", l1_reg=0.05, max_iter=0)
+
+
+def test_lin_reg_dispatches_f32_by_dtype():
+    size = 500
+    df = (
+        pds.frame(size=size)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(0.0, 1.0).alias("x3"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 0.5 - pl.col("x2") * 0.25 + pl.col("x3") * 0.15 + pds.random() * 0.01,
+        )
+    )
+    df32 = df.cast(pl.Float32)
+    for kwargs in [{}, {"l2_reg": 0.1}, {"l1_reg": 0.001}, {"add_bias": True}]:
+        c64 = df.select(pds.lin_reg("x1", "x2", "x3", target="y", **kwargs))["coeffs"]
+        c32 = df32.select(pds.lin_reg("x1", "x2", "x3", target="y", **kwargs))["coeffs"]
+        assert c64.dtype == pl.List(pl.Float64)
+        assert c32.dtype == pl.List(pl.Float32)
+        assert np.allclose(c32[0].to_numpy(), c64[0].to_numpy(), atol=1e-4)
+
+    pred = df32.select(pds.lin_reg("x1", "x2", "x3", target="y", return_pred=True)).unnest(
+        "lr_pred"
+    )
+    assert pred["pred"].dtype == pl.Float32
+    assert pred["resid"].dtype == pl.Float32
+    # A single Float64 column keeps the f64 path
+    mixed = df32.with_columns(pl.col("x1").cast(pl.Float64))
+    assert mixed.select(pds.lin_reg("x1", "x2", "x3", target="y"))["coeffs"].dtype == pl.List(
+        pl.Float64
+    )
 
 
 def test_f32_lin_reg():
@@ -1054,46 +1910,1983 @@ def test_lin_reg_skip_null():
     )
 
 
-def test_lin_reg_in_group_by():
-    df = pl.DataFrame(
-        {
-            "A": [1] * 4 + [2] * 4,
-            "Y": [1] * 8,
-            "X1": [1, 2, 3, 4, 5, 6, 7, 8],
-            "X2": [2, 3, 4, 1, 6, 7, 8, 5],
-        }
+def test_lin_reg_bias_first():
+    df = pds.frame(size=1000).select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(0.0, 1.0).alias("x2"),
+    ).with_columns(
+        y=pl.col("x1") * 0.5 - pl.col("x2") + 2.0,
     )
 
-    first = df.filter(pl.col("A").eq(1)).with_columns(
-        pds.lin_reg(
-            pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
-        ).alias("pred")
+    last = df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True)).item().to_list()
+    first = (
+        df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True, bias_first=True))
+        .item()
+        .to_list()
     )
+    assert first == [last[-1]] + last[:-1]
 
-    second = df.filter(pl.col("A").eq(2)).with_columns(
-        pds.lin_reg(
-            pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
-        ).alias("pred")
+    report = df.select(
+        pds.lin_reg_report("x1", "x2", target="y", add_bias=True, bias_first=True).alias("report")
+    ).unnest("report")
+    assert report["features"].to_list() == ["__bias__", "x1", "x2"]
+
+
+def test_lin_reg_ridge_loocv():
+    rng = np.random.default_rng(11)
+    size = 60
+    x = rng.normal(0.0, 1.0, size=(size, 3))
+    y = x @ np.array([1.0, -2.0, 0.5]) + 1.0 + rng.normal(0.0, 1.0, size=size)
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "x3": x[:, 2], "y": y})
+    lambdas = [0.0, 0.5, 5.0, 50.0]
+
+    res = df.select(
+        pds.lin_reg_ridge_loocv("x1", "x2", "x3", target="y", lambdas=lambdas, add_bias=True)
+    ).unnest("ridge_loocv")
+
+    # Brute force leave-one-out with the regular ridge fit
+    errors = []
+    for lam in lambdas:
+        sse = 0.0
+        for i in range(size):
+            train = df.filter(pl.int_range(pl.len()) != i)
+            coeffs = (
+                train.select(pds.lin_reg("x1", "x2", "x3", target="y", l2_reg=lam, add_bias=True))
+                .item()
+                .to_numpy()
+            )
+            pred = x[i] @ coeffs[:3] + coeffs[3]
+            sse += (y[i] - pred) ** 2
+        errors.append(sse / size)
+
+    assert np.allclose(res["loo_mse"][0].to_numpy(), errors)
+    assert res["best_lambda"][0] == lambdas[int(np.argmin(errors))]
+
+
+def test_lin_reg_cv():
+    df = (
+        pds.frame(size=500)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(0.0, 1.0).alias("x3"),
+            pds.random(-0.1, 0.1).alias("noise"),
+        )
+        .with_columns(y=pl.col("x1") - 2.0 * pl.col("x2") + 1.0 + pl.col("noise"))
     )
+    lambdas = [0.0001, 0.001, 0.01, 0.1]
+    res = df.select(
+        pds.lin_reg_cv("x1", "x2", "x3", target="y", lambdas=lambdas, add_bias=True, seed=42)
+    ).unnest("lstsq_cv")
+
+    cv_mse = res["cv_mse"][0].to_numpy()
+    cv_se = res["cv_se"][0].to_numpy()
+    lambda_min = res["lambda_min"][0]
+    lambda_1se = res["lambda_1se"][0]
+    i = int(np.argmin(cv_mse))
+    assert lambda_min == lambdas[i]
+    assert lambda_1se >= lambda_min
+    assert cv_mse[lambdas.index(lambda_1se)] <= cv_mse[i] + cv_se[i]
+
+    # The final coefficients are the same as the regular fit on all rows
+    for lam, field in [(lambda_min, "coeffs_min"), (lambda_1se, "coeffs_1se")]:
+        ans = df.select(
+            pds.lin_reg("x1", "x2", "x3", target="y", l1_reg=lam, add_bias=True, tol=1e-5)
+        ).item()
+        assert np.allclose(res[field][0].to_numpy(), ans.to_numpy())
+
+    assert res["folds"][0].to_list() == [0, 1, 2, 3, 4]
+
+
+def test_lin_reg_cv_custom_folds():
+    rng = np.random.default_rng(3)
+    size = 300
+    x = rng.normal(0.0, 1.0, size=(size, 2))
+    y = 1.0 + x[:, 0] - 2.0 * x[:, 1] + rng.normal(0.0, 0.5, size=size)
+    fold = np.repeat([10, 20, 30], size // 3)  # blocked folds
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "y": y, "fold": fold})
+
+    lambdas = [0.0, 1.0, 10.0]
+    res = df.select(
+        pds.lin_reg_cv("x1", "x2", target="y", lambdas=lambdas, l1_ratio=0.0, folds="fold", add_bias=True)
+    ).unnest("lstsq_cv")
+    assert res["folds"][0].to_list() == [10, 20, 30]
+
+    # Ridge by hand on each block. The bias is not penalized.
+    design = np.column_stack([x, np.ones(size)])
+    penalty = np.diag([1.0, 1.0, 0.0])
+    errors = np.zeros((len(lambdas), 3))
+    for k, lam in enumerate(lambdas):
+        for f, v in enumerate([10, 20, 30]):
+            train, test = fold != v, fold == v
+            xtx = design[train].T @ design[train] + lam * penalty
+            coeffs = np.linalg.solve(xtx, design[train].T @ y[train])
+            errors[k, f] = ((y[test] - design[test] @ coeffs) ** 2).mean()
+
+    best = int(np.argmin(errors.mean(axis=1)))
+    assert np.allclose(res["cv_mse"][0].to_numpy(), errors.mean(axis=1))
+    assert np.allclose(res["fold_mse"][0].to_numpy(), errors[best])
+    assert res["lambda_min"][0] == lambdas[best]
+    cv_std = errors.std(axis=1, ddof=1)
+    assert np.allclose(res["cv_std"][0].to_numpy(), cv_std)
+    assert np.allclose(res["cv_se"][0].to_numpy(), cv_std / np.sqrt(3))
+
+    with pytest.raises(Exception):
+        df.select(
+            pds.lin_reg_cv("x1", "x2", target="y", lambdas=lambdas, folds=pl.col("fold") * 0)
+        )
 
-    test = (
-        df.group_by("A", maintain_order=True)
-        .agg(
-            "Y",
-            "X1",
-            "X2",
-            pds.lin_reg(
-                pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
-            ).alias("pred"),
+
+def test_lin_reg_robust():
+    df = (
+        pds.frame(size=2000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(-0.1, 0.1).alias("noise"),
+        )
+        .with_row_index()
+        .with_columns(
+            y=pl.col("x1") * 2.0 - pl.col("x2") + 0.5 + pl.col("noise")
+            + pl.when(pl.col("index") < 100).then(20.0).otherwise(0.0),
         )
-        .explode("Y", "X1", "X2", "pred")
     )
 
-    test_first = test.filter(pl.col("A") == 1)
-    test_second = test.filter(pl.col("A") == 2)
+    for estimate_scale in [False, True]:
+        res = df.select(
+            pds.lin_reg_robust(
+                "x1", "x2", target="y", add_bias=True, estimate_scale=estimate_scale
+            )
+        ).unnest("robust")
+        coeffs = res["coeffs"][0].to_numpy()
+        assert res["converged"][0]
+        assert np.allclose(coeffs, [2.0, -1.0, 0.5], atol=0.2)
+        assert res["scale"][0] > 0
 
-    assert_frame_equal(first, test_first)
-    assert_frame_equal(second, test_second)
+
+def test_lin_reg_mm():
+    rng = np.random.default_rng(3)
+    size = 1000
+    x = rng.uniform(0.0, 10.0, size=size)
+    y = 2.0 * x + 1.0 + rng.normal(0.0, 0.5, size=size)
+    # 40% bad leverage points, which break OLS and Huber IRLS
+    n_out = 400
+    x[:n_out] = rng.uniform(15.0, 16.0, size=n_out)
+    y[:n_out] = rng.uniform(-5.0, -4.0, size=n_out)
+    df = pl.DataFrame({"x": x, "y": y})
+
+    res = df.select(pds.lin_reg_mm("x", target="y", add_bias=True, seed=1)).unnest("mm")
+    assert res["converged"][0]
+    assert np.allclose(res["coeffs"][0].to_numpy(), [2.0, 1.0], atol=0.1)
+    weights = res["weights"][0].to_numpy()
+    assert len(weights) == size
+    assert np.all(weights[:n_out] == 0.0)
+    assert np.mean(weights[n_out:] > 0.5) > 0.95
+
+    # Same as OLS on clean data
+    clean = df.slice(n_out)
+    res = clean.select(pds.lin_reg_mm("x", target="y", add_bias=True, seed=1)).unnest("mm")
+    ols = clean.select(pds.lin_reg("x", target="y", add_bias=True)).item().to_numpy()
+    assert np.allclose(res["coeffs"][0].to_numpy(), ols, atol=0.02)
+    assert np.isclose(res["scale"][0], 0.5, atol=0.05)
+
+
+def test_lin_reg_ransac():
+    rng = np.random.default_rng(5)
+    size = 500
+    n_out = 150
+    x = rng.uniform(0.0, 10.0, size=size)
+    y = 3.0 * x - 2.0 + rng.uniform(-0.05, 0.05, size=size)
+    # 30% of the points are off the line
+    y[:n_out] = rng.uniform(-25.0, 25.0, size=n_out)
+    df = pl.DataFrame({"x": x, "y": y})
+
+    res = df.select(
+        pds.lin_reg_ransac("x", target="y", add_bias=True, residual_threshold=0.5, seed=1)
+    ).unnest("ransac")
+    coeffs = res["coeffs"][0].to_numpy()
+    assert np.allclose(coeffs, [3.0, -2.0], atol=0.01)
+    inliers = res["inliers"][0].to_numpy()
+    assert len(inliers) == size
+    assert res["n_inliers"][0] == inliers.sum()
+    assert np.all(inliers[n_out:])
+    assert inliers[:n_out].mean() < 0.1
+
+    # Refitting on the inliers gives the same coefficients
+    refit = (
+        df.filter(pl.Series(inliers))
+        .select(pds.lin_reg("x", target="y", add_bias=True))
+        .item()
+        .to_numpy()
+    )
+    assert np.allclose(coeffs, refit)
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_ransac("x", target="y", n_trials=0)
+
+
+def test_lin_reg_theil_sen():
+    rng = np.random.default_rng(29)
+    size = 400
+    n_out = 80
+    x = rng.uniform(0.0, 10.0, size=size)
+    y = 2.0 * x + 1.0 + rng.normal(0.0, 0.1, size=size)
+    # A cluster of outliers far above the line at large x
+    x[:n_out] = rng.uniform(8.0, 10.0, size=n_out)
+    y[:n_out] = rng.uniform(60.0, 70.0, size=n_out)
+    df = pl.DataFrame({"x": x, "y": y})
+
+    coeffs = df.select(pds.lin_reg_theil_sen("x", target="y"))["coeffs"][0].to_numpy()
+    assert np.allclose(coeffs, [2.0, 1.0], atol=0.1)
+    ols = df.select(pds.lin_reg("x", target="y", add_bias=True))["coeffs"][0].to_numpy()
+    assert abs(ols[0] - 2.0) > 1.0
+
+    first = df.select(pds.lin_reg_theil_sen("x", target="y", bias_first=True))["coeffs"][0]
+    assert np.allclose(first.to_numpy(), coeffs[::-1])
+
+    # Subsampled pairs are reproducible with a seed and close to the full estimate
+    sub = [
+        df.select(pds.lin_reg_theil_sen("x", target="y", max_pairs=5000, seed=3))["coeffs"][0]
+        .to_numpy()
+        for _ in range(2)
+    ]
+    assert np.array_equal(sub[0], sub[1])
+    assert np.allclose(sub[0], coeffs, atol=0.1)
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_theil_sen("x", target="y", max_pairs=0)
+
+
+def test_lin_reg_huber():
+    rng = np.random.default_rng(11)
+    size = 1000
+    x1 = rng.normal(size=size)
+    x2 = rng.uniform(-1.0, 1.0, size=size)
+    y = 1.5 * x1 - 0.5 * x2 + 2.0 + rng.normal(0.0, 0.3, size=size)
+    df = pl.DataFrame({"x1": x1, "x2": x2, "y": y})
+    clean = df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True)).item().to_numpy()
+
+    # Gross outliers in the target
+    y_bad = y.copy()
+    y_bad[:20] += 50.0
+    df_bad = df.with_columns(y=pl.Series(y_bad))
+    huber = (
+        df_bad.select(pds.lin_reg_huber("x1", "x2", target="y", add_bias=True)).item().to_numpy()
+    )
+    ols = df_bad.select(pds.lin_reg("x1", "x2", target="y", add_bias=True)).item().to_numpy()
+    assert np.allclose(huber, clean, atol=0.05)
+    assert np.max(np.abs(huber - clean)) < 0.1 * np.max(np.abs(ols - clean))
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_huber("x1", target="y", delta=0.0)
+
+
+def test_lin_reg_quantile():
+    from sklearn.linear_model import QuantileRegressor
+
+    # Heteroscedastic noise, so that the conditional quantiles have different slopes
+    rng = np.random.default_rng(23)
+    # Odd, so that size * tau is not an integer and the minimizer is unique
+    size = 1001
+    x = rng.uniform(0.0, 4.0, size=size)
+    y = 1.0 + 2.0 * x + rng.uniform(-1.5, 1.5, size=size) * (1.0 + x)
+    df = pl.DataFrame({"x": x, "y": y})
+
+    for tau in [0.1, 0.5, 0.9]:
+        coeffs = (
+            df.select(pds.lin_reg_quantile("x", target="y", tau=tau, add_bias=True))
+            .item()
+            .to_numpy()
+        )
+        resid = y - (coeffs[0] * x + coeffs[1])
+        # Up to the p = 2 rows on the fit
+        assert abs(np.mean(resid < -1e-9) - tau) <= 2 / size
+        # The exact minimizer of the pinball loss. LAD regression if tau = 0.5
+        sk = QuantileRegressor(quantile=tau, alpha=0.0, solver="highs").fit(x.reshape(-1, 1), y)
+        assert np.allclose(coeffs, [sk.coef_[0], sk.intercept_], atol=1e-6)
+
+    # The median of the target if there is only the bias
+    coeffs = df.select(pds.lin_reg_quantile(target="y", add_bias=True)).item().to_numpy()
+    assert np.isclose(coeffs[0], np.median(y))
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_quantile("x", target="y", tau=1.0)
+
+
+def test_logistic_reg():
+    import statsmodels.api as sm
+    from sklearn.linear_model import LogisticRegression
+
+    rng = np.random.default_rng(17)
+    size = 2000
+    x1 = rng.normal(size=size)
+    x2 = rng.uniform(-2.0, 2.0, size=size)
+    prob = 1.0 / (1.0 + np.exp(-(1.5 * x1 - 0.7 * x2 + 0.3)))
+    y = (rng.uniform(size=size) < prob).astype(np.float64)
+    df = pl.DataFrame({"x1": x1, "x2": x2, "y": y})
+
+    # Unpenalized, same as the maximum likelihood estimate
+    coeffs = df.select(pds.logistic_reg("x1", "x2", target="y", add_bias=True)).item().to_numpy()
+    X = np.column_stack([x1, x2, np.ones(size)])
+    sm_res = sm.Logit(y, X).fit(disp=0)
+    assert np.allclose(coeffs, sm_res.params, atol=1e-6)
+
+    pred = df.select(pds.logistic_reg("x1", "x2", target="y", add_bias=True, return_pred=True))
+    assert np.allclose(pred["prob"].to_numpy(), sm_res.predict(X), atol=1e-6)
+
+    # L2, with the bias unpenalized, same as scikit-learn with C = 1 / l2_reg
+    coeffs = (
+        df.select(
+            pds.logistic_reg("x1", "x2", target="y", add_bias=True, bias_first=True, l2_reg=50.0)
+        )
+        .item()
+        .to_numpy()
+    )
+    sk = LogisticRegression(C=1 / 50.0, tol=1e-10, max_iter=1000).fit(X[:, :2], y)
+    assert np.allclose(coeffs, np.concatenate([sk.intercept_, sk.coef_[0]]), atol=1e-4)
+
+    # Perfectly separated classes terminate with finite coefficients and the right signs
+    sep = df.with_columns(y=(pl.col("x1") > 0).cast(pl.Float64))
+    coeffs = sep.select(pds.logistic_reg("x1", target="y", add_bias=True)).item().to_numpy()
+    assert np.all(np.isfinite(coeffs))
+    assert coeffs[0] > 10.0
+
+    # Nulls are skipped, and the predictions are NaN for those rows
+    df_null = df.with_columns(x2=pl.when(pl.int_range(size) < 10).then(None).otherwise(pl.col("x2")))
+    pred = df_null.select(
+        pds.logistic_reg("x1", "x2", target="y", add_bias=True, return_pred=True)
+    )["prob"].to_numpy()
+    assert np.all(np.isnan(pred[:10]))
+    assert np.all((pred[10:] > 0.0) & (pred[10:] < 1.0))
+
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.logistic_reg("x1", target="x2"))
+
+
+def test_poisson_reg():
+    import statsmodels.api as sm
+
+    # Counts with rate exp(0.8 * x1 - 0.5 * x2 + 1.2) per unit of exposure
+    rng = np.random.default_rng(29)
+    size = 3000
+    x1 = rng.uniform(-1.0, 1.0, size=size)
+    x2 = rng.uniform(-1.0, 1.0, size=size)
+    exposure = rng.uniform(1.0, 10.0, size=size)
+    y = rng.poisson(exposure * np.exp(0.8 * x1 - 0.5 * x2 + 1.2)).astype(np.float64)
+    df = pl.DataFrame({"x1": x1, "x2": x2, "exposure": exposure, "y": y})
+
+    res = df.select(
+        pds.poisson_reg(
+            "x1", "x2", target="y", offset=pl.col("exposure").log(), add_bias=True
+        )
+    ).unnest("poisson")
+    assert res["converged"][0]
+    assert res["n_iter"][0] < 100
+    coeffs = res["coeffs"][0].to_numpy()
+    assert np.allclose(coeffs, [0.8, -0.5, 1.2], atol=0.05)
+
+    X = np.column_stack([x1, x2, np.ones(size)])
+    sm_res = sm.GLM(y, X, family=sm.families.Poisson(), offset=np.log(exposure)).fit()
+    assert np.allclose(coeffs, sm_res.params, atol=1e-6)
+
+    pred = df.select(
+        pds.poisson_reg(
+            "x1", "x2", target="y", offset=pl.col("exposure").log(), add_bias=True, return_pred=True
+        )
+    )["pred"].to_numpy()
+    assert np.allclose(pred, sm_res.predict(X, offset=np.log(exposure)), rtol=1e-6)
+
+    # Without the offset, the exposure is absorbed into the bias and the fit is different
+    coeffs_no_offset = (
+        df.select(pds.poisson_reg("x1", "x2", target="y", add_bias=True, bias_first=True))
+        .unnest("poisson")["coeffs"][0]
+        .to_numpy()
+    )
+    assert coeffs_no_offset[0] > 2.0
+
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.poisson_reg("x1", target="x2"))
+
+
+def test_glm_reg():
+    import statsmodels.api as sm
+
+    rng = np.random.default_rng(31)
+    size = 2000
+    x1 = rng.normal(size=size)
+    x2 = rng.normal(size=size)
+    p = 1.0 / (1.0 + np.exp(-(1.5 * x1 - 0.7 * x2 + 0.3)))
+    df = pl.DataFrame(
+        {
+            "x1": x1,
+            "x2": x2,
+            "label": (rng.uniform(size=size) < p).astype(np.float64),
+            "count": rng.poisson(np.exp(0.5 * x1 - 0.3 * x2 + 1.0)).astype(np.float64),
+            "amount": rng.gamma(2.0, np.exp(0.4 * x1 + 0.2 * x2 + 1.0) / 2.0),
+        }
+    )
+
+    # The canonical links match the dedicated implementations
+    res = df.select(
+        pds.glm_reg("x1", "x2", target="label", family="binomial", add_bias=True).alias("glm"),
+        pds.logistic_reg("x1", "x2", target="label", add_bias=True).alias("logistic"),
+        pds.glm_reg(
+            "x1", "x2", target="count", family="poisson", link="log", add_bias=True
+        ).alias("glm_poisson"),
+        pds.poisson_reg("x1", "x2", target="count", add_bias=True),
+    )
+    glm = res["glm"][0]
+    assert glm["converged"]
+    assert np.allclose(glm["coeffs"], res["logistic"][0], atol=1e-8)
+    assert np.allclose(res["glm_poisson"][0]["coeffs"], res["poisson"][0]["coeffs"], atol=1e-8)
+    pred = df.select(
+        pds.glm_reg("x1", "x2", target="label", family="binomial", add_bias=True, return_pred=True),
+        pds.logistic_reg("x1", "x2", target="label", add_bias=True, return_pred=True),
+    )
+    assert np.allclose(pred["pred"], pred["prob"], atol=1e-8)
+
+    # Gaussian with the identity link is OLS
+    coeffs = df.select(
+        pds.glm_reg("x1", "x2", target="amount", add_bias=True).struct.field("coeffs")
+    )["coeffs"][0]
+    ols = df.select(pds.lin_reg("x1", "x2", target="amount", add_bias=True))["coeffs"][0]
+    assert np.allclose(coeffs, ols, atol=1e-8)
+
+    # A non-canonical link and prior weights
+    X = np.column_stack([x1, x2, np.ones(size)])
+    w = rng.uniform(0.5, 2.0, size=size)
+    res = (
+        df.with_columns(w=w)
+        .select(
+            pds.glm_reg(
+                "x1", "x2", target="amount", family="gamma", link="log", weights="w", add_bias=True
+            )
+        )
+        .unnest("glm")
+    )
+    assert res["converged"][0]
+    sm_res = sm.GLM(
+        df["amount"].to_numpy(),
+        X,
+        family=sm.families.Gamma(sm.families.links.Log()),
+        var_weights=w,
+    ).fit()
+    assert np.allclose(res["coeffs"][0].to_numpy(), sm_res.params, atol=1e-6)
+
+    with pytest.raises(ValueError):
+        pds.glm_reg("x1", target="label", family="tweedie")
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.glm_reg("x1", target="x2", family="poisson"))
+
+
+def test_offset():
+    import statsmodels.api as sm
+
+    # Counts with rate exp(0.6 * x1 - 0.4 * x2 + 0.5) per unit of exposure
+    rng = np.random.default_rng(37)
+    size = 2000
+    x1 = rng.uniform(-1.0, 1.0, size=size)
+    x2 = rng.uniform(-1.0, 1.0, size=size)
+    exposure = rng.uniform(1.0, 20.0, size=size)
+    y = rng.poisson(exposure * np.exp(0.6 * x1 - 0.4 * x2 + 0.5)).astype(np.float64)
+    df = pl.DataFrame({"x1": x1, "x2": x2, "exposure": exposure, "y": y})
+
+    offset = pl.col("exposure").log()
+    res = df.select(
+        pds.glm_reg("x1", "x2", target="y", family="poisson", offset=offset, add_bias=True),
+        pds.poisson_reg("x1", "x2", target="y", offset=offset, add_bias=True),
+    )
+    coeffs = res["glm"][0]["coeffs"].to_numpy()
+    assert np.allclose(coeffs, [0.6, -0.4, 0.5], atol=0.05)
+    assert np.allclose(coeffs, res["poisson"][0]["coeffs"], atol=1e-8)
+    X = np.column_stack([x1, x2, np.ones(size)])
+    sm_res = sm.GLM(y, X, family=sm.families.Poisson(), exposure=exposure).fit()
+    assert np.allclose(coeffs, sm_res.params, atol=1e-6)
+
+    pred = df.select(
+        pds.glm_reg(
+            "x1", "x2", target="y", family="poisson", offset=offset, add_bias=True, return_pred=True
+        )
+    )["pred"].to_numpy()
+    assert np.allclose(pred, sm_res.predict(X, exposure=exposure), rtol=1e-6)
+
+    # OLS with an offset is OLS on target - offset, with the offset added back to the predictions.
+    # A null in the offset is skipped like a null in a feature.
+    df = pl.DataFrame(
+        {
+            "x": rng.normal(size=200),
+            "o": [None] + list(rng.normal(size=199)),
+        }
+    ).with_columns(y=2.0 * pl.col("x") + pl.col("o").fill_null(0.0) + 1.0 + rng.normal(size=200))
+    res = df.select(
+        pds.lin_reg("x", target="y", offset="o", add_bias=True).alias("offset"),
+        pds.lin_reg(
+            pl.col("x").filter(pl.col("o").is_not_null()),
+            target=(pl.col("y") - pl.col("o")).filter(pl.col("o").is_not_null()),
+            add_bias=True,
+        ).alias("diff"),
+    )
+    assert np.allclose(res["offset"][0], res["diff"][0])
+
+    pred = df.select(
+        pds.lin_reg("x", target="y", offset="o", add_bias=True, return_pred=True)
+    ).unnest("lr_pred")
+    coeffs = res["offset"][0].to_numpy()
+    expected = coeffs[0] * df["x"].to_numpy() + coeffs[1] + df["o"].to_numpy()
+    assert np.isnan(pred["pred"][0])
+    assert np.allclose(pred["pred"].to_numpy()[1:], expected[1:])
+    assert np.allclose(pred["resid"].to_numpy()[1:], (df["y"].to_numpy() - expected)[1:])
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x", target=["y", "o"], offset="o")
+
+
+def test_lin_reg_ar1():
+    rng = np.random.default_rng(42)
+    size = 5000
+    x = rng.uniform(0.0, 10.0, size=size)
+    u = rng.normal(0.0, 1.0, size=size)
+    e = np.zeros(size)
+    for t in range(1, size):
+        e[t] = 0.7 * e[t - 1] + u[t]
+
+    df = pl.DataFrame({"x": x, "y": 2.0 * x + 3.0 + e})
+    for prais_winsten in [False, True]:
+        res = df.select(
+            pds.lin_reg_ar1("x", target="y", add_bias=True, prais_winsten=prais_winsten)
+        ).unnest("ar1")
+        assert res["converged"][0]
+        assert abs(res["rho"][0] - 0.7) < 0.05
+        assert np.allclose(res["coeffs"][0].to_numpy(), [2.0, 3.0], atol=0.2)
+
+    # Prais-Winsten keeps the first row, scaled by sqrt(1 - rho^2). The coefficients are the OLS
+    # ones on the transformed data at the final rho. On a short series, the first row matters.
+    n = 30
+    res = df.head(n).select(
+        pds.lin_reg_ar1("x", target="y", add_bias=True, prais_winsten=True, tol=1e-12)
+    ).unnest("ar1")
+    rho = res["rho"][0]
+    design = np.column_stack([x[:n], np.ones(n)])
+    target = df["y"].to_numpy()[:n]
+
+    def transform(m, keep_first):
+        rest = m[1:] - rho * m[:-1]
+        return np.concatenate([np.sqrt(1.0 - rho**2) * m[:1], rest]) if keep_first else rest
+
+    pw = np.linalg.lstsq(transform(design, True), transform(target, True), rcond=None)[0]
+    co = np.linalg.lstsq(transform(design, False), transform(target, False), rcond=None)[0]
+    assert np.allclose(res["coeffs"][0].to_numpy(), pw)
+    assert not np.allclose(pw, co)
+
+
+def test_lin_reg_fixed_effects():
+    rng = np.random.default_rng(7)
+    n_groups, size = 20, 2000
+    g = rng.integers(0, n_groups, size=size)
+    alpha = rng.normal(0.0, 5.0, size=n_groups)
+    x1 = rng.normal(0.0, 1.0, size=size) + alpha[g]  # correlated with the group effect
+    x2 = rng.normal(0.0, 1.0, size=size)
+    y = 2.0 * x1 - x2 + alpha[g] + rng.normal(0.0, 0.5, size=size)
+    df = pl.DataFrame({"g": g.astype(str), "x1": x1, "x2": x2, "y": y})
+
+    res = df.select(pds.lin_reg_fixed_effects("x1", "x2", target="y", group="g")).unnest(
+        "fixed_effects"
+    )
+    assert res["n_groups"][0] == n_groups
+    assert np.allclose(res["beta"].to_numpy(), [2.0, -1.0], atol=0.05)
+
+    # Same as OLS with one dummy per group
+    dummies = df.select("g").to_dummies()
+    lsdv = (
+        pl.concat([df, dummies], how="horizontal")
+        .select(pds.lin_reg_report("x1", "x2", *dummies.columns, target="y").alias("report"))
+        .unnest("report")
+        .head(2)
+    )
+    assert np.allclose(res["beta"].to_numpy(), lsdv["beta"].to_numpy())
+    assert np.allclose(res["std_err"].to_numpy(), lsdv["std_err"].to_numpy())
+
+
+def test_lin_reg_group_slopes():
+    from scipy.stats import studentized_range
+
+    rng = np.random.default_rng(11)
+    size = 600
+    g = rng.integers(0, 3, size=size)
+    x = rng.normal(0.0, 1.0, size=size)
+    slopes = np.array([1.0, 1.0, 2.0])
+    y = slopes[g] * x + g + rng.normal(0.0, 0.5, size=size)
+    df = pl.DataFrame({"g": g, "x": x, "y": y})
+
+    res = df.select(pds.lin_reg_group_slopes("x", target="y", group="g")).unnest("group_slopes")
+    assert res["group_a"].to_list() == ["0", "0", "1"]
+    assert res["group_b"].to_list() == ["1", "2", "2"]
+
+    # Separate fits per group, with the pooled residual variance
+    fits = [np.polyfit(x[g == i], y[g == i], 1) for i in range(3)]
+    ssr = sum(((y[g == i] - np.polyval(fits[i], x[g == i])) ** 2).sum() for i in range(3))
+    dof = size - 6
+    sxx = [((x[g == i] - x[g == i].mean()) ** 2).sum() for i in range(3)]
+    for row, (a, b) in enumerate([(0, 1), (0, 2), (1, 2)]):
+        diff = fits[b][0] - fits[a][0]
+        se = np.sqrt(ssr / dof * (1 / sxx[a] + 1 / sxx[b]))
+        p = studentized_range.sf(abs(diff) / se * np.sqrt(2), 3, dof)
+        q = studentized_range.ppf(0.95, 3, dof) / np.sqrt(2)
+        assert np.isclose(res["diff"][row], diff)
+        assert np.isclose(res["std_err"][row], se)
+        assert np.isclose(res["p_adj"][row], p, atol=1e-6)
+        assert np.isclose(res["lower"][row], diff - q * se, atol=1e-6)
+        assert np.isclose(res["upper"][row], diff + q * se, atol=1e-6)
+
+    # Only the slope of group 2 differs
+    assert res["p_adj"][0] > 0.05
+    assert res["p_adj"][1] < 1e-6
+    assert res["p_adj"][2] < 1e-6
+
+
+def test_lin_reg_best_subset():
+    import itertools
+
+    rng = np.random.default_rng(5)
+    size = 300
+    x = rng.normal(0.0, 1.0, size=(size, 5))
+    y = 1.0 + 2.0 * x[:, 0] - x[:, 2] + 0.5 * x[:, 4] + rng.normal(0.0, 1.0, size=size)
+    names = [f"x{i}" for i in range(5)]
+    df = pl.DataFrame({**{n: x[:, i] for i, n in enumerate(names)}, "y": y})
+
+    res = df.select(pds.lin_reg_best_subset(*names, target="y", add_bias=True)).unnest(
+        "best_subset"
+    )
+    assert res["criterion"].to_list() == ["aic", "bic", "adj_r2"]
+
+    # Brute force with NumPy
+    tss = ((y - y.mean()) ** 2).sum()
+    best = {}
+    for k in range(1, 6):
+        for subset in itertools.combinations(range(5), k):
+            design = np.column_stack([x[:, subset], np.ones(size)])
+            coeffs, *_ = np.linalg.lstsq(design, y, rcond=None)
+            rss = ((y - design @ coeffs) ** 2).sum()
+            n_coeffs = k + 1
+            values = {
+                "aic": size * np.log(rss / size) + 2 * n_coeffs,
+                "bic": size * np.log(rss / size) + n_coeffs * np.log(size),
+                "adj_r2": -(1 - (rss / (size - n_coeffs)) / (tss / (size - 1))),
+            }
+            for c, v in values.items():
+                if c not in best or v < best[c][0]:
+                    best[c] = (v, subset, coeffs)
+
+    for row, c in enumerate(["aic", "bic", "adj_r2"]):
+        value, subset, coeffs = best[c]
+        assert res["features"][row].to_list() == [names[i] for i in subset] + ["__bias__"]
+        assert np.allclose(res["coeffs"][row].to_numpy(), coeffs)
+        assert np.isclose(res["value"][row], -value if c == "adj_r2" else value)
+
+    # The true model is selected by BIC
+    assert res["features"][1].to_list() == ["x0", "x2", "x4", "__bias__"]
+
+
+def test_lin_reg_stepwise():
+    rng = np.random.default_rng(12)
+    size = 500
+    x = rng.normal(0.0, 1.0, size=(size, 4))
+    x[:, 3] = x[:, 0] + 0.05 * rng.normal(0.0, 1.0, size=size)  # almost a copy of x0
+    y = 1.0 + 2.0 * x[:, 0] - x[:, 1] + x[:, 3] + rng.normal(0.0, 1.0, size=size)
+    names = [f"x{i}" for i in range(4)]
+    df = pl.DataFrame({**{n: x[:, i] for i, n in enumerate(names)}, "y": y})
+
+    def aic(cols):
+        design = np.column_stack([x[:, cols], np.ones(size)])
+        coeffs, *_ = np.linalg.lstsq(design, y, rcond=None)
+        rss = ((y - design @ coeffs) ** 2).sum()
+        return size * np.log(rss / size) + 2 * (len(cols) + 1), coeffs
+
+    # Forward selection by hand
+    def forward(allowed):
+        selected, (value, coeffs) = [], (size * np.log(((y - y.mean()) ** 2).sum() / size) + 2, None)
+        while True:
+            trials = [(aic(selected + [j]), j) for j in allowed if j not in selected]
+            if len(trials) == 0:
+                return selected, coeffs
+            (v, c), j = min(trials, key=lambda t: t[0][0])
+            if v >= value:
+                return selected, coeffs
+            selected, value, coeffs = selected + [j], v, c
+
+    res = df.select(pds.lin_reg_stepwise(*names, target="y", add_bias=True)).unnest("stepwise")
+    selected, coeffs = forward(range(4))
+    assert res["features"][0].to_list() == [names[j] for j in selected] + ["__bias__"]
+    assert np.allclose(res["coeffs"][0].to_numpy(), coeffs)
+    assert res["rejected"][0].to_list() == []
+
+    # With the VIF guard, only one of x0 and x3 can be selected
+    res = df.select(
+        pds.lin_reg_stepwise(*names, target="y", add_bias=True, max_vif=10.0)
+    ).unnest("stepwise")
+    features = res["features"][0].to_list()
+    assert ("x0" in features) != ("x3" in features)
+    assert res["rejected"][0].to_list() == ["x3" if "x0" in features else "x0"]
+    selected, coeffs = forward([names.index(f) for f in features[:-1]])
+    assert np.allclose(res["coeffs"][0].to_numpy(), coeffs)
+
+
+def test_lin_reg_bootstrap_ci():
+    rng = np.random.default_rng(47)
+    size = 500
+    x = rng.normal(0.0, 1.0, size=(size, 2))
+    y = 1.0 + x @ np.array([2.0, -0.5]) + rng.normal(0.0, 1.0, size=size)
+    df = pl.DataFrame({"x0": x[:, 0], "x1": x[:, 1], "y": y})
+
+    expr = pds.lin_reg_bootstrap_ci("x0", "x1", target="y", add_bias=True, n_boot=2000, seed=5)
+    boot = df.select(expr).unnest("bootstrap_ci")
+    report = df.select(
+        pds.lin_reg_report("x0", "x1", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    assert boot["features"].to_list() == ["x0", "x1", "__bias__"]
+    assert np.allclose(boot["beta"].to_numpy(), report["beta"].to_numpy())
+
+    # On clean Gaussian data, the percentile intervals are close to the analytic ones
+    lower, upper = boot["lower"].to_numpy(), boot["upper"].to_numpy()
+    ci_lower, ci_upper = report["ci_lower"].to_numpy(), report["ci_upper"].to_numpy()
+    width = ci_upper - ci_lower
+    assert np.all(np.abs(lower - ci_lower) < 0.15 * width)
+    assert np.all(np.abs(upper - ci_upper) < 0.15 * width)
+    assert np.all((lower < boot["beta"].to_numpy()) & (boot["beta"].to_numpy() < upper))
+    assert np.allclose(boot["boot_se"].to_numpy(), report["std_err"].to_numpy(), rtol=0.15)
+
+    # Seeded, so reproducible
+    assert df.select(expr).unnest("bootstrap_ci").equals(boot)
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_bootstrap_ci("x0", target="y", confidence=1.5)
+
+
+def test_lin_reg_sign_stability():
+    rng = np.random.default_rng(21)
+    size = 200
+    x = rng.normal(0.0, 1.0, size=(size, 2))
+    # x0 has a strong effect, x1 has none
+    y = 3.0 * x[:, 0] + rng.normal(0.0, 1.0, size=size)
+    df = pl.DataFrame({"x0": x[:, 0], "x1": x[:, 1], "y": y})
+
+    expr = pds.lin_reg_sign_stability("x0", "x1", target="y", add_bias=True, n_boot=300, seed=3)
+    res = df.select(expr).unnest("sign_stability")
+    assert res["features"].to_list() == ["x0", "x1", "__bias__"]
+    ans = df.select(pds.lin_reg("x0", "x1", target="y", add_bias=True)).item()
+    assert np.allclose(res["beta"].to_numpy(), ans.to_numpy())
+
+    agreement = res["sign_agreement"].to_numpy()
+    assert agreement[0] == 1.0
+    assert np.all((agreement >= 0.0) & (agreement <= 1.0))
+    # Seeded, so reproducible
+    assert np.array_equal(agreement, df.select(expr).unnest("sign_stability")["sign_agreement"].to_numpy())
+
+
+def test_lin_reg_vif():
+    rng = np.random.default_rng(26)
+    size = 400
+    x = rng.normal(0.0, 1.0, size=(size, 3))
+    x[:, 1] += 0.8 * x[:, 0]  # correlated features
+    y = 1.0 + x[:, 0] - x[:, 2] + rng.normal(0.0, 1.0, size=size)
+    names = ["x0", "x1", "x2"]
+    df = pl.DataFrame({**{n: x[:, i] for i, n in enumerate(names)}, "y": y})
+
+    res = df.select(pds.lin_reg_vif(*names, target="y")).unnest("vif")
+    assert res["feature"].to_list() == names
+    # With a bias, the VIFs are the diagonal of the inverse of the correlation matrix
+    expected = np.diag(np.linalg.inv(np.corrcoef(x, rowvar=False)))
+    assert np.allclose(res["vif"].to_numpy(), expected)
+
+    # A feature that is a linear combination of the others
+    collinear = df.with_columns(x3=pl.col("x0") - 2.0 * pl.col("x1") + 3.0)
+    res = collinear.select(pds.lin_reg_vif(*names, "x3", target="y")).unnest("vif")
+    assert np.isinf(res["vif"].to_numpy()[[0, 1, 3]]).all()
+    assert np.isfinite(res["vif"][2])
+
+
+def test_lin_reg_relative_importance():
+    import itertools
+    import math
+
+    rng = np.random.default_rng(11)
+    size = 400
+    x = rng.normal(0.0, 1.0, size=(size, 3))
+    x[:, 1] += 0.6 * x[:, 0]  # correlated features
+    y = 0.5 + 1.5 * x[:, 0] + x[:, 1] - 0.5 * x[:, 2] + rng.normal(0.0, 1.0, size=size)
+    names = ["x0", "x1", "x2"]
+    df = pl.DataFrame({**{n: x[:, i] for i, n in enumerate(names)}, "y": y})
+
+    res = df.select(
+        pds.lin_reg_relative_importance(*names, target="y", add_bias=True)
+    ).unnest("relative_importance")
+    assert res["feature"].to_list() == names
+
+    def r2(subset):
+        if len(subset) == 0:
+            return 0.0
+        design = np.column_stack([x[:, list(subset)], np.ones(size)])
+        coeffs, *_ = np.linalg.lstsq(design, y, rcond=None)
+        rss = ((y - design @ coeffs) ** 2).sum()
+        return 1.0 - rss / ((y - y.mean()) ** 2).sum()
+
+    # Brute force over all orderings
+    importance = np.zeros(3)
+    for order in itertools.permutations(range(3)):
+        for k, j in enumerate(order):
+            importance[j] += r2(order[: k + 1]) - r2(order[:k])
+    importance /= math.factorial(3)
+
+    full_r2 = r2(range(3))
+    assert np.allclose(res["importance"].to_numpy(), importance)
+    assert np.isclose(res["importance"].sum(), full_r2)
+    assert np.allclose(res["share"].to_numpy(), importance / full_r2)
+
+
+def test_lin_reg_generalized_ridge():
+    rng = np.random.default_rng(17)
+    size, k = 200, 12
+    x = rng.normal(size=(size, k))
+    beta = np.sin(np.linspace(0.0, np.pi, k))
+    y = x @ beta + 1.0 + rng.normal(size=size)
+    names = [f"x{i}" for i in range(k)]
+    df = pl.DataFrame(x, schema=names).with_columns(y=pl.Series(y))
+
+    # Second difference operator, (k - 2) x k
+    gamma = np.zeros((k - 2, k))
+    for i in range(k - 2):
+        gamma[i, i : i + 3] = [1.0, -2.0, 1.0]
+    gamma = gamma * 10.0
+
+    coeffs = df.select(
+        pds.lin_reg_generalized_ridge(*names, target="y", penalty=gamma, add_bias=True)
+    )["coeffs"][0].to_numpy()
+    design = np.column_stack([x, np.ones(size)])
+    penalty = np.zeros((k + 1, k + 1))
+    penalty[:k, :k] = gamma.T @ gamma
+    expected = np.linalg.solve(design.T @ design + penalty, design.T @ y)
+    assert np.allclose(coeffs, expected)
+
+    # The profile is smoother than OLS
+    ols = df.select(pds.lin_reg(*names, target="y", add_bias=True))["coeffs"][0].to_numpy()
+    assert (np.diff(coeffs[:k], 2) ** 2).sum() < 0.1 * (np.diff(ols[:k], 2) ** 2).sum()
+
+    # Gamma = sqrt(lambda) * I is Ridge
+    ridge = df.select(pds.lin_reg(*names, target="y", l2_reg=4.0, add_bias=True))["coeffs"][0]
+    coeffs = df.select(
+        pds.lin_reg_generalized_ridge(*names, target="y", penalty=2.0 * np.eye(k), add_bias=True)
+    )["coeffs"][0]
+    assert np.allclose(coeffs.to_numpy(), ridge.to_numpy())
+
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.lin_reg_generalized_ridge(*names, target="y", penalty=np.eye(k + 1)))
+
+
+def test_lin_reg_adaptive_lasso():
+    # Adaptive Lasso is Lasso on the features scaled by 1 / weight, with the coefficients scaled back
+    rng = np.random.default_rng(11)
+    size = 500
+    x = rng.normal(size=(size, 4))
+    y = x @ np.array([2.0, 0.0, -1.5, 0.05]) + 1.0 + rng.normal(size=size) * 0.1
+    names = [f"x{i}" for i in range(4)]
+    df = pl.DataFrame(x, schema=names).with_columns(y=pl.Series(y))
+
+    l1_reg, gamma, l2_init = 0.05, 1.0, 0.01
+    coeffs = df.select(
+        pds.lin_reg_adaptive_lasso(
+            *names,
+            target="y",
+            l1_reg=l1_reg,
+            gamma=gamma,
+            l2_init=l2_init,
+            add_bias=True,
+            tol=1e-10,
+        )
+    )["coeffs"][0].to_numpy()
+
+    init = df.select(pds.lin_reg(*names, target="y", l2_reg=l2_init, add_bias=True))["coeffs"][0]
+    w = np.abs(init.to_numpy()[:4]) ** -gamma
+    scaled = df.with_columns(pl.col(n) / w[i] for i, n in enumerate(names))
+    lasso = scaled.select(
+        pds.lin_reg(*names, target="y", l1_reg=l1_reg, add_bias=True, tol=1e-10)
+    )["coeffs"][0].to_numpy()
+    lasso[:4] = lasso[:4] / w
+    assert np.allclose(coeffs, lasso, atol=1e-6)
+    # The irrelevant feature is dropped and the small one is heavily penalized
+    assert coeffs[1] == 0.0
+    assert abs(coeffs[3]) < 0.05
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_adaptive_lasso(*names, target="y", l1_reg=0.1, gamma=0.0)
+
+
+def test_lin_reg_lasso_path():
+    rng = np.random.default_rng(24)
+    size = 500
+    x = rng.normal(size=(size, 4))
+    y = x @ np.array([2.0, 0.0, -1.5, 0.05]) + 1.0 + rng.normal(size=size) * 0.1
+    names = [f"x{i}" for i in range(4)]
+    df = pl.DataFrame(x, schema=names).with_columns(y=pl.Series(y))
+
+    # Above this, Lasso sets all coefficients to 0
+    lambda_max = np.abs((x - x.mean(axis=0)).T @ (y - y.mean())).max() / size
+    lambdas = [0.001, lambda_max * 1.01, 0.1, 0.01]
+    tol = 1e-10
+    path = df.select(
+        pds.lin_reg_lasso_path(*names, target="y", lambdas=lambdas, add_bias=True, tol=tol)
+    )["lasso_path"][0]
+    assert len(path) == len(lambdas)
+
+    for coeffs, l1_reg in zip(path, lambdas):
+        cold = df.select(pds.lin_reg(*names, target="y", l1_reg=l1_reg, add_bias=True, tol=tol))[
+            "coeffs"
+        ][0].to_numpy()
+        assert np.allclose(coeffs.to_numpy(), cold, atol=1e-7)
+
+    largest = path[1].to_numpy()
+    assert np.all(largest[:4] == 0.0)
+    assert np.isclose(largest[4], y.mean())
+
+    with pytest.raises(ValueError):
+        pds.lin_reg_lasso_path(*names, target="y", lambdas=[])
+    with pytest.raises(ValueError):
+        pds.lin_reg_lasso_path(*names, target="y", lambdas=[0.1, -0.1])
+
+
+def test_lin_reg_conditioning():
+    rng = np.random.default_rng(12)
+    size = 300
+    x = 100.0 + rng.normal(size=(size, 3))
+    df = pl.DataFrame(x, schema=["x0", "x1", "x2"]).with_columns(y=pl.Series(rng.normal(size=size)))
+    res = df.select(pds.lin_reg_conditioning("x0", "x1", "x2", target="y")).unnest("conditioning")
+
+    ones = np.ones((size, 1))
+    centered = x - x.mean(axis=0)
+    assert np.isclose(res["cond_without_bias"][0], np.linalg.cond(x))
+    assert np.isclose(res["cond_with_bias"][0], np.linalg.cond(np.hstack([x, ones])))
+    assert np.isclose(res["cond_centered"][0], np.linalg.cond(np.hstack([centered, ones])))
+    # Far from 0 features make the bias column nearly collinear with them
+    assert res["cond_with_bias"][0] > 50 * res["cond_centered"][0]
+
+
+def test_lin_reg_xtx_eigenvalues():
+    rng = np.random.default_rng(14)
+    size = 200
+    x = rng.normal(size=(size, 3))
+    x[:, 2] = x[:, 0] + 0.01 * rng.normal(size=size)  # nearly collinear
+    df = pl.DataFrame(x, schema=["x0", "x1", "x2"]).with_columns(y=pl.Series(rng.normal(size=size)))
+
+    for add_bias in [False, True]:
+        res = df.select(
+            pds.lin_reg_xtx_eigenvalues("x0", "x1", "x2", target="y", add_bias=add_bias)
+        ).item()
+        xb = np.hstack([x, np.ones((size, 1))]) if add_bias else x
+        ans = np.sort(np.linalg.eigvalsh(xb.T @ xb))[::-1]
+        assert np.allclose(res.to_numpy(), ans)
+        assert res[-1] < 1e-3 * res[0]
+
+
+def test_lin_reg_pred_interval():
+    from scipy.stats import t as t_dist
+
+    rng = np.random.default_rng(13)
+    size = 120
+    x = rng.normal(size=(size, 3))
+    y = x @ np.array([1.0, -0.5, 0.25]) + 2.0 + rng.normal(size=size)
+    df = pl.DataFrame(x, schema=["x0", "x1", "x2"]).with_columns(y=pl.Series(y))
+    xb = np.hstack([x, np.ones((size, 1))])
+    xtx = xb.T @ xb
+
+    for l2_reg in [0.0, 5.0]:
+        res = df.select(
+            pds.lin_reg_pred_interval(
+                "x0", "x1", "x2", target="y", add_bias=True, l2_reg=l2_reg, confidence=0.9
+            )
+        ).unnest("pred_interval")
+
+        a_inv = np.linalg.inv(xtx + l2_reg * np.diag([1.0, 1.0, 1.0, 0.0]))
+        beta = a_inv @ xb.T @ y
+        dof = size - np.trace(a_inv @ xtx)
+        mse = np.sum((y - xb @ beta) ** 2) / dof
+        fit_var = mse * np.einsum("ij,jk,ik->i", xb, a_inv @ xtx @ a_inv, xb)
+        q = t_dist.ppf(0.95, dof)
+        assert np.allclose(res["prediction"].to_numpy(), xb @ beta)
+        assert np.allclose(res["fit_var"].to_numpy(), fit_var)
+        assert np.allclose(res["pred_var"].to_numpy(), fit_var + mse)
+        assert np.allclose(res["upper"].to_numpy(), xb @ beta + q * np.sqrt(fit_var + mse))
+
+    # Rows with nulls are null
+    df_null = df.with_columns(y=pl.when(pl.int_range(pl.len()) == 3).then(None).otherwise(pl.col("y")))
+    res = df_null.select(
+        pds.lin_reg_pred_interval("x0", "x1", "x2", target="y", interval="confidence")
+    ).unnest("pred_interval")
+    assert res.height == size
+    assert res["lower"].null_count() == 1
+
+
+def test_lin_reg_residualize():
+    rng = np.random.default_rng(14)
+    size = 100
+    z = rng.normal(size=(size, 2))
+    v = z @ np.array([1.0, -2.0]) + 3.0 + rng.normal(size=size)
+    df = pl.DataFrame(z, schema=["z0", "z1"]).with_columns(v=pl.Series(v))
+
+    res = df.select(pds.lin_reg_residualize("z0", "z1", column="v", add_bias=True))["v"]
+    zb = np.hstack([z, np.ones((size, 1))])
+    expected = v - zb @ np.linalg.lstsq(zb, v, rcond=None)[0]
+    assert np.allclose(res.to_numpy(), expected)
+
+    # Skipped rows are null, and the rest is the residual of the fit on the non-null rows
+    df_null = df.with_columns(z0=pl.when(pl.int_range(pl.len()) < 5).then(None).otherwise(pl.col("z0")))
+    res = df_null.select(pds.lin_reg_residualize("z0", "z1", column="v", null_policy="skip"))["v"]
+    assert res.len() == size
+    assert res[:5].is_null().all()
+    expected = v[5:] - z[5:] @ np.linalg.lstsq(z[5:], v[5:], rcond=None)[0]
+    assert np.allclose(res[5:].to_numpy(), expected)
+
+
+def test_lin_reg_chow_test():
+    from scipy.stats import f as f_dist
+
+    rng = np.random.default_rng(15)
+    size = 200
+    x = rng.normal(size=size)
+    # The slope changes at row 120
+    slope = np.where(np.arange(size) < 120, 1.0, 1.5)
+    y = slope * x + 0.5 + rng.normal(size=size) * 0.5
+    df = pl.DataFrame({"x": x, "y": y, "t": np.arange(size)})
+
+    def rss(a, b):
+        a = np.column_stack([a, np.ones(len(a))])
+        return np.sum((b - a @ np.linalg.lstsq(a, b, rcond=None)[0]) ** 2)
+
+    split = 120
+    pooled = rss(x, y)
+    separate = rss(x[:split], y[:split]) + rss(x[split:], y[split:])
+    f_stat = ((pooled - separate) / 2) / (separate / (size - 4))
+
+    for s in [split, "t >= 120", pl.col("t") >= 120]:
+        res = df.select(pds.lin_reg_chow_test("x", target="y", split=s, add_bias=True)).unnest(
+            "chow_test"
+        )
+        assert np.isclose(res["statistic"][0], f_stat)
+        assert res["df1"][0] == 2
+        assert res["df2"][0] == size - 4
+        assert np.isclose(res["pvalue"][0], f_dist.sf(f_stat, 2, size - 4))
+        assert res["pvalue"][0] < 0.01
+
+    # No break before row 120, so splitting there finds nothing
+    no_break = df.head(split).select(
+        pds.lin_reg_chow_test("x", target="y", split=60, add_bias=True)
+    )["chow_test"][0]
+    assert no_break["pvalue"] > 0.01
+
+    # Each part needs at least as many rows as coefficients
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.lin_reg_chow_test("x", target="y", split=size - 1, add_bias=True))
+    with pytest.raises(pl.exceptions.ComputeError):
+        df.select(pds.lin_reg_chow_test("x", target="y", split=pl.col("t") >= size, add_bias=True))
+
+
+def test_lin_reg_driscoll_kraay():
+    from scipy.stats import t as t_dist
+
+    rng = np.random.default_rng(8)
+    n_periods, n_panels = 60, 10
+    time = np.repeat(np.arange(n_periods), n_panels)
+    panel = np.tile(np.arange(n_panels), n_periods)
+    shock = rng.normal(0.0, 1.0, size=n_periods)[time]  # common to all panels
+    x = rng.normal(0.0, 1.0, size=(time.size, 2)) + shock[:, None]
+    y = 1.0 + x[:, 0] - 0.5 * x[:, 1] + shock + rng.normal(0.0, 1.0, size=time.size)
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "y": y, "time": time, "panel": panel})
+
+    lags = 3
+    res = df.select(
+        pds.lin_reg_driscoll_kraay(
+            "x1", "x2", target="y", time="time", panel="panel", add_bias=True, lags=lags
+        )
+    ).unnest("driscoll_kraay")
+
+    # By hand
+    design = np.column_stack([x, np.ones(time.size)])
+    bread = np.linalg.inv(design.T @ design)
+    coeffs = bread @ design.T @ y
+    e = y - design @ coeffs
+    h = np.zeros((n_periods, 3))
+    np.add.at(h, time, design * e[:, None])
+    s = h.T @ h
+    for lag in range(1, lags + 1):
+        g = h[lag:].T @ h[:-lag]
+        s += (1 - lag / (lags + 1)) * (g + g.T)
+    cov = bread @ s @ bread
+    se = np.sqrt(np.diag(cov))
+
+    assert res["features"].to_list() == ["x1", "x2", "__bias__"]
+    assert np.allclose(res["beta"].to_numpy(), coeffs)
+    assert np.allclose(res["dk_se"].to_numpy(), se)
+    assert np.allclose(np.vstack(res["cov"].to_list()), cov)
+    t = coeffs / se
+    assert np.allclose(res["t"].to_numpy(), t)
+    assert np.allclose(res["p>|t|"].to_numpy(), 2 * t_dist.sf(np.abs(t), n_periods - 1))
+
+    # Duplicated (panel, time) pairs
+    with pytest.raises(Exception):
+        df.select(
+            pds.lin_reg_driscoll_kraay("x1", "x2", target="y", time="time", panel=pl.col("panel") * 0)
+        )
+
+
+def test_lin_reg_influence():
+    import statsmodels.api as sm
+    from statsmodels.stats.outliers_influence import OLSInfluence
+
+    rng = np.random.default_rng(31)
+    size = 100
+    x1 = rng.normal(size=size)
+    x2 = rng.normal(size=size)
+    y = 1.0 + 0.5 * x1 - x2 + rng.normal(size=size)
+    x1[5] = 8.0  # a high leverage row
+    df = pl.DataFrame({"x1": x1, "x2": x2, "y": y})
+
+    infl = OLSInfluence(sm.OLS(y, sm.add_constant(np.column_stack([x1, x2]))).fit())
+    res = df.select(pds.lin_reg_influence("x1", "x2", target="y", add_bias=True)).unnest("influence")
+    assert len(res) == size
+    assert np.allclose(res["leverage"].to_numpy(), infl.hat_matrix_diag)
+    assert np.allclose(res["studentized_resid"].to_numpy(), infl.resid_studentized_internal)
+    assert np.allclose(res["cooks_distance"].to_numpy(), infl.cooks_distance[0])
+    assert res["cooks_distance"].arg_max() == 5
+
+    # Skipped rows are null
+    df_null = df.with_columns(pl.when(pl.int_range(size) == 3).then(None).otherwise(pl.col("y")).alias("y"))
+    res = df_null.select(
+        pds.lin_reg_influence("x1", "x2", target="y", add_bias=True, null_policy="skip")
+    ).unnest("influence")
+    assert res["leverage"].null_count() == 1
+    assert res["cooks_distance"][3] is None
+
+    # The only row with x = 1 determines its coefficient by itself
+    df = pl.DataFrame({"x": [1.0] + [0.0] * 9, "y": rng.normal(size=10)})
+    res = df.select(pds.lin_reg_influence("x", target="y", add_bias=True)).unnest("influence")
+    assert res["cooks_distance"][0] == float("inf")
+
+
+def test_lin_reg_group_influence():
+    rng = np.random.default_rng(8)
+    size = 200
+    g = np.repeat(np.arange(10), 20)
+    x = rng.normal(0.0, 1.0, size=size)
+    y = 1.0 + 2.0 * x + rng.normal(0.0, 0.5, size=size)
+    # Group 7 follows a different line
+    y[g == 7] = -3.0 * x[g == 7] + 5.0
+    df = pl.DataFrame({"g": g, "x": x, "y": y})
+
+    res = df.select(
+        pds.lin_reg_group_influence("x", target="y", group="g", add_bias=True)
+    ).unnest("group_influence")
+    assert res["group"].to_list() == list(range(10))
+    assert res["n"].to_list() == [20] * 10
+    assert np.isclose(res["leverage"].sum(), 2.0)
+
+    # Row level DFFITS by leave-one-out refits
+    design = np.column_stack([x, np.ones(size)])
+    full = design @ np.linalg.lstsq(design, y, rcond=None)[0]
+    h = np.einsum("ij,ji->i", design, np.linalg.pinv(design))
+    dffits = np.empty(size)
+    for i in range(size):
+        keep = np.arange(size) != i
+        coeffs = np.linalg.lstsq(design[keep], y[keep], rcond=None)[0]
+        s = np.sqrt(((y[keep] - design[keep] @ coeffs) ** 2).sum() / (size - 1 - 2))
+        dffits[i] = (full[i] - design[i] @ coeffs) / (s * np.sqrt(h[i]))
+
+    for i in range(10):
+        d = dffits[g == i]
+        assert np.isclose(res["dffits_abs_sum"][i], np.abs(d).sum())
+        assert np.isclose(res["dffits_norm"][i], np.sqrt((d**2).sum()))
+        assert np.isclose(res["dffits_max_abs"][i], np.abs(d).max())
+
+    assert res["dffits_norm"].arg_max() == 7
+
+def test_lin_reg_partial():
+    df = (
+        pds.frame(size=1000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(0.0, 1.0).alias("x3"),
+            pds.random(-0.5, 0.5).alias("noise"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 0.7 - pl.col("x2") + 0.3 * pl.col("x3") + pl.col("noise"),
+        )
+    )
+
+    report = df.select(
+        pds.lin_reg_report("x1", "x2", "x3", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    res = df.select(
+        pds.lin_reg_partial("x2", "x3", target="y", focal="x1", add_bias=True)
+    ).unnest("partial_regression")
+
+    assert np.isclose(res["beta"][0], report["beta"][0])
+    assert np.isclose(res["std_err"][0], report["std_err"][0])
+
+
+def test_lin_reg_min_norm_when_rank_deficient():
+    df = (
+        pds.frame(size=500)
+        .select(pds.random(0.0, 1.0).alias("x1"))
+        .with_columns(
+            x2=pl.col("x1") * 2.0,
+            y=pl.col("x1") * 5.0,
+        )
+    )
+    x = df.select("x1", "x2").to_numpy()
+    y = df["y"].to_numpy()
+    np_coeffs, _, _, _ = np.linalg.lstsq(x, y, rcond=None)
+    coeffs = df.select(pds.lin_reg("x1", "x2", target="y", solver="qr")).item().to_numpy()
+    assert np.allclose(coeffs, np_coeffs, atol=1e-6)
+
+    rank = (
+        df.select(pds.lin_reg_profile("x1", "x2", target="y", add_bias=True))
+        .unnest("profile")["rank"]
+        .item()
+    )
+    assert rank == 2  # x2 = 2 * x1, plus the bias column
+
+
+def test_lin_reg_profile_ill_conditioned():
+    df = (
+        pds.frame(size=500)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(-1e-9, 1e-9).alias("tiny"),
+        )
+        .with_columns(
+            x3=pl.col("x1") + pl.col("tiny"),  # almost the same as x1
+            y=pl.col("x1") + pl.col("x2"),
+        )
+    )
+    normal = df.select(pds.lin_reg_profile("x1", "x2", target="y", add_bias=True)).unnest(
+        "profile"
+    )
+    assert not normal["ill_conditioned"].item()
+    assert normal["cond"].item() < 100.0
+
+    collinear = df.select(
+        pds.lin_reg_profile("x1", "x2", "x3", target="y", add_bias=True)
+    ).unnest("profile")
+    assert collinear["ill_conditioned"].item()
+
+    # The threshold is configurable
+    strict = df.select(
+        pds.lin_reg_profile("x1", "x2", target="y", add_bias=True, warn_collinearity=1.0)
+    ).unnest("profile")
+    assert strict["ill_conditioned"].item()
+
+
+def test_lin_reg_qr_direct():
+    # Polynomial features on [0, 1] are badly conditioned
+    t = np.linspace(0.0, 1.0, 200)
+    names = [f"t{j}" for j in range(1, 8)]
+    truth = np.arange(8, dtype=np.float64) - 3.0
+    design = np.column_stack([t**j for j in range(1, 8)] + [np.ones_like(t)])
+    df = pl.DataFrame({**{n: t ** (j + 1) for j, n in enumerate(names)}, "y": design @ truth})
+
+    def coeffs(solver):
+        return (
+            df.select(pds.lin_reg(*names, target="y", add_bias=True, solver=solver))
+            .explode("coeffs")["coeffs"]
+            .to_numpy()
+        )
+
+    err_qr = np.abs(coeffs("qr") - truth).max()
+    err_direct = np.abs(coeffs("qr_direct") - truth).max()
+    assert err_direct < 1e-8
+    assert err_direct < err_qr
+
+    # Same result on well conditioned data, also with Ridge
+    df2 = pds.frame(size=1000).select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(0.0, 1.0).alias("x2"),
+        pds.random(0.0, 1.0).alias("y"),
+    )
+    for l2_reg in [0.0, 0.5]:
+        res = df2.select(
+            pds.lin_reg("x1", "x2", target="y", add_bias=True, l2_reg=l2_reg, solver="qr").alias(
+                "qr"
+            ),
+            pds.lin_reg(
+                "x1", "x2", target="y", add_bias=True, l2_reg=l2_reg, solver="qr_direct"
+            ).alias("qr_direct"),
+        )
+        assert np.allclose(res["qr"].explode().to_numpy(), res["qr_direct"].explode().to_numpy())
+
+def test_lin_reg_multi_target_per_target_l2():
+    df = (
+        pds.frame(size=1000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+        )
+        .with_columns(
+            y1=pl.col("x1") + pl.col("x2"),
+            y2=pl.col("x1") * 2.0 - pl.col("x2"),
+        )
+    )
+
+    multi = df.select(
+        pds.lin_reg("x1", "x2", target=["y1", "y2"], l2_reg=[0.1, 5.0], add_bias=True)
+    ).unnest("coeffs")
+    for i, (t, l2) in enumerate([("y1", 0.1), ("y2", 5.0)]):
+        single = df.select(pds.lin_reg("x1", "x2", target=t, l2_reg=l2, add_bias=True)).item()
+        assert np.allclose(multi[f"target_{i}"][0].to_numpy(), single.to_numpy())
+
+
+def test_lin_reg_multi_target_matches_single():
+    df = (
+        pds.frame(size=1000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(0.0, 1.0).alias("x3"),
+            pds.random(-0.1, 0.1).alias("noise"),
+        )
+        .with_columns(
+            y1=pl.col("x1") + pl.col("x2") + pl.col("noise"),
+            y2=pl.col("x1") * 2.0 - pl.col("x3") + 0.5,
+            y3=-pl.col("x2") + pl.col("x3") * 3.0 - pl.col("noise"),
+        )
+    )
+    targets = ["y1", "y2", "y3"]
+    for add_bias in [False, True]:
+        multi = df.select(
+            pds.lin_reg("x1", "x2", "x3", target=targets, add_bias=add_bias)
+        ).unnest("coeffs")
+        assert multi.columns == ["target_0", "target_1", "target_2"]
+        for i, t in enumerate(targets):
+            single = df.select(pds.lin_reg("x1", "x2", "x3", target=t, add_bias=add_bias)).item()
+            assert np.allclose(multi[f"target_{i}"][0].to_numpy(), single.to_numpy())
+
+
+def test_lin_reg_predict_from_coeffs():
+    df = (
+        pds.frame(size=1000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random_int(0, 3).alias("group"),
+        )
+        .with_columns(
+            y=pl.col("x1") * (pl.col("group") + 1) - pl.col("x2") + pl.col("group") * 0.5,
+            train=pl.int_range(pl.len()) % 2 == 0,
+        )
+    )
+    # Fit on the train rows of each group, and predict on the test rows
+    coeffs = (
+        df.filter(pl.col("train"))
+        .group_by("group")
+        .agg(pds.lin_reg("x1", "x2", target="y", add_bias=True))
+    )
+    test = df.filter(~pl.col("train")).join(coeffs, on="group")
+    pred = test.select(
+        pds.lin_reg_predict_from_coeffs("x1", "x2", coeffs="coeffs", add_bias=True)
+    )["prediction"]
+    assert np.allclose(pred.to_numpy(), test["y"].to_numpy())
+
+    # A single list applies to all rows, and matches lin_reg's own predictions
+    single = df.select(
+        pds.lin_reg_predict_from_coeffs(
+            "x1", "x2", coeffs=pds.lin_reg("x1", "x2", target="y", add_bias=True), add_bias=True
+        )
+    )["prediction"]
+    own = df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True, return_pred=True)).unnest(
+        "lr_pred"
+    )["pred"]
+    assert np.allclose(single.to_numpy(), own.to_numpy())
+
+    # Null features give null predictions
+    with_null = df.with_columns(x1=pl.when(pl.col("x1") < 0.1).then(None).otherwise(pl.col("x1")))
+    pred = with_null.join(coeffs, on="group").select(
+        pds.lin_reg_predict_from_coeffs("x1", "x2", coeffs="coeffs", add_bias=True)
+    )["prediction"]
+    assert pred.null_count() == with_null["x1"].null_count()
+
+    # Without the bias, the lists are one value too long
+    with pytest.raises(pl.exceptions.ComputeError):
+        test.select(pds.lin_reg_predict_from_coeffs("x1", "x2", coeffs="coeffs"))
+
+
+def test_lin_reg_residual_pacf():
+    # AR(1) errors: the PACF is phi at lag 1 and 0 at higher lags
+    rng = np.random.default_rng(29)
+    size, phi = 5000, 0.6
+    shocks = rng.normal(size=size)
+    errors = np.zeros(size)
+    for t in range(1, size):
+        errors[t] = phi * errors[t - 1] + shocks[t]
+    x = rng.normal(size=size)
+    df = pl.DataFrame({"x": x, "y": 1.0 + 2.0 * x + errors})
+
+    res = (
+        df.select(pds.lin_reg_residual_pacf("x", target="y", lag=5, add_bias=True))
+        .unnest("residual_pacf")
+        .row(0, named=True)
+    )
+    pacf = np.array(res["pacf"])
+    assert len(pacf) == 5
+    assert abs(pacf[0] - phi) < 0.05
+    assert np.all(np.abs(pacf[1:]) < 0.06)
+    assert res["bound"] == pytest.approx(2.0 / np.sqrt(size))
+
+
+def test_lin_reg_white_test():
+    df = (
+        pds.frame(size=2000)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(-0.5, 0.5).alias("noise"),
+        )
+        .with_columns(
+            y_homo=pl.col("x1") + pl.col("x2") + pl.col("noise"),
+            y_hetero=pl.col("x1") + pl.col("x2") + pl.col("noise") * (1.0 + 5.0 * pl.col("x1")),
+        )
+    )
+    homo = df.select(pds.lin_reg_white_test("x1", "x2", target="y_homo", add_bias=True)).unnest(
+        "white_test"
+    )
+    hetero = df.select(
+        pds.lin_reg_white_test("x1", "x2", target="y_hetero", add_bias=True)
+    ).unnest("white_test")
+    assert homo["dof"][0] == 5
+    assert hetero["pvalue"][0] < 0.01
+    assert homo["pvalue"][0] > hetero["pvalue"][0]
+
+
+def test_lin_reg_breusch_pagan():
+    from statsmodels.stats.diagnostic import het_breuschpagan
+
+    rng = np.random.default_rng(43)
+    size = 1000
+    x = rng.uniform(0.0, 1.0, size=(size, 2))
+    noise = rng.normal(size=size)
+    df = pl.DataFrame(
+        {
+            "x1": x[:, 0],
+            "x2": x[:, 1],
+            "y_homo": x[:, 0] + x[:, 1] + noise,
+            "y_hetero": x[:, 0] + x[:, 1] + noise * (0.1 + 3.0 * x[:, 0]),
+        }
+    )
+    design = np.column_stack([x, np.ones(size)])
+    for target in ["y_homo", "y_hetero"]:
+        res = df.select(
+            pds.lin_reg_breusch_pagan("x1", "x2", target=target, add_bias=True)
+        ).unnest("breusch_pagan")
+        y = df[target].to_numpy()
+        resid = y - design @ np.linalg.lstsq(design, y, rcond=None)[0]
+        lm, lm_pvalue, _, _ = het_breuschpagan(resid, design)
+        assert res["dof"][0] == 2
+        assert np.isclose(res["statistic"][0], lm)
+        assert np.isclose(res["pvalue"][0], lm_pvalue)
+        if target == "y_homo":
+            assert res["statistic"][0] < 10.0
+        else:
+            assert res["statistic"][0] > 100.0
+            assert res["pvalue"][0] < 1e-10
+
+
+def test_lin_reg_pred_clip():
+    df = pds.frame(size=1000).select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(-1.0, 1.0).alias("y"),
+    )
+    res = df.select(
+        pds.lin_reg("x1", target="y", add_bias=True, return_pred=True).alias("raw"),
+        pds.lin_reg("x1", target="y", add_bias=True, return_pred=True, clip=(0.0, 0.01)).alias(
+            "clipped"
+        ),
+    ).select(
+        raw=pl.col("raw").struct.field("pred"),
+        clipped=pl.col("clipped").struct.field("pred"),
+    )
+    assert_series_equal(
+        res["clipped"], res["raw"].clip(0.0, 0.01), check_names=False
+    )
+
+
+def test_lin_reg_weight_2_is_duplicated_row():
+    df = (
+        pds.frame(size=200)
+        .select(
+            pds.random(0.0, 1.0).alias("x1"),
+            pds.random(0.0, 1.0).alias("x2"),
+            pds.random(-0.2, 0.2).alias("noise"),
+        )
+        .with_columns(
+            y=pl.col("x1") * 2.0 - pl.col("x2") + pl.col("noise"),
+            w=pl.when(pl.int_range(pl.len()) < 20).then(2.0).otherwise(1.0),
+        )
+    )
+    duplicated = pl.concat([df, df.head(20)])
+    for kwargs in [{}, {"l2_reg": 0.5}]:
+        weighted = df.select(
+            pds.lin_reg("x1", "x2", target="y", weights="w", add_bias=True, **kwargs)
+        ).item()
+        dup = duplicated.select(pds.lin_reg("x1", "x2", target="y", add_bias=True, **kwargs)).item()
+        assert np.allclose(weighted.to_numpy(), dup.to_numpy())
+
+    # Standard errors use X^T W X
+    report_w = df.select(
+        pds.lin_reg_report(
+            "x1", "x2", target="y", weights="w", add_bias=True, weight_type="frequency"
+        ).alias("report")
+    ).unnest("report")
+    report_dup = duplicated.select(
+        pds.lin_reg_report("x1", "x2", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    assert np.allclose(report_w["beta"].to_numpy(), report_dup["beta"].to_numpy())
+    assert np.allclose(report_w["std_err"].to_numpy(), report_dup["std_err"].to_numpy())
+
+    # So do Lasso and Elastic Net, whose loss is normalized by the total weight, with or without a
+    # penalized bias and for both algorithms
+    for kwargs in [
+        {"l1_reg": 0.01},
+        {"l1_reg": 0.01, "l2_reg": 0.1},
+        {"l1_reg": 0.01, "penalize_bias": True},
+        {"l1_reg": 0.01, "l2_reg": 0.1, "algorithm": "fista"},
+    ]:
+        args = dict(
+            target="y", add_bias=True, tol=1e-12, stop_criterion="dual_gap", max_iter=100_000
+        )
+        weighted = df.select(pds.lin_reg("x1", "x2", weights="w", **args, **kwargs)).item()
+        dup = duplicated.select(pds.lin_reg("x1", "x2", **args, **kwargs)).item()
+        assert np.allclose(weighted.to_numpy(), dup.to_numpy(), atol=1e-6)
+
+    # Weights of rows skipped for nulls are dropped with them
+    with_null = df.with_columns(
+        x1=pl.when(pl.int_range(pl.len()) == 5).then(None).otherwise(pl.col("x1"))
+    )
+    skipped = with_null.select(
+        pds.lin_reg("x1", "x2", target="y", weights="w", add_bias=True, null_policy="skip")
+    ).item()
+    dropped = with_null.drop_nulls().select(
+        pds.lin_reg("x1", "x2", target="y", weights="w", add_bias=True)
+    ).item()
+    assert np.allclose(skipped.to_numpy(), dropped.to_numpy())
+
+
+def test_lin_reg_report_frequency_weights():
+    # A frequency weighted report on aggregated rows should match the unweighted
+    # report on the expanded rows.
+    df = pl.DataFrame(
+        {
+            "x": [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+            "y": [1.2, 1.9, 3.4, 3.8, 5.3, 5.9],
+            "w": [3, 1, 2, 4, 2, 3],
+        }
+    )
+    expanded = df.select(pl.col("x", "y").repeat_by("w").explode())
+
+    cols = [
+        "beta",
+        "std_err",
+        "t",
+        "p>|t|",
+        "0.025",
+        "0.975",
+        "ci_lower",
+        "ci_upper",
+        "r2",
+        "adj_r2",
+    ]
+    res = df.select(
+        pds.lin_reg_report(
+            "x", target="y", weights="w", weight_type="frequency", add_bias=True
+        ).alias("report")
+    ).unnest("report")
+    ans = expanded.select(
+        pds.lin_reg_report("x", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    assert_frame_equal(res.select(cols), ans.select(cols))
+
+
+def test_lin_reg_report_ridge_df_resid():
+    df = pds.frame(size=200).select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(0.0, 1.0).alias("x2"),
+        pds.random(0.0, 1.0).alias("x3"),
+        pds.random(-0.5, 0.5).alias("noise"),
+    ).with_columns(y=pl.col("x1") - pl.col("x2") + 0.5 * pl.col("x3") + pl.col("noise"))
+    n, p = 200, 4  # 3 features and the bias
+
+    ols = df.select(
+        pds.lin_reg_report("x1", "x2", "x3", target="y", add_bias=True).alias("report")
+    ).unnest("report")
+    assert np.isclose(ols["df_resid"][0], n - p)
+
+    x = df.select("x1", "x2", "x3").with_columns(pl.lit(1.0)).to_numpy()
+    y = df["y"].to_numpy()
+    prev = n - p
+    for l2 in [0.1, 1.0, 10.0, 100.0]:
+        res = df.select(
+            pds.lin_reg_report("x1", "x2", "x3", target="y", add_bias=True, l2_reg=l2).alias(
+                "report"
+            )
+        ).unnest("report")
+        # Same coefficients as lin_reg with l2_reg
+        coeffs = df.select(
+            pds.lin_reg("x1", "x2", "x3", target="y", add_bias=True, l2_reg=l2)
+        ).item()
+        assert np.allclose(res["beta"].to_numpy(), coeffs.to_numpy())
+        # n - trace(H), with H = X (XtX + l2 * D)^-1 Xt
+        d = np.diag([1.0, 1.0, 1.0, 0.0])
+        h = x @ np.linalg.inv(x.T @ x + l2 * d) @ x.T
+        df_resid = res["df_resid"][0]
+        assert np.isclose(df_resid, n - np.trace(h))
+        assert df_resid > prev
+        prev = df_resid
+    # The bias is not penalized, so at most n - 1
+    assert prev < n - 1
+
+
+def test_lin_reg_n_obs():
+    df = pds.frame(size=200).select(
+        pds.random(0.0, 1.0).alias("x1"),
+        pds.random(0.0, 1.0).alias("x2"),
+        pds.random(-0.5, 0.5).alias("noise"),
+    ).with_columns(
+        y=pl.col("x1") - pl.col("x2") + pl.col("noise"),
+    ).with_columns(
+        x1=pl.when(pl.int_range(pl.len()) % 7 == 0).then(None).otherwise(pl.col("x1")),
+        y=pl.when(pl.int_range(pl.len()) % 11 == 0).then(None).otherwise(pl.col("y")),
+    )
+    n_complete = df.drop_nulls().height
+
+    report = df.select(
+        pds.lin_reg_report(
+            "x1", "x2", target="y", add_bias=True, null_policy="skip"
+        ).alias("report")
+    ).unnest("report")
+    assert report["n_obs"][0] == n_complete
+    assert np.isclose(report["df_resid"][0], n_complete - 3)
+
+    n_obs = (
+        df.select(
+            pds.lin_reg_profile("x1", "x2", target="y", add_bias=True, null_policy="skip")
+        )
+        .unnest("profile")["n_obs"]
+        .item()
+    )
+    assert n_obs == n_complete
+
+
+def test_lin_reg_no_complete_rows():
+    df = pl.DataFrame(
+        {
+            "x1": [None, 1.0, 2.0, None],
+            "x2": [1.0, None, None, 2.0],
+            "y": [1.0, 2.0, 3.0, 4.0],
+        }
+    )
+    with pytest.raises(pl.exceptions.ComputeError, match="No complete rows"):
+        df.select(pds.lin_reg("x1", "x2", target="y", null_policy="skip"))
+
+
+def test_lin_reg_predict_path():
+    df = pl.DataFrame(
+        {
+            "x1": [1.0, 2.0, None, 4.0],
+            "x2": [0.5, -1.0, 2.0, 3.0],
+        }
+    )
+    coefficients = [[1.0, 2.0, 0.5], [0.0, -1.0, 1.0], [3.0, 0.0, -2.0]]
+    res = df.select(
+        pds.lin_reg_predict_path("x1", "x2", coefficients=coefficients, add_bias=True)
+    )["predictions"]
+
+    x = df.select("x1", "x2").fill_null(0.0).to_numpy()
+    b = np.array(coefficients).T
+    ans = x @ b[:2] + b[2]
+    assert res[2] is None
+    for i in [0, 1, 3]:
+        assert np.allclose(res[i].to_numpy(), ans[i])
+
+
+def test_lin_reg_train_test():
+    rng = np.random.default_rng(15)
+    size = 100
+    x = rng.normal(size=(size, 2))
+    y = 1.0 + x @ np.array([2.0, -1.0]) + rng.normal(size=size)
+    train = np.arange(size) < 70
+    df = pl.DataFrame({"x1": x[:, 0], "x2": x[:, 1], "y": y, "train": train}).with_columns(
+        # A null in the train set, which is skipped, and an unknown target in the test set
+        pl.when(pl.int_range(pl.len()) == 3).then(None).otherwise(pl.col("x1")).alias("x1"),
+        pl.when(pl.int_range(pl.len()) == 90).then(None).otherwise(pl.col("y")).alias("y"),
+    )
+
+    res = df.select(
+        pds.lin_reg_train_test("x1", "x2", target="y", train="train", add_bias=True)
+    ).unnest("lr_pred")
+    coeffs = (
+        df.filter(pl.col("train"))
+        .select(pds.lin_reg("x1", "x2", target="y", add_bias=True))
+        .item()
+        .to_numpy()
+    )
+    pred = x @ coeffs[:2] + coeffs[2]
+
+    assert res["pred"][:70].null_count() == 70
+    assert np.allclose(res["pred"][70:].to_numpy(), pred[70:])
+    assert res["resid"][90] is None
+    test = np.arange(70, size) != 90
+    assert np.allclose(res["resid"][70:].to_numpy()[test], (y - pred)[70:][test])
+
+    res = df.select(
+        pds.lin_reg_train_test("x1", "x2", target="y", train="train", add_bias=True, score="all")
+    ).unnest("lr_pred")
+    assert res["pred"][3] is None
+    assert res["pred"].null_count() == 1
+
+
+def test_lin_reg_predict_grouped():
+    df = pl.DataFrame(
+        {
+            "customer": ["b", "a", "b", "a", "c"],
+            "w": [1.0, 2.0, 0.5, 1.0, 3.0],
+            "x1": [1.0, 2.0, None, 4.0, -1.0],
+            "x2": [0.5, -1.0, 2.0, 3.0, 1.0],
+        }
+    )
+    coefficients = [1.0, 2.0, 0.5]
+    pred = df.select(
+        pds.lin_reg_predict_path("x1", "x2", coefficients=[coefficients], add_bias=True)
+        .list.first()
+        .alias("pred"),
+        "customer",
+        "w",
+    ).drop_nulls()
+
+    for how in ["sum", "mean"]:
+        res = df.select(
+            pds.lin_reg_predict_grouped(
+                "x1",
+                "x2",
+                coefficients=coefficients,
+                group="customer",
+                weights="w",
+                add_bias=True,
+                how=how,
+            )
+        ).unnest("grouped_prediction")
+        agg = (pl.col("w") * pl.col("pred")).sum()
+        if how == "mean":
+            agg = agg / pl.col("w").sum()
+        ans = pred.group_by("customer").agg(agg.alias("prediction")).sort("customer")
+        assert res["group"].to_list() == ["a", "b", "c"]
+        assert np.allclose(res["prediction"].to_numpy(), ans["prediction"].to_numpy())
+
+    # Unweighted sum
+    res = df.select(
+        pds.lin_reg_predict_grouped("x1", "x2", coefficients=coefficients, group="customer", add_bias=True)
+    ).unnest("grouped_prediction")
+    ans = pred.group_by("customer").agg(pl.col("pred").sum()).sort("customer")
+    assert np.allclose(res["prediction"].to_numpy(), ans["pred"].to_numpy())
+    assert res["weight"].to_list() == [2.0, 1.0, 1.0]
+
+    with pytest.raises(Exception):
+        df.select(
+            pds.lin_reg_predict_grouped(
+                "x1", coefficients=[1.0], group="customer", weights=-pl.col("w")
+            )
+        )
+
+
+def test_lin_reg_in_group_by():
+    df = pl.DataFrame(
+        {
+            "A": [1] * 4 + [2] * 4,
+            "Y": [1] * 8,
+            "X1": [1, 2, 3, 4, 5, 6, 7, 8],
+            "X2": [2, 3, 4, 1, 6, 7, 8, 5],
+        }
+    )
+
+    first = df.filter(pl.col("A").eq(1)).with_columns(
+        pds.lin_reg(
+            pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
+        ).alias("pred")
+    )
+
+    second = df.filter(pl.col("A").eq(2)).with_columns(
+        pds.lin_reg(
+            pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
+        ).alias("pred")
+    )
+
+    test = (
+        df.group_by("A", maintain_order=True)
+        .agg(
+            "Y",
+            "X1",
+            "X2",
+            pds.lin_reg(
+                pl.col("X1"), pl.col("X2"), target=pl.col("Y"), add_bias=False, return_pred=True
+            ).alias("pred"),
+        )
+        .explode("Y", "X1", "X2", "pred")
+    )
+
+    test_first = test.filter(pl.col("A") == 1)
+    test_second = test.filter(pl.col("A") == 2)
+
+    assert_frame_equal(first, test_first)
+    assert_frame_equal(second, test_second)
+
+
+def test_lin_reg_svd_rcond_nearly_collinear():
+    rng = np.random.default_rng(43)
+    size = 500
+    x1 = rng.normal(size=size)
+    x2 = x1 + rng.normal(size=size) * 1e-7
+    x3 = rng.normal(size=size)
+    y = x1 + x2 - 0.5 * x3 + rng.normal(size=size) * 0.1
+    df = pl.DataFrame({"x1": x1, "x2": x2, "x3": x3, "y": y})
+    # A tiny perturbation of the target
+    df = df.with_columns(y2=pl.col("y") + pl.Series(rng.normal(size=size) * 1e-3))
+
+    rcond = 1e-4
+    x = np.column_stack([x1, x2, x3])
+    np_coeffs = np.linalg.lstsq(x, y, rcond=rcond)[0]
+    coeffs = df.select(pds.lin_reg("x1", "x2", "x3", target="y", solver="svd_rcond", rcond=rcond))[
+        "coeffs"
+    ][0].to_numpy()
+    assert np.allclose(coeffs, np_coeffs, atol=1e-8)
+    # The collinear pair shares the effect
+    assert np.allclose(coeffs[:2], [1.0, 1.0], atol=0.05)
+
+    def fit(target, solver):
+        return df.select(
+            pds.lin_reg("x1", "x2", "x3", target=target, solver=solver, rcond=rcond)
+        )["coeffs"][0].to_numpy()
+
+    change_rcond = np.abs(fit("y", "svd_rcond") - fit("y2", "svd_rcond")).max()
+    change_qr = np.abs(fit("y", "qr") - fit("y2", "qr")).max()
+    assert change_rcond < 1e-2
+    assert change_qr > 100 * change_rcond
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x1", target="y", solver="svd_rcond", return_pred=True)
+
+
+def test_lin_reg_drop_nonfinite():
+    rng = np.random.default_rng(31)
+    size = 200
+    x1 = rng.normal(size=size)
+    x2 = rng.normal(size=size)
+    y = 2.0 * x1 - x2 + 0.5 + rng.normal(size=size) * 0.1
+    df = pl.DataFrame({"x1": x1, "x2": x2, "y": y})
+    bad = (
+        df.with_row_index()
+        .with_columns(
+            x1=pl.when(pl.col("index") == 3)
+            .then(None)
+            .when(pl.col("index") == 7)
+            .then(float("inf"))
+            .when(pl.col("index") == 11)
+            .then(float("nan"))
+            .otherwise(pl.col("x1")),
+            y=pl.when(pl.col("index") == 13)
+            .then(None)
+            .when(pl.col("index") == 17)
+            .then(float("-inf"))
+            .when(pl.col("index") == 19)
+            .then(float("nan"))
+            .otherwise(pl.col("y")),
+        )
+        .drop("index")
+    )
+    clean = df.filter(~pl.int_range(0, size).is_in([3, 7, 11, 13, 17, 19]))
+
+    expected = clean.select(pds.lin_reg("x1", "x2", target="y", add_bias=True))["coeffs"][0]
+    coeffs = bad.select(
+        pds.lin_reg("x1", "x2", target="y", add_bias=True, drop_nonfinite=True)
+    )["coeffs"][0]
+    assert np.allclose(coeffs.to_numpy(), expected.to_numpy())
+
+    # Predictions of the dropped rows are null or NaN, the rest come from the clean fit
+    pred = bad.select(
+        pds.lin_reg("x1", "x2", target="y", add_bias=True, drop_nonfinite=True, return_pred=True)
+    ).unnest("lr_pred")["pred"]
+    assert pred.len() == size
+    assert np.allclose(pred[0], x1[0] * expected[0] + x2[0] * expected[1] + expected[2])
+
+    # Without it, the non-finite values poison the fit
+    poisoned = bad.select(pds.lin_reg("x1", "x2", target="y", add_bias=True))["coeffs"][0]
+    assert not np.all(np.isfinite(poisoned.to_numpy()))
+
+    with pytest.raises(pl.exceptions.ComputeError):
+        bad.fill_null(0.0).select(
+            pds.lin_reg("x1", "x2", target="y", null_policy="zero", drop_nonfinite=True)
+        )
+
+
+def test_lin_reg_no_complete_rows():
+    # Every row has a null in one of the features
+    df = pl.DataFrame(
+        {
+            "x1": [1.0, None, 3.0, None, 5.0, None],
+            "x2": [None, 2.0, None, 4.0, None, 6.0],
+            "y": [1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+        }
+    )
+    with pytest.raises(pl.exceptions.ComputeError, match="No complete rows left"):
+        df.select(pds.lin_reg("x1", "x2", target="y", null_policy="skip"))
+    with pytest.raises(pl.exceptions.ComputeError, match="No complete rows left"):
+        df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True, return_pred=True))
+
+    # The same when all the rows are dropped for non-finite values
+    df = pl.DataFrame({"x1": [np.inf, -np.inf, np.nan], "y": [1.0, 2.0, 3.0]})
+    with pytest.raises(pl.exceptions.ComputeError, match="No complete rows left"):
+        df.select(pds.lin_reg("x1", target="y", drop_nonfinite=True))
+
+
+def test_lin_reg_tls_errors_in_variables():
+    rng = np.random.default_rng(17)
+    size = 5000
+    x1_true = rng.normal(size=size) * 2.0
+    x2_true = rng.normal(size=size) * 2.0
+    y = 2.0 * x1_true - 1.0 * x2_true + 3.0 + rng.normal(size=size)
+    # Same noise on the features as on the target
+    df = pl.DataFrame(
+        {
+            "x1": x1_true + rng.normal(size=size),
+            "x2": x2_true + rng.normal(size=size),
+            "y": y,
+        }
+    )
+
+    def fit(solver):
+        return df.select(pds.lin_reg("x1", "x2", target="y", add_bias=True, solver=solver))[
+            "coeffs"
+        ][0].to_numpy()
+
+    tls = fit("tls")
+    ols = fit("qr")
+    true = np.array([2.0, -1.0])
+    # OLS is biased towards 0 when the features are noisy, TLS is not
+    assert np.all(np.abs(tls[:2] - true) < np.abs(ols[:2] - true))
+    assert np.allclose(tls, [2.0, -1.0, 3.0], atol=0.1)
+
+    # Reference: the right singular vector of the smallest singular value of the centered [X | y]
+    z = df.select("x1", "x2", "y").to_numpy()
+    z = z - z.mean(axis=0)
+    v = np.linalg.svd(z)[2][-1]
+    assert np.allclose(tls[:2], -v[:2] / v[2], atol=1e-8)
+
+    with pytest.raises(ValueError):
+        pds.lin_reg("x1", target="y", solver="tls", l2_reg=0.1)
 
 
 def test_lin_reg_with_rcond():